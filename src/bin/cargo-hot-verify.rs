@@ -0,0 +1,7 @@
+//! `cargo hot-verify`; see `base::perf::cli`.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    ExitCode::from(base::perf::cli::run(std::env::args().skip(1)))
+}
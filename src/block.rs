@@ -0,0 +1,191 @@
+//! Fixed-size block processing over arbitrarily sized input.
+//!
+//! Audio hosts hand out buffers of whatever size they like, but kernels want
+//! exact `[T; BLOCK]` frames. `for_each_block` runs a kernel over the whole
+//! frames of one slice; `BlockAdapter` does the same for a stream, carrying
+//! the partial frame between calls. In both, the main loop iterates over
+//! `as_chunks::<BLOCK>()` so it has no bounds checks, and every remainder or
+//! carry-over step lives in a `#[cold]`, out-of-line function.
+
+/// Calls `full` for each whole `BLOCK` frame of `data`, then `tail` for any remainder.
+#[inline]
+pub fn for_each_block<T, const BLOCK: usize>(
+    data: &[T],
+    mut full: impl FnMut(&[T; BLOCK]),
+    tail: impl FnOnce(&[T]),
+) {
+    let (blocks, rest) = data.as_chunks::<BLOCK>();
+    for block in blocks {
+        full(block);
+    }
+    if !rest.is_empty() {
+        cold_tail(rest, tail);
+    }
+}
+
+/// Mutable variant of [`for_each_block`].
+#[inline]
+pub fn for_each_block_mut<T, const BLOCK: usize>(
+    data: &mut [T],
+    mut full: impl FnMut(&mut [T; BLOCK]),
+    tail: impl FnOnce(&mut [T]),
+) {
+    let (blocks, rest) = data.as_chunks_mut::<BLOCK>();
+    for block in blocks {
+        full(block);
+    }
+    if !rest.is_empty() {
+        cold_tail(rest, tail);
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn cold_tail<S>(rest: S, tail: impl FnOnce(S)) {
+    tail(rest)
+}
+
+/// Re-frames a stream of slices into exact `BLOCK` frames.
+#[derive(Debug, Clone, Copy)]
+pub struct BlockAdapter<T, const BLOCK: usize> {
+    pending: [T; BLOCK],
+    fill: usize,
+}
+
+impl<T: Copy + Default, const BLOCK: usize> BlockAdapter<T, BLOCK> {
+    pub fn new() -> Self {
+        const { assert!(BLOCK > 0, "block size must not be zero") };
+        Self {
+            pending: [T::default(); BLOCK],
+            fill: 0,
+        }
+    }
+
+    /// Samples held back until the next frame completes.
+    pub fn pending(&self) -> usize {
+        self.fill
+    }
+
+    /// Feeds `input`, calling `f` for every frame completed by it, in order.
+    #[inline]
+    pub fn push(&mut self, input: &[T], mut f: impl FnMut(&[T; BLOCK])) {
+        let mut input = input;
+        if self.fill != 0 {
+            input = self.top_up(input, &mut f);
+        }
+        let (blocks, rest) = input.as_chunks::<BLOCK>();
+        for block in blocks {
+            f(block);
+        }
+        if !rest.is_empty() {
+            self.stash(rest);
+        }
+    }
+
+    /// Hands out the partial frame (if any) and clears it.
+    pub fn flush(&mut self, f: impl FnOnce(&[T])) {
+        if self.fill != 0 {
+            f(&self.pending[..self.fill]);
+            self.fill = 0;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.fill = 0;
+    }
+
+    /// Completes the pending frame from the front of `input`; returns the unused input.
+    #[cold]
+    #[inline(never)]
+    fn top_up<'a>(&mut self, input: &'a [T], f: &mut impl FnMut(&[T; BLOCK])) -> &'a [T] {
+        let take = (BLOCK - self.fill).min(input.len());
+        let (head, rest) = input.split_at(take);
+        self.pending[self.fill..self.fill + take].copy_from_slice(head);
+        self.fill += take;
+        if self.fill == BLOCK {
+            f(&self.pending);
+            self.fill = 0;
+        }
+        rest
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn stash(&mut self, rest: &[T]) {
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.fill = rest.len();
+    }
+}
+
+impl<T: Copy + Default, const BLOCK: usize> Default for BlockAdapter<T, BLOCK> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_for_each_block() {
+        let data: Vec<u32> = (0..10).collect();
+        let mut sums = Vec::new();
+        let mut tail_len = 0;
+        for_each_block::<_, 4>(
+            &data,
+            |b| sums.push(b.iter().sum::<u32>()),
+            |t| tail_len = t.len(),
+        );
+        assert_eq!(sums, [6, 22]);
+        assert_eq!(tail_len, 2);
+
+        let mut called = false;
+        for_each_block::<_, 5>(&data, |_| {}, |_| called = true);
+        assert!(!called);
+    }
+
+    #[test]
+    fn test_for_each_block_mut() {
+        let mut data = [1.0f32; 7];
+        for_each_block_mut::<_, 3>(&mut data, |b| b[0] = 0.0, |t| t.fill(2.0));
+        assert_eq!(data, [0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_adapter_reframes_any_split() {
+        let stream: Vec<u16> = (0..50).collect();
+        for split in [1, 3, 4, 7, 16, 50] {
+            let mut adapter: BlockAdapter<u16, 4> = BlockAdapter::new();
+            let mut frames = Vec::new();
+            for chunk in stream.chunks(split) {
+                adapter.push(chunk, |frame| frames.extend_from_slice(frame));
+            }
+            assert_eq!(frames, &stream[..48], "split {}", split);
+            assert_eq!(adapter.pending(), 2);
+            let mut rest = Vec::new();
+            adapter.flush(|partial| rest.extend_from_slice(partial));
+            assert_eq!(rest, [48, 49]);
+            assert_eq!(adapter.pending(), 0);
+        }
+    }
+
+    #[test]
+    fn test_adapter_small_pushes() {
+        let mut adapter: BlockAdapter<f32, 3> = BlockAdapter::default();
+        let mut count = 0;
+        adapter.push(&[1.0], |_| count += 1);
+        adapter.push(&[], |_| count += 1);
+        adapter.push(&[2.0], |_| count += 1);
+        assert_eq!(count, 0);
+        adapter.push(&[3.0, 4.0], |frame| {
+            assert_eq!(frame, &[1.0, 2.0, 3.0]);
+            count += 1;
+        });
+        assert_eq!((count, adapter.pending()), (1, 1));
+        adapter.reset();
+        adapter.flush(|_| panic!("nothing pending"));
+    }
+}
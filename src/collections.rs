@@ -0,0 +1,5 @@
+pub mod capacity;
+pub mod ring_buffer;
+
+pub use capacity::{Capacity, const_validate_capacity};
+pub use ring_buffer::RingBuffer;
@@ -0,0 +1,116 @@
+//! Capacity arithmetic for composing fixed-capacity containers.
+//!
+//! Stable Rust rejects `RingBuffer<T, { A + B }>` inside a type generic over
+//! `A` and `B` ("generic parameters may not be used in const operations").
+//! The usual way around it is to take the derived capacity as one more const
+//! parameter and check it at compile time:
+//!
+//! * the const fns (`sum`, `next_pow2`, ...) compute it where the
+//!   parameters are concrete, e.g. `Mixer<3, 5, { sum(3, 5) }>`;
+//! * the `Capacity` types describe the derivation inside the generic code,
+//!   where `const_validate_capacity::<Sum<Cap<A>, Cap<B>>>(AB, ..)` in a
+//!   `const { .. }` block rejects a wrong argument as a const evaluation error.
+
+use core::marker::PhantomData;
+
+pub const fn sum(a: usize, b: usize) -> usize {
+    a + b
+}
+
+pub const fn product(a: usize, b: usize) -> usize {
+    a * b
+}
+
+pub const fn max(a: usize, b: usize) -> usize {
+    if a > b { a } else { b }
+}
+
+/// Smallest power of two `>= n` (1 for 0).
+pub const fn next_pow2(n: usize) -> usize {
+    n.next_power_of_two()
+}
+
+/// A capacity known at the type level.
+pub trait Capacity {
+    const N: usize;
+}
+
+/// The literal capacity `N`.
+pub struct Cap<const N: usize>;
+impl<const N: usize> Capacity for Cap<N> {
+    const N: usize = N;
+}
+
+pub struct Sum<A, B>(PhantomData<(A, B)>);
+impl<A: Capacity, B: Capacity> Capacity for Sum<A, B> {
+    const N: usize = A::N + B::N;
+}
+
+pub struct Product<A, B>(PhantomData<(A, B)>);
+impl<A: Capacity, B: Capacity> Capacity for Product<A, B> {
+    const N: usize = A::N * B::N;
+}
+
+pub struct Max<A, B>(PhantomData<(A, B)>);
+impl<A: Capacity, B: Capacity> Capacity for Max<A, B> {
+    const N: usize = max(A::N, B::N);
+}
+
+pub struct NextPow2<C>(PhantomData<C>);
+impl<C: Capacity> Capacity for NextPow2<C> {
+    const N: usize = next_pow2(C::N);
+}
+
+/// `n` if it equals `C::N`; see the module docs.
+#[track_caller]
+pub const fn const_validate_capacity<C: Capacity>(n: usize, message: &'static str) -> usize {
+    if n != C::N {
+        panic!("{}", message);
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::RingBuffer;
+    use crate::sync::SpscQueue;
+
+    /// Downstream-style composite: a history of `A + B` samples fed through
+    /// a queue rounded up to a power of two.
+    struct Mixer<const A: usize, const B: usize, const AB: usize, const Q: usize> {
+        history: RingBuffer<u8, AB>,
+        queue: SpscQueue<u8, Q>,
+    }
+
+    impl<const A: usize, const B: usize, const AB: usize, const Q: usize> Mixer<A, B, AB, Q> {
+        fn new() -> Self {
+            const {
+                const_validate_capacity::<Sum<Cap<A>, Cap<B>>>(AB, "AB must be A + B");
+                const_validate_capacity::<NextPow2<Cap<AB>>>(Q, "Q must be AB rounded up to 2^k");
+            };
+            Self {
+                history: RingBuffer::new(),
+                queue: SpscQueue::new(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_const_fns() {
+        assert_eq!(sum(3, 5), 8);
+        assert_eq!(product(4, 6), 24);
+        assert_eq!(max(4, 6), 6);
+        assert_eq!((next_pow2(0), next_pow2(5), next_pow2(8)), (1, 8, 8));
+        assert_eq!(<Max<Cap<2>, Product<Cap<3>, Cap<4>>>>::N, 12);
+    }
+
+    #[test]
+    fn test_composed_capacities() {
+        let mixer = Mixer::<3, 5, { sum(3, 5) }, { next_pow2(sum(3, 5)) }>::new();
+        assert_eq!(mixer.history.capacity(), 8);
+        assert_eq!(mixer.queue.capacity(), 8);
+        let wide = Mixer::<3, 6, 9, 16>::new();
+        assert_eq!(wide.queue.capacity(), 16);
+    }
+}
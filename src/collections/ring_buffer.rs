@@ -0,0 +1,333 @@
+//! Fixed-capacity circular buffer for a single RT context.
+//!
+//! Unlike `sync::SpscQueue` there are no atomics: the buffer is owned by one
+//! context and accessed through `&mut`, which keeps delay lines and history
+//! buffers clear of `AtomicCheck`. Pushing into a full buffer overwrites the
+//! oldest element. Index wrapping uses a compare instead of `%`.
+//!
+//! With the `alloc` feature, `new_boxed` builds a buffer on the heap once at
+//! startup; using it afterwards never allocates.
+
+use core::mem::MaybeUninit;
+
+use crate::validate::const_validate_nonzero;
+
+/// Circular buffer of up to `N` elements with overwrite-oldest semantics.
+pub struct RingBuffer<T, const N: usize> {
+    buffer: [MaybeUninit<T>; N],
+    /// Index of the oldest element.
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    pub const fn new() -> Self {
+        const { const_validate_nonzero(N, "RingBuffer capacity must be non-zero") };
+        Self {
+            buffer: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[inline(always)]
+    fn physical(&self, logical: usize) -> usize {
+        let index = self.head + logical;
+        if index >= N { index - N } else { index }
+    }
+
+    /// Appends `value`, returning the overwritten oldest element when full.
+    #[inline]
+    pub fn push(&mut self, value: T) -> Option<T> {
+        if self.len == N {
+            let slot = &mut self.buffer[self.head];
+            // SAFETY: a full buffer has every slot initialized.
+            let old = unsafe { slot.assume_init_read() };
+            slot.write(value);
+            self.head = self.physical(1);
+            Some(old)
+        } else {
+            let tail = self.physical(self.len);
+            self.buffer[tail].write(value);
+            self.len += 1;
+            None
+        }
+    }
+
+    /// Removes and returns the oldest element.
+    #[inline]
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        // SAFETY: `head` is initialized while `len > 0`.
+        let value = unsafe { self.buffer[self.head].assume_init_read() };
+        self.head = self.physical(1);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Element `index` counted from the oldest (0).
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        // SAFETY: logical indices below `len` are initialized.
+        Some(unsafe { self.buffer[self.physical(index)].assume_init_ref() })
+    }
+
+    /// Element `age` steps back from the newest (0), as used by delay lines.
+    #[inline]
+    pub fn get_from_newest(&self, age: usize) -> Option<&T> {
+        if age >= self.len {
+            return None;
+        }
+        self.get(self.len - 1 - age)
+    }
+
+    pub fn oldest(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn newest(&self) -> Option<&T> {
+        self.get_from_newest(0)
+    }
+
+    /// Contents as two slices, oldest first; the second is empty unless wrapped.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let first_len = if self.head + self.len > N {
+            N - self.head
+        } else {
+            self.len
+        };
+        let first = &self.buffer[self.head..self.head + first_len];
+        let second = &self.buffer[..self.len - first_len];
+        // SAFETY: both ranges cover exactly the initialized elements.
+        unsafe { (slice_assume_init(first), slice_assume_init(second)) }
+    }
+
+    /// Mutable variant of `as_slices()`.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let first_len = if self.head + self.len > N {
+            N - self.head
+        } else {
+            self.len
+        };
+        let second_len = self.len - first_len;
+        let (front, back) = self.buffer.split_at_mut(self.head);
+        // SAFETY: both ranges cover exactly the initialized elements.
+        unsafe {
+            (
+                slice_assume_init_mut(&mut back[..first_len]),
+                slice_assume_init_mut(&mut front[..second_len]),
+            )
+        }
+    }
+
+    /// Iterates from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        let (first, second) = self.as_slices();
+        Chain { first, second }
+    }
+
+    /// Drops all elements.
+    pub fn clear(&mut self) {
+        while self.pop_oldest().is_some() {}
+    }
+}
+
+/// `Iterator::chain` is not `ExactSizeIterator`; this one is.
+struct Chain<'a, T> {
+    first: &'a [T],
+    second: &'a [T],
+}
+
+impl<'a, T> Iterator for Chain<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if let Some((item, rest)) = self.first.split_first() {
+            self.first = rest;
+            Some(item)
+        } else if let Some((item, rest)) = self.second.split_first() {
+            self.second = rest;
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.first.len() + self.second.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Chain<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some((item, rest)) = self.second.split_last() {
+            self.second = rest;
+            Some(item)
+        } else if let Some((item, rest)) = self.first.split_last() {
+            self.first = rest;
+            Some(item)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Chain<'_, T> {}
+
+unsafe fn slice_assume_init<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+    // SAFETY: `MaybeUninit<T>` has the layout of `T`; the caller guarantees initialization.
+    unsafe { &*(slice as *const [MaybeUninit<T>] as *const [T]) }
+}
+
+unsafe fn slice_assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+    // SAFETY: as for `slice_assume_init`.
+    unsafe { &mut *(slice as *mut [MaybeUninit<T>] as *mut [T]) }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Empty buffer on the heap, initialized in place so a large `N` never
+    /// passes through the stack.
+    pub fn new_boxed() -> alloc::boxed::Box<Self> {
+        const { const_validate_nonzero(N, "RingBuffer capacity must be non-zero") };
+        let mut boxed = alloc::boxed::Box::<Self>::new_uninit();
+        let ptr = boxed.as_mut_ptr();
+        // SAFETY: `head` and `len` are written; `buffer` is `MaybeUninit` and
+        // needs no initialization.
+        unsafe {
+            core::ptr::addr_of_mut!((*ptr).head).write(0);
+            core::ptr::addr_of_mut!((*ptr).len).write(0);
+            boxed.assume_init()
+        }
+    }
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::vec::Vec;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_boxed_buffer() {
+        let mut ring = RingBuffer::<u64, 65536>::new_boxed();
+        assert!(ring.is_empty());
+        for i in 0..65537 {
+            ring.push(i);
+        }
+        assert_eq!((ring.len(), ring.iter().next()), (65536, Some(&1)));
+    }
+
+    #[test]
+    fn test_push_until_full() {
+        let mut ring: RingBuffer<u32, 3> = RingBuffer::new();
+        assert_eq!(ring.push(1), None);
+        assert_eq!(ring.push(2), None);
+        assert_eq!(ring.push(3), None);
+        assert!(ring.is_full());
+        assert_eq!(ring.push(4), Some(1));
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [2, 3, 4]);
+    }
+
+    #[test]
+    fn test_get_and_delay_line_access() {
+        let mut ring: RingBuffer<u32, 4> = RingBuffer::new();
+        for i in 0..6 {
+            ring.push(i);
+        }
+        assert_eq!(ring.oldest(), Some(&2));
+        assert_eq!(ring.newest(), Some(&5));
+        assert_eq!(ring.get(1), Some(&3));
+        assert_eq!(ring.get_from_newest(1), Some(&4));
+        assert_eq!(ring.get(4), None);
+        assert_eq!(ring.get_from_newest(4), None);
+    }
+
+    #[test]
+    fn test_as_slices_wrapped() {
+        let mut ring: RingBuffer<u32, 4> = RingBuffer::new();
+        for i in 0..6 {
+            ring.push(i);
+        }
+        let (first, second) = ring.as_slices();
+        assert_eq!(first, &[2, 3]);
+        assert_eq!(second, &[4, 5]);
+
+        let (first, second) = ring.as_mut_slices();
+        first[0] = 20;
+        second[1] = 50;
+        assert_eq!(ring.iter().copied().collect::<Vec<_>>(), [20, 3, 4, 50]);
+    }
+
+    #[test]
+    fn test_iter_reverse_and_len() {
+        let mut ring: RingBuffer<u32, 3> = RingBuffer::new();
+        for i in 0..5 {
+            ring.push(i);
+        }
+        let iter = ring.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(ring.iter().rev().copied().collect::<Vec<_>>(), [4, 3, 2]);
+    }
+
+    #[test]
+    fn test_pop_oldest() {
+        let mut ring: RingBuffer<u32, 2> = RingBuffer::new();
+        ring.push(1);
+        ring.push(2);
+        ring.push(3);
+        assert_eq!(ring.pop_oldest(), Some(2));
+        assert_eq!(ring.pop_oldest(), Some(3));
+        assert_eq!(ring.pop_oldest(), None);
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_drop_releases_items() {
+        use std::rc::Rc;
+        let item = Rc::new(());
+        {
+            let mut ring: RingBuffer<Rc<()>, 2> = RingBuffer::new();
+            ring.push(item.clone());
+            ring.push(item.clone());
+            ring.push(item.clone());
+            assert_eq!(Rc::strong_count(&item), 3);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+}
@@ -0,0 +1,155 @@
+//! Compile-time configuration tables.
+//!
+//! `config_table!` turns `key: type = value` pairs into a `static
+//! ConfigTable` sorted by key at compile time (duplicate keys fail the
+//! build). Lookups are a binary search over `&'static str` keys with typed
+//! getters, so firmware gets named configuration without parsing, allocation
+//! or `core::fmt` at runtime.
+
+use crate::const_str::compare;
+
+/// A configuration value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigValue {
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    F32(f32),
+    Bool(bool),
+    Str(&'static str),
+}
+
+/// Types retrievable with `ConfigTable::get`.
+pub trait ConfigType: Sized {
+    fn from_value(value: &ConfigValue) -> Option<Self>;
+}
+
+macro_rules! impl_config_type {
+    ($($variant:ident => $ty:ty),+) => {
+        $(impl ConfigType for $ty {
+            #[inline]
+            fn from_value(value: &ConfigValue) -> Option<Self> {
+                match value {
+                    ConfigValue::$variant(v) => Some(*v),
+                    _ => None,
+                }
+            }
+        })+
+    };
+}
+
+impl_config_type!(U32 => u32, I32 => i32, U64 => u64, F32 => f32, Bool => bool, Str => &'static str);
+
+/// `N` key/value pairs sorted by key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfigTable<const N: usize> {
+    entries: [(&'static str, ConfigValue); N],
+}
+
+impl<const N: usize> ConfigTable<N> {
+    /// Sorts `entries` by key; panics (at compile time in a `const`) on duplicates.
+    pub const fn new(mut entries: [(&'static str, ConfigValue); N]) -> Self {
+        let mut i = 1;
+        while i < N {
+            let mut j = i;
+            while j > 0 && compare(entries[j - 1].0, entries[j].0).is_gt() {
+                let tmp = entries[j - 1];
+                entries[j - 1] = entries[j];
+                entries[j] = tmp;
+                j -= 1;
+            }
+            i += 1;
+        }
+        let mut k = 1;
+        while k < N {
+            assert!(
+                !compare(entries[k - 1].0, entries[k].0).is_eq(),
+                "duplicate config key"
+            );
+            k += 1;
+        }
+        Self { entries }
+    }
+
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Entries in key order.
+    pub fn entries(&self) -> &[(&'static str, ConfigValue); N] {
+        &self.entries
+    }
+
+    #[inline]
+    pub fn value(&self, key: &str) -> Option<&ConfigValue> {
+        self.entries
+            .binary_search_by(|(k, _)| (*k).cmp(key))
+            .ok()
+            .map(|index| &self.entries[index].1)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.value(key).is_some()
+    }
+
+    /// Typed lookup; `None` if the key is missing or holds another type.
+    #[inline]
+    pub fn get<T: ConfigType>(&self, key: &str) -> Option<T> {
+        self.value(key).and_then(T::from_value)
+    }
+
+    /// Typed lookup with a fallback.
+    #[inline]
+    pub fn get_or<T: ConfigType>(&self, key: &str, default: T) -> T {
+        self.get(key).unwrap_or(default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::config_table! {
+        static BOARD {
+            "uart.baud": u32 = 115_200,
+            "adc.rate": u32 = 48_000,
+            "gain.default": f32 = 0.5,
+            "trim.offset": i32 = -12,
+            "serial": u64 = 0x1234_5678_9abc,
+            "name": str = "rev-b",
+            "debug": bool = false,
+        }
+    }
+
+    #[test]
+    fn test_sorted_at_compile_time() {
+        let keys: [&str; 7] = core::array::from_fn(|i| BOARD.entries()[i].0);
+        let mut sorted = keys;
+        sorted.sort();
+        assert_eq!(keys, sorted);
+        assert_eq!(BOARD.len(), 7);
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        assert_eq!(BOARD.get::<u32>("adc.rate"), Some(48_000));
+        assert_eq!(BOARD.get::<f32>("gain.default"), Some(0.5));
+        assert_eq!(BOARD.get::<i32>("trim.offset"), Some(-12));
+        assert_eq!(BOARD.get::<u64>("serial"), Some(0x1234_5678_9abc));
+        assert_eq!(BOARD.get::<&str>("name"), Some("rev-b"));
+        assert_eq!(BOARD.get::<bool>("debug"), Some(false));
+    }
+
+    #[test]
+    fn test_missing_and_mistyped() {
+        assert_eq!(BOARD.get::<u32>("adc"), None);
+        assert_eq!(BOARD.get::<f32>("adc.rate"), None);
+        assert_eq!(BOARD.get_or("missing", 7u32), 7);
+        assert!(BOARD.contains("uart.baud"));
+        assert_eq!(BOARD.value("debug"), Some(&ConfigValue::Bool(false)));
+    }
+}
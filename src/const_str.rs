@@ -0,0 +1,329 @@
+//! `const fn` string helpers for compile-time code and macro authors.
+//!
+//! Comparison, FNV-1a and xxHash64 hashing, ASCII case transforms and Rust
+//! path segments, all usable in `const` items and `const { .. }` blocks
+//! (where `str` methods like `==`, `to_uppercase` or `rsplit` are not
+//! available). Transforms that produce new text write into a byte array
+//! sized by the caller; `const_concat!`, `const_upper!` and `const_lower!`
+//! wrap them into `&'static str` constants.
+
+use core::cmp::Ordering;
+
+/// Byte-wise comparison, as `Ord for str`.
+pub const fn compare(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] < b[i] {
+            return Ordering::Less;
+        }
+        if a[i] > b[i] {
+            return Ordering::Greater;
+        }
+        i += 1;
+    }
+    if a.len() < b.len() {
+        Ordering::Less
+    } else if a.len() > b.len() {
+        Ordering::Greater
+    } else {
+        Ordering::Equal
+    }
+}
+
+pub const fn eq(a: &str, b: &str) -> bool {
+    compare(a, b).is_eq()
+}
+
+/// 32-bit FNV-1a.
+pub const fn fnv1a_32(s: &str) -> u32 {
+    let bytes = s.as_bytes();
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    hash
+}
+
+/// 64-bit FNV-1a.
+pub const fn fnv1a_64(s: &str) -> u64 {
+    let bytes = s.as_bytes();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+const P1: u64 = 0x9e37_79b1_85eb_ca87;
+const P2: u64 = 0xc2b2_ae3d_27d4_eb4f;
+const P3: u64 = 0x1656_67b1_9e37_79f9;
+const P4: u64 = 0x85eb_ca77_c2b2_ae63;
+const P5: u64 = 0x27d4_eb2f_1656_67c5;
+
+const fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    let mut value = 0;
+    let mut i = 8;
+    while i > 0 {
+        i -= 1;
+        value = value << 8 | bytes[at + i] as u64;
+    }
+    value
+}
+
+const fn read_u32(bytes: &[u8], at: usize) -> u64 {
+    (bytes[at] as u64)
+        | (bytes[at + 1] as u64) << 8
+        | (bytes[at + 2] as u64) << 16
+        | (bytes[at + 3] as u64) << 24
+}
+
+const fn round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(P2))
+        .rotate_left(31)
+        .wrapping_mul(P1)
+}
+
+const fn merge(acc: u64, value: u64) -> u64 {
+    (acc ^ round(0, value)).wrapping_mul(P1).wrapping_add(P4)
+}
+
+/// xxHash64 of `s` with `seed` (same output as the reference `XXH64`).
+pub const fn xxh64(s: &str, seed: u64) -> u64 {
+    let bytes = s.as_bytes();
+    let len = bytes.len();
+    let mut i = 0;
+    let mut hash = if len >= 32 {
+        let mut v = [
+            seed.wrapping_add(P1).wrapping_add(P2),
+            seed.wrapping_add(P2),
+            seed,
+            seed.wrapping_sub(P1),
+        ];
+        while i + 32 <= len {
+            let mut lane = 0;
+            while lane < 4 {
+                v[lane] = round(v[lane], read_u64(bytes, i + lane * 8));
+                lane += 1;
+            }
+            i += 32;
+        }
+        let mut hash = v[0]
+            .rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18));
+        let mut lane = 0;
+        while lane < 4 {
+            hash = merge(hash, v[lane]);
+            lane += 1;
+        }
+        hash
+    } else {
+        seed.wrapping_add(P5)
+    };
+    hash = hash.wrapping_add(len as u64);
+    while i + 8 <= len {
+        hash ^= round(0, read_u64(bytes, i));
+        hash = hash.rotate_left(27).wrapping_mul(P1).wrapping_add(P4);
+        i += 8;
+    }
+    if i + 4 <= len {
+        hash ^= read_u32(bytes, i).wrapping_mul(P1);
+        hash = hash.rotate_left(23).wrapping_mul(P2).wrapping_add(P3);
+        i += 4;
+    }
+    while i < len {
+        hash ^= (bytes[i] as u64).wrapping_mul(P5);
+        hash = hash.rotate_left(11).wrapping_mul(P1);
+        i += 1;
+    }
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(P2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(P3);
+    hash ^ hash >> 32
+}
+
+/// `s` with ASCII letters uppercased; `N` must be `s.len()`.
+pub const fn to_upper<const N: usize>(s: &str) -> [u8; N] {
+    let mut out = copy::<N>(s);
+    let mut i = 0;
+    while i < N {
+        out[i] = out[i].to_ascii_uppercase();
+        i += 1;
+    }
+    out
+}
+
+/// `s` with ASCII letters lowercased; `N` must be `s.len()`.
+pub const fn to_lower<const N: usize>(s: &str) -> [u8; N] {
+    let mut out = copy::<N>(s);
+    let mut i = 0;
+    while i < N {
+        out[i] = out[i].to_ascii_lowercase();
+        i += 1;
+    }
+    out
+}
+
+const fn copy<const N: usize>(s: &str) -> [u8; N] {
+    let bytes = s.as_bytes();
+    assert!(bytes.len() == N, "length does not match the output array");
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = bytes[i];
+        i += 1;
+    }
+    out
+}
+
+/// Sum of the lengths of `parts`.
+pub const fn total_len(parts: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        len += parts[i].len();
+        i += 1;
+    }
+    len
+}
+
+/// `parts` joined; `N` must be `total_len(parts)`.
+pub const fn concat<const N: usize>(parts: &[&str]) -> [u8; N] {
+    assert!(
+        total_len(parts) == N,
+        "length does not match the output array"
+    );
+    let mut out = [0; N];
+    let mut at = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let bytes = parts[i].as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            out[at] = bytes[j];
+            at += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Text of a transform result; its input was UTF-8 and ASCII case changes
+/// or concatenation keep it so.
+pub const fn from_utf8(bytes: &[u8]) -> &str {
+    match core::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => panic!("not UTF-8"),
+    }
+}
+
+/// Number of `::`-separated segments of a Rust path.
+pub const fn segment_count(path: &str) -> usize {
+    let bytes = path.as_bytes();
+    let mut count = 1;
+    let mut i = 0;
+    while i + 1 < bytes.len() {
+        if bytes[i] == b':' && bytes[i + 1] == b':' {
+            count += 1;
+            i += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Segment `index` of a `::`-separated path; empty if out of range.
+pub const fn segment(path: &str, index: usize) -> &str {
+    let bytes = path.as_bytes();
+    let mut current = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i <= bytes.len() {
+        let boundary =
+            i == bytes.len() || (i + 1 < bytes.len() && bytes[i] == b':' && bytes[i + 1] == b':');
+        if boundary {
+            if current == index {
+                let (head, _) = bytes.split_at(i);
+                let (_, segment) = head.split_at(start);
+                return from_utf8(segment);
+            }
+            current += 1;
+            start = i + 2;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    ""
+}
+
+/// First segment of a path: the crate in `module_path!()`.
+pub const fn first_segment(path: &str) -> &str {
+    segment(path, 0)
+}
+
+/// Last segment of a path: the item name.
+pub const fn last_segment(path: &str) -> &str {
+    segment(path, segment_count(path) - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert!(compare("a", "b").is_lt());
+        assert!(compare("ab", "a").is_gt());
+        assert!(compare("", "").is_eq());
+        const SAME: bool = eq("mix", "mix");
+        assert!(SAME && !eq("mix", "mux"));
+    }
+
+    #[test]
+    fn test_hashes() {
+        const EMPTY: u32 = fnv1a_32("");
+        assert_eq!(EMPTY, 0x811c_9dc5);
+        assert_eq!(fnv1a_32("a"), 0xe40c_292c);
+        assert_eq!(fnv1a_64("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(xxh64("", 0), 0xef46_db37_51d8_e999);
+        assert_eq!(xxh64("abc", 0), 0x44bc_2cf5_ad77_0999);
+        // Long enough for the four-lane loop and every tail step.
+        let long = "Nobody inspects the spammish repetition";
+        assert_eq!(xxh64(long, 0), 0xfbcea83c8a378bf1);
+        assert_ne!(xxh64(long, 1), xxh64(long, 0));
+    }
+
+    #[test]
+    fn test_case_and_concat() {
+        const UPPER: [u8; 9] = to_upper("dsp::Mix1");
+        assert_eq!(from_utf8(&UPPER), "DSP::MIX1");
+        assert_eq!(&to_lower::<4>("MiX!"), b"mix!");
+        const PARTS: &[&str] = &["base", "::", "mix"];
+        const JOINED: [u8; total_len(PARTS)] = concat(PARTS);
+        assert_eq!(from_utf8(&JOINED), "base::mix");
+    }
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(segment_count("base::dsp::mix"), 3);
+        assert_eq!(segment_count("mix"), 1);
+        assert_eq!(first_segment("base::dsp::mix"), "base");
+        assert_eq!(segment("base::dsp::mix", 1), "dsp");
+        assert_eq!(last_segment("base::dsp::mix"), "mix");
+        assert_eq!(last_segment("mix"), "mix");
+        assert_eq!(segment("base::dsp", 5), "");
+        const NAME: &str = last_segment(module_path!());
+        assert_eq!(NAME, "tests");
+    }
+}
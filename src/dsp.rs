@@ -0,0 +1,59 @@
+//! Allocation-free filter kernels.
+//!
+//! The block kernels are free, non-generic, panic-free functions marked with
+//! `mark_hot!`, so they appear in this crate's own LLVM IR and are verified
+//! by its test suite (feature `perf`): reference code that passes every
+//! default check. The filter types wrap them with state and a
+//! per-sample API.
+
+pub mod biquad;
+pub mod fir;
+
+pub use biquad::{Biquad, BiquadCoeffs, BiquadState, biquad_block};
+pub use fir::{Fir, fir_block};
+
+#[cfg(all(test, feature = "perf"))]
+mod tests {
+    use crate::perf::profile::{BuildProfile, ProfileMatrix, crate_ir};
+    use crate::perf::verify_hot_path::{
+        find_hot_entries_from_ir, find_hot_functions_from_ir, verify_hot_path_functions,
+    };
+    use std::path::Path;
+
+    #[test]
+    fn test_kernels_pass_default_checks() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let ir = crate_ir(manifest, "base", BuildProfile::Release).unwrap();
+        let hot = find_hot_functions_from_ir(&ir);
+        assert!(hot.contains("base::dsp::biquad::biquad_block"), "{:?}", hot);
+        assert!(hot.contains("base::dsp::fir::fir_block"), "{:?}", hot);
+        let entries = find_hot_entries_from_ir(&ir);
+        let fir = entries
+            .iter()
+            .find(|e| e.name.ends_with("fir_block"))
+            .unwrap();
+        assert_eq!(fir.file.as_deref(), Some("src/dsp/fir.rs"));
+        assert_eq!(fir.crate_name.as_deref(), Some("base"));
+        assert_eq!(
+            fir.crate_version.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        verify_hot_path_functions(&ir).unwrap();
+    }
+
+    #[test]
+    fn test_kernels_across_profiles() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let report = ProfileMatrix::default()
+            .with_build_profiles(
+                manifest,
+                "base",
+                &[BuildProfile::Debug, BuildProfile::Release],
+            )
+            .unwrap()
+            .run();
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.for_profile("release").count(), 0, "{}", report);
+        assert!(report.for_profile("debug").count() > 0);
+    }
+}
@@ -0,0 +1,248 @@
+//! Second-order IIR section (transposed direct form II).
+//!
+//! Coefficient design follows the RBJ audio EQ cookbook. Design runs once
+//! at setup; it uses a local polynomial `sin_cos` so it stays libm-free too.
+
+use crate::math::audio::db_to_linear;
+
+/// Normalized biquad coefficients (`a0 == 1`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BiquadCoeffs {
+    pub b0: f32,
+    pub b1: f32,
+    pub b2: f32,
+    pub a1: f32,
+    pub a2: f32,
+}
+
+/// `(sin x, cos x)` for `x` in `[0, pi]`, accurate to ~1e-6.
+fn sin_cos(x: f32) -> (f32, f32) {
+    use core::f32::consts::FRAC_PI_2;
+    // Evaluate around pi/2 so both series stay on [-pi/2, pi/2].
+    let t = x - FRAC_PI_2;
+    let t2 = t * t;
+    let sin_t = t
+        * (1.0
+            - t2 / 6.0
+                * (1.0 - t2 / 20.0 * (1.0 - t2 / 42.0 * (1.0 - t2 / 72.0 * (1.0 - t2 / 110.0)))));
+    let cos_t = 1.0
+        - t2 / 2.0 * (1.0 - t2 / 12.0 * (1.0 - t2 / 30.0 * (1.0 - t2 / 56.0 * (1.0 - t2 / 90.0))));
+    // sin(t + pi/2) = cos t, cos(t + pi/2) = -sin t
+    (cos_t, -sin_t)
+}
+
+impl BiquadCoeffs {
+    /// Pass-through.
+    pub const IDENTITY: BiquadCoeffs = BiquadCoeffs {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    fn normalize(b: [f32; 3], a: [f32; 3]) -> Self {
+        let inv = 1.0 / a[0];
+        Self {
+            b0: b[0] * inv,
+            b1: b[1] * inv,
+            b2: b[2] * inv,
+            a1: a[1] * inv,
+            a2: a[2] * inv,
+        }
+    }
+
+    /// `(cos w0, alpha)` for cutoff `freq` at `sample_rate`; `freq` is clamped below Nyquist.
+    fn prototype(sample_rate: f32, freq: f32, q: f32) -> (f32, f32) {
+        let nyquist = sample_rate * 0.5;
+        let freq = freq.clamp(0.0, nyquist * 0.999);
+        let w0 = core::f32::consts::TAU * freq / sample_rate;
+        let (sin, cos) = sin_cos(w0);
+        (cos, sin / (2.0 * q))
+    }
+
+    pub fn lowpass(sample_rate: f32, freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::prototype(sample_rate, freq, q);
+        let b1 = 1.0 - cos;
+        Self::normalize(
+            [b1 * 0.5, b1, b1 * 0.5],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub fn highpass(sample_rate: f32, freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::prototype(sample_rate, freq, q);
+        let b1 = 1.0 + cos;
+        Self::normalize(
+            [b1 * 0.5, -b1, b1 * 0.5],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Band-pass with 0 dB peak gain.
+    pub fn bandpass(sample_rate: f32, freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::prototype(sample_rate, freq, q);
+        Self::normalize([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    pub fn notch(sample_rate: f32, freq: f32, q: f32) -> Self {
+        let (cos, alpha) = Self::prototype(sample_rate, freq, q);
+        Self::normalize(
+            [1.0, -2.0 * cos, 1.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Peaking EQ boosting or cutting `gain_db` around `freq`.
+    pub fn peaking(sample_rate: f32, freq: f32, q: f32, gain_db: f32) -> Self {
+        let (cos, alpha) = Self::prototype(sample_rate, freq, q);
+        let a = db_to_linear(gain_db * 0.5);
+        Self::normalize(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        )
+    }
+}
+
+/// Filter memory of one biquad section.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BiquadState {
+    pub s1: f32,
+    pub s2: f32,
+}
+
+/// Filters `input` into `output` (up to the shorter length).
+pub fn biquad_block(
+    coeffs: &BiquadCoeffs,
+    state: &mut BiquadState,
+    input: &[f32],
+    output: &mut [f32],
+) {
+    crate::mark_hot!(biquad_block);
+    let BiquadCoeffs { b0, b1, b2, a1, a2 } = *coeffs;
+    let (mut s1, mut s2) = (state.s1, state.s2);
+    for (x, y) in input.iter().zip(output.iter_mut()) {
+        let out = b0 * x + s1;
+        s1 = b1 * x - a1 * out + s2;
+        s2 = b2 * x - a2 * out;
+        *y = out;
+    }
+    state.s1 = s1;
+    state.s2 = s2;
+}
+
+/// Biquad section with its coefficients and state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Biquad {
+    pub coeffs: BiquadCoeffs,
+    pub state: BiquadState,
+}
+
+impl Biquad {
+    pub const fn new(coeffs: BiquadCoeffs) -> Self {
+        Self {
+            coeffs,
+            state: BiquadState { s1: 0.0, s2: 0.0 },
+        }
+    }
+
+    #[inline]
+    pub fn process(&mut self, x: f32) -> f32 {
+        let c = &self.coeffs;
+        let y = c.b0 * x + self.state.s1;
+        self.state.s1 = c.b1 * x - c.a1 * y + self.state.s2;
+        self.state.s2 = c.b2 * x - c.a2 * y;
+        y
+    }
+
+    #[inline]
+    pub fn process_block(&mut self, input: &[f32], output: &mut [f32]) {
+        biquad_block(&self.coeffs, &mut self.state, input, output);
+    }
+
+    pub fn process_in_place(&mut self, buffer: &mut [f32]) {
+        for sample in buffer {
+            *sample = self.process(*sample);
+        }
+    }
+
+    /// Swaps coefficients, keeping state (no click for small changes).
+    pub fn set_coeffs(&mut self, coeffs: BiquadCoeffs) {
+        self.coeffs = coeffs;
+    }
+
+    pub fn reset(&mut self) {
+        self.state = BiquadState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    /// Steady-state gain at `freq` measured by running a sine through the filter.
+    fn measured_gain(coeffs: BiquadCoeffs, freq: f32) -> f32 {
+        let fs = 48_000.0;
+        let mut filter = Biquad::new(coeffs);
+        let mut peak = 0.0f32;
+        for n in 0..48_000 {
+            let x = (core::f32::consts::TAU * freq * n as f32 / fs).sin();
+            let y = filter.process(x);
+            if n > 24_000 {
+                peak = peak.max(y.abs());
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn test_sin_cos() {
+        for i in 0..=100 {
+            let x = i as f32 * core::f32::consts::PI / 100.0;
+            let (s, c) = sin_cos(x);
+            assert!((s - x.sin()).abs() < 2e-6, "sin {}", x);
+            assert!((c - x.cos()).abs() < 2e-6, "cos {}", x);
+        }
+    }
+
+    #[test]
+    fn test_lowpass_and_highpass() {
+        let lp = BiquadCoeffs::lowpass(48_000.0, 1_000.0, core::f32::consts::FRAC_1_SQRT_2);
+        assert!((measured_gain(lp, 100.0) - 1.0).abs() < 0.01);
+        assert!((measured_gain(lp, 1_000.0) - core::f32::consts::FRAC_1_SQRT_2).abs() < 0.01);
+        assert!(measured_gain(lp, 10_000.0) < 0.02);
+
+        let hp = BiquadCoeffs::highpass(48_000.0, 1_000.0, core::f32::consts::FRAC_1_SQRT_2);
+        assert!(measured_gain(hp, 100.0) < 0.02);
+        assert!((measured_gain(hp, 10_000.0) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_notch_peaking_bandpass() {
+        let notch = BiquadCoeffs::notch(48_000.0, 1_000.0, 2.0);
+        assert!(measured_gain(notch, 1_000.0) < 0.01);
+        let peak = BiquadCoeffs::peaking(48_000.0, 1_000.0, 1.0, 6.0);
+        assert!((measured_gain(peak, 1_000.0) - db_to_linear(6.0)).abs() < 0.02);
+        let band = BiquadCoeffs::bandpass(48_000.0, 1_000.0, 1.0);
+        assert!((measured_gain(band, 1_000.0) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_block_matches_per_sample() {
+        let coeffs = BiquadCoeffs::lowpass(48_000.0, 2_000.0, 0.9);
+        let input: [f32; 64] = core::array::from_fn(|i| if i % 7 == 0 { 1.0 } else { -0.25 });
+        let mut block = Biquad::new(coeffs);
+        let mut output = [0.0; 64];
+        block.process_block(&input, &mut output);
+        let mut sample = Biquad::new(coeffs);
+        let mut in_place = input;
+        sample.process_in_place(&mut in_place);
+        assert_eq!(output, in_place);
+        assert_eq!(block.state, sample.state);
+
+        let mut identity = Biquad::new(BiquadCoeffs::IDENTITY);
+        identity.process_block(&input, &mut output);
+        assert_eq!(output, input);
+    }
+}
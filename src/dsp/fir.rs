@@ -0,0 +1,128 @@
+//! Direct-form FIR filter over a doubled delay line.
+//!
+//! Each input sample is written twice, `N` apart, so the last `N` samples
+//! are always one contiguous window and the convolution is a plain dot
+//! product: no modulo, no wrap branch inside the tap loop.
+
+/// Filters `input` into `output` with `taps`.
+///
+/// `delay` must hold `2 * taps.len()` samples and `pos` index into its first
+/// half; both persist between calls. Mismatched sizes leave `output` untouched.
+pub fn fir_block(
+    taps: &[f32],
+    delay: &mut [f32],
+    pos: &mut usize,
+    input: &[f32],
+    output: &mut [f32],
+) {
+    crate::mark_hot!(fir_block);
+    let n = taps.len();
+    if n == 0 || delay.len() != 2 * n || *pos >= n {
+        return;
+    }
+    let mut p = *pos;
+    for (x, y) in input.iter().zip(output.iter_mut()) {
+        p = if p == 0 { n - 1 } else { p - 1 };
+        let Some((head, tail)) = delay.split_at_mut_checked(n) else {
+            return;
+        };
+        let (Some(a), Some(b)) = (head.get_mut(p), tail.get_mut(p)) else {
+            return;
+        };
+        *a = *x;
+        *b = *x;
+        let Some(window) = delay.get(p..p + n) else {
+            return;
+        };
+        let mut acc = 0.0;
+        for (t, d) in taps.iter().zip(window) {
+            acc += t * d;
+        }
+        *y = acc;
+    }
+    *pos = p;
+}
+
+/// FIR filter with `N` taps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fir<const N: usize> {
+    taps: [f32; N],
+    delay: [[f32; N]; 2],
+    pos: usize,
+}
+
+impl<const N: usize> Fir<N> {
+    pub const fn new(taps: [f32; N]) -> Self {
+        const { assert!(N > 0, "FIR needs at least one tap") };
+        Self {
+            taps,
+            delay: [[0.0; N]; 2],
+            pos: 0,
+        }
+    }
+
+    pub fn taps(&self) -> &[f32; N] {
+        &self.taps
+    }
+
+    #[inline]
+    pub fn process(&mut self, x: f32) -> f32 {
+        let mut y = 0.0;
+        self.process_block(&[x], core::slice::from_mut(&mut y));
+        y
+    }
+
+    #[inline]
+    pub fn process_block(&mut self, input: &[f32], output: &mut [f32]) {
+        fir_block(
+            &self.taps,
+            self.delay.as_flattened_mut(),
+            &mut self.pos,
+            input,
+            output,
+        );
+    }
+
+    pub fn reset(&mut self) {
+        self.delay = [[0.0; N]; 2];
+        self.pos = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impulse_response() {
+        let taps = [0.5, 0.25, -0.125, 1.0];
+        let mut fir = Fir::new(taps);
+        let mut impulse = [0.0f32; 6];
+        impulse[0] = 1.0;
+        let mut out = [0.0; 6];
+        fir.process_block(&impulse, &mut out);
+        assert_eq!(out, [0.5, 0.25, -0.125, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_moving_sum_across_blocks() {
+        let mut fir = Fir::new([1.0; 3]);
+        let mut out = [0.0; 2];
+        fir.process_block(&[1.0, 2.0], &mut out);
+        assert_eq!(out, [1.0, 3.0]);
+        fir.process_block(&[3.0, 4.0], &mut out);
+        assert_eq!(out, [6.0, 9.0]);
+        assert_eq!(fir.process(5.0), 12.0);
+        fir.reset();
+        assert_eq!(fir.process(5.0), 5.0);
+    }
+
+    #[test]
+    fn test_mismatched_delay_is_noop() {
+        let mut delay = [0.0; 3];
+        let mut pos = 0;
+        let mut out = [7.0; 2];
+        fir_block(&[1.0, 1.0], &mut delay, &mut pos, &[1.0, 1.0], &mut out);
+        assert_eq!(out, [7.0; 2]);
+    }
+}
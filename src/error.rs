@@ -0,0 +1,167 @@
+//! Compact, allocation-free error type for firmware and hot paths.
+//!
+//! An `Error` is a `u16` `ErrorCode`, a static message and an optional root
+//! cause. Everything is `Copy` and fits in a few words, so constructing an
+//! error in a hot function is a handful of stores. `context()` wraps an error
+//! in a higher-level one, keeping the innermost cause for diagnosis.
+//!
+//! `bail!` and `ensure!` return early with an `Error` built from a code and message.
+
+use core::fmt;
+
+/// Numeric error identifier, stable across builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct ErrorCode(pub u16);
+
+impl ErrorCode {
+    pub const fn new(code: u16) -> Self {
+        Self(code)
+    }
+
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{:04}", self.0)
+    }
+}
+
+/// Innermost error preserved through `context()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cause {
+    pub code: ErrorCode,
+    pub message: &'static str,
+}
+
+/// Error with code, message and optional root cause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Error {
+    code: ErrorCode,
+    message: &'static str,
+    cause: Option<Cause>,
+}
+
+impl Error {
+    pub const fn new(code: ErrorCode, message: &'static str) -> Self {
+        Self {
+            code,
+            message,
+            cause: None,
+        }
+    }
+
+    pub const fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub const fn message(&self) -> &'static str {
+        self.message
+    }
+
+    /// Innermost error this one was derived from, if any.
+    pub const fn cause(&self) -> Option<Cause> {
+        self.cause
+    }
+
+    /// Wraps `self` in a higher-level error, keeping the root cause.
+    #[must_use]
+    pub const fn context(self, code: ErrorCode, message: &'static str) -> Self {
+        let cause = match self.cause {
+            Some(cause) => cause,
+            None => Cause {
+                code: self.code,
+                message: self.message,
+            },
+        };
+        Self {
+            code,
+            message,
+            cause: Some(cause),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)?;
+        if let Some(cause) = self.cause {
+            write!(f, " (caused by {}: {})", cause.code, cause.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Adds context to the error arm of a `Result`.
+pub trait Context<T> {
+    fn context(self, code: ErrorCode, message: &'static str) -> Result<T, Error>;
+}
+
+impl<T> Context<T> for Result<T, Error> {
+    #[inline]
+    fn context(self, code: ErrorCode, message: &'static str) -> Result<T, Error> {
+        self.map_err(|e| e.context(code, message))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::string::ToString;
+
+    const SENSOR_TIMEOUT: ErrorCode = ErrorCode::new(7);
+    const CALIBRATION: ErrorCode = ErrorCode::new(42);
+    const STARTUP: ErrorCode = ErrorCode::new(100);
+
+    fn read_sensor(ok: bool) -> Result<u16, Error> {
+        crate::ensure!(ok, SENSOR_TIMEOUT, "sensor timed out");
+        Ok(512)
+    }
+
+    fn calibrate(ok: bool) -> Result<u16, Error> {
+        let raw = read_sensor(ok).context(CALIBRATION, "calibration failed")?;
+        if raw > 1000 {
+            crate::bail!(CALIBRATION, "reading out of range");
+        }
+        Ok(raw)
+    }
+
+    #[test]
+    fn test_display() {
+        let err = Error::new(SENSOR_TIMEOUT, "sensor timed out");
+        assert_eq!(err.to_string(), "E0007: sensor timed out");
+        let err = err.context(CALIBRATION, "calibration failed");
+        assert_eq!(
+            err.to_string(),
+            "E0042: calibration failed (caused by E0007: sensor timed out)"
+        );
+    }
+
+    #[test]
+    fn test_context_keeps_root_cause() {
+        let err = Error::new(SENSOR_TIMEOUT, "sensor timed out")
+            .context(CALIBRATION, "calibration failed")
+            .context(STARTUP, "startup failed");
+        assert_eq!(err.code(), STARTUP);
+        assert_eq!(err.cause().unwrap().code, SENSOR_TIMEOUT);
+    }
+
+    #[test]
+    fn test_macros() {
+        assert_eq!(calibrate(true), Ok(512));
+        let err = calibrate(false).unwrap_err();
+        assert_eq!(err.code(), CALIBRATION);
+        assert_eq!(err.cause().unwrap().message, "sensor timed out");
+    }
+
+    #[test]
+    fn test_error_is_small() {
+        assert!(core::mem::size_of::<Error>() <= 6 * core::mem::size_of::<usize>());
+    }
+}
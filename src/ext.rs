@@ -0,0 +1,136 @@
+//! Hot-path-friendly combinators for `Option` and `Result`.
+//!
+//! `unwrap_unchecked_dbg()` panics on misuse in debug builds and compiles to
+//! an optimizer hint in release, so the release IR carries no panic path.
+//! `inspect_err_cold()` moves error handling into a `#[cold]` out-of-line
+//! function, keeping the success path as the fallthrough.
+
+use crate::error::{Error, ErrorCode};
+
+#[cold]
+#[inline(never)]
+fn cold_path<E>(error: &E, f: impl FnOnce(&E)) {
+    f(error)
+}
+
+/// Extensions for `Option`.
+pub trait OptionExt<T> {
+    /// Unwraps, checking in debug builds only.
+    ///
+    /// # Safety
+    /// The value must be `Some`; release builds treat `None` as unreachable.
+    unsafe fn unwrap_unchecked_dbg(self) -> T;
+
+    /// Converts `None` into an `Error` with `code` and `message`.
+    fn ok_or_code(self, code: ErrorCode, message: &'static str) -> Result<T, Error>;
+}
+
+impl<T> OptionExt<T> for Option<T> {
+    #[inline(always)]
+    #[track_caller]
+    unsafe fn unwrap_unchecked_dbg(self) -> T {
+        if cfg!(debug_assertions) {
+            self.expect("unwrap_unchecked_dbg on None")
+        } else {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.unwrap_unchecked() }
+        }
+    }
+
+    #[inline(always)]
+    fn ok_or_code(self, code: ErrorCode, message: &'static str) -> Result<T, Error> {
+        match self {
+            Some(value) => Ok(value),
+            None => Err(Error::new(code, message)),
+        }
+    }
+}
+
+/// Extensions for `Result`.
+pub trait ResultExt<T, E> {
+    /// Unwraps, checking in debug builds only.
+    ///
+    /// # Safety
+    /// The value must be `Ok`; release builds treat `Err` as unreachable.
+    unsafe fn unwrap_unchecked_dbg(self) -> T;
+
+    /// Replaces any error with an `Error` carrying `code` and `message`.
+    fn ok_or_code(self, code: ErrorCode, message: &'static str) -> Result<T, Error>;
+
+    /// Calls `f` on the error in a `#[cold]`, never-inlined function.
+    fn inspect_err_cold(self, f: impl FnOnce(&E)) -> Self;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    #[inline(always)]
+    #[track_caller]
+    unsafe fn unwrap_unchecked_dbg(self) -> T {
+        if cfg!(debug_assertions) {
+            match self {
+                Ok(value) => value,
+                Err(_) => panic!("unwrap_unchecked_dbg on Err"),
+            }
+        } else {
+            // SAFETY: guaranteed by the caller.
+            unsafe { self.unwrap_unchecked() }
+        }
+    }
+
+    #[inline(always)]
+    fn ok_or_code(self, code: ErrorCode, message: &'static str) -> Result<T, Error> {
+        match self {
+            Ok(value) => Ok(value),
+            Err(_) => Err(Error::new(code, message)),
+        }
+    }
+
+    #[inline(always)]
+    fn inspect_err_cold(self, f: impl FnOnce(&E)) -> Self {
+        if let Err(error) = &self {
+            cold_path(error, f);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISSING: ErrorCode = ErrorCode::new(3);
+
+    #[test]
+    fn test_unwrap_unchecked_dbg() {
+        assert_eq!(unsafe { Some(5).unwrap_unchecked_dbg() }, 5);
+        assert_eq!(unsafe { Ok::<_, ()>(6).unwrap_unchecked_dbg() }, 6);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "unwrap_unchecked_dbg on None")]
+    fn test_unwrap_unchecked_dbg_checks_in_debug() {
+        let value: Option<u8> = None;
+        unsafe { value.unwrap_unchecked_dbg() };
+    }
+
+    #[test]
+    fn test_ok_or_code() {
+        assert_eq!(Some(1).ok_or_code(MISSING, "missing"), Ok(1));
+        let err = None::<u8>.ok_or_code(MISSING, "missing").unwrap_err();
+        assert_eq!(err.code(), MISSING);
+        let err = Err::<u8, &str>("io")
+            .ok_or_code(MISSING, "missing")
+            .unwrap_err();
+        assert_eq!(err.message(), "missing");
+    }
+
+    #[test]
+    fn test_inspect_err_cold() {
+        let mut seen = 0;
+        let ok: Result<u8, u8> = Ok(1);
+        assert_eq!(ok.inspect_err_cold(|e| seen += *e), Ok(1));
+        let err: Result<u8, u8> = Err(4);
+        assert_eq!(err.inspect_err_cold(|e| seen += *e), Err(4));
+        assert_eq!(seen, 4);
+    }
+}
@@ -0,0 +1,337 @@
+//! Allocation-free number formatting without `core::fmt`.
+//!
+//! Formatters write ASCII into caller-provided fixed-size buffers and return
+//! the written `&str`. Decimal conversion divides by ten with a multiply and
+//! shift instead of `udiv`, so formatting a `u32` stays clear of
+//! `DivisionCheck`; `u64` values above `u32::MAX` use a 128-bit multiply,
+//! which is a library call on 32-bit targets.
+//!
+//! `BufWriter` composes several pieces into one buffer, truncating on overflow.
+
+/// Maximum decimal length of a `u64`.
+pub const U64_MAX_LEN: usize = 20;
+/// Maximum decimal length of an `i64` including the sign.
+pub const I64_MAX_LEN: usize = 20;
+/// Hex digits of a `u64`.
+pub const HEX_U64_LEN: usize = 16;
+/// Maximum length of `f32_to_str` output: sign, 10 integer digits, point, 9 decimals.
+pub const F32_MAX_LEN: usize = 21;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[inline(always)]
+const fn div10_u32(value: u32) -> u32 {
+    ((value as u64 * 0xCCCC_CCCD) >> 35) as u32
+}
+
+#[inline(always)]
+const fn div10_u64(value: u64) -> u64 {
+    if value <= u32::MAX as u64 {
+        div10_u32(value as u32) as u64
+    } else {
+        ((value as u128 * 0xCCCC_CCCC_CCCC_CCCD) >> 67) as u64
+    }
+}
+
+/// Writes the decimal digits of `value` right-aligned into `buf`; returns the start index.
+pub const fn encode_u64(mut value: u64, buf: &mut [u8; U64_MAX_LEN]) -> usize {
+    let mut pos = U64_MAX_LEN;
+    loop {
+        let quotient = div10_u64(value);
+        pos -= 1;
+        buf[pos] = b'0' + (value - quotient * 10) as u8;
+        value = quotient;
+        if value == 0 {
+            return pos;
+        }
+    }
+}
+
+/// Writes `value` as lowercase hex right-aligned into `buf` with at least
+/// `min_digits` digits; returns the start index.
+pub const fn encode_hex(mut value: u64, min_digits: usize, buf: &mut [u8; HEX_U64_LEN]) -> usize {
+    let mut pos = HEX_U64_LEN;
+    loop {
+        pos -= 1;
+        buf[pos] = HEX_DIGITS[(value & 0xF) as usize];
+        value >>= 4;
+        if value == 0 && HEX_U64_LEN - pos >= min_digits {
+            return pos;
+        }
+        if pos == 0 {
+            return pos;
+        }
+    }
+}
+
+#[inline(always)]
+fn ascii(bytes: &[u8]) -> &str {
+    // SAFETY: callers pass encoder output (ASCII) or an already-validated UTF-8 prefix.
+    unsafe { core::str::from_utf8_unchecked(bytes) }
+}
+
+/// Formats `value` in decimal.
+pub fn u64_to_str(value: u64, buf: &mut [u8; U64_MAX_LEN]) -> &str {
+    let start = encode_u64(value, buf);
+    ascii(&buf[start..])
+}
+
+/// Formats `value` in decimal with a leading `-` for negatives.
+pub fn i64_to_str(value: i64, buf: &mut [u8; I64_MAX_LEN]) -> &str {
+    let mut digits = [0u8; U64_MAX_LEN];
+    let start = encode_u64(value.unsigned_abs(), &mut digits);
+    let len = U64_MAX_LEN - start;
+    let offset = if value < 0 {
+        buf[0] = b'-';
+        1
+    } else {
+        0
+    };
+    // i64::MIN has 19 digits, so sign plus digits always fit.
+    buf[offset..offset + len].copy_from_slice(&digits[start..]);
+    ascii(&buf[..offset + len])
+}
+
+/// Formats `value` as lowercase hex, zero-padded to `min_digits`.
+pub fn u64_to_hex(value: u64, min_digits: usize, buf: &mut [u8; HEX_U64_LEN]) -> &str {
+    let start = encode_hex(value, min_digits, buf);
+    ascii(&buf[start..])
+}
+
+/// Formats `value` with `decimals` (at most 9) fractional digits, rounding half up.
+///
+/// Magnitudes beyond `u32::MAX` saturate; NaN and infinities print as `nan`/`inf`.
+pub fn f32_to_str(value: f32, decimals: u8, buf: &mut [u8; F32_MAX_LEN]) -> &str {
+    let mut writer = BufWriter::new(buf);
+    writer.write_f32(value, decimals);
+    let len = writer.len();
+    ascii(&buf[..len])
+}
+
+const POW10: [u32; 10] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+];
+
+/// Cursor over a caller buffer that silently truncates once full.
+pub struct BufWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+    truncated: bool,
+}
+
+impl<'a> BufWriter<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self {
+            buf,
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// True if any write did not fit.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn as_str(&self) -> &str {
+        ascii_or_prefix(&self.buf[..self.len])
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        let room = self.buf.len() - self.len;
+        let take = if bytes.len() > room {
+            self.truncated = true;
+            room
+        } else {
+            bytes.len()
+        };
+        self.buf[self.len..self.len + take].copy_from_slice(&bytes[..take]);
+        self.len += take;
+        self
+    }
+
+    pub fn write_str(&mut self, s: &str) -> &mut Self {
+        self.write_bytes(s.as_bytes())
+    }
+
+    pub fn write_u64(&mut self, value: u64) -> &mut Self {
+        let mut digits = [0u8; U64_MAX_LEN];
+        let start = encode_u64(value, &mut digits);
+        self.write_bytes(&digits[start..])
+    }
+
+    pub fn write_i64(&mut self, value: i64) -> &mut Self {
+        if value < 0 {
+            self.write_bytes(b"-");
+        }
+        self.write_u64(value.unsigned_abs())
+    }
+
+    pub fn write_hex(&mut self, value: u64, min_digits: usize) -> &mut Self {
+        let mut digits = [0u8; HEX_U64_LEN];
+        let start = encode_hex(value, min_digits, &mut digits);
+        self.write_bytes(&digits[start..])
+    }
+
+    pub fn write_f32(&mut self, value: f32, decimals: u8) -> &mut Self {
+        if value.is_nan() {
+            return self.write_bytes(b"nan");
+        }
+        if value.is_sign_negative() && value != 0.0 {
+            self.write_bytes(b"-");
+        }
+        let magnitude = if value < 0.0 { -value } else { value };
+        if magnitude.is_infinite() {
+            return self.write_bytes(b"inf");
+        }
+
+        let decimals = if decimals > 9 { 9 } else { decimals as usize };
+        let scale = POW10[decimals];
+        let (mut integer, mut scaled) = if magnitude >= 4_294_967_296.0 {
+            (u32::MAX, 0)
+        } else {
+            let integer = magnitude as u32;
+            let fraction = magnitude - integer as f32;
+            (integer, (fraction * scale as f32 + 0.5) as u32)
+        };
+        if scaled >= scale {
+            scaled -= scale;
+            integer = integer.saturating_add(1);
+        }
+
+        self.write_u64(integer as u64);
+        if decimals > 0 {
+            self.write_bytes(b".");
+            let mut digits = [0u8; U64_MAX_LEN];
+            let start = encode_u64(scaled as u64, &mut digits);
+            for _ in (U64_MAX_LEN - start)..decimals {
+                self.write_bytes(b"0");
+            }
+            self.write_bytes(&digits[start..]);
+        }
+        self
+    }
+}
+
+/// `BufWriter` only ever receives ASCII from its own encoders, but `write_bytes`
+/// and `write_str` may cut a multi-byte character; keep the valid prefix.
+fn ascii_or_prefix(bytes: &[u8]) -> &str {
+    match core::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(e) => ascii(&bytes[..e.valid_up_to()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u64_to_str() {
+        let mut buf = [0; U64_MAX_LEN];
+        assert_eq!(u64_to_str(0, &mut buf), "0");
+        assert_eq!(u64_to_str(7, &mut buf), "7");
+        assert_eq!(u64_to_str(1234567890, &mut buf), "1234567890");
+        assert_eq!(u64_to_str(u32::MAX as u64 + 1, &mut buf), "4294967296");
+        assert_eq!(u64_to_str(u64::MAX, &mut buf), "18446744073709551615");
+    }
+
+    #[test]
+    fn test_div10_exhaustive_edges() {
+        for value in [0u32, 9, 10, 99, 100, 429496729, 4294967289, u32::MAX] {
+            assert_eq!(div10_u32(value), value / 10);
+        }
+        for value in [u32::MAX as u64 + 1, 10_000_000_000_000_000_000, u64::MAX] {
+            assert_eq!(div10_u64(value), value / 10);
+        }
+    }
+
+    #[test]
+    fn test_i64_to_str() {
+        let mut buf = [0; I64_MAX_LEN];
+        assert_eq!(i64_to_str(0, &mut buf), "0");
+        assert_eq!(i64_to_str(-42, &mut buf), "-42");
+        assert_eq!(i64_to_str(i64::MIN, &mut buf), "-9223372036854775808");
+        assert_eq!(i64_to_str(i64::MAX, &mut buf), "9223372036854775807");
+    }
+
+    #[test]
+    fn test_hex() {
+        let mut buf = [0; HEX_U64_LEN];
+        assert_eq!(u64_to_hex(0, 0, &mut buf), "0");
+        assert_eq!(u64_to_hex(0xBEEF, 0, &mut buf), "beef");
+        assert_eq!(u64_to_hex(0xF, 4, &mut buf), "000f");
+        assert_eq!(u64_to_hex(u64::MAX, 0, &mut buf), "ffffffffffffffff");
+        assert_eq!(u64_to_hex(1, 99, &mut buf), "0000000000000001");
+    }
+
+    #[test]
+    fn test_f32_to_str() {
+        let mut buf = [0; F32_MAX_LEN];
+        assert_eq!(f32_to_str(3.25, 2, &mut buf), "3.25");
+        assert_eq!(f32_to_str(-0.5, 1, &mut buf), "-0.5");
+        assert_eq!(f32_to_str(0.05, 3, &mut buf), "0.050");
+        assert_eq!(f32_to_str(9.999, 2, &mut buf), "10.00");
+        assert_eq!(f32_to_str(2.5, 0, &mut buf), "3");
+        assert_eq!(f32_to_str(0.0, 1, &mut buf), "0.0");
+        assert_eq!(f32_to_str(f32::NAN, 1, &mut buf), "nan");
+        assert_eq!(f32_to_str(f32::NEG_INFINITY, 1, &mut buf), "-inf");
+        assert_eq!(f32_to_str(1e12, 0, &mut buf), "4294967295");
+        assert_eq!(f32_to_str(-1e12, 2, &mut buf), "-4294967295.00");
+    }
+
+    #[test]
+    fn test_buf_writer() {
+        let mut buf = [0u8; 32];
+        let mut w = BufWriter::new(&mut buf);
+        w.write_str("t=")
+            .write_u64(12)
+            .write_str(" v=")
+            .write_i64(-3)
+            .write_str(" a=0x")
+            .write_hex(0x1f, 2);
+        assert_eq!(w.as_str(), "t=12 v=-3 a=0x1f");
+        assert!(!w.is_truncated());
+    }
+
+    #[test]
+    fn test_buf_writer_truncates() {
+        let mut buf = [0u8; 4];
+        let mut w = BufWriter::new(&mut buf);
+        w.write_str("abc").write_u64(12345);
+        assert_eq!(w.as_str(), "abc1");
+        assert!(w.is_truncated());
+
+        let mut buf = [0u8; 2];
+        let mut w = BufWriter::new(&mut buf);
+        w.write_str("aé");
+        assert_eq!(w.as_str(), "a");
+    }
+
+    #[test]
+    fn test_const_encoding() {
+        const DIGITS: ([u8; U64_MAX_LEN], usize) = {
+            let mut buf = [0u8; U64_MAX_LEN];
+            let start = encode_u64(2024, &mut buf);
+            (buf, start)
+        };
+        assert_eq!(&DIGITS.0[DIGITS.1..], b"2024");
+    }
+}
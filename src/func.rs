@@ -0,0 +1,174 @@
+//! Heap-free closure storage for callback-style APIs.
+//!
+//! `InlineFn<'a, Args, Ret, SIZE>` stores any `FnMut` whose captures fit in
+//! `SIZE` bytes (alignment up to 16) and calls it through a function pointer,
+//! the `no_std` replacement for `Box<dyn FnMut + 'a>`. `Args` is a tuple of
+//! the closure's parameters: `InlineFn<'_, (f32, f32), f32, 32>` holds a
+//! `FnMut(f32, f32) -> f32`. Oversized closures are rejected at compile time.
+//! `'a` bounds the closure's borrows, as for `Box<dyn FnMut + 'a>`.
+//!
+//! Verifier guidance: storage avoids the allocation check, but `call()` is
+//! still an indirect call the optimizer cannot inline, so `FunctionCallCheck`
+//! reports it inside a hot function. Keep `InlineFn` dispatch outside the
+//! hot loop (select once, then run a monomorphic kernel), or mark the callee
+//! itself with `mark_hot!` so its body is verified separately.
+
+use core::marker::PhantomData;
+use core::mem::{MaybeUninit, align_of, size_of};
+
+/// Calls a closure with its arguments packed in a tuple.
+pub trait FnArgs<Args, Ret> {
+    fn call_with(&mut self, args: Args) -> Ret;
+}
+
+macro_rules! impl_fn_args {
+    ($($arg:ident),*) => {
+        impl<F, Ret, $($arg),*> FnArgs<($($arg,)*), Ret> for F
+        where
+            F: FnMut($($arg),*) -> Ret,
+        {
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn call_with(&mut self, ($($arg,)*): ($($arg,)*)) -> Ret {
+                self($($arg),*)
+            }
+        }
+    };
+}
+
+impl_fn_args!();
+impl_fn_args!(A);
+impl_fn_args!(A, B);
+impl_fn_args!(A, B, C);
+impl_fn_args!(A, B, C, D);
+
+/// Maximum supported closure alignment.
+pub const MAX_ALIGN: usize = 16;
+
+#[repr(C, align(16))]
+struct Storage<const SIZE: usize>([MaybeUninit<u8>; SIZE]);
+
+/// `FnMut(Args) -> Ret + 'a` stored inline in `SIZE` bytes.
+///
+/// A closure cannot outlive what it borrows:
+///
+/// ```compile_fail
+/// use base::func::InlineFn;
+///
+/// fn make() -> InlineFn<'static, (), usize, 16> {
+///     let v = [1, 2, 3];
+///     let r = &v;
+///     InlineFn::new(move || r.len())
+/// }
+/// ```
+pub struct InlineFn<'a, Args, Ret, const SIZE: usize> {
+    storage: Storage<SIZE>,
+    call: unsafe fn(*mut u8, Args) -> Ret,
+    drop: unsafe fn(*mut u8),
+    /// The erased closure may be neither `Send` nor `Sync`, and may borrow
+    /// for `'a`.
+    _marker: PhantomData<(*mut (), &'a ())>,
+}
+
+unsafe fn call_erased<F: FnArgs<Args, Ret>, Args, Ret>(ptr: *mut u8, args: Args) -> Ret {
+    // SAFETY: `ptr` points to a live `F` written by `InlineFn::new`.
+    unsafe { (*(ptr as *mut F)).call_with(args) }
+}
+
+unsafe fn drop_erased<F>(ptr: *mut u8) {
+    // SAFETY: `ptr` points to a live `F` that is dropped exactly once.
+    unsafe { core::ptr::drop_in_place(ptr as *mut F) }
+}
+
+impl<'a, Args, Ret, const SIZE: usize> InlineFn<'a, Args, Ret, SIZE> {
+    /// Stores `f` inline. Fails to compile if `f` is larger than `SIZE` or over-aligned.
+    pub fn new<F: FnArgs<Args, Ret> + 'a>(f: F) -> Self {
+        const {
+            assert!(
+                size_of::<F>() <= SIZE,
+                "closure does not fit InlineFn storage"
+            );
+            assert!(align_of::<F>() <= MAX_ALIGN, "closure alignment exceeds 16");
+        };
+        let mut storage = Storage([const { MaybeUninit::uninit() }; SIZE]);
+        // SAFETY: size and alignment were checked above.
+        unsafe { (storage.0.as_mut_ptr() as *mut F).write(f) };
+        Self {
+            storage,
+            call: call_erased::<F, Args, Ret>,
+            drop: drop_erased::<F>,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Invokes the stored closure.
+    #[inline]
+    pub fn call(&mut self, args: Args) -> Ret {
+        // SAFETY: `call` matches the closure type stored in `storage`.
+        unsafe { (self.call)(self.storage.0.as_mut_ptr() as *mut u8, args) }
+    }
+}
+
+impl<Args, Ret, const SIZE: usize> Drop for InlineFn<'_, Args, Ret, SIZE> {
+    fn drop(&mut self) {
+        // SAFETY: the closure is live until here and dropped only once.
+        unsafe { (self.drop)(self.storage.0.as_mut_ptr() as *mut u8) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    #[test]
+    fn test_call_with_arguments() {
+        let gain = 2.0f32;
+        let mut f: InlineFn<(f32, f32), f32, 16> = InlineFn::new(move |a, b| (a + b) * gain);
+        assert_eq!(f.call((1.0, 2.0)), 6.0);
+    }
+
+    #[test]
+    fn test_no_arguments_and_state() {
+        let mut count = 0u32;
+        let mut f: InlineFn<(), u32, 16> = InlineFn::new(move || {
+            count += 1;
+            count
+        });
+        assert_eq!(f.call(()), 1);
+        assert_eq!(f.call(()), 2);
+    }
+
+    #[test]
+    fn test_borrowing_closure() {
+        let mut total = 0;
+        {
+            let mut f: InlineFn<(i32,), (), 8> = InlineFn::new(|x| total += x);
+            f.call((3,));
+            f.call((4,));
+        }
+        assert_eq!(total, 7);
+    }
+
+    #[test]
+    fn test_drop_runs_once() {
+        use std::rc::Rc;
+        let captured = Rc::new(());
+        {
+            let inner = captured.clone();
+            let mut f: InlineFn<(), usize, 16> = InlineFn::new(move || Rc::strong_count(&inner));
+            assert_eq!(f.call(()), 2);
+        }
+        assert_eq!(Rc::strong_count(&captured), 1);
+    }
+
+    #[test]
+    fn test_overaligned_capture() {
+        #[repr(align(16))]
+        #[derive(Clone, Copy)]
+        struct Aligned([f32; 4]);
+        let v = Aligned([1.0, 2.0, 3.0, 4.0]);
+        let mut f: InlineFn<(), f32, 16> = InlineFn::new(move || v.0.iter().sum());
+        assert_eq!(f.call(()), 10.0);
+    }
+}
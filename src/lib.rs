@@ -1,9 +1,28 @@
-#![cfg_attr(not(feature = "perf"), no_std)]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(base_portable_simd, feature(portable_simd))]
 
-pub mod macros;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-#[cfg(feature = "perf")]
+pub mod block;
+pub mod collections;
+pub mod config;
+pub mod const_str;
+pub mod dsp;
+pub mod error;
+pub mod ext;
+pub mod format;
+pub mod func;
+pub mod log;
+pub mod macros;
+pub mod math;
+pub mod mem;
 pub mod perf;
+pub mod simd;
+pub mod slice;
+pub mod sync;
+pub mod time;
+pub mod validate;
 
 #[doc(hidden)]
 pub use paste;
@@ -0,0 +1,307 @@
+//! Deferred, allocation-free logging for hot paths.
+//!
+//! Hot-path log macros never format. Each call site becomes a `static LogSite`
+//! (level, format string, location) whose address serves as the interned ID;
+//! the macro pushes that reference plus up to `MAX_ARGS` raw arguments into a
+//! lock-free SPSC ring. The non-RT side drains `LogRecord`s and renders them
+//! with `LogRecord::write_to()`, substituting `{}` placeholders in order.
+//!
+//! The hot side is a handful of plain stores and the ring's acquire/release
+//! index update, so the emitted IR passes the allocation, call and atomic checks.
+
+use core::fmt;
+
+use crate::perf::clock;
+use crate::sync::{Consumer, Producer, SpscQueue};
+
+/// Maximum number of arguments per log call.
+pub const MAX_ARGS: usize = 4;
+
+/// Log severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        })
+    }
+}
+
+/// Static description of one log call site.
+#[derive(Debug)]
+pub struct LogSite {
+    pub level: Level,
+    pub format: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+}
+
+/// Raw log argument captured without formatting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogArg {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    Bool(bool),
+    Str(&'static str),
+}
+
+macro_rules! impl_log_arg {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(impl From<$source> for LogArg {
+            #[inline(always)]
+            fn from(value: $source) -> Self {
+                LogArg::$variant(value as $target)
+            }
+        })+
+    };
+}
+
+impl_log_arg!(Unsigned, u64, u8, u16, u32, u64, usize);
+impl_log_arg!(Signed, i64, i8, i16, i32, i64, isize);
+impl_log_arg!(Float, f64, f32, f64);
+
+impl From<bool> for LogArg {
+    #[inline(always)]
+    fn from(value: bool) -> Self {
+        LogArg::Bool(value)
+    }
+}
+
+impl From<&'static str> for LogArg {
+    #[inline(always)]
+    fn from(value: &'static str) -> Self {
+        LogArg::Str(value)
+    }
+}
+
+impl fmt::Display for LogArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogArg::Unsigned(v) => write!(f, "{}", v),
+            LogArg::Signed(v) => write!(f, "{}", v),
+            LogArg::Float(v) => write!(f, "{}", v),
+            LogArg::Bool(v) => write!(f, "{}", v),
+            LogArg::Str(v) => f.write_str(v),
+        }
+    }
+}
+
+/// One captured log call.
+#[derive(Debug, Clone, Copy)]
+pub struct LogRecord {
+    pub site: &'static LogSite,
+    pub timestamp: u64,
+    args: [LogArg; MAX_ARGS],
+    len: u8,
+}
+
+impl LogRecord {
+    #[inline(always)]
+    pub fn new(site: &'static LogSite, timestamp: u64, args: &[LogArg]) -> Self {
+        let mut stored = [LogArg::Unsigned(0); MAX_ARGS];
+        let len = if args.len() < MAX_ARGS {
+            args.len()
+        } else {
+            MAX_ARGS
+        };
+        stored[..len].copy_from_slice(&args[..len]);
+        Self {
+            site,
+            timestamp,
+            args: stored,
+            len: len as u8,
+        }
+    }
+
+    pub fn args(&self) -> &[LogArg] {
+        &self.args[..self.len as usize]
+    }
+
+    /// Interned ID of the call site (its static address).
+    pub fn site_id(&self) -> usize {
+        self.site as *const LogSite as usize
+    }
+
+    /// Renders the message, replacing each `{}` with the next argument.
+    pub fn write_to(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        let mut args = self.args().iter();
+        let mut rest = self.site.format;
+        while let Some(pos) = rest.find(['{', '}']) {
+            out.write_str(&rest[..pos])?;
+            let tail = &rest[pos..];
+            if tail.starts_with("{{") || tail.starts_with("}}") {
+                out.write_str(&tail[..1])?;
+                rest = &tail[2..];
+            } else if let Some(after) = tail.strip_prefix("{}") {
+                match args.next() {
+                    Some(arg) => write!(out, "{}", arg)?,
+                    None => out.write_str("{?}")?,
+                }
+                rest = after;
+            } else {
+                out.write_str(&tail[..1])?;
+                rest = &tail[1..];
+            }
+        }
+        out.write_str(rest)
+    }
+}
+
+impl fmt::Display for LogRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] ", self.site.level)?;
+        self.write_to(f)
+    }
+}
+
+/// Ring buffer holding log records for one producer context.
+pub type LogRing<const N: usize> = SpscQueue<LogRecord, N>;
+
+/// Hot-side handle for emitting records into a `LogRing`.
+pub struct Logger<'a, const N: usize> {
+    producer: Producer<'a, LogRecord, N>,
+    min_level: Level,
+    dropped: u32,
+}
+
+impl<'a, const N: usize> Logger<'a, N> {
+    pub fn new(producer: Producer<'a, LogRecord, N>) -> Self {
+        Self {
+            producer,
+            min_level: Level::Debug,
+            dropped: 0,
+        }
+    }
+
+    /// Discards records below `level` at the call site.
+    pub fn set_min_level(&mut self, level: Level) {
+        self.min_level = level;
+    }
+
+    #[inline(always)]
+    pub fn enabled(&self, level: Level) -> bool {
+        level >= self.min_level
+    }
+
+    #[inline(always)]
+    pub fn emit(&mut self, site: &'static LogSite, args: &[LogArg]) {
+        if !self.enabled(site.level) {
+            return;
+        }
+        let record = LogRecord::new(site, clock::cycles(), args);
+        if self.producer.push(record).is_err() {
+            self.dropped = self.dropped.saturating_add(1);
+        }
+    }
+
+    /// Number of records lost because the ring was full.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+/// Non-RT handle for draining a `LogRing`.
+pub struct LogDrain<'a, const N: usize> {
+    consumer: Consumer<'a, LogRecord, N>,
+}
+
+impl<const N: usize> Iterator for LogDrain<'_, N> {
+    type Item = LogRecord;
+
+    fn next(&mut self) -> Option<LogRecord> {
+        self.consumer.pop()
+    }
+}
+
+/// Splits a ring into its hot-side logger and non-RT drain.
+pub fn split<const N: usize>(ring: &mut LogRing<N>) -> (Logger<'_, N>, LogDrain<'_, N>) {
+    let (producer, consumer) = ring.split();
+    (Logger::new(producer), LogDrain { consumer })
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::string::String;
+    use std::string::ToString;
+
+    static SITE: LogSite = LogSite {
+        level: Level::Warn,
+        format: "gain {} clipped at {} ({{raw}})",
+        file: "dsp.rs",
+        line: 7,
+    };
+
+    #[test]
+    fn test_render_record() {
+        let record = LogRecord::new(&SITE, 0, &[LogArg::from(3u8), LogArg::from(-1.5f32)]);
+        let mut out = String::new();
+        record.write_to(&mut out).unwrap();
+        assert_eq!(out, "gain 3 clipped at -1.5 ({raw})");
+        assert_eq!(record.to_string(), "[WARN] gain 3 clipped at -1.5 ({raw})");
+    }
+
+    #[test]
+    fn test_missing_and_extra_args() {
+        let record = LogRecord::new(&SITE, 0, &[LogArg::from(true)]);
+        assert_eq!(
+            record.to_string(),
+            "[WARN] gain true clipped at {?} ({raw})"
+        );
+
+        let args = [LogArg::from(1u32); 6];
+        let record = LogRecord::new(&SITE, 0, &args);
+        assert_eq!(record.args().len(), MAX_ARGS);
+    }
+
+    #[test]
+    fn test_site_id_is_stable() {
+        let a = LogRecord::new(&SITE, 0, &[]);
+        let b = LogRecord::new(&SITE, 1, &[]);
+        assert_eq!(a.site_id(), b.site_id());
+    }
+
+    #[test]
+    fn test_logger_level_filter_and_drops() {
+        let mut ring: LogRing<2> = LogRing::new();
+        let (mut logger, mut drain) = split(&mut ring);
+        logger.set_min_level(Level::Error);
+        logger.emit(&SITE, &[]);
+        assert!(drain.next().is_none());
+
+        logger.set_min_level(Level::Debug);
+        logger.emit(&SITE, &[]);
+        logger.emit(&SITE, &[]);
+        logger.emit(&SITE, &[]);
+        assert_eq!(logger.dropped(), 1);
+        assert_eq!(drain.count(), 2);
+    }
+
+    #[test]
+    fn test_log_macros() {
+        let mut ring: LogRing<4> = LogRing::new();
+        let (mut logger, mut drain) = split(&mut ring);
+        let voltage = 3300u32;
+        crate::log_info!(logger, "vbat {} mV", voltage);
+        crate::log_error!(logger, "sensor {} offline: {}", 2i8, "timeout");
+        let first = drain.next().unwrap();
+        assert_eq!(first.site.level, Level::Info);
+        assert_eq!(first.to_string(), "[INFO] vbat 3300 mV");
+        assert_eq!(
+            drain.next().unwrap().to_string(),
+            "[ERROR] sensor 2 offline: timeout"
+        );
+    }
+}
@@ -1,2 +1,24 @@
+pub mod aligned;
+pub mod bench;
+pub mod branch;
+pub mod checkpoint;
+pub mod config_table;
+pub mod const_str;
+pub mod const_verify;
+pub mod contract;
+pub mod error;
+#[cfg(feature = "std")]
+pub mod forbid_alloc;
 pub mod invoke;
+pub mod log;
+pub mod lookup_table;
 pub mod mark_hot;
+pub mod mark_hot_type;
+pub mod mem_region;
+pub mod metric;
+pub mod ram_static;
+#[cfg(feature = "perf")]
+pub mod register_check;
+pub mod retry;
+pub mod scoped_timeout;
+pub mod trace;
@@ -0,0 +1,13 @@
+/// Embeds a file like `include_bytes!`, aligned to `$align` (16, 32, 64 or 4096).
+///
+/// Evaluates to `&'static AlignedBytes<LEN, ALIGN>`; the path is relative to
+/// the invoking file.
+#[macro_export]
+macro_rules! include_bytes_aligned {
+    ($align:literal, $path:literal) => {{
+        const LEN: usize = include_bytes!($path).len();
+        static ALIGNED: $crate::mem::AlignedBytes<LEN, $align> =
+            $crate::mem::AlignedBytes::new(*include_bytes!($path));
+        &ALIGNED
+    }};
+}
@@ -0,0 +1,49 @@
+/// Benchmarks a closure with the cycle counter and returns a `perf::bench::BenchResult`.
+///
+/// `bench!(name, iterations, || ...)` reports the median of `DEFAULT_RUNS` runs.
+/// Adding `verify = ir` (feature `perf`) also verifies the hot function `name`
+/// in the given LLVM IR and returns `(BenchResult, Result<Vec<String>, String>)`.
+#[macro_export]
+macro_rules! bench {
+    ($name:ident, $iterations:expr, $f:expr, verify = $ir:expr) => {
+        (
+            $crate::bench!($name, $iterations, $f),
+            $crate::perf::HotPathVerifier::default().verify($ir, stringify!($name)),
+        )
+    };
+    ($name:ident, $iterations:expr, $f:expr) => {
+        $crate::perf::bench::run::<{ $crate::perf::bench::DEFAULT_RUNS }, _>(
+            stringify!($name),
+            $iterations,
+            $f,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_bench_macro() {
+        let result = bench!(add, 100, || core::hint::black_box(2) + 2);
+        assert_eq!(result.name, "add");
+        assert_eq!(result.iterations, 100);
+        assert_eq!(result.runs, crate::perf::bench::DEFAULT_RUNS);
+    }
+
+    #[cfg(feature = "perf")]
+    #[test]
+    fn test_bench_macro_with_verify() {
+        let ir = "define i32 @add(i32 %a, i32 %b) {  %1 = add i32 %a, %b  ret i32 %1\n}";
+        let (result, verification) = bench!(add, 10, || core::hint::black_box(2) + 2, verify = ir);
+        assert_eq!(result.name, "add");
+        assert_eq!(verification, Ok(Vec::new()));
+    }
+
+    #[cfg(feature = "perf")]
+    #[test]
+    fn test_bench_macro_reports_violation() {
+        let ir = "define i32 @alloc_heavy() {  %1 = call ptr @malloc(i64 16)  ret i32 0\n}";
+        let (_, verification) = bench!(alloc_heavy, 1, || 0, verify = ir);
+        assert!(verification.unwrap_err().contains("allocation"));
+    }
+}
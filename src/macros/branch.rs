@@ -0,0 +1,63 @@
+/// Marks the current code path as cold, so the compiler lays out the other
+/// side of the enclosing branch as the fallthrough and attaches branch weights.
+#[macro_export]
+macro_rules! cold_path {
+    () => {
+        ::core::hint::cold_path()
+    };
+}
+
+/// Evaluates a `bool` expected to be `false`; the `true` path is made cold.
+#[macro_export]
+macro_rules! unlikely {
+    ($cond:expr) => {{
+        let cond: bool = $cond;
+        if cond {
+            ::core::hint::cold_path();
+        }
+        cond
+    }};
+}
+
+/// Evaluates a `bool` expected to be `true`; the `false` path is made cold.
+#[macro_export]
+macro_rules! likely {
+    ($cond:expr) => {{
+        let cond: bool = $cond;
+        if !cond {
+            ::core::hint::cold_path();
+        }
+        cond
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_branch_hints_preserve_value() {
+        fn get(data: &[u32], i: usize) -> u32 {
+            if crate::unlikely!(i >= data.len()) {
+                return 0;
+            }
+            data[i]
+        }
+        assert_eq!(get(&[1, 2], 1), 2);
+        assert_eq!(get(&[1, 2], 5), 0);
+        assert!(crate::likely!(1 + 1 == 2));
+        assert!(!crate::likely!(false));
+    }
+
+    #[test]
+    fn test_cold_path_in_match() {
+        let classify = |x: i32| match x {
+            0 => 'z',
+            _ if x < 0 => {
+                crate::cold_path!();
+                'n'
+            }
+            _ => 'p',
+        };
+        assert_eq!(classify(-3), 'n');
+        assert_eq!(classify(4), 'p');
+    }
+}
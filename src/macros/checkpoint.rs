@@ -0,0 +1,7 @@
+/// Marks hot loop `id` as alive in a `perf::watchdog::Watchdog`.
+#[macro_export]
+macro_rules! checkpoint {
+    ($watchdog:expr, $id:expr) => {
+        $watchdog.checkpoint($id)
+    };
+}
@@ -0,0 +1,27 @@
+/// Defines a `static` `config::ConfigTable` from `"key": type = value` pairs.
+///
+/// Supported types: `u32`, `i32`, `u64`, `f32`, `bool`, `str`. Keys are
+/// sorted at compile time and duplicates are rejected.
+///
+/// ```ignore
+/// config_table! {
+///     pub static BOARD {
+///         "adc.rate": u32 = 48_000,
+///         "name": str = "rev-b",
+///     }
+/// }
+/// let rate: u32 = BOARD.get("adc.rate").unwrap();
+/// ```
+#[macro_export]
+macro_rules! config_table {
+    ($vis:vis static $name:ident { $($key:literal: $ty:ident = $value:expr),* $(,)? }) => {
+        $vis static $name: $crate::config::ConfigTable<{ [$($key),*].len() }> =
+            $crate::config::ConfigTable::new([$(($key, $crate::config_table!(@value $ty, $value))),*]);
+    };
+    (@value u32, $v:expr) => { $crate::config::ConfigValue::U32($v) };
+    (@value i32, $v:expr) => { $crate::config::ConfigValue::I32($v) };
+    (@value u64, $v:expr) => { $crate::config::ConfigValue::U64($v) };
+    (@value f32, $v:expr) => { $crate::config::ConfigValue::F32($v) };
+    (@value bool, $v:expr) => { $crate::config::ConfigValue::Bool($v) };
+    (@value str, $v:expr) => { $crate::config::ConfigValue::Str($v) };
+}
@@ -0,0 +1,48 @@
+/// Joins string constants (not only literals, unlike `concat!`) into a
+/// `&'static str` constant: `const_concat!(PREFIX, "::", NAME)`.
+#[macro_export]
+macro_rules! const_concat {
+    ($($part:expr),+ $(,)?) => {{
+        const PARTS: &[&str] = &[$($part),+];
+        const BYTES: [u8; $crate::const_str::total_len(PARTS)] = $crate::const_str::concat(PARTS);
+        const TEXT: &str = $crate::const_str::from_utf8(&BYTES);
+        TEXT
+    }};
+}
+
+/// ASCII-uppercased copy of a string constant, as a `&'static str`.
+#[macro_export]
+macro_rules! const_upper {
+    ($s:expr) => {{
+        const INPUT: &str = $s;
+        const BYTES: [u8; INPUT.len()] = $crate::const_str::to_upper(INPUT);
+        const TEXT: &str = $crate::const_str::from_utf8(&BYTES);
+        TEXT
+    }};
+}
+
+/// ASCII-lowercased copy of a string constant, as a `&'static str`.
+#[macro_export]
+macro_rules! const_lower {
+    ($s:expr) => {{
+        const INPUT: &str = $s;
+        const BYTES: [u8; INPUT.len()] = $crate::const_str::to_lower(INPUT);
+        const TEXT: &str = $crate::const_str::from_utf8(&BYTES);
+        TEXT
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    const CRATE: &str = crate::const_str::first_segment(module_path!());
+    const METRIC: &str = crate::const_concat!(CRATE, "::", "mix");
+
+    #[test]
+    fn test_const_str_macros() {
+        assert_eq!(METRIC, "base::mix");
+        assert_eq!(crate::const_upper!(METRIC), "BASE::MIX");
+        assert_eq!(crate::const_lower!("Voice_A"), "voice_a");
+        const EMPTY: &str = crate::const_upper!("");
+        assert_eq!(EMPTY, "");
+    }
+}
@@ -0,0 +1,64 @@
+/// Evaluates calls to hot functions at compile time.
+///
+/// `const_verify!(dsp::gain(2, 3) => 6, dsp::clip(300))` expands to a
+/// `const` item that runs every call during compilation, so the build fails
+/// unless each function is a `const fn` that finishes on those inputs. Const
+/// evaluation has no I/O, heap or FFI, so that rules them out for the whole
+/// call tree by construction, with no IR dump or verifier run involved. An
+/// `=> expected` result is compared with `==`, which const evaluation allows
+/// for primitives; use a `bool` expression for other types.
+///
+/// Place it next to the function, at module level or in a test: inside the
+/// function's own body the `const` would depend on itself.
+#[macro_export]
+macro_rules! const_verify {
+    ($($call:expr $(=> $expected:expr)?),+ $(,)?) => {
+        const _: () = {
+            $(
+                let _result = $call;
+                $(
+                    assert!(
+                        _result == $expected,
+                        concat!("const_verify!: ", stringify!($call), " != ", stringify!($expected))
+                    );
+                )?
+            )+
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    const fn saturate(x: i32, limit: i32) -> i32 {
+        if x > limit {
+            limit
+        } else if x < -limit {
+            -limit
+        } else {
+            x
+        }
+    }
+
+    const fn mix(a: [i16; 4], b: [i16; 4]) -> [i16; 4] {
+        let mut out = [0; 4];
+        let mut i = 0;
+        while i < 4 {
+            out[i] = a[i].saturating_add(b[i]);
+            i += 1;
+        }
+        out
+    }
+
+    const_verify!(
+        saturate(5, 3) => 3,
+        saturate(-5, 3) => -3,
+        saturate(1, 3),
+        mix([1, 2, 3, i16::MAX], [1, 1, 1, 1])[3] == i16::MAX => true,
+    );
+
+    #[test]
+    fn test_const_verify_in_function() {
+        const_verify!(saturate(0, 1) => 0);
+        assert_eq!(saturate(9, 4), 4);
+    }
+}
@@ -0,0 +1,86 @@
+/// Precondition: asserted in debug builds, an optimizer hint in release.
+///
+/// With `debug_assertions` a false `cond` panics through `violated`. Without
+/// them `cond` becomes `core::hint::assert_unchecked`, so a false contract
+/// is undefined behaviour; keep `cond` cheap and free of side effects, it is
+/// still evaluated. `ContractCheck` reports any contract panic left in hot
+/// IR, e.g. from a release profile with `debug-assertions = true`.
+#[macro_export]
+macro_rules! requires {
+    ($cond:expr $(,)?) => {
+        $crate::__contract!("precondition", $cond)
+    };
+}
+
+/// Postcondition on `value`, which is returned: `ensures!(x + 1, |r| *r > x)`.
+/// Lowered like `requires!`.
+#[macro_export]
+macro_rules! ensures {
+    ($value:expr, |$result:ident| $cond:expr $(,)?) => {{
+        let value = $value;
+        {
+            let $result = &value;
+            $crate::__contract!("postcondition", $cond);
+        }
+        value
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __contract {
+    ($kind:literal, $cond:expr) => {{
+        #[cfg(debug_assertions)]
+        if !$cond {
+            $crate::macros::contract::violated($kind, stringify!($cond));
+        }
+        #[cfg(not(debug_assertions))]
+        // SAFETY: the caller states `$cond` as a contract of this function;
+        // debug builds check it.
+        unsafe {
+            ::core::hint::assert_unchecked($cond)
+        }
+    }};
+}
+
+/// Panic path of a failed contract, kept out of line so the check itself
+/// stays a compare and a branch.
+#[doc(hidden)]
+#[cold]
+#[inline(never)]
+#[track_caller]
+pub fn violated(kind: &str, condition: &str) -> ! {
+    panic!("{} violated: {}", kind, condition)
+}
+
+#[cfg(test)]
+mod tests {
+    fn isqrt(x: u32) -> u32 {
+        crate::requires!(x < 1 << 30);
+        let mut r = 0u32;
+        while (r + 1) * (r + 1) <= x {
+            r += 1;
+        }
+        crate::ensures!(r, |r| r * r <= x)
+    }
+
+    #[test]
+    fn test_contracts_hold() {
+        assert_eq!(isqrt(17), 4);
+        assert_eq!(crate::ensures!(3 + 4, |sum| *sum == 7), 7);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "precondition violated: x < 1 << 30")]
+    fn test_requires_panics_in_debug() {
+        isqrt(u32::MAX);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "postcondition violated: *r % 2 == 0")]
+    fn test_ensures_panics_in_debug() {
+        crate::ensures!(3, |r| *r % 2 == 0);
+    }
+}
@@ -0,0 +1,17 @@
+/// Returns early with `Err(error::Error::new(code, message))`.
+#[macro_export]
+macro_rules! bail {
+    ($code:expr, $message:expr $(,)?) => {
+        return ::core::result::Result::Err($crate::error::Error::new($code, $message).into())
+    };
+}
+
+/// Returns early with an `error::Error` unless `cond` holds.
+#[macro_export]
+macro_rules! ensure {
+    ($cond:expr, $code:expr, $message:expr $(,)?) => {
+        if !$cond {
+            $crate::bail!($code, $message);
+        }
+    };
+}
@@ -0,0 +1,16 @@
+/// Evaluates a block with allocations forbidden on the current thread.
+///
+/// Requires `perf::GuardedAlloc` as the global allocator. The plain form panics
+/// when the block ends if it allocated; `count =>` returns `(value, AllocStats)`.
+#[macro_export]
+macro_rules! forbid_alloc {
+    (count => $body:block) => {
+        $crate::perf::alloc_guard::guard($crate::perf::alloc_guard::AllocMode::Count, || $body)
+    };
+    ($($body:tt)*) => {
+        $crate::perf::alloc_guard::guard($crate::perf::alloc_guard::AllocMode::Panic, || {
+            $($body)*
+        })
+        .0
+    };
+}
@@ -15,10 +15,55 @@ macro_rules! invoke {
     };
 }
 
+/// Like `invoke!`, but for fallible bodies: `?` inside the block returns
+/// from the closure, not from the enclosing function.
+///
+/// The body evaluates to the `Result` or `Option` itself. Give the return
+/// type after `->` when inference cannot pick the error type (`?` converts
+/// errors with `From`): `try_invoke!(-> Result<u8, Error> { .. })`,
+/// `try_invoke!(a, b -> Option<u8> { .. })`; `move` works as in `invoke!`.
+/// Parameters of the `->` forms are moved into locals the closure captures,
+/// so their types are known inside the body.
+#[macro_export]
+macro_rules! try_invoke {
+    (move -> $ret:ty { $($body:tt)* }) => {
+        (move || -> $ret { $($body)* })()
+    };
+    (move $($param:ident),+ -> $ret:ty { $($body:tt)* }) => {
+        {
+            $(let $param = $param;)+
+            (move || -> $ret { $($body)* })()
+        }
+    };
+    (move $($param:ident),+ => $body:expr) => {
+        $crate::invoke!(move $($param),+ => $body)
+    };
+    (move $body:expr) => {
+        $crate::invoke!(move $body)
+    };
+    (-> $ret:ty { $($body:tt)* }) => {
+        (|| -> $ret { $($body)* })()
+    };
+    ($($param:ident),+ -> $ret:ty { $($body:tt)* }) => {
+        {
+            $(let $param = $param;)+
+            (|| -> $ret { $($body)* })()
+        }
+    };
+    ($($param:ident),+ => $body:expr) => {
+        $crate::invoke!($($param),+ => $body)
+    };
+    ($body:expr) => {
+        $crate::invoke!($body)
+    };
+}
+
 #[cfg(test)]
+#[allow(clippy::redundant_closure_call)]
 mod tests {
     extern crate alloc;
     use alloc::string::String;
+    use core::num::ParseIntError;
 
     #[test]
     fn test_invoke_with_params() {
@@ -64,4 +109,40 @@ mod tests {
         let result = invoke!(a, b, c => { a + b + c });
         assert_eq!(result, 6);
     }
+
+    #[test]
+    fn test_try_invoke_result() {
+        fn parse_sum(a: &str, b: &str) -> u32 {
+            let sum = try_invoke!(-> Result<u32, ParseIntError> {
+                Ok(a.parse::<u32>()? + b.parse::<u32>()?)
+            });
+            sum.unwrap_or(0)
+        }
+        assert_eq!(parse_sum("2", "3"), 5);
+        assert_eq!(parse_sum("2", "x"), 0);
+    }
+
+    #[test]
+    fn test_try_invoke_option() {
+        let values = [1, 2, 3];
+        let first = try_invoke!({ Some(values.first()? + values.get(2)?) });
+        assert_eq!(first, Some(4));
+        let missing = try_invoke!({ Some(values.get(5)? + 1) });
+        assert_eq!(missing, None);
+    }
+
+    #[test]
+    fn test_try_invoke_params_and_move() {
+        let a = "4";
+        let b = "5";
+        let product = try_invoke!(a, b -> Result<u32, ParseIntError> {
+            Ok(a.parse::<u32>()? * b.parse::<u32>()?)
+        });
+        assert_eq!(product, Ok(20));
+        let s = String::from("12");
+        let parsed = try_invoke!(move -> Result<u32, ParseIntError> { s.parse() });
+        assert_eq!(parsed, Ok(12));
+        let v = Some(2);
+        assert_eq!(try_invoke!(move v => { Some(v? * 2) }), Some(4));
+    }
 }
@@ -0,0 +1,50 @@
+/// Emits a deferred log record at `level` through a `log::Logger`.
+///
+/// The format string is interned as a static `LogSite`; arguments are captured
+/// raw and formatted later by the drain. At most `log::MAX_ARGS` arguments.
+#[macro_export]
+macro_rules! rt_log {
+    ($logger:expr, $level:expr, $fmt:literal $(, $arg:expr)* $(,)?) => {{
+        static SITE: $crate::log::LogSite = $crate::log::LogSite {
+            level: $level,
+            format: $fmt,
+            file: file!(),
+            line: line!(),
+        };
+        const {
+            assert!(
+                [$(stringify!($arg)),*].len() <= $crate::log::MAX_ARGS,
+                "too many log arguments"
+            )
+        };
+        $logger.emit(&SITE, &[$($crate::log::LogArg::from($arg)),*])
+    }};
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($logger:expr, $($rest:tt)+) => {
+        $crate::rt_log!($logger, $crate::log::Level::Debug, $($rest)+)
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($logger:expr, $($rest:tt)+) => {
+        $crate::rt_log!($logger, $crate::log::Level::Info, $($rest)+)
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($logger:expr, $($rest:tt)+) => {
+        $crate::rt_log!($logger, $crate::log::Level::Warn, $($rest)+)
+    };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($logger:expr, $($rest:tt)+) => {
+        $crate::rt_log!($logger, $crate::log::Level::Error, $($rest)+)
+    };
+}
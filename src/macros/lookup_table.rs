@@ -0,0 +1,19 @@
+/// Builds a `math::LookupTable<$n>` in a `const` context from a `const fn`.
+///
+/// `lookup_table!(257, 0.0, 1.0, my_const_fn)` samples `my_const_fn` at 257
+/// evenly spaced points over `[0.0, 1.0]`.
+#[macro_export]
+macro_rules! lookup_table {
+    ($n:expr, $x0:expr, $x1:expr, $f:path) => {{
+        let x0: f32 = $x0;
+        let x1: f32 = $x1;
+        let step = (x1 - x0) / ($n - 1) as f32;
+        let mut table = [0.0f32; $n];
+        let mut i = 0;
+        while i < $n {
+            table[i] = $f(x0 + step * i as f32);
+            i += 1;
+        }
+        $crate::math::LookupTable::from_table(table, x0, x1)
+    }};
+}
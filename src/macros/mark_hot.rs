@@ -1,14 +1,108 @@
+/// Registers `$func` in the `.hot_funcs` section for hot-path verification.
+///
+/// The record also carries the marker's `file!()`/`line!()` and the crate
+/// name and version, which verification reports print next to violations.
+///
+/// `mark_hot!(f, allow(volatile_load, volatile_store))` additionally opts the
+/// function out of the named checks, e.g. for MMIO through `mem::Volatile`.
+/// A temporary exemption can carry an expiry,
+/// `allow(division, until = "2025-09-01")`: from the day after, the checks
+/// run again and the expired exemption itself is reported as an error.
+/// `mark_hot!(f, require(fma))` opts it into checks that only make sense for
+/// some functions (see `HotEntry::require`); both lists may be given, allow
+/// first. A trailing `tier = isr` adds the interrupt-handler profile's checks
+/// (`Profile::InterruptHandler`) for the function.
+///
+/// The record stores the function's `perf::hot_id` next to the string;
+/// `hot_id!(f)` gives the same ID elsewhere in the module, e.g. for
+/// `trace_enter!`.
+///
+/// Build release images with `base/strip-markers` to drop the records (and
+/// their strings) entirely; verification builds keep the default so the IR
+/// still carries them.
 #[macro_export]
 macro_rules! mark_hot {
-    ($func:ident) => {
-        $crate::paste::paste! {
-            #[used]
-            #[unsafe(link_section = ".hot_funcs")]
-            static [<HOT_FUNC_ $func:upper>]: &str = concat!(module_path!(), "::", stringify!($func), "\0");
+    ($func:ident $(, allow($($allow:tt)+))? $(, require($($required:ident),+ $(,)?))? $(, tier = $tier:ident)?) => {
+        $crate::__hot_marker! {
+            $crate::paste::paste! {
+                #[used]
+                #[unsafe(link_section = ".hot_funcs")]
+                static [<HOT_FUNC_ $func:upper>]: $crate::perf::HotRecord = $crate::perf::HotRecord {
+                    record: concat!(
+                        module_path!(), "::", stringify!($func),
+                        $(";allow=", $crate::__hot_allow!($($allow)+),)?
+                        $(";require=", $(stringify!($required), ",",)+)?
+                        $(";tier=", stringify!($tier),)?
+                        ";file=", file!(), ";line=", line!(),
+                        ";crate=", env!("CARGO_PKG_NAME"), ";version=", env!("CARGO_PKG_VERSION"), "\0"
+                    ),
+                    id: $crate::hot_id!($func),
+                };
+            }
         }
     };
 }
 
+/// `perf::hot_id` of `$func` in the current module, as stored by
+/// `mark_hot!($func)`.
+#[macro_export]
+macro_rules! hot_id {
+    ($func:ident) => {
+        $crate::perf::hot_id(concat!(module_path!(), "::", stringify!($func)))
+    };
+}
+
+/// `check,check,[;until=DATE]` record text for a `mark_hot!` allow list.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hot_allow {
+    (@list [$($list:tt)*] until = $until:literal $(,)?) => {
+        concat!($($list)* ";until=", $until)
+    };
+    (@list [$($list:tt)*] $check:ident $(, $($rest:tt)*)?) => {
+        $crate::__hot_allow!(@list [$($list)* stringify!($check), ",",] $($($rest)*)?)
+    };
+    (@list [$($list:tt)*]) => {
+        concat!($($list)*)
+    };
+    ($($allow:tt)+) => {
+        $crate::__hot_allow!(@list [] $($allow)+)
+    };
+}
+
+/// Emits the `mark_hot!` record; empty with the `strip-markers` feature.
+#[doc(hidden)]
+#[cfg(not(feature = "strip-markers"))]
+#[macro_export]
+macro_rules! __hot_marker {
+    ($($record:tt)*) => {
+        $($record)*
+    };
+}
+
+#[doc(hidden)]
+#[cfg(feature = "strip-markers")]
+#[macro_export]
+macro_rules! __hot_marker {
+    ($($record:tt)*) => {};
+}
+
+/// Twin functions, one marked, for the zero-overhead self-test
+/// (`perf::verify_hot_path::verify_zero_overhead`).
+#[doc(hidden)]
+pub mod probe {
+    #[inline(never)]
+    pub fn marked(x: u32, y: u32) -> u32 {
+        crate::mark_hot!(marked);
+        x.wrapping_mul(0x9e37_79b9).rotate_left(5) ^ y
+    }
+
+    #[inline(never)]
+    pub fn unmarked(x: u32, y: u32) -> u32 {
+        x.wrapping_mul(0x9e37_79b9).rotate_left(5) ^ y
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -29,6 +123,94 @@ mod tests {
         assert_eq!(add(2, 3), 5);
     }
 
+    #[test]
+    fn test_mark_hot_with_allow_list() {
+        fn poll(reg: &crate::mem::Volatile<u32>) -> u32 {
+            mark_hot!(poll, allow(volatile_load, volatile_store));
+            reg.read()
+        }
+        assert_eq!(poll(&crate::mem::Volatile::new(7)), 7);
+    }
+
+    #[test]
+    fn test_mark_hot_allow_until() {
+        assert_eq!(
+            crate::__hot_allow!(division, atomic, until = "2025-09-01"),
+            "division,atomic,;until=2025-09-01"
+        );
+        assert_eq!(crate::__hot_allow!(division,), "division,");
+        fn mix(a: u32, b: u32) -> u32 {
+            mark_hot!(mix, allow(division, until = "2025-09-01"), require(fma));
+            a / b.max(1)
+        }
+        assert_eq!(mix(6, 3), 2);
+    }
+
+    #[test]
+    fn test_mark_hot_with_requirements() {
+        fn dot(a: f32, b: f32, c: f32) -> f32 {
+            mark_hot!(dot, allow(division), require(fma));
+            a.mul_add(b, c)
+        }
+        fn axpy(a: f32, b: f32, c: f32) -> f32 {
+            mark_hot!(axpy, require(fma));
+            a.mul_add(b, c)
+        }
+        assert_eq!(dot(2.0, 3.0, 1.0), 7.0);
+        assert_eq!(axpy(1.0, 1.0, 1.0), 2.0);
+    }
+
+    #[test]
+    fn test_mark_hot_with_tier() {
+        fn on_tick(count: &mut u32) {
+            mark_hot!(on_tick, allow(volatile_store), tier = isr);
+            *count += 1;
+        }
+        let mut count = 0;
+        on_tick(&mut count);
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_mark_hot_extern_c_callback() {
+        #[unsafe(no_mangle)]
+        extern "C" fn base_test_hot_callback(value: u32) -> u32 {
+            mark_hot!(base_test_hot_callback);
+            value.wrapping_mul(3)
+        }
+        let callback: extern "C" fn(u32) -> u32 = base_test_hot_callback;
+        assert_eq!(callback(5), 15);
+    }
+
+    #[cfg(all(feature = "std", target_os = "linux"))]
+    #[test]
+    fn test_strip_markers_removes_records() {
+        fn strip_probe_fn() -> u32 {
+            mark_hot!(strip_probe_fn);
+            7
+        }
+        assert_eq!(strip_probe_fn(), 7);
+        let image = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        // Built at run time so the needle itself is not in the image.
+        let needle = format!("::{};file=", core::hint::black_box("strip_probe_fn"));
+        let needle = needle.as_bytes();
+        let present = image.windows(needle.len()).any(|w| w == needle);
+        assert_eq!(present, cfg!(not(feature = "strip-markers")));
+    }
+
+    #[test]
+    fn test_hot_id_matches_record_path() {
+        fn mix() {
+            mark_hot!(mix);
+        }
+        mix();
+        assert_eq!(
+            hot_id!(mix),
+            crate::perf::hot_id("base::macros::mark_hot::tests::mix")
+        );
+        assert_ne!(hot_id!(mix), hot_id!(mux));
+    }
+
     #[test]
     fn test_multiple_hot_functions() {
         fn func1() -> i32 {
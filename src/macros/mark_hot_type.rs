@@ -0,0 +1,51 @@
+/// Records the layout of a hot struct in the `hot_types` section for
+/// `perf::layout`.
+///
+/// `mark_hot_type!(Voice)` records size and alignment only.
+/// `mark_hot_type!(Voice { phase, freq, gain, name })` also records each
+/// field's offset and size; the list must name every field (a missing one
+/// is a compile error). Add `hot(phase, freq)` to mark the frequently
+/// accessed fields, otherwise all fields count as hot.
+#[macro_export]
+macro_rules! mark_hot_type {
+    ($ty:ident) => {
+        $crate::mark_hot_type!(@record $ty, &[]);
+    };
+    ($ty:ident { $($field:ident),+ $(,)? } $(, hot($($hot:ident),+ $(,)?))?) => {
+        const _: () = {
+            // Destructuring without `..` fails unless every field is listed;
+            // the hot list must name fields too.
+            #[allow(dead_code)]
+            fn complete(value: &$ty) {
+                let $ty { $($field: _),+ } = value;
+                $($(let _ = &value.$hot;)+)?
+            }
+        };
+        const _: () = {
+            const HOT: &[&str] = &[$($(stringify!($hot)),+)?];
+            $crate::mark_hot_type!(@record $ty, &[$(
+                $crate::perf::layout::FieldLayout {
+                    name: stringify!($field),
+                    offset: ::core::mem::offset_of!($ty, $field),
+                    size: $crate::perf::layout::field_size::<$ty, _>(|value| &value.$field),
+                    hot: $crate::perf::layout::is_hot(HOT, stringify!($field)),
+                }
+            ),+]);
+        };
+    };
+    (@record $ty:ident, $fields:expr) => {
+        const _: () = {
+            #[used]
+            #[cfg_attr(
+                any(target_os = "linux", target_os = "none"),
+                unsafe(link_section = "hot_types")
+            )]
+            static RECORD: $crate::perf::layout::TypeLayout = $crate::perf::layout::TypeLayout {
+                name: concat!(module_path!(), "::", stringify!($ty)),
+                size: ::core::mem::size_of::<$ty>(),
+                align: ::core::mem::align_of::<$ty>(),
+                fields: $fields,
+            };
+        };
+    };
+}
@@ -0,0 +1,95 @@
+/// Declares a byte buffer partitioned into named, typed, non-overlapping regions.
+///
+/// ```ignore
+/// mem_region! {
+///     pub struct Sram(8192, 64) {
+///         #[align(32)]
+///         dma_rx: [u8; 1024],
+///         samples: [f32; 256],
+///     }
+/// }
+/// ```
+///
+/// Generates `Sram` (backed by `AlignedBytes<8192, 64>`), `Sram::REGIONS`,
+/// `Sram::USED`, and `Sram::split(&mut self) -> SramParts<'_>` with one
+/// `&mut` field per region. Region types must implement `mem::ZeroInit`;
+/// `#[align(N)]` defaults to the type's alignment and may only raise it.
+#[macro_export]
+macro_rules! mem_region {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($size:expr, $align:literal) {
+            $($(#[align($field_align:literal)])? $field:ident: $ty:ty),+ $(,)?
+        }
+    ) => {
+        $crate::paste::paste! {
+            $(#[$meta])*
+            $vis struct $name {
+                storage: $crate::mem::AlignedBytes<{ $size }, $align>,
+            }
+
+            /// Mutable views of each region.
+            #[allow(dead_code)]
+            $vis struct [<$name Parts>]<'a> {
+                $(pub $field: &'a mut $ty,)+
+            }
+
+            #[allow(dead_code)]
+            impl $name {
+                pub const REGIONS: [$crate::mem::MemRegion; [$(stringify!($field)),+].len()] = {
+                    let regions = $crate::mem::region::layout([$((
+                        stringify!($field),
+                        ::core::mem::size_of::<$ty>(),
+                        $crate::mem_region!(@align $ty $(, $field_align)?),
+                    )),+]);
+                    assert!(
+                        $crate::mem::region::layout_end(&regions) <= $size,
+                        "regions exceed backing buffer"
+                    );
+                    assert!(
+                        $crate::mem::region::layout_align(&regions) <= $align,
+                        "region alignment exceeds buffer alignment"
+                    );
+                    regions
+                };
+
+                /// Bytes covered by regions, including alignment padding.
+                pub const USED: usize = $crate::mem::region::layout_end(&Self::REGIONS);
+
+                pub const fn new() -> Self {
+                    $($crate::mem::region::assert_zero_init::<$ty>();)+
+                    Self {
+                        storage: $crate::mem::AlignedBytes::zeroed(),
+                    }
+                }
+
+                pub fn split(&mut self) -> [<$name Parts>]<'_> {
+                    let base = self.storage.as_mut_ptr();
+                    let mut index = 0;
+                    $(
+                        let $field = {
+                            let offset = Self::REGIONS[index].offset;
+                            index += 1;
+                            // SAFETY: REGIONS are in bounds, aligned and disjoint
+                            // (checked at compile time); the type is ZeroInit and
+                            // the buffer starts zeroed.
+                            unsafe { &mut *(base.add(offset) as *mut $ty) }
+                        };
+                    )+
+                    let _ = index;
+                    [<$name Parts>] { $($field,)+ }
+                }
+            }
+        }
+    };
+    (@align $ty:ty) => {
+        ::core::mem::align_of::<$ty>()
+    };
+    (@align $ty:ty, $field_align:literal) => {{
+        assert!(
+            $field_align >= ::core::mem::align_of::<$ty>(),
+            "region alignment below the type's alignment"
+        );
+        $field_align
+    }};
+}
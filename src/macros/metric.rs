@@ -0,0 +1,38 @@
+/// Defines a static `perf::metrics::Metric` and registers it in the `hot_metrics` section.
+///
+/// `metric!(pub static RX_PACKETS: counter)` defines one cell;
+/// `metric!(static TICKS: counter[4])` defines one single-writer cell per
+/// core or instance. The registered name is `module_path!()::NAME`.
+#[macro_export]
+macro_rules! metric {
+    ($vis:vis static $name:ident: $kind:ident) => {
+        $vis static $name: $crate::perf::metrics::Metric = $crate::perf::metrics::Metric::new();
+        $crate::metric!(@entry $name, $kind, ::core::slice::from_ref(&$name));
+    };
+    ($vis:vis static $name:ident: $kind:ident[$cells:expr]) => {
+        $vis static $name: [$crate::perf::metrics::Metric; $cells] =
+            [const { $crate::perf::metrics::Metric::new() }; $cells];
+        $crate::metric!(@entry $name, $kind, &$name);
+    };
+    (@entry $name:ident, $kind:ident, $cells:expr) => {
+        $crate::paste::paste! {
+            #[used]
+            #[cfg_attr(
+                any(target_os = "linux", target_os = "none"),
+                unsafe(link_section = "hot_metrics")
+            )]
+            static [<METRIC_ENTRY_ $name>]: $crate::perf::metrics::MetricEntry =
+                $crate::perf::metrics::MetricEntry {
+                    name: concat!(module_path!(), "::", stringify!($name)),
+                    kind: $crate::metric!(@kind $kind),
+                    cells: $cells,
+                };
+        }
+    };
+    (@kind counter) => {
+        $crate::perf::metrics::MetricKind::Counter
+    };
+    (@kind gauge) => {
+        $crate::perf::metrics::MetricKind::Gauge
+    };
+}
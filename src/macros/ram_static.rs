@@ -0,0 +1,31 @@
+/// Declares a static and records its size against a RAM region's budget
+/// (see `mem::budget`).
+///
+/// ```ignore
+/// ram_static!("sram1", static RX: SpscQueue<u8, 1024> = SpscQueue::new(););
+/// ```
+///
+/// Attributes go on the static as usual, e.g. `#[unsafe(link_section = ".sram1")]`
+/// to also place it in the region.
+#[macro_export]
+macro_rules! ram_static {
+    ($region:literal, $(#[$meta:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr;) => {
+        $(#[$meta])*
+        $vis static $name: $ty = $init;
+
+        $crate::paste::paste! {
+            #[used]
+            #[cfg_attr(
+                any(target_os = "linux", target_os = "none"),
+                unsafe(link_section = "ram_statics")
+            )]
+            static [<RAM_STATIC_ $name>]: $crate::mem::budget::StaticRecord =
+                $crate::mem::budget::StaticRecord {
+                    region: $region,
+                    name: stringify!($name),
+                    type_name: stringify!($ty),
+                    bytes: ::core::mem::size_of::<$ty>(),
+                };
+        }
+    };
+}
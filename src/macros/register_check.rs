@@ -0,0 +1,26 @@
+/// Registers a `HotPathCheck` in the `hot_checks` section so
+/// `CheckRegistry::linked()` picks it up in any binary that links the crate.
+///
+/// `register_check!(MyCheck)` registers a unit struct;
+/// `register_check!(MyCheck = MyCheck::new(4))` builds it with an expression.
+#[macro_export]
+macro_rules! register_check {
+    ($check:ident) => {
+        $crate::register_check!($check = $check);
+    };
+    ($check:ident = $ctor:expr) => {
+        $crate::paste::paste! {
+            #[used]
+            #[cfg_attr(
+                any(target_os = "linux", target_os = "none"),
+                unsafe(link_section = "hot_checks")
+            )]
+            static [<HOT_CHECK_ $check:upper>]: $crate::perf::verify_hot_path::registry::CheckRegistration =
+                $crate::perf::verify_hot_path::registry::CheckRegistration {
+                    name: stringify!($check),
+                    crate_name: env!("CARGO_PKG_NAME"),
+                    factory: || $crate::perf::verify_hot_path::registry::boxed($ctor),
+                };
+        }
+    };
+}
@@ -0,0 +1,80 @@
+/// Calls a fallible closure until it returns `Ok`, at most `attempts` times
+/// (always at least once), and evaluates to the first `Ok` or the last
+/// `Err`: `retry!(3, || read_sensor())`.
+///
+/// An optional back-off hook runs between attempts with the number of the
+/// failed attempt (from 1) and its error, e.g. to wait or reset a bus:
+/// `retry!(3, || read_sensor(), |attempt, _err| delay(attempt * 10))`. It
+/// does not run after the last attempt.
+#[macro_export]
+macro_rules! retry {
+    ($attempts:expr, $op:expr $(,)?) => {
+        $crate::retry!($attempts, $op, |_: u32, _: &_| {})
+    };
+    ($attempts:expr, $op:expr, $backoff:expr $(,)?) => {{
+        let attempts: u32 = $attempts;
+        // `FnMut` closures need `mut`; plain `Fn` ones would warn.
+        #[allow(unused_mut)]
+        let mut op = $op;
+        #[allow(unused_mut)]
+        let mut backoff = $backoff;
+        let mut attempt: u32 = 1;
+        loop {
+            match op() {
+                ::core::result::Result::Ok(value) => break ::core::result::Result::Ok(value),
+                ::core::result::Result::Err(error) if attempt >= attempts => {
+                    break ::core::result::Result::Err(error);
+                }
+                ::core::result::Result::Err(error) => {
+                    backoff(attempt, &error);
+                    attempt += 1;
+                }
+            }
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    fn flaky(fails: u32, calls: &Cell<u32>) -> Result<u32, u32> {
+        calls.set(calls.get() + 1);
+        if calls.get() <= fails {
+            Err(calls.get())
+        } else {
+            Ok(42)
+        }
+    }
+
+    #[test]
+    fn test_retry_succeeds_within_attempts() {
+        let calls = Cell::new(0);
+        assert_eq!(crate::retry!(3, || flaky(2, &calls)), Ok(42));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_returns_last_error() {
+        let calls = Cell::new(0);
+        assert_eq!(crate::retry!(3, || flaky(5, &calls)), Err(3));
+        assert_eq!(calls.get(), 3);
+
+        let calls = Cell::new(0);
+        assert_eq!(crate::retry!(0, || flaky(5, &calls)), Err(1));
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let calls = Cell::new(0);
+        let mut waited = [0u32; 4];
+        let mut hooks = 0;
+        let result = crate::retry!(4, || flaky(10, &calls), |attempt, error: &u32| {
+            waited[hooks] = attempt * 10 + error;
+            hooks += 1;
+        });
+        assert_eq!(result, Err(4));
+        assert_eq!(hooks, 3);
+        assert_eq!(waited, [11, 22, 33, 0]);
+    }
+}
@@ -0,0 +1,84 @@
+/// Evaluates a block under a `time::Deadline` and yields
+/// `Result<T, time::Timeout>`.
+///
+/// The block names a `time::TimeoutScope` and marks its yield points with
+/// `scope.check()?`; the first check after the deadline ends the block with
+/// `Err(Timeout)`, otherwise the block's value is returned in `Ok`. Checks
+/// read the cycle counter unless a clock is given:
+/// `scoped_timeout!(deadline, || ticks(), |scope| { .. })`. Other `?`s in
+/// the block need errors convertible into `Timeout`.
+#[macro_export]
+macro_rules! scoped_timeout {
+    ($deadline:expr, |$scope:ident| $body:block) => {{
+        #[allow(unused_mut)]
+        let mut $scope = $crate::time::TimeoutScope::new($deadline);
+        // A block that only leaves through `check()?` never reaches `Ok`.
+        #[allow(unreachable_code)]
+        let result = (|| -> ::core::result::Result<_, $crate::time::Timeout> {
+            ::core::result::Result::Ok($body)
+        })();
+        result
+    }};
+    ($deadline:expr, $now:expr, |$scope:ident| $body:block) => {{
+        #[allow(unused_mut)]
+        let mut $scope = $crate::time::TimeoutScope::with_clock($deadline, $now);
+        // A block that only leaves through `check()?` never reaches `Ok`.
+        #[allow(unreachable_code)]
+        let result = (|| -> ::core::result::Result<_, $crate::time::Timeout> {
+            ::core::result::Result::Ok($body)
+        })();
+        result
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::time::{Deadline, Timeout};
+    use core::cell::Cell;
+
+    #[test]
+    fn test_scoped_timeout_completes() {
+        let now = Cell::new(0u64);
+        let result = crate::scoped_timeout!(Deadline::after(0, 100), || now.get(), |scope| {
+            let mut sum = 0;
+            for step in 1..=4 {
+                now.set(now.get() + 10);
+                scope.check()?;
+                sum += step;
+            }
+            sum
+        });
+        assert_eq!(result, Ok(10));
+    }
+
+    #[test]
+    fn test_scoped_timeout_expires_at_yield_point() {
+        let now = Cell::new(0u64);
+        let steps = Cell::new(0);
+        let result = crate::scoped_timeout!(Deadline::after(0, 25), || now.get(), |scope| {
+            loop {
+                now.set(now.get() + 10);
+                scope.check()?;
+                steps.set(steps.get() + 1);
+            }
+        });
+        let result: Result<(), Timeout> = result;
+        assert_eq!(
+            result,
+            Err(Timeout {
+                deadline: Deadline::at(25),
+                now: 30
+            })
+        );
+        assert_eq!(steps.get(), 2);
+    }
+
+    #[test]
+    fn test_scoped_timeout_cycle_counter() {
+        let result = crate::scoped_timeout!(Deadline::NEVER, |scope| {
+            scope.check()?;
+            scope.remaining() > 0
+        });
+        assert_eq!(result, Ok(true));
+    }
+}
@@ -0,0 +1,33 @@
+/// Records entry into trace region `id` through a `perf::trace::Tracer`.
+#[macro_export]
+macro_rules! trace_enter {
+    ($tracer:expr, $id:expr) => {
+        $tracer.enter($id)
+    };
+}
+
+/// Records exit from trace region `id` through a `perf::trace::Tracer`.
+#[macro_export]
+macro_rules! trace_exit {
+    ($tracer:expr, $id:expr) => {
+        $tracer.exit($id)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::perf::trace::{TraceKind, TraceRing, split};
+
+    #[test]
+    fn test_trace_region() {
+        let mut ring: TraceRing<4> = TraceRing::new();
+        let (mut tracer, mut drain) = split(&mut ring);
+        trace_enter!(tracer, 3);
+        trace_exit!(tracer, 3);
+        let enter = drain.next().unwrap();
+        let exit = drain.next().unwrap();
+        assert_eq!((enter.id, enter.kind), (3, TraceKind::Enter));
+        assert_eq!((exit.id, exit.kind), (3, TraceKind::Exit));
+        assert!(drain.next().is_none());
+    }
+}
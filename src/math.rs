@@ -0,0 +1,6 @@
+pub mod audio;
+pub mod lut;
+pub mod stats;
+
+pub use lut::LookupTable;
+pub use stats::{Ema, MovingAverage, Welford, WindowMax, WindowMin};
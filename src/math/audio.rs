@@ -0,0 +1,180 @@
+//! Decibel conversions and soft-clipping curves without libm.
+//!
+//! `exp2`/`log2` split the float into exponent bits and a reduced mantissa,
+//! then evaluate a short polynomial; everything else is built on those two.
+//! Accuracy is a few ULP of `f32` over the audio range (about 1e-5 dB), and
+//! nothing calls `powf`/`log10f`, which `LibmCheck` flags in hot functions.
+
+/// Level reported for silence (and anything quieter).
+pub const MIN_DB: f32 = -144.0;
+
+const LOG2_10_OVER_20: f32 = 0.166_096_4;
+const DB_PER_LOG2: f32 = 6.020_6;
+
+/// `2^x`. Inputs below -126 flush to 0; above 127 saturate to `f32::MAX`.
+#[inline]
+pub fn exp2(x: f32) -> f32 {
+    if x < -126.0 {
+        return 0.0;
+    }
+    if x >= 128.0 {
+        return f32::MAX;
+    }
+    // Round to nearest so the polynomial only covers [-0.5, 0.5].
+    let shifted = x + 0.5;
+    let mut whole = shifted as i32;
+    if (whole as f32) > shifted {
+        whole -= 1;
+    }
+    let f = x - whole as f32;
+    let poly = 1.0
+        + f * (core::f32::consts::LN_2
+            + f * (0.240_226_5
+                + f * (0.055_504_11 + f * (0.009_618_13 + f * (0.001_333_36 + f * 0.000_154_04)))));
+    if whole >= 128 {
+        return f32::MAX;
+    }
+    poly * f32::from_bits(((whole + 127) as u32) << 23)
+}
+
+/// `log2(x)` for positive normal `x`; returns `f32::NEG_INFINITY` for `x <= 0`.
+#[inline]
+pub fn log2(x: f32) -> f32 {
+    if x <= 0.0 || x.is_nan() {
+        return f32::NEG_INFINITY;
+    }
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 23) & 0xFF) as i32 - 127;
+    let mut mantissa = f32::from_bits((bits & 0x007F_FFFF) | 0x3F80_0000);
+    // Center the mantissa on 1 so the series converges fast.
+    if mantissa > core::f32::consts::SQRT_2 {
+        mantissa *= 0.5;
+        exponent += 1;
+    }
+    let y = (mantissa - 1.0) / (mantissa + 1.0);
+    let y2 = y * y;
+    let series = y * (2.0 + y2 * (0.666_666_7 + y2 * (0.4 + y2 * 0.285_714_3)));
+    exponent as f32 + series * core::f32::consts::LOG2_E
+}
+
+/// Gain in dB to linear amplitude.
+#[inline]
+pub fn db_to_linear(db: f32) -> f32 {
+    if db <= MIN_DB {
+        0.0
+    } else {
+        exp2(db * LOG2_10_OVER_20)
+    }
+}
+
+/// Linear amplitude to dB, clamped at `MIN_DB`. Sign is ignored.
+#[inline]
+pub fn linear_to_db(linear: f32) -> f32 {
+    let magnitude = if linear < 0.0 { -linear } else { linear };
+    let db = DB_PER_LOG2 * log2(magnitude);
+    if db > MIN_DB { db } else { MIN_DB }
+}
+
+/// Level of `amplitude` relative to `full_scale` in dBFS.
+#[inline]
+pub fn dbfs(amplitude: f32, full_scale: f32) -> f32 {
+    linear_to_db(amplitude / full_scale)
+}
+
+/// Level of a 16-bit sample in dBFS (full scale is 32768).
+#[inline]
+pub fn dbfs_i16(sample: i16) -> f32 {
+    linear_to_db(sample as f32 * const { 1.0 / 32768.0 })
+}
+
+/// Clamps to `[-1, 1]`.
+#[inline(always)]
+pub fn hard_clip(x: f32) -> f32 {
+    x.clamp(-1.0, 1.0)
+}
+
+/// Cubic soft clip `1.5x - 0.5x^3`, flat at ±1 beyond `|x| = 1`.
+#[inline(always)]
+pub fn soft_clip_cubic(x: f32) -> f32 {
+    let x = hard_clip(x);
+    x * (1.5 - 0.5 * x * x)
+}
+
+/// Rational `tanh` approximation, exact at 0 and saturating to ±1 beyond `|x| = 3`.
+#[inline(always)]
+pub fn tanh_approx(x: f32) -> f32 {
+    if x > 3.0 {
+        1.0
+    } else if x < -3.0 {
+        -1.0
+    } else {
+        let x2 = x * x;
+        x * (27.0 + x2) / (27.0 + 9.0 * x2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    #[test]
+    fn test_exp2() {
+        for x in [-20.0f32, -3.7, -1.0, -0.5, 0.0, 0.3, 0.5, 1.0, 7.25, 30.0] {
+            let expected = 2.0f32.powf(x);
+            assert!(
+                ((exp2(x) - expected) / expected).abs() < 2e-6,
+                "exp2({})",
+                x
+            );
+        }
+        assert_eq!(exp2(0.0), 1.0);
+        assert_eq!(exp2(10.0), 1024.0);
+        assert_eq!(exp2(-200.0), 0.0);
+        assert_eq!(exp2(500.0), f32::MAX);
+    }
+
+    #[test]
+    fn test_log2() {
+        for x in [1e-6f32, 0.001, 0.5, 0.7, 1.0, 1.5, 1.999, 3.0, 1e6] {
+            assert!((log2(x) - x.log2()).abs() < 2e-6, "log2({})", x);
+        }
+        assert_eq!(log2(8.0), 3.0);
+        assert_eq!(log2(0.0), f32::NEG_INFINITY);
+        assert_eq!(log2(-1.0), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_db_round_trip() {
+        for db in [-120.0f32, -60.0, -6.0, 0.0, 3.0, 12.0] {
+            let linear = db_to_linear(db);
+            assert!(((linear - 10f32.powf(db / 20.0)) / linear).abs() < 1e-5);
+            assert!((linear_to_db(linear) - db).abs() < 1e-3, "{} dB", db);
+        }
+        assert!((db_to_linear(-6.0206) - 0.5).abs() < 1e-6);
+        assert_eq!(db_to_linear(-200.0), 0.0);
+        assert_eq!(linear_to_db(0.0), MIN_DB);
+    }
+
+    #[test]
+    fn test_dbfs() {
+        assert!(dbfs(1.0, 1.0).abs() < 1e-6);
+        assert!((dbfs(0.5, 1.0) + 6.0206).abs() < 1e-3);
+        assert!((dbfs_i16(i16::MIN) - 0.0).abs() < 1e-6);
+        assert!((dbfs_i16(16384) + 6.0206).abs() < 1e-3);
+        assert_eq!(dbfs_i16(0), MIN_DB);
+    }
+
+    #[test]
+    fn test_clipping_curves() {
+        assert_eq!(hard_clip(1.5), 1.0);
+        assert_eq!(soft_clip_cubic(0.0), 0.0);
+        assert_eq!(soft_clip_cubic(1.0), 1.0);
+        assert_eq!(soft_clip_cubic(-4.0), -1.0);
+        assert!(soft_clip_cubic(0.5) > 0.5);
+        for x in [-2.5f32, -1.0, -0.1, 0.0, 0.4, 1.3, 2.9] {
+            assert!((tanh_approx(x) - x.tanh()).abs() < 0.03, "tanh({})", x);
+        }
+        assert_eq!(tanh_approx(10.0), 1.0);
+    }
+}
@@ -0,0 +1,174 @@
+//! Interpolated lookup tables to replace libm calls on hot paths.
+//!
+//! `LookupTable<N>` samples a function at `N` evenly spaced points over
+//! `[x0, x1]`. Lookups scale by a precomputed reciprocal step, so there is no
+//! `fdiv`, and inputs outside the range clamp to the end points. Tables whose
+//! `N - 1` segments are a power of two also accept a 32-bit phase
+//! (`linear_phase`), indexed with a shift; that is the usual wavetable and
+//! oscillator layout.
+//!
+//! Build tables at compile time with `lookup_table!` and a `const fn`, or at
+//! startup with `LookupTable::from_fn`.
+
+use crate::validate::{const_validate_pow2, const_validate_range};
+
+/// `N` samples of a function over `[x0, x1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookupTable<const N: usize> {
+    table: [f32; N],
+    x0: f32,
+    x1: f32,
+    inv_step: f32,
+}
+
+impl<const N: usize> LookupTable<N> {
+    /// Wraps precomputed samples; `table[i]` is `f(x0 + i * (x1 - x0) / (N - 1))`.
+    pub const fn from_table(table: [f32; N], x0: f32, x1: f32) -> Self {
+        const { assert!(N >= 2, "lookup table needs at least two samples") };
+        Self {
+            table,
+            x0,
+            x1,
+            inv_step: (N - 1) as f32 / (x1 - x0),
+        }
+    }
+
+    /// Samples `f` at `N` points over `[x0, x1]`.
+    pub fn from_fn(x0: f32, x1: f32, f: impl Fn(f32) -> f32) -> Self {
+        let step = (x1 - x0) / (N - 1) as f32;
+        let mut table = [0.0; N];
+        for (i, sample) in table.iter_mut().enumerate() {
+            *sample = f(x0 + step * i as f32);
+        }
+        Self::from_table(table, x0, x1)
+    }
+
+    pub fn table(&self) -> &[f32; N] {
+        &self.table
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.x0, self.x1)
+    }
+
+    /// Segment index and fraction for `x`, clamped to the table.
+    #[inline(always)]
+    fn locate(&self, x: f32) -> (usize, f32) {
+        let pos = (x - self.x0) * self.inv_step;
+        let max = (N - 1) as f32;
+        // NaN and negatives fall through to 0 (`as` saturates).
+        let pos = if pos > max { max } else { pos };
+        let index = pos as usize;
+        let index = if index >= N - 1 { N - 2 } else { index };
+        let frac = pos - index as f32;
+        (index, if frac > 0.0 { frac } else { 0.0 })
+    }
+
+    /// Linear interpolation.
+    #[inline]
+    pub fn linear(&self, x: f32) -> f32 {
+        let (i, t) = self.locate(x);
+        let a = self.table[i];
+        let b = self.table[i + 1];
+        a + (b - a) * t
+    }
+
+    /// Catmull-Rom cubic interpolation, extrapolating linearly past the end samples.
+    #[inline]
+    pub fn cubic(&self, x: f32) -> f32 {
+        let (i, t) = self.locate(x);
+        let p1 = self.table[i];
+        let p2 = self.table[i + 1];
+        let p0 = if i == 0 {
+            2.0 * p1 - p2
+        } else {
+            self.table[i - 1]
+        };
+        let p3 = if i + 2 < N {
+            self.table[i + 2]
+        } else {
+            2.0 * p2 - p1
+        };
+        let a = -0.5 * p0 + 1.5 * p1 - 1.5 * p2 + 0.5 * p3;
+        let b = p0 - 2.5 * p1 + 2.0 * p2 - 0.5 * p3;
+        let c = -0.5 * p0 + 0.5 * p2;
+        ((a * t + b) * t + c) * t + p1
+    }
+
+    /// Linear interpolation at `phase / 2^32` of the range, using shifts only.
+    ///
+    /// Requires `N - 1` to be a power of two.
+    #[inline]
+    pub fn linear_phase(&self, phase: u32) -> f32 {
+        const {
+            const_validate_pow2(N - 1, "linear_phase needs 2^k + 1 samples, k <= 16");
+            const_validate_range(
+                N - 1,
+                1,
+                1 << 16,
+                "linear_phase needs 2^k + 1 samples, k <= 16",
+            );
+        };
+        let bits = (N - 1).trailing_zeros();
+        let index = (phase >> (32 - bits)) as usize;
+        let frac = ((phase << bits) >> 8) as f32 * const { 1.0 / (1u32 << 24) as f32 };
+        let a = self.table[index];
+        let b = self.table[index + 1];
+        a + (b - a) * frac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    const fn square(x: f32) -> f32 {
+        x * x
+    }
+
+    const SQUARES: LookupTable<5> = crate::lookup_table!(5, 0.0, 4.0, square);
+
+    #[test]
+    fn test_const_table() {
+        assert_eq!(SQUARES.table(), &[0.0, 1.0, 4.0, 9.0, 16.0]);
+        assert_eq!(SQUARES.linear(2.0), 4.0);
+        assert_eq!(SQUARES.linear(2.5), 6.5);
+    }
+
+    #[test]
+    fn test_clamps_out_of_range() {
+        assert_eq!(SQUARES.linear(-3.0), 0.0);
+        assert_eq!(SQUARES.linear(10.0), 16.0);
+        assert_eq!(SQUARES.linear(4.0), 16.0);
+        assert_eq!(SQUARES.linear(f32::NAN), 0.0);
+        assert_eq!(SQUARES.cubic(10.0), 16.0);
+    }
+
+    #[test]
+    fn test_cubic_beats_linear() {
+        let table: LookupTable<17> = LookupTable::from_fn(0.0, core::f32::consts::PI, f32::sin);
+        let mut linear_err = 0.0f32;
+        let mut cubic_err = 0.0f32;
+        for i in 0..1000 {
+            let x = i as f32 * core::f32::consts::PI / 1000.0;
+            linear_err = linear_err.max((table.linear(x) - x.sin()).abs());
+            cubic_err = cubic_err.max((table.cubic(x) - x.sin()).abs());
+        }
+        assert!(linear_err < 5e-3, "linear {}", linear_err);
+        assert!(cubic_err < linear_err * 0.5, "cubic {}", cubic_err);
+        assert_eq!(SQUARES.cubic(3.0), 9.0);
+    }
+
+    #[test]
+    fn test_linear_phase() {
+        let tau = core::f32::consts::TAU;
+        let table: LookupTable<257> = LookupTable::from_fn(0.0, tau, f32::sin);
+        assert_eq!(table.linear_phase(0), 0.0);
+        for phase in [0x1000_0000u32, 0x4000_0000, 0x9abc_def0, u32::MAX] {
+            let x = phase as f32 / 4_294_967_296.0 * tau;
+            assert!((table.linear_phase(phase) - x.sin()).abs() < 1e-3);
+            assert!((table.linear_phase(phase) - table.linear(x)).abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Fixed-state running statistics for RT loops.
+//!
+//! All types live entirely inline (no allocation) and update in O(1)
+//! amortized time per sample. Window sizes are const generics so the ring
+//! indexing stays branch-light and free of integer division.
+
+/// Exponential moving average `y += alpha * (x - y)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ema {
+    alpha: f32,
+    value: f32,
+    primed: bool,
+}
+
+impl Ema {
+    /// `alpha` in `(0, 1]`; larger values react faster.
+    pub const fn new(alpha: f32) -> Self {
+        Self {
+            alpha,
+            value: 0.0,
+            primed: false,
+        }
+    }
+
+    /// The first sample initializes the average instead of decaying from zero.
+    #[inline]
+    pub fn update(&mut self, sample: f32) -> f32 {
+        if self.primed {
+            self.value += self.alpha * (sample - self.value);
+        } else {
+            self.value = sample;
+            self.primed = true;
+        }
+        self.value
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn reset(&mut self) {
+        self.primed = false;
+        self.value = 0.0;
+    }
+}
+
+/// Simple moving average over the last `N` samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingAverage<const N: usize> {
+    samples: [f32; N],
+    next: usize,
+    len: usize,
+    sum: f32,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "window must not be empty") };
+        Self {
+            samples: [0.0; N],
+            next: 0,
+            len: 0,
+            sum: 0.0,
+        }
+    }
+
+    #[inline]
+    pub fn update(&mut self, sample: f32) -> f32 {
+        self.sum += sample - self.samples[self.next];
+        self.samples[self.next] = sample;
+        self.next = if self.next + 1 == N { 0 } else { self.next + 1 };
+        if self.len < N {
+            self.len += 1;
+        }
+        self.value()
+    }
+
+    /// Average of the samples seen so far (up to `N`).
+    pub fn value(&self) -> f32 {
+        if self.len == N {
+            self.sum * const { 1.0 / N as f32 }
+        } else if self.len == 0 {
+            0.0
+        } else {
+            self.sum / self.len as f32
+        }
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sliding-window extremum via a monotonic deque in a fixed array.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MonotonicWindow<const N: usize> {
+    /// `(sequence number, value)` pairs, monotonic in value from front to back.
+    entries: [(u64, f32); N],
+    head: usize,
+    len: usize,
+    seq: u64,
+}
+
+impl<const N: usize> MonotonicWindow<N> {
+    const fn new() -> Self {
+        const { assert!(N > 0, "window must not be empty") };
+        Self {
+            entries: [(0, 0.0); N],
+            head: 0,
+            len: 0,
+            seq: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn slot(&self, offset: usize) -> usize {
+        let index = self.head + offset;
+        if index >= N { index - N } else { index }
+    }
+
+    /// Pushes `sample`, dropping back entries for which `dominates(sample, entry)` holds.
+    #[inline]
+    fn push(&mut self, sample: f32, dominates: impl Fn(f32, f32) -> bool) -> f32 {
+        let seq = self.seq;
+        self.seq += 1;
+
+        if self.len > 0 && self.entries[self.head].0 + N as u64 <= seq {
+            self.head = self.slot(1);
+            self.len -= 1;
+        }
+        while self.len > 0 && dominates(sample, self.entries[self.slot(self.len - 1)].1) {
+            self.len -= 1;
+        }
+        let back = self.slot(self.len);
+        self.entries[back] = (seq, sample);
+        self.len += 1;
+        self.entries[self.head].1
+    }
+
+    fn value(&self) -> Option<f32> {
+        if self.len == 0 {
+            None
+        } else {
+            Some(self.entries[self.head].1)
+        }
+    }
+}
+
+/// Minimum of the last `N` samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowMin<const N: usize> {
+    window: MonotonicWindow<N>,
+}
+
+impl<const N: usize> WindowMin<N> {
+    pub const fn new() -> Self {
+        Self {
+            window: MonotonicWindow::new(),
+        }
+    }
+
+    #[inline]
+    pub fn update(&mut self, sample: f32) -> f32 {
+        self.window.push(sample, |new, old| new <= old)
+    }
+
+    pub fn value(&self) -> Option<f32> {
+        self.window.value()
+    }
+}
+
+impl<const N: usize> Default for WindowMin<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Maximum of the last `N` samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowMax<const N: usize> {
+    window: MonotonicWindow<N>,
+}
+
+impl<const N: usize> WindowMax<N> {
+    pub const fn new() -> Self {
+        Self {
+            window: MonotonicWindow::new(),
+        }
+    }
+
+    #[inline]
+    pub fn update(&mut self, sample: f32) -> f32 {
+        self.window.push(sample, |new, old| new >= old)
+    }
+
+    pub fn value(&self) -> Option<f32> {
+        self.window.value()
+    }
+}
+
+impl<const N: usize> Default for WindowMax<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Running mean and variance (Welford's algorithm), numerically stable.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Welford {
+    count: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    pub const fn new() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    #[inline]
+    pub fn update(&mut self, sample: f64) {
+        self.count += 1;
+        let delta = sample - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (sample - self.mean);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Population variance.
+    pub fn variance(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+
+    /// Unbiased sample variance.
+    pub fn sample_variance(&self) -> f64 {
+        if self.count < 2 {
+            0.0
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema() {
+        let mut ema = Ema::new(0.5);
+        assert_eq!(ema.update(10.0), 10.0);
+        assert_eq!(ema.update(20.0), 15.0);
+        assert_eq!(ema.update(15.0), 15.0);
+        ema.reset();
+        assert_eq!(ema.update(4.0), 4.0);
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let mut avg: MovingAverage<4> = MovingAverage::new();
+        assert_eq!(avg.value(), 0.0);
+        assert_eq!(avg.update(4.0), 4.0);
+        assert_eq!(avg.update(8.0), 6.0);
+        avg.update(0.0);
+        assert_eq!(avg.update(4.0), 4.0);
+        assert_eq!(avg.update(8.0), 5.0);
+    }
+
+    #[test]
+    fn test_window_min_max() {
+        let samples = [5.0, 3.0, 4.0, 6.0, 1.0, 2.0, 7.0, 7.0, 0.5];
+        let mut min: WindowMin<3> = WindowMin::new();
+        let mut max: WindowMax<3> = WindowMax::new();
+        assert_eq!(min.value(), None);
+        for (i, sample) in samples.iter().enumerate() {
+            let start = i.saturating_sub(2);
+            let window = &samples[start..=i];
+            let expected_min = window.iter().copied().fold(f32::INFINITY, f32::min);
+            let expected_max = window.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            assert_eq!(min.update(*sample), expected_min, "min at {}", i);
+            assert_eq!(max.update(*sample), expected_max, "max at {}", i);
+        }
+    }
+
+    #[test]
+    fn test_window_of_one() {
+        let mut max: WindowMax<1> = WindowMax::new();
+        assert_eq!(max.update(3.0), 3.0);
+        assert_eq!(max.update(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_welford() {
+        let mut stats = Welford::new();
+        for sample in [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            stats.update(sample);
+        }
+        assert_eq!(stats.count(), 8);
+        assert_eq!(stats.mean(), 5.0);
+        assert_eq!(stats.variance(), 4.0);
+        assert!((stats.sample_variance() - 32.0 / 7.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,9 @@
+pub mod aligned;
+pub mod budget;
+pub mod region;
+pub mod volatile;
+
+pub use aligned::{Align, AlignedBytes, SupportedAlign};
+pub use budget::{Overage, StaticRecord};
+pub use region::{MemRegion, ZeroInit};
+pub use volatile::Volatile;
@@ -0,0 +1,144 @@
+//! Byte buffers with guaranteed alignment.
+//!
+//! A plain `static [u8; N]` is only 1-byte aligned, so DMA descriptors and
+//! SIMD loads over it show up as `align 1` accesses (`UnalignedAccessCheck`).
+//! `AlignedBytes<N, A>` fixes the alignment in the type for `A` in
+//! 16/32/64/4096; `include_bytes_aligned!` does the same for embedded files.
+
+use core::ops::{Deref, DerefMut};
+
+/// Type-level alignment selector for `AlignedBytes`.
+pub struct Align<const A: usize>;
+
+/// Implemented for the supported alignments; `Marker` is a ZST with that alignment.
+pub trait SupportedAlign {
+    type Marker: Copy;
+}
+
+macro_rules! impl_align {
+    ($($align:literal => $marker:ident),+) => {
+        $(
+            #[doc(hidden)]
+            #[repr(align($align))]
+            #[derive(Clone, Copy)]
+            pub struct $marker;
+
+            impl SupportedAlign for Align<$align> {
+                type Marker = $marker;
+            }
+        )+
+    };
+}
+
+impl_align!(16 => Align16, 32 => Align32, 64 => Align64, 4096 => Align4096);
+
+/// `N` bytes aligned to `A`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AlignedBytes<const N: usize, const A: usize>
+where
+    Align<A>: SupportedAlign,
+{
+    _align: [<Align<A> as SupportedAlign>::Marker; 0],
+    bytes: [u8; N],
+}
+
+impl<const N: usize, const A: usize> AlignedBytes<N, A>
+where
+    Align<A>: SupportedAlign,
+{
+    pub const ALIGN: usize = A;
+
+    pub const fn new(bytes: [u8; N]) -> Self {
+        Self { _align: [], bytes }
+    }
+
+    pub const fn zeroed() -> Self {
+        Self::new([0; N])
+    }
+
+    pub const fn as_array(&self) -> &[u8; N] {
+        &self.bytes
+    }
+
+    pub const fn as_mut_array(&mut self) -> &mut [u8; N] {
+        &mut self.bytes
+    }
+
+    pub const fn as_ptr(&self) -> *const u8 {
+        self.bytes.as_ptr()
+    }
+
+    pub const fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.bytes.as_mut_ptr()
+    }
+}
+
+impl<const N: usize, const A: usize> Default for AlignedBytes<N, A>
+where
+    Align<A>: SupportedAlign,
+{
+    fn default() -> Self {
+        Self::zeroed()
+    }
+}
+
+impl<const N: usize, const A: usize> Deref for AlignedBytes<N, A>
+where
+    Align<A>: SupportedAlign,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize, const A: usize> DerefMut for AlignedBytes<N, A>
+where
+    Align<A>: SupportedAlign,
+{
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::align_of;
+
+    #[test]
+    fn test_alignment() {
+        assert_eq!(align_of::<AlignedBytes<3, 16>>(), 16);
+        assert_eq!(align_of::<AlignedBytes<3, 64>>(), 64);
+        assert_eq!(align_of::<AlignedBytes<1, 4096>>(), 4096);
+        assert_eq!(core::mem::size_of::<AlignedBytes<64, 32>>(), 64);
+
+        let buf: AlignedBytes<5, 32> = AlignedBytes::zeroed();
+        assert_eq!(buf.as_ptr() as usize & 31, 0);
+        assert_eq!(buf.len(), 5);
+    }
+
+    #[test]
+    fn test_static_buffer() {
+        static TABLE: AlignedBytes<4, 64> = AlignedBytes::new([1, 2, 3, 4]);
+        assert_eq!(TABLE.as_ptr() as usize & 63, 0);
+        assert_eq!(&TABLE[..], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut buf: AlignedBytes<4, 16> = AlignedBytes::default();
+        buf.copy_from_slice(&[9, 8, 7, 6]);
+        buf.as_mut_array()[0] = 1;
+        assert_eq!(buf.as_array(), &[1, 8, 7, 6]);
+    }
+
+    #[test]
+    fn test_include_bytes_aligned() {
+        let bytes = crate::include_bytes_aligned!(64, "aligned.rs");
+        assert_eq!(bytes.as_ptr() as usize & 63, 0);
+        assert!(bytes.starts_with(b"//! Byte buffers"));
+    }
+}
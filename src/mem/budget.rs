@@ -0,0 +1,193 @@
+//! RAM budgets for statics, per memory region.
+//!
+//! `ram_static!` declares a static and places a `StaticRecord` (region,
+//! name, type, size) in the `ram_statics` link section. `verify` sums the
+//! records linked into the binary per region and reports the first region
+//! over its budget with a per-static breakdown; call it from a test (or a
+//! host-side check of the firmware build) so an oversized container fails
+//! the build instead of the linker's `region overflowed` or a stack crash.
+//! Like `perf::verify_hot_path::registry`, enumeration needs the ELF
+//! start/stop symbols (Linux and bare-metal targets); `check` works on any
+//! list of records.
+
+use core::fmt;
+
+/// Section record produced by `ram_static!`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticRecord {
+    pub region: &'static str,
+    pub name: &'static str,
+    /// Type as written at the declaration.
+    pub type_name: &'static str,
+    pub bytes: usize,
+}
+
+// Keeps the section (and its start/stop symbols) present in every binary.
+#[used]
+#[cfg_attr(
+    any(target_os = "linux", target_os = "none"),
+    unsafe(link_section = "ram_statics")
+)]
+static SENTINEL: StaticRecord = StaticRecord {
+    region: "",
+    name: "",
+    type_name: "",
+    bytes: 0,
+};
+
+#[cfg(any(target_os = "linux", target_os = "none"))]
+unsafe extern "C" {
+    static __start_ram_statics: u8;
+    static __stop_ram_statics: u8;
+}
+
+/// Every `ram_static!` record in the final binary, in link order.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn linked_records() -> &'static [StaticRecord] {
+    // SAFETY: the linker places every `ram_statics` record contiguously
+    // between these symbols; records are `repr(C)` statics of one type.
+    unsafe {
+        let start = core::ptr::addr_of!(__start_ram_statics) as *const StaticRecord;
+        let stop = core::ptr::addr_of!(__stop_ram_statics) as *const StaticRecord;
+        let len = (stop as usize - start as usize) / core::mem::size_of::<StaticRecord>();
+        core::slice::from_raw_parts(start, len)
+    }
+}
+
+/// Bytes of statics recorded for `region`.
+pub fn used(records: &[StaticRecord], region: &str) -> usize {
+    records
+        .iter()
+        .filter(|record| record.region == region)
+        .map(|record| record.bytes)
+        .sum()
+}
+
+/// A region whose statics exceed its budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overage<'a> {
+    pub region: &'a str,
+    pub budget: usize,
+    pub used: usize,
+    records: &'a [StaticRecord],
+}
+
+impl<'a> Overage<'a> {
+    /// The region's statics, in link order.
+    pub fn statics(&self) -> impl Iterator<Item = &'a StaticRecord> + '_ {
+        self.records.iter().filter(|r| r.region == self.region)
+    }
+}
+
+impl fmt::Display for Overage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "region `{}`: {} bytes of statics exceed the {}-byte budget by {}",
+            self.region,
+            self.used,
+            self.budget,
+            self.used - self.budget
+        )?;
+        for record in self.statics() {
+            write!(
+                f,
+                "\n  {:>8}  {}: {}",
+                record.bytes, record.name, record.type_name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// First region in `budgets` (`(region, bytes)`) whose records exceed it.
+pub fn check<'a>(
+    records: &'a [StaticRecord],
+    budgets: &[(&'a str, usize)],
+) -> Result<(), Overage<'a>> {
+    for &(region, budget) in budgets {
+        let used = used(records, region);
+        if used > budget {
+            return Err(Overage {
+                region,
+                budget,
+                used,
+                records,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `check` over the records linked into this binary.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn verify<'a>(budgets: &[(&'a str, usize)]) -> Result<(), Overage<'a>> {
+    check(linked_records(), budgets)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::sync::SpscQueue;
+    use std::string::ToString;
+
+    crate::ram_static!(
+        "test_sram",
+        static RX: SpscQueue<u32, 256> = SpscQueue::new();
+    );
+    crate::ram_static!("test_sram", static TABLE: [f32; 512] = [0.0; 512];);
+
+    fn record(name: &'static str, bytes: usize) -> StaticRecord {
+        StaticRecord {
+            region: "sram1",
+            name,
+            type_name: "RingBuffer<f32, 4096>",
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_check_reports_breakdown() {
+        let records = [
+            record("HISTORY", 16400),
+            record("DELAY", 16400),
+            StaticRecord {
+                region: "ccm",
+                ..record("SCRATCH", 4096)
+            },
+        ];
+        assert_eq!(used(&records, "sram1"), 32800);
+        assert!(check(&records, &[("sram1", 32800), ("ccm", 8192)]).is_ok());
+
+        let overage = check(&records, &[("ccm", 8192), ("sram1", 32768)]).unwrap_err();
+        assert_eq!((overage.region, overage.used), ("sram1", 32800));
+        assert_eq!(overage.statics().count(), 2);
+        let text = overage.to_string();
+        assert!(text.starts_with(
+            "region `sram1`: 32800 bytes of statics exceed the 32768-byte budget by 32\n"
+        ));
+        assert!(
+            text.ends_with("16400  DELAY: RingBuffer<f32, 4096>"),
+            "{}",
+            text
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linked_records() {
+        assert_eq!(RX.capacity(), 256);
+        assert_eq!(TABLE.len(), 512);
+        let expected = core::mem::size_of::<SpscQueue<u32, 256>>() + 2048;
+        assert_eq!(used(linked_records(), "test_sram"), expected);
+        assert!(verify(&[("test_sram", expected)]).is_ok());
+        let overage = verify(&[("test_sram", 2048)]).unwrap_err();
+        assert!(
+            overage
+                .statics()
+                .any(|r| r.type_name == "SpscQueue<u32, 256>")
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Compile-time partitioning of a static byte array into typed sub-regions.
+//!
+//! `mem_region!` declares a backing buffer (an `AlignedBytes`) and a list of
+//! named regions. Offsets are laid out sequentially at compile time, each
+//! rounded up to the region's alignment, and a const assertion rejects
+//! layouts that overflow the buffer or ask for more alignment than it has.
+//! `split()` then hands out one `&mut T` per region; they never overlap by
+//! construction, replacing hand-maintained linker-script arithmetic.
+
+/// Placement of one region inside its backing buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemRegion {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    pub align: usize,
+}
+
+impl MemRegion {
+    pub const fn end(&self) -> usize {
+        self.offset + self.size
+    }
+
+    pub const fn overlaps(&self, other: &MemRegion) -> bool {
+        self.offset < other.end() && other.offset < self.end()
+    }
+}
+
+/// Types for which the all-zero bit pattern is a valid value.
+///
+/// # Safety
+/// Implementors must accept all-zero bytes as a valid, initialized value.
+pub unsafe trait ZeroInit {}
+
+macro_rules! impl_zero_init {
+    ($($ty:ty),+) => {
+        $(unsafe impl ZeroInit for $ty {})+
+    };
+}
+
+impl_zero_init!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+unsafe impl<T: ZeroInit, const N: usize> ZeroInit for [T; N] {}
+
+#[doc(hidden)]
+pub const fn assert_zero_init<T: ZeroInit>() {}
+
+/// Lays out `(name, size, align)` triples sequentially starting at offset 0.
+///
+/// Alignments must be powers of two. Use `layout_end` for the total size.
+pub const fn layout<const K: usize>(specs: [(&'static str, usize, usize); K]) -> [MemRegion; K] {
+    let mut regions = [MemRegion {
+        name: "",
+        offset: 0,
+        size: 0,
+        align: 1,
+    }; K];
+    let mut cursor = 0;
+    let mut i = 0;
+    while i < K {
+        let (name, size, align) = specs[i];
+        assert!(
+            align.is_power_of_two(),
+            "region alignment must be a power of two"
+        );
+        let offset = (cursor + align - 1) & !(align - 1);
+        regions[i] = MemRegion {
+            name,
+            offset,
+            size,
+            align,
+        };
+        cursor = offset + size;
+        i += 1;
+    }
+    regions
+}
+
+/// End offset of the last region (0 for none).
+pub const fn layout_end(regions: &[MemRegion]) -> usize {
+    if regions.is_empty() {
+        0
+    } else {
+        regions[regions.len() - 1].end()
+    }
+}
+
+/// Largest alignment requested by any region.
+pub const fn layout_align(regions: &[MemRegion]) -> usize {
+    let mut align = 1;
+    let mut i = 0;
+    while i < regions.len() {
+        if regions[i].align > align {
+            align = regions[i].align;
+        }
+        i += 1;
+    }
+    align
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::mem_region! {
+        /// Test SRAM split.
+        pub struct Sram(256, 64) {
+            header: u32,
+            #[align(32)]
+            dma_rx: [u8; 33],
+            samples: [f32; 8],
+            tail: u8,
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let r = Sram::REGIONS;
+        assert_eq!(
+            r[0],
+            MemRegion {
+                name: "header",
+                offset: 0,
+                size: 4,
+                align: 4
+            }
+        );
+        assert_eq!((r[1].offset, r[1].size), (32, 33));
+        assert_eq!((r[2].offset, r[2].size), (68, 32));
+        assert_eq!(r[3].offset, 100);
+        assert_eq!(Sram::USED, 101);
+        for (i, a) in r.iter().enumerate() {
+            assert_eq!(a.offset & (a.align - 1), 0);
+            for b in &r[i + 1..] {
+                assert!(!a.overlaps(b), "{} overlaps {}", a.name, b.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        let mut sram = Sram::new();
+        let parts = sram.split();
+        assert_eq!(*parts.header, 0);
+        assert_eq!(parts.dma_rx.as_ptr() as usize & 31, 0);
+        *parts.header = 0xAABB_CCDD;
+        parts.dma_rx.fill(0xEE);
+        parts.samples[7] = 1.5;
+        *parts.tail = 1;
+        let parts = sram.split();
+        assert_eq!(*parts.header, 0xAABB_CCDD);
+        assert_eq!(parts.dma_rx[32], 0xEE);
+        assert_eq!(parts.samples[7], 1.5);
+        assert_eq!(parts.samples[0], 0.0);
+    }
+
+    #[test]
+    fn test_static_region() {
+        crate::mem_region! {
+            struct Scratch(64, 16) {
+                a: [u16; 4],
+                #[align(16)]
+                b: [u64; 2],
+            }
+        }
+        static mut SCRATCH: Scratch = Scratch::new();
+        // SAFETY: only this test touches SCRATCH.
+        let parts = unsafe { (*core::ptr::addr_of_mut!(SCRATCH)).split() };
+        parts.b[1] = 7;
+        assert_eq!(Scratch::REGIONS[1].offset, 16);
+        assert_eq!(parts.a, &[0; 4]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Memory cell accessed only through volatile loads and stores.
+//!
+//! `Volatile<T>` is for memory the compiler cannot see change: MMIO
+//! registers, DMA buffers, memory shared with a debugger. Every `read()` and
+//! `write()` is one volatile access that is never elided, merged or
+//! reordered with other *volatile* accesses. That is the only ordering it
+//! gives: ordinary loads and stores may move across it, and it provides no
+//! synchronization between threads or cores. Use atomics (or `EventFlags`)
+//! for that, and a barrier where the hardware requires one.
+//!
+//! Inlined, these accesses are indistinguishable from raw `read_volatile`
+//! in IR and trip `VolatileLoadCheck`/`VolatileStoreCheck`. When the access
+//! is the point of the function, register it with
+//! `mark_hot!(f, allow(volatile_load, volatile_store))` to suppress both.
+
+use core::cell::UnsafeCell;
+
+/// `T` behind volatile-only access.
+#[repr(transparent)]
+pub struct Volatile<T> {
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: every access is a single volatile read or write of a `Copy` value;
+// callers sharing it across threads accept torn/racy reads as with hardware registers.
+unsafe impl<T: Copy + Send> Sync for Volatile<T> {}
+
+impl<T: Copy> Volatile<T> {
+    pub const fn new(value: T) -> Self {
+        Self {
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Views a raw location (e.g. a register address) as a `Volatile<T>`.
+    ///
+    /// # Safety
+    /// `ptr` must be valid, aligned for `T`, and live for `'a`.
+    pub const unsafe fn from_ptr<'a>(ptr: *mut T) -> &'a Self {
+        // SAFETY: repr(transparent) over UnsafeCell<T>, which has T's layout.
+        unsafe { &*(ptr as *const Self) }
+    }
+
+    #[inline(always)]
+    pub fn read(&self) -> T {
+        // SAFETY: the cell always holds an initialized T.
+        unsafe { self.value.get().read_volatile() }
+    }
+
+    #[inline(always)]
+    pub fn write(&self, value: T) {
+        // SAFETY: as above; interior mutability via UnsafeCell.
+        unsafe { self.value.get().write_volatile(value) }
+    }
+
+    /// Read-modify-write as two separate volatile accesses (not atomic).
+    #[inline(always)]
+    pub fn update(&self, f: impl FnOnce(T) -> T) {
+        self.write(f(self.read()));
+    }
+
+    pub fn as_ptr(&self) -> *mut T {
+        self.value.get()
+    }
+}
+
+impl<T: Copy + Default> Default for Volatile<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write_update() {
+        let reg = Volatile::new(0u32);
+        reg.write(0x10);
+        reg.update(|v| v | 0x1);
+        assert_eq!(reg.read(), 0x11);
+    }
+
+    #[test]
+    fn test_from_ptr() {
+        let mut backing = [0u16; 2];
+        // SAFETY: `backing` outlives `reg` and is aligned.
+        let reg = unsafe { Volatile::from_ptr(backing.as_mut_ptr().add(1)) };
+        reg.write(0xBEEF);
+        assert_eq!(reg.read(), 0xBEEF);
+        assert_eq!(backing[1], 0xBEEF);
+    }
+
+    #[test]
+    fn test_static_register() {
+        static STATUS: Volatile<u8> = Volatile::new(0);
+        STATUS.write(3);
+        assert_eq!(STATUS.read(), 3);
+    }
+}
@@ -1,7 +1,71 @@
+#[cfg(feature = "std")]
+pub mod alloc_guard;
+pub mod bench;
+pub mod clock;
+#[cfg(feature = "pmu")]
+pub mod counters;
+pub mod hot;
+pub mod layout;
+pub mod metrics;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod os;
+pub mod rate;
+#[cfg(feature = "std")]
+pub mod rt_alloc;
+pub mod runtime;
+pub mod stack;
+pub mod trace;
+pub mod watchdog;
+
+#[cfg(feature = "perf")]
+pub mod artifacts;
+#[cfg(feature = "perf")]
+pub mod cli;
+#[cfg(feature = "perf")]
+pub mod profile;
+#[cfg(feature = "perf")]
 pub mod verify_hot_path;
 
+#[cfg(feature = "std")]
+pub use alloc_guard::{AllocMode, AllocStats, GuardedAlloc};
+pub use bench::BenchResult;
+pub use hot::{HotRecord, hot_id};
+pub use layout::{FieldLayout, LayoutWarning, TypeLayout};
+pub use metrics::{Metric, MetricEntry, MetricKind, MetricSample};
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub use os::{RtSetupError, RtThreadConfig};
+pub use rate::TokenBucket;
+#[cfg(feature = "std")]
+pub use rt_alloc::{BlockPool, RtPolicy, RtPool, RtZoneAlloc, enter_rt, exit_rt};
+pub use runtime::{JitterTracker, LoopSnapshot, LoopStats};
+#[cfg(feature = "std")]
+pub use stack::measure_stack_usage;
+pub use stack::{paint_stack, stack_high_water};
+pub use trace::{Span, TimelineDecoder, TraceEvent, TraceKind, TraceRing, Tracer};
+pub use watchdog::{Stall, Watchdog};
+
+#[cfg(feature = "perf")]
+pub use artifacts::{DiscoveryError, IrQuery};
+#[cfg(feature = "perf")]
+pub use profile::{BuildProfile, MatrixReport, Policy, ProfileMatrix, ProfileViolation};
+#[cfg(feature = "perf")]
 pub use verify_hot_path::{
-    AllocationCheck, AtomicCheck, DivisionCheck, FunctionCallCheck, HotPathCheck, HotPathVerifier,
-    IndirectionCheck, NonInboundsGepCheck, Severity, UnalignedAccessCheck, VolatileLoadCheck,
-    VolatileStoreCheck, find_hot_functions_from_ir, verify_hot_function, verify_hot_path_functions,
+    ASYNC_POLL_LIMIT, AllocaInLoopCheck, AllocationCheck, Arch, AsyncPanicPathCheck, AtomicCheck,
+    Attestation, BatchReport, BranchHintCheck, CacheStats, CallCheck, CallReport, Callee, CheckDoc,
+    CheckRegistration, CheckRegistry, CodeSize, CodeSizeCheck, ContractCheck, DeadCodeCheck,
+    DeallocationCheck, Delta, DivisionCheck, FalseSharingCheck, FloatingPointCheck, FmaCheck,
+    FunctionCallCheck, FunctionCallCheckWith, FunctionCost, FunctionCoverage, FunctionPolicy,
+    GpuIsa, HotCandidate, HotEntry, HotPathCheck, HotPathVerifier, ISR_ALLOCA_LIMIT,
+    ISR_INSTRUCTION_LIMIT, IndirectionCheck, InlinedFrame, InstructionCountCheck, IntrinsicPolicy,
+    IrWatcher, LibcCompareCheck, LibmCheck, LoopInfo, LoopReport, MEM_INLINE_BYTES,
+    MemIntrinsicCheck, NonInboundsGepCheck, Outcome, OverflowCheck, PanicPathCheck, ParseError,
+    PointerChaseCheck, PointerParam, Profile, RecursionCheck, RegisterPressure, Server, Severity,
+    SoftFloatCheck, SourceLocation, StackAllocaSizeCheck, StaticWrite, SymbolEntry, SymbolMap,
+    SymbolSamples, TargetLoweringCheck, TargetProfile, TrapCheck, TrendRecord,
+    UnalignedAccessCheck, VerifyCache, VerifyObserver, Violation, VolatileLoadCheck,
+    VolatileStoreCheck, WasmFunction, WithSeverity, code_sizes, cost_report, demangle, explain,
+    find_hot_entries_from_ir, find_hot_functions_from_ir, kernel_entries, loop_report,
+    noalias_advisories, parse_errors, pointer_params, poll_functions, register_pressure,
+    unexercised, verify_gpu, verify_hot_function, verify_hot_path_functions,
+    verify_register_pressure, verify_wasm, verify_zero_overhead,
 };
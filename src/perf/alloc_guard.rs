@@ -0,0 +1,221 @@
+//! Runtime allocation guard for tests of hot code paths.
+//!
+//! Install `GuardedAlloc` as the global allocator, then wrap hot code in
+//! `forbid_alloc!`. Allocator calls made by the current thread inside the
+//! scope are counted; in `AllocMode::Panic` the scope panics on exit if any
+//! were seen. Global allocators must not unwind, so the panic is deferred to
+//! the end of the scope rather than raised from inside `alloc()`.
+//!
+//! This is the runtime complement to `AllocationCheck`: it catches
+//! allocations hidden behind calls the IR checks cannot see through.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// What a guarded scope does when it observes allocator traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocMode {
+    /// Panic when the scope ends if any allocation happened.
+    Panic,
+    /// Only count allocations.
+    Count,
+}
+
+/// Allocator calls observed inside a guarded scope.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub reallocations: usize,
+    pub deallocations: usize,
+    /// Bytes requested by allocations and reallocations.
+    pub bytes: usize,
+}
+
+impl AllocStats {
+    /// True if no allocator call was observed.
+    pub fn is_clean(&self) -> bool {
+        self.allocations == 0 && self.reallocations == 0 && self.deallocations == 0
+    }
+}
+
+thread_local! {
+    static ACTIVE: Cell<bool> = const { Cell::new(false) };
+    static STATS: Cell<AllocStats> = const {
+        Cell::new(AllocStats {
+            allocations: 0,
+            reallocations: 0,
+            deallocations: 0,
+            bytes: 0,
+        })
+    };
+}
+
+fn record(update: impl FnOnce(&mut AllocStats)) {
+    // `try_with` keeps the allocator usable during thread teardown.
+    let active = ACTIVE.try_with(Cell::get).unwrap_or(false);
+    if active {
+        let _ = STATS.try_with(|stats| {
+            let mut current = stats.get();
+            update(&mut current);
+            stats.set(current);
+        });
+    }
+}
+
+/// Global allocator wrapper that reports calls made inside guarded scopes.
+pub struct GuardedAlloc<A = System> {
+    inner: A,
+}
+
+impl GuardedAlloc<System> {
+    pub const fn system() -> Self {
+        Self { inner: System }
+    }
+}
+
+impl<A> GuardedAlloc<A> {
+    pub const fn new(inner: A) -> Self {
+        Self { inner }
+    }
+}
+
+// SAFETY: all calls are forwarded unchanged to the wrapped allocator.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for GuardedAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(|stats| {
+            stats.allocations += 1;
+            stats.bytes += layout.size();
+        });
+        unsafe { self.inner.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(|stats| {
+            stats.allocations += 1;
+            stats.bytes += layout.size();
+        });
+        unsafe { self.inner.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(|stats| {
+            stats.reallocations += 1;
+            stats.bytes += new_size;
+        });
+        unsafe { self.inner.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        record(|stats| stats.deallocations += 1);
+        unsafe { self.inner.dealloc(ptr, layout) }
+    }
+}
+
+/// Restores the enclosing scope's state, also when the body unwinds.
+struct ScopeGuard {
+    was_active: bool,
+    outer: AllocStats,
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.set(self.was_active));
+        STATS.with(|stats| stats.set(self.outer));
+    }
+}
+
+/// Runs `f` with allocation tracking enabled on the current thread.
+///
+/// Scopes nest; an inner scope's calls are not attributed to the outer one.
+pub fn guard<R>(mode: AllocMode, f: impl FnOnce() -> R) -> (R, AllocStats) {
+    let scope = ScopeGuard {
+        was_active: ACTIVE.with(|active| active.replace(true)),
+        outer: STATS.with(|stats| stats.replace(AllocStats::default())),
+    };
+
+    let result = f();
+
+    ACTIVE.with(|active| active.set(false));
+    let observed = STATS.with(Cell::get);
+    drop(scope);
+
+    if mode == AllocMode::Panic && !observed.is_clean() {
+        panic!(
+            "allocation in forbid_alloc scope: {} alloc, {} realloc, {} dealloc ({} bytes)",
+            observed.allocations, observed.reallocations, observed.deallocations, observed.bytes
+        );
+    }
+
+    (result, observed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::boxed::Box;
+    use std::vec::Vec;
+
+    #[global_allocator]
+    static GLOBAL: GuardedAlloc = GuardedAlloc::system();
+
+    #[test]
+    fn test_clean_scope() {
+        let (value, stats) = guard(AllocMode::Count, || 2 + 2);
+        assert_eq!(value, 4);
+        assert!(stats.is_clean());
+    }
+
+    #[test]
+    fn test_counts_allocations() {
+        let (_, stats) = guard(AllocMode::Count, || {
+            let boxed = Box::new(7u64);
+            core::hint::black_box(&boxed);
+        });
+        assert_eq!(stats.allocations, 1);
+        assert_eq!(stats.deallocations, 1);
+        assert_eq!(stats.bytes, 8);
+    }
+
+    #[test]
+    fn test_counts_reallocations() {
+        let (_, stats) = guard(AllocMode::Count, || {
+            let mut v: Vec<u8> = Vec::with_capacity(1);
+            v.extend_from_slice(&[0; 64]);
+            core::hint::black_box(&v);
+        });
+        assert!(stats.reallocations >= 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "allocation in forbid_alloc scope")]
+    fn test_panic_mode() {
+        guard(AllocMode::Panic, || core::hint::black_box(Box::new(1)));
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        let (inner, outer) = guard(AllocMode::Count, || {
+            let _a = core::hint::black_box(Box::new(1));
+            let (_, inner) = guard(AllocMode::Count, || {
+                let _b = core::hint::black_box(Box::new(2));
+                let _c = core::hint::black_box(Box::new(3));
+            });
+            inner
+        });
+        assert_eq!(inner.allocations, 2);
+        assert_eq!(outer.allocations, 1);
+    }
+
+    #[test]
+    fn test_forbid_alloc_macro() {
+        let value = crate::forbid_alloc! { 6 * 7 };
+        assert_eq!(value, 42);
+
+        let (value, stats) = crate::forbid_alloc!(count => {
+            let v = core::hint::black_box(Box::new(1u32));
+            *v
+        });
+        assert_eq!(value, 1);
+        assert_eq!(stats.allocations, 1);
+    }
+}
@@ -0,0 +1,211 @@
+//! Locating emitted LLVM IR under a cargo `target/` directory.
+//!
+//! Cargo lays out artifacts as `target/<profile-dir>/deps/<crate>-<hash>.ll`
+//! for host builds and `target/<triple>/<profile-dir>/deps/...` when
+//! `--target` is given, where `dev`/`test` build into `debug`, `release`/
+//! `bench` into `release`, and custom profiles into a directory of their
+//! own name. `IrQuery` resolves that path for a crate, target and profile
+//! and explains what to do when nothing is there.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// Why no IR file was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryError {
+    /// The `<triple>/<profile>/deps` directory does not exist: nothing was built.
+    NotBuilt { dir: PathBuf, hint: String },
+    /// The crate was built, but without `--emit=llvm-ir`.
+    NoIr {
+        dir: PathBuf,
+        crate_name: String,
+        hint: String,
+    },
+}
+
+impl fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoveryError::NotBuilt { dir, hint } => {
+                write!(f, "{} does not exist; build first: {}", dir.display(), hint)
+            }
+            DiscoveryError::NoIr {
+                dir,
+                crate_name,
+                hint,
+            } => write!(
+                f,
+                "no {}-*.ll in {}; IR emission is not enabled: {}",
+                crate_name,
+                dir.display(),
+                hint
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiscoveryError {}
+
+/// Which IR file to look for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IrQuery {
+    pub target_dir: PathBuf,
+    pub crate_name: String,
+    /// Target triple, `None` for a host build without `--target`.
+    pub target: Option<String>,
+    /// Cargo profile name (`dev`, `release`, `bench`, custom).
+    pub profile: String,
+}
+
+impl IrQuery {
+    /// Host release build of `crate_name` under `target_dir`.
+    pub fn new(target_dir: impl Into<PathBuf>, crate_name: &str) -> Self {
+        Self {
+            target_dir: target_dir.into(),
+            crate_name: crate_name.to_string(),
+            target: None,
+            profile: "release".to_string(),
+        }
+    }
+
+    pub fn target(mut self, triple: &str) -> Self {
+        self.target = Some(triple.to_string());
+        self
+    }
+
+    pub fn profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_string();
+        self
+    }
+
+    /// Directory cargo uses for `profile`.
+    pub fn profile_dir(profile: &str) -> &str {
+        match profile {
+            "dev" | "test" | "debug" => "debug",
+            "bench" | "release" => "release",
+            custom => custom,
+        }
+    }
+
+    /// `<target_dir>[/<triple>]/<profile-dir>/deps`.
+    pub fn deps_dir(&self) -> PathBuf {
+        let mut dir = self.target_dir.clone();
+        if let Some(target) = &self.target {
+            dir.push(target);
+        }
+        dir.push(Self::profile_dir(&self.profile));
+        dir.push("deps");
+        dir
+    }
+
+    /// The command that emits the IR this query looks for.
+    pub fn hint(&self) -> String {
+        let mut hint = String::from("cargo rustc --lib");
+        match self.profile.as_str() {
+            "dev" | "debug" => {}
+            "release" => hint.push_str(" --release"),
+            other => hint.push_str(&format!(" --profile {}", other)),
+        }
+        if let Some(target) = &self.target {
+            hint.push_str(&format!(" --target {}", target));
+        }
+        hint.push_str(" -- --emit=llvm-ir -Ccodegen-units=1");
+        hint
+    }
+
+    /// All matching `.ll` files, newest first.
+    pub fn candidates(&self) -> Result<Vec<PathBuf>, DiscoveryError> {
+        let dir = self.deps_dir();
+        let entries = std::fs::read_dir(&dir).map_err(|_| DiscoveryError::NotBuilt {
+            dir: dir.clone(),
+            hint: self.hint(),
+        })?;
+        let prefix = format!("{}-", self.crate_name.replace('-', "_"));
+        let mut files: Vec<(PathBuf, Option<std::time::SystemTime>)> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "ll")
+                    && path
+                        .file_name()
+                        .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+            })
+            .map(|path| {
+                let modified = path.metadata().and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect();
+        if files.is_empty() {
+            return Err(DiscoveryError::NoIr {
+                dir,
+                crate_name: self.crate_name.clone(),
+                hint: self.hint(),
+            });
+        }
+        files.sort_by_key(|file| core::cmp::Reverse(file.1));
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// The newest matching `.ll` file (older ones are stale builds).
+    pub fn locate(&self) -> Result<PathBuf, DiscoveryError> {
+        Ok(self.candidates()?.remove(0))
+    }
+}
+
+/// The `target` directory for a manifest: `CARGO_TARGET_DIR` if set, else `<manifest_dir>/target`.
+pub fn target_dir(manifest_dir: &Path) -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest_dir.join("target"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let query = IrQuery::new("/w/target", "my-fw")
+            .target("thumbv7em-none-eabihf")
+            .profile("bench");
+        assert_eq!(
+            query.deps_dir(),
+            Path::new("/w/target/thumbv7em-none-eabihf/release/deps")
+        );
+        assert!(
+            query
+                .hint()
+                .contains("--profile bench --target thumbv7em-none-eabihf")
+        );
+        assert_eq!(
+            IrQuery::new("t", "c").profile("dev").deps_dir(),
+            Path::new("t/debug/deps")
+        );
+        assert_eq!(IrQuery::profile_dir("fast-rt"), "fast-rt");
+    }
+
+    #[test]
+    fn test_locate_and_errors() {
+        let root = std::env::temp_dir().join(format!("hot-verify-ir-{}", std::process::id()));
+        let query = IrQuery::new(&root, "my-fw").target("thumbv6m-none-eabi");
+        let err = query.locate().unwrap_err();
+        assert!(matches!(err, DiscoveryError::NotBuilt { .. }));
+        assert!(
+            err.to_string()
+                .contains("--release --target thumbv6m-none-eabi")
+        );
+
+        let deps = query.deps_dir();
+        std::fs::create_dir_all(&deps).unwrap();
+        std::fs::write(deps.join("my_fw-abc.rlib"), "").unwrap();
+        std::fs::write(deps.join("other-abc.ll"), "").unwrap();
+        let err = query.locate().unwrap_err();
+        assert!(matches!(err, DiscoveryError::NoIr { .. }));
+        assert!(err.to_string().contains("--emit=llvm-ir"), "{}", err);
+
+        std::fs::write(deps.join("my_fw-abc.ll"), "").unwrap();
+        assert_eq!(query.locate().unwrap(), deps.join("my_fw-abc.ll"));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+//! Minimal `no_std` microbenchmark harness on top of the cycle counter.
+//!
+//! `bench!` times `RUNS` runs of `iterations` calls each and reports the
+//! median cycles per iteration, which is robust against the occasional
+//! interrupt or preemption. With the `perf` feature, `bench!(..., verify = ir)`
+//! also runs hot-path verification on the benched function.
+
+use core::fmt;
+
+use crate::perf::clock;
+
+/// Runs per benchmark used by `bench!`.
+pub const DEFAULT_RUNS: usize = 11;
+
+/// Cycles per iteration across all runs of one benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchResult {
+    pub name: &'static str,
+    pub runs: usize,
+    pub iterations: u32,
+    pub median: u64,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} cycles/iter (min {}, max {}, {} runs x {} iterations)",
+            self.name, self.median, self.min, self.max, self.runs, self.iterations
+        )
+    }
+}
+
+/// Sorts in place without allocating; run counts are small.
+fn insertion_sort(values: &mut [u64]) {
+    for i in 1..values.len() {
+        let mut j = i;
+        while j > 0 && values[j - 1] > values[j] {
+            values.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+/// Summarizes per-run cycles-per-iteration samples.
+fn summarize(name: &'static str, iterations: u32, samples: &mut [u64]) -> BenchResult {
+    insertion_sort(samples);
+    BenchResult {
+        name,
+        runs: samples.len(),
+        iterations,
+        median: samples[samples.len() / 2],
+        min: samples[0],
+        max: samples[samples.len() - 1],
+    }
+}
+
+/// Times `RUNS` runs of `iterations` calls to `f`.
+pub fn run<const RUNS: usize, R>(
+    name: &'static str,
+    iterations: u32,
+    mut f: impl FnMut() -> R,
+) -> BenchResult {
+    const { assert!(RUNS > 0, "bench needs at least one run") };
+    let iterations = iterations.max(1);
+    let mut samples = [0u64; RUNS];
+    for sample in samples.iter_mut() {
+        let start = clock::cycles();
+        for _ in 0..iterations {
+            core::hint::black_box(f());
+        }
+        let elapsed = clock::cycles().wrapping_sub(start);
+        *sample = elapsed / iterations as u64;
+    }
+    summarize(name, iterations, &mut samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_median() {
+        let mut samples = [9, 1, 5, 3, 7];
+        let result = summarize("x", 10, &mut samples);
+        assert_eq!(result.median, 5);
+        assert_eq!(result.min, 1);
+        assert_eq!(result.max, 9);
+        assert_eq!(result.runs, 5);
+    }
+
+    #[test]
+    fn test_run_counts_calls() {
+        let mut calls = 0u32;
+        let result = run::<3, _>("count", 4, || calls += 1);
+        assert_eq!(calls, 12);
+        assert_eq!(result.iterations, 4);
+        assert!(result.min <= result.median && result.median <= result.max);
+    }
+
+    #[test]
+    fn test_zero_iterations_runs_once() {
+        let mut calls = 0u32;
+        run::<1, _>("zero", 0, || calls += 1);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_display() {
+        extern crate std;
+        let result = BenchResult {
+            name: "mix",
+            runs: 3,
+            iterations: 100,
+            median: 12,
+            min: 10,
+            max: 40,
+        };
+        assert_eq!(
+            std::format!("{}", result),
+            "mix: 12 cycles/iter (min 10, max 40, 3 runs x 100 iterations)"
+        );
+    }
+}
@@ -0,0 +1,797 @@
+//! `cargo hot-verify`: command-line front end for the hot-path verifier.
+//!
+//! ```text
+//! cargo hot-verify [--profile realtime|consttime|codesize|embedded|async|isr]
+//!                  [--format text|flycheck] [--allow-warnings] [--progress]
+//!                  [--min-severity info|warning|error|critical]
+//!                  [--attest <file.rs>] [--cache <file>] [--trend <file.csv>]
+//!                  [--baseline <file.csv> [--max-growth <percent>]]
+//!                  [--perf-data <file> [--min-share <percent>]]
+//!                  [--coverage <file>]
+//!                  [--watch [--interval <ms>]] <file.ll|dir>...
+//! cargo hot-verify --explain <HP0001|check>
+//! cargo hot-verify --server [--profile <name>] [--core <name>]
+//! cargo hot-verify [options] --crate <name> [--target <triple>]
+//!                  [--cargo-profile <name>] [--target-dir <dir>]
+//! ```
+//!
+//! With `--crate`, the newest `<name>-*.ll` for the target and profile is
+//! located under the target directory (see `perf::artifacts`). `--core`
+//! adds target-lowering advisories for a `TargetProfile` preset; `--core
+//! auto` takes it from each file's target triple.
+//!
+//! The exit code is `Outcome::exit_code()`: 0 clean, 1 warnings only,
+//! 2 errors, 3 the verifier could not run. `--allow-warnings` turns 1 into 0.
+//! The logic lives here (rather than in the binary) so it can be tested.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use crate::perf::artifacts::IrQuery;
+use crate::perf::verify_hot_path::{
+    Attestation, HotEntry, HotPathVerifier, IrWatcher, Outcome, Profile, Server, Severity,
+    TargetProfile, TrendRecord, VerifyCache, VerifyObserver, Violation, coverage, explain,
+    flycheck, perf_data, trend,
+};
+
+pub const USAGE: &str = "\
+usage: cargo hot-verify [options] <file.ll|dir>...
+       cargo hot-verify [options] --crate <name> [--target <triple>]
+
+options:
+  --profile <name>    realtime (default), consttime, codesize, embedded,
+                      async, isr
+  --format <fmt>      text (default) or flycheck
+  --core <name>       cortex-m0, cortex-m3, cortex-m4f, cortex-m7, x86_64,
+                      aarch64, or auto (from the IR's target triple)
+  --allow-warnings    exit 0 when only warnings are found
+  --min-severity <s>  hide findings below info (default), warning, error
+                      or critical
+  --progress          print one status line per function on stderr
+  --attest <file.rs>  write a .hot_verified attestation include
+  --trend <file.csv>  append per-function counts and cycles to a history
+  --baseline <file>   fail when cycles grow past a trend history's newest
+                      record of the function
+  --max-growth <pct>  with --baseline: allowed growth (default 10)
+  --perf-data <file>  suggest unmarked functions from perf report/script
+                      output or symbol,samples CSV
+  --min-share <pct>   with --perf-data: sample share to suggest (default 1)
+  --coverage <file>   warn about hot functions the tests never run
+                      (llvm-cov export JSON, .profraw or .profdata)
+  --cache <file>      reuse results for unchanged functions
+                      (e.g. target/hot-verify/cache.txt)
+  --watch             re-verify whenever the IR changes; print deltas
+  --interval <ms>     watch poll interval (default 500)
+  --crate <name>      verify the newest IR of this crate under target/
+  --target <triple>   with --crate: cross-compiled artifacts
+  --cargo-profile <p> with --crate: dev, release (default), bench, custom
+  --target-dir <dir>  with --crate: default $CARGO_TARGET_DIR or ./target
+  --explain <code>    describe a check (HP0001 or its name) and exit
+  --server            serve JSON-RPC on stdio for editors (no files needed)
+  -h, --help          show this help
+
+exit codes: 0 clean, 1 warnings, 2 errors, 3 verifier failure
+";
+
+/// Which core the target-lowering advisories assume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Core {
+    Fixed(TargetProfile),
+    /// From each file's `target triple`.
+    Auto,
+}
+
+/// Output style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Flycheck,
+}
+
+/// Parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub profile: Profile,
+    pub format: Format,
+    pub core: Option<Core>,
+    pub allow_warnings: bool,
+    /// Findings below this are neither printed nor counted.
+    pub min_severity: Severity,
+    /// Per-function status lines on stderr.
+    pub progress: bool,
+    pub files: Vec<PathBuf>,
+    /// Write an `Attestation` include here (`.rs`).
+    pub attest: Option<PathBuf>,
+    /// Append `TrendRecord`s of this run here.
+    pub trend: Option<PathBuf>,
+    /// Trend history to gate cycle estimates against.
+    pub baseline: Option<PathBuf>,
+    /// Allowed cycle growth over the baseline, in percent.
+    pub max_growth: u32,
+    /// Profile to compare with the marked functions (`perf_data`).
+    pub perf_data: Option<PathBuf>,
+    /// Share of samples, in percent, that makes a function a candidate.
+    pub min_share: u32,
+    /// Test coverage to check the hot functions against (`coverage`).
+    pub coverage: Option<PathBuf>,
+    /// Incremental cache file (`VerifyCache`).
+    pub cache: Option<PathBuf>,
+    pub watch: bool,
+    pub interval_ms: u64,
+    /// Locate IR for this crate instead of taking files.
+    pub crate_query: Option<IrQuery>,
+    /// Print the documentation of this code or check instead of verifying.
+    pub explain: Option<String>,
+    /// Run `verify_hot_path::server` on stdio.
+    pub server: bool,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parses arguments after the program name; a leading `hot-verify`
+    /// (inserted by `cargo` for subcommands) is skipped.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut args = args.into_iter().peekable();
+        if args.peek().is_some_and(|arg| arg == "hot-verify") {
+            args.next();
+        }
+        let mut options = Options {
+            profile: Profile::RealTime,
+            format: Format::Text,
+            core: None,
+            allow_warnings: false,
+            min_severity: Severity::Info,
+            progress: false,
+            files: Vec::new(),
+            attest: None,
+            trend: None,
+            baseline: None,
+            max_growth: 10,
+            perf_data: None,
+            min_share: 1,
+            coverage: None,
+            cache: None,
+            watch: false,
+            interval_ms: 500,
+            crate_query: None,
+            explain: None,
+            server: false,
+            help: false,
+        };
+        let mut crate_name = None;
+        let mut target = None;
+        let mut cargo_profile = None;
+        let mut target_dir = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--crate" => crate_name = Some(args.next().ok_or("--crate needs a value")?),
+                "--target" => target = Some(args.next().ok_or("--target needs a value")?),
+                "--cargo-profile" => {
+                    cargo_profile = Some(args.next().ok_or("--cargo-profile needs a value")?)
+                }
+                "--target-dir" => {
+                    target_dir = Some(args.next().ok_or("--target-dir needs a value")?)
+                }
+                "-h" | "--help" => options.help = true,
+                "--server" => options.server = true,
+                "--explain" => {
+                    options.explain = Some(args.next().ok_or("--explain needs a code")?);
+                }
+                "--allow-warnings" => options.allow_warnings = true,
+                "--progress" => options.progress = true,
+                "--min-severity" => {
+                    let name = args.next().ok_or("--min-severity needs a value")?;
+                    options.min_severity = Severity::from_name(&name)
+                        .ok_or_else(|| format!("unknown severity `{}`", name))?;
+                }
+                "--profile" => {
+                    let name = args.next().ok_or("--profile needs a value")?;
+                    options.profile = Profile::from_name(&name)
+                        .ok_or_else(|| format!("unknown profile `{}`", name))?;
+                }
+                "--core" => {
+                    let name = args.next().ok_or("--core needs a value")?;
+                    options.core = Some(if name == "auto" {
+                        Core::Auto
+                    } else {
+                        Core::Fixed(
+                            TargetProfile::from_name(&name)
+                                .ok_or_else(|| format!("unknown core `{}`", name))?,
+                        )
+                    });
+                }
+                "--watch" => options.watch = true,
+                "--interval" => {
+                    let ms = args.next().ok_or("--interval needs a value")?;
+                    options.interval_ms = ms
+                        .parse()
+                        .map_err(|_| format!("invalid interval `{}`", ms))?;
+                }
+                "--attest" => {
+                    let path = args.next().ok_or("--attest needs a value")?;
+                    options.attest = Some(PathBuf::from(path));
+                }
+                "--trend" => {
+                    let path = args.next().ok_or("--trend needs a value")?;
+                    options.trend = Some(PathBuf::from(path));
+                }
+                "--baseline" => {
+                    let path = args.next().ok_or("--baseline needs a value")?;
+                    options.baseline = Some(PathBuf::from(path));
+                }
+                "--max-growth" => {
+                    let percent = args.next().ok_or("--max-growth needs a value")?;
+                    options.max_growth = percent
+                        .parse()
+                        .map_err(|_| format!("invalid growth `{}`", percent))?;
+                }
+                "--perf-data" => {
+                    let path = args.next().ok_or("--perf-data needs a value")?;
+                    options.perf_data = Some(PathBuf::from(path));
+                }
+                "--min-share" => {
+                    let percent = args.next().ok_or("--min-share needs a value")?;
+                    options.min_share = percent
+                        .parse()
+                        .map_err(|_| format!("invalid share `{}`", percent))?;
+                }
+                "--coverage" => {
+                    let path = args.next().ok_or("--coverage needs a value")?;
+                    options.coverage = Some(PathBuf::from(path));
+                }
+                "--cache" => {
+                    let path = args.next().ok_or("--cache needs a value")?;
+                    options.cache = Some(PathBuf::from(path));
+                }
+                "--format" => {
+                    options.format = match args.next().as_deref() {
+                        Some("text") => Format::Text,
+                        Some("flycheck") => Format::Flycheck,
+                        Some(other) => return Err(format!("unknown format `{}`", other)),
+                        None => return Err("--format needs a value".to_string()),
+                    };
+                }
+                flag if flag.starts_with('-') => return Err(format!("unknown option `{}`", flag)),
+                file => options.files.push(PathBuf::from(file)),
+            }
+        }
+        if let Some(name) = crate_name {
+            let dir = target_dir
+                .map(PathBuf::from)
+                .unwrap_or_else(|| crate::perf::artifacts::target_dir(Path::new(".")));
+            let mut query = IrQuery::new(dir, &name);
+            if let Some(triple) = &target {
+                query = query.target(triple);
+            }
+            if let Some(profile) = &cargo_profile {
+                query = query.profile(profile);
+            }
+            options.crate_query = Some(query);
+        } else if target.is_some() || cargo_profile.is_some() || target_dir.is_some() {
+            return Err("--target, --cargo-profile and --target-dir need --crate".to_string());
+        }
+        if options.files.is_empty()
+            && options.crate_query.is_none()
+            && options.explain.is_none()
+            && !options.server
+            && !options.help
+        {
+            return Err("no IR files given".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Verifier for `ir`. `--core auto` needs the IR; without it (watch
+    /// mode) no target advisories are added.
+    pub fn verifier(&self, ir: Option<&str>) -> HotPathVerifier {
+        let verifier = HotPathVerifier::new()
+            .with_profile(self.profile)
+            .with_min_severity(self.min_severity);
+        match self.target(ir) {
+            Some(target) => verifier.with_target(target),
+            None => verifier,
+        }
+    }
+
+    fn target(&self, ir: Option<&str>) -> Option<TargetProfile> {
+        match self.core {
+            Some(Core::Fixed(target)) => Some(target),
+            Some(Core::Auto) => ir.and_then(TargetProfile::from_ir),
+            None => None,
+        }
+    }
+}
+
+/// Rustc-style two-line rendering.
+fn text(violation: &Violation) -> String {
+    let mut out = format!(
+        "{}[{}]: {}: {}\n",
+        violation.severity.name(),
+        violation.check,
+        violation.function,
+        violation.message
+    );
+    if let Some(location) = &violation.location {
+        out.push_str(&format!(
+            "  --> {}:{}:{}\n",
+            location.file,
+            location.line,
+            location.column.max(1)
+        ));
+    }
+    if let Some(code) = explain::code(&violation.check) {
+        out.push_str(&format!(
+            "  = help: `cargo hot-verify --explain {}`\n",
+            code
+        ));
+    }
+    out
+}
+
+/// Writes diagnostics as they are found and, with `--progress`, one status
+/// line per function.
+struct Stream<'a> {
+    format: Format,
+    progress: bool,
+    out: &'a mut dyn Write,
+    err: &'a mut dyn Write,
+}
+
+impl VerifyObserver for Stream<'_> {
+    fn on_function_start(&mut self, index: usize, total: usize, entry: &HotEntry) {
+        if self.progress {
+            let _ = write!(self.err, "[{}/{}] {} ... ", index + 1, total, entry.name);
+        }
+    }
+
+    fn on_violation(&mut self, violation: &Violation) {
+        let _ = match self.format {
+            Format::Text => write!(self.out, "{}", text(violation)),
+            Format::Flycheck => writeln!(self.out, "{}", flycheck::line(violation)),
+        };
+        let _ = self.out.flush();
+    }
+
+    fn on_function_done(&mut self, _entry: &HotEntry, violations: usize) {
+        if self.progress {
+            let _ = match violations {
+                0 => writeln!(self.err, "ok"),
+                n => writeln!(self.err, "{} finding(s)", n),
+            };
+        }
+    }
+}
+
+/// Verifies every file and writes diagnostics to `out`, errors to `err`.
+pub fn run_with(options: &Options, out: &mut dyn Write, err: &mut dyn Write) -> Outcome {
+    if options.help {
+        let _ = write!(out, "{}", USAGE);
+        return Outcome::Clean;
+    }
+    if let Some(code) = &options.explain {
+        return match explain::explain(code) {
+            Some(doc) => {
+                let _ = write!(out, "{}", doc.render());
+                Outcome::Clean
+            }
+            None => {
+                let _ = writeln!(err, "hot-verify: no check with code `{}`", code);
+                Outcome::ToolFailure
+            }
+        };
+    }
+    let mut cache = options.cache.as_deref().map(VerifyCache::load);
+    let mut attestation = options
+        .attest
+        .as_ref()
+        .map(|_| Attestation::new(&options.verifier(None), options.profile.name()));
+    let mut trend = Vec::new();
+    let revision = (options.trend.is_some() || options.baseline.is_some()).then(|| {
+        (
+            trend::git_revision().unwrap_or_else(|| "unknown".to_string()),
+            trend::unix_time(),
+        )
+    });
+    let mut entries = Vec::new();
+    let mut outcome = Outcome::Clean;
+    let executed = match options.coverage.as_deref().map(coverage::load) {
+        Some(Ok(executed)) => Some(executed),
+        Some(Err(error)) => {
+            let path = options.coverage.as_ref().unwrap();
+            let _ = writeln!(err, "hot-verify: {}: {}", path.display(), error);
+            outcome = outcome.combine(Outcome::ToolFailure);
+            None
+        }
+        None => None,
+    };
+    for file in &options.files {
+        let ir = match std::fs::read_to_string(file) {
+            Ok(ir) => ir,
+            Err(error) => {
+                let _ = writeln!(err, "hot-verify: {}: {}", file.display(), error);
+                outcome = outcome.combine(Outcome::ToolFailure);
+                continue;
+            }
+        };
+        let verifier = options.verifier(Some(&ir));
+        let mut stream = Stream {
+            format: options.format,
+            progress: options.progress,
+            out: &mut *out,
+            err: &mut *err,
+        };
+        let mut violations = verifier.verify_entries_observed(&ir, cache.as_mut(), &mut stream);
+        if let Some(executed) = &executed {
+            for violation in coverage::unexercised(executed, &verifier.entries(&ir))
+                .into_iter()
+                .filter(|v| v.severity >= options.min_severity)
+            {
+                stream.on_violation(&violation);
+                violations.push(violation);
+            }
+        }
+        if options.perf_data.is_some() {
+            entries.extend(verifier.entries(&ir));
+        }
+        if let Some(attestation) = attestation.as_mut() {
+            attestation.record(&ir, &violations);
+        }
+        if let Some((revision, timestamp)) = &revision {
+            // Cycles need some target; without `--core` use the IR's triple.
+            let target = options
+                .target(Some(&ir))
+                .or_else(|| TargetProfile::from_ir(&ir))
+                .unwrap_or_else(TargetProfile::host);
+            trend.extend(TrendRecord::collect(
+                &ir,
+                &violations,
+                &target,
+                revision,
+                *timestamp,
+            ));
+        }
+        outcome = outcome.combine(Outcome::from_violations(&violations));
+    }
+    if let (Some(cache), Some(path)) = (&cache, &options.cache) {
+        let stats = cache.stats();
+        let _ = writeln!(
+            err,
+            "hot-verify: {} cached, {} verified",
+            stats.hits, stats.misses
+        );
+        if let Err(error) = cache.save(path) {
+            let _ = writeln!(err, "hot-verify: {}: {}", path.display(), error);
+            outcome = outcome.combine(Outcome::ToolFailure);
+        }
+    }
+    if let Some(path) = &options.perf_data {
+        // Suggestions only; they do not change the outcome.
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                let samples = perf_data::parse(&text);
+                for candidate in
+                    perf_data::hot_candidates(&samples, &entries, f64::from(options.min_share))
+                {
+                    let _ = writeln!(out, "note[hot_candidate]: {}", candidate);
+                }
+            }
+            Err(error) => {
+                let _ = writeln!(err, "hot-verify: {}: {}", path.display(), error);
+                outcome = outcome.combine(Outcome::ToolFailure);
+            }
+        }
+    }
+    let mut regressed = false;
+    if let Some(path) = &options.baseline {
+        // No history yet means nothing to regress against.
+        match trend::load(path) {
+            Ok(history) => {
+                let baseline = trend::baseline(&history);
+                for regression in
+                    trend::regressions(&baseline, &trend, f64::from(options.max_growth))
+                {
+                    let _ = writeln!(out, "error[cycle_regression]: {}", regression);
+                    regressed = true;
+                    outcome = outcome.combine(Outcome::Errors);
+                }
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                let _ = writeln!(err, "hot-verify: {}: {}", path.display(), error);
+                outcome = outcome.combine(Outcome::ToolFailure);
+            }
+        }
+    }
+    // A regressed run is not recorded, so it cannot become the baseline.
+    if let Some(path) = options.trend.as_ref().filter(|_| !regressed)
+        && let Err(error) = trend::append(path, &trend)
+    {
+        let _ = writeln!(err, "hot-verify: {}: {}", path.display(), error);
+        outcome = outcome.combine(Outcome::ToolFailure);
+    }
+    if let (Some(attestation), Some(path)) = (&attestation, &options.attest)
+        && let Err(error) = std::fs::write(path, attestation.to_rust())
+    {
+        let _ = writeln!(err, "hot-verify: {}: {}", path.display(), error);
+        outcome = outcome.combine(Outcome::ToolFailure);
+    }
+    outcome
+}
+
+/// Entry point for the binary; returns the process exit code.
+pub fn run<I: IntoIterator<Item = String>>(args: I) -> u8 {
+    let mut stdout = std::io::stdout();
+    let mut stderr = std::io::stderr();
+    let mut options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(error) => {
+            let _ = write!(stderr, "hot-verify: {}\n\n{}", error, USAGE);
+            return Outcome::ToolFailure.exit_code();
+        }
+    };
+    if let Some(query) = &options.crate_query {
+        match query.locate() {
+            Ok(file) => options.files.push(file),
+            Err(error) => {
+                let _ = writeln!(stderr, "hot-verify: {}", error);
+                return Outcome::ToolFailure.exit_code();
+            }
+        }
+    }
+    if options.server && !options.help {
+        let mut server = Server::new(options.verifier(None));
+        let mut stdin = std::io::stdin().lock();
+        return match server.serve(&mut stdin, &mut stdout) {
+            Ok(()) => 0,
+            Err(error) => {
+                let _ = writeln!(stderr, "hot-verify: {}", error);
+                Outcome::ToolFailure.exit_code()
+            }
+        };
+    }
+    if options.watch {
+        let verifier = options.verifier(None);
+        let cache = options
+            .cache
+            .as_deref()
+            .map(VerifyCache::load)
+            .unwrap_or_default();
+        let mut watcher = IrWatcher::new(options.files.clone(), cache);
+        let interval = std::time::Duration::from_millis(options.interval_ms);
+        watcher.run(&verifier, interval, |deltas| {
+            for delta in deltas {
+                let _ = write!(stdout, "{}", delta.render());
+            }
+            let _ = stdout.flush();
+        });
+    }
+    let outcome = run_with(&options, &mut stdout, &mut stderr);
+    if outcome == Outcome::Warnings && options.allow_warnings {
+        0
+    } else {
+        outcome.exit_code()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let options = Options::parse(args(&[
+            "hot-verify",
+            "--profile",
+            "embedded",
+            "--format",
+            "flycheck",
+            "a.ll",
+        ]))
+        .unwrap();
+        assert_eq!(options.profile, Profile::Embedded);
+        assert_eq!(options.format, Format::Flycheck);
+        assert_eq!(options.files, [PathBuf::from("a.ll")]);
+
+        assert!(Options::parse(args(&[])).is_err());
+        assert!(Options::parse(args(&["--profile", "fast", "a.ll"])).is_err());
+        assert!(Options::parse(args(&["--bogus", "a.ll"])).is_err());
+        assert!(Options::parse(args(&["--help"])).unwrap().help);
+        let watch = Options::parse(args(&["--watch", "--interval", "50", "deps"])).unwrap();
+        assert!(watch.watch);
+        assert_eq!(watch.interval_ms, 50);
+
+        let cross = Options::parse(args(&[
+            "--crate",
+            "fw",
+            "--target",
+            "thumbv7em-none-eabihf",
+            "--target-dir",
+            "t",
+        ]))
+        .unwrap();
+        assert_eq!(
+            cross.crate_query.unwrap().deps_dir(),
+            Path::new("t/thumbv7em-none-eabihf/release/deps")
+        );
+        assert!(Options::parse(args(&["--target", "x", "a.ll"])).is_err());
+
+        let core = Options::parse(args(&["--core", "cortex-m0", "a.ll"])).unwrap();
+        assert_eq!(core.core, Some(Core::Fixed(TargetProfile::CORTEX_M0)));
+        let auto = Options::parse(args(&["--core", "auto", "a.ll"])).unwrap();
+        assert_eq!(auto.core, Some(Core::Auto));
+        assert!(Options::parse(args(&["--core", "z80", "a.ll"])).is_err());
+        let gate = Options::parse(args(&["--baseline", "h.csv", "--max-growth", "5", "a.ll"]));
+        assert_eq!(gate.unwrap().max_growth, 5);
+        assert!(Options::parse(args(&["--max-growth", "-1", "a.ll"])).is_err());
+        let quiet = Options::parse(args(&["--min-severity", "Warning", "a.ll"])).unwrap();
+        assert_eq!(quiet.min_severity, Severity::Warning);
+        assert!(Options::parse(args(&["--min-severity", "loud", "a.ll"])).is_err());
+    }
+
+    #[test]
+    fn test_run_outcomes() {
+        let dir = std::env::temp_dir().join(format!("hot-verify-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ir = dir.join("k.ll");
+        std::fs::write(
+            &ir,
+            r#"@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @k(i32 %a) {
+  %1 = udiv i32 %a, 3
+  ret i32 %1
+}
+"#,
+        )
+        .unwrap();
+        let path = ir.to_string_lossy().to_string();
+
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let options = Options::parse(args(&["--format", "flycheck", &path])).unwrap();
+        assert_eq!(run_with(&options, &mut out, &mut err), Outcome::Warnings);
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.starts_with("<unknown>:1:1: warning[division]: k:"),
+            "{}",
+            out
+        );
+
+        let options = Options::parse(args(&["--profile", "embedded", &path])).unwrap();
+        assert_eq!(
+            run_with(&options, &mut Vec::new(), &mut err),
+            Outcome::Errors
+        );
+        let mut out = Vec::new();
+        let options = Options::parse(args(&["--min-severity", "error", &path])).unwrap();
+        assert_eq!(run_with(&options, &mut out, &mut err), Outcome::Clean);
+        assert!(out.is_empty());
+
+        let mut out = Vec::new();
+        let options = Options::parse(args(&[&path])).unwrap();
+        run_with(&options, &mut out, &mut Vec::new());
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.ends_with("  = help: `cargo hot-verify --explain HP0008`\n"),
+            "{}",
+            out
+        );
+        let mut out = Vec::new();
+        let options = Options::parse(args(&["--explain", "HP0008"])).unwrap();
+        assert_eq!(run_with(&options, &mut out, &mut err), Outcome::Clean);
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .starts_with("HP0008 (division)")
+        );
+        let options = Options::parse(args(&["--explain", "HP0999"])).unwrap();
+        assert_eq!(
+            run_with(&options, &mut Vec::new(), &mut Vec::new()),
+            Outcome::ToolFailure
+        );
+
+        let missing = dir.join("missing.ll").to_string_lossy().to_string();
+        let options = Options::parse(args(&[&path, &missing])).unwrap();
+        let mut err = Vec::new();
+        assert_eq!(
+            run_with(&options, &mut Vec::new(), &mut err),
+            Outcome::ToolFailure
+        );
+        assert!(String::from_utf8(err).unwrap().contains("missing.ll"));
+
+        let options = Options::parse(args(&["--progress", &path])).unwrap();
+        let mut err = Vec::new();
+        run_with(&options, &mut Vec::new(), &mut err);
+        assert_eq!(
+            String::from_utf8(err).unwrap(),
+            "[1/1] k ... 1 finding(s)\n"
+        );
+
+        let attest = dir.join("hot_verified.rs");
+        let options = Options::parse(args(&["--attest", attest.to_str().unwrap(), &path])).unwrap();
+        run_with(&options, &mut Vec::new(), &mut Vec::new());
+        let source = std::fs::read_to_string(&attest).unwrap();
+        assert!(
+            source.contains("policy=realtime\\nfn warnings k\\n"),
+            "{}",
+            source
+        );
+
+        let cache = dir.join("cache.txt").to_string_lossy().to_string();
+        let options = Options::parse(args(&["--cache", &cache, &path])).unwrap();
+        for expected in ["0 cached, 1 verified", "1 cached, 0 verified"] {
+            let mut err = Vec::new();
+            assert_eq!(
+                run_with(&options, &mut Vec::new(), &mut err),
+                Outcome::Warnings
+            );
+            assert!(String::from_utf8(err).unwrap().contains(expected));
+        }
+
+        let history = dir.join("trend.csv");
+        let history = history.to_str().unwrap();
+        let gated = ["--trend", history, "--baseline", history, &path];
+        let options = Options::parse(args(&gated)).unwrap();
+        for _ in 0..2 {
+            run_with(&options, &mut Vec::new(), &mut Vec::new());
+        }
+        let mut records = trend::load(Path::new(history)).unwrap();
+        assert_eq!(records.len(), 2);
+        records[1].cycles /= 2;
+        records[1].timestamp += 1;
+        trend::append(Path::new(history), &records[1..]).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(
+            run_with(&options, &mut out, &mut Vec::new()),
+            Outcome::Errors
+        );
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .contains("error[cycle_regression]: k:")
+        );
+        assert_eq!(trend::load(Path::new(history)).unwrap().len(), 3);
+
+        let profile = dir.join("perf.csv");
+        std::fs::write(&profile, "k,70\nother::mix,25\nlog,5\n").unwrap();
+        let profile = profile.to_str().unwrap();
+        let options = Options::parse(args(&["--perf-data", profile, &path])).unwrap();
+        let mut out = Vec::new();
+        assert_eq!(
+            run_with(&options, &mut out, &mut Vec::new()),
+            Outcome::Warnings
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.contains("note[hot_candidate]: other::mix: 25.0% of samples (25)"),
+            "{}",
+            out
+        );
+        assert!(out.contains("candidate]: log") && !out.contains("candidate]: k:"));
+        let options = Options::parse(args(&["--perf-data", profile, "--min-share", "30", &path]));
+        let mut out = Vec::new();
+        run_with(&options.unwrap(), &mut out, &mut Vec::new());
+        assert!(!String::from_utf8(out).unwrap().contains("hot_candidate"));
+
+        let executed = dir.join("coverage.txt");
+        std::fs::write(&executed, "  k:\n    Function count: 0\n").unwrap();
+        let options = Options::parse(args(&["--coverage", executed.to_str().unwrap(), &path]));
+        let mut out = Vec::new();
+        run_with(&options.unwrap(), &mut out, &mut Vec::new());
+        assert!(
+            String::from_utf8(out)
+                .unwrap()
+                .contains("warning[unexercised]: k: never executed")
+        );
+
+        assert_eq!(run(args(&["--allow-warnings", &path])), 0);
+        assert_eq!(run(args(&[&path])), 1);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+//! Free-running cycle counter access.
+//!
+//! `cycles()` reads the cheapest monotonic counter of the target: TSC on x86,
+//! `CNTVCT_EL0` on AArch64, DWT `CYCCNT` on bare-metal Cortex-M3 and later
+//! (32-bit, wraps) and `rdcycle` on RISC-V. Unsupported targets, including
+//! hosted 32-bit ARM and ARMv6-M (which has no DWT), return 0.
+
+/// Reads the target's cycle counter.
+#[inline(always)]
+pub fn cycles() -> u64 {
+    imp::cycles()
+}
+
+#[cfg(target_arch = "x86_64")]
+mod imp {
+    #[inline(always)]
+    pub fn cycles() -> u64 {
+        // SAFETY: RDTSC is available on every x86_64 CPU.
+        unsafe { core::arch::x86_64::_rdtsc() }
+    }
+}
+
+#[cfg(target_arch = "x86")]
+mod imp {
+    #[inline(always)]
+    pub fn cycles() -> u64 {
+        // SAFETY: RDTSC is available on every i586+ CPU.
+        unsafe { core::arch::x86::_rdtsc() }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod imp {
+    #[inline(always)]
+    pub fn cycles() -> u64 {
+        let value: u64;
+        // SAFETY: CNTVCT_EL0 is readable from EL0 on all supported OSes.
+        unsafe { core::arch::asm!("mrs {}, cntvct_el0", out(reg) value, options(nomem, nostack)) };
+        value
+    }
+}
+
+// ARMv6-M is the Cortex-M profile without compare-and-swap, and also the
+// one without DWT.
+#[cfg(all(target_arch = "arm", target_os = "none", target_has_atomic = "32"))]
+mod imp {
+    /// DWT cycle count register; requires `DEMCR.TRCENA` and `DWT_CTRL.CYCCNTENA`.
+    const DWT_CYCCNT: *const u32 = 0xE000_1004 as *const u32;
+
+    #[inline(always)]
+    pub fn cycles() -> u64 {
+        // SAFETY: DWT_CYCCNT is a memory-mapped, always-readable register on Cortex-M3+.
+        unsafe { core::ptr::read_volatile(DWT_CYCCNT) as u64 }
+    }
+}
+
+#[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+mod imp {
+    #[inline(always)]
+    pub fn cycles() -> u64 {
+        let value: usize;
+        // SAFETY: `rdcycle` is a read-only CSR access.
+        unsafe { core::arch::asm!("rdcycle {}", out(reg) value, options(nomem, nostack)) };
+        value as u64
+    }
+}
+
+#[cfg(not(any(
+    target_arch = "x86_64",
+    target_arch = "x86",
+    target_arch = "aarch64",
+    all(target_arch = "arm", target_os = "none", target_has_atomic = "32"),
+    target_arch = "riscv32",
+    target_arch = "riscv64"
+)))]
+mod imp {
+    #[inline(always)]
+    pub fn cycles() -> u64 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycles_monotonic() {
+        let a = cycles();
+        let b = cycles();
+        assert!(b >= a);
+    }
+}
@@ -0,0 +1,373 @@
+//! Hardware performance-counter sampling around a closure.
+//!
+//! On Linux (with `std`) the counters come from `perf_event_open` for the
+//! calling thread, user space only. On Cortex-M they come from the DWT
+//! profiling counters, which have no cache or branch events; fields a backend
+//! cannot provide are `None`.
+//!
+//! ```ignore
+//! let (out, delta) = base::perf::counters::measure(|| kernel(&mut buf))?;
+//! println!("{:?} cache misses", delta.cache_misses);
+//! ```
+
+use core::fmt;
+
+/// Counter deltas over one measured region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CounterDelta {
+    pub cycles: Option<u64>,
+    pub instructions: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub branch_misses: Option<u64>,
+}
+
+impl CounterDelta {
+    /// Instructions per cycle, if both counters are available.
+    pub fn ipc(&self) -> Option<f64> {
+        match (self.instructions, self.cycles) {
+            (Some(instructions), Some(cycles)) if cycles > 0 => {
+                Some(instructions as f64 / cycles as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Why counters could not be opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterError {
+    /// No counter backend exists for this target.
+    Unsupported,
+    /// The OS refused the request (errno), e.g. due to `perf_event_paranoid`.
+    Os(i32),
+}
+
+impl fmt::Display for CounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CounterError::Unsupported => f.write_str("no hardware counter backend for this target"),
+            CounterError::Os(errno) => write!(
+                f,
+                "perf_event_open failed (errno {}); check /proc/sys/kernel/perf_event_paranoid",
+                errno
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CounterError {}
+
+/// Runs `f` and returns its result together with the counter deltas.
+pub fn measure<R>(f: impl FnOnce() -> R) -> Result<(R, CounterDelta), CounterError> {
+    let mut counters = imp::Counters::open()?;
+    counters.start();
+    let result = f();
+    let delta = counters.stop();
+    Ok((result, delta))
+}
+
+#[cfg(all(
+    feature = "std",
+    target_os = "linux",
+    any(
+        target_arch = "x86_64",
+        target_arch = "x86",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )
+))]
+mod imp {
+    use super::{CounterDelta, CounterError};
+    use core::ffi::{c_long, c_ulong};
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_PERF_EVENT_OPEN: c_long = 298;
+    #[cfg(target_arch = "x86")]
+    const SYS_PERF_EVENT_OPEN: c_long = 336;
+    #[cfg(target_arch = "arm")]
+    const SYS_PERF_EVENT_OPEN: c_long = 364;
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_PERF_EVENT_OPEN: c_long = 241;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+
+    const FLAG_DISABLED: u64 = 1 << 0;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const PERF_EVENT_IOC_ENABLE: c_ulong = 0x2400;
+    const PERF_EVENT_IOC_DISABLE: c_ulong = 0x2401;
+    const PERF_EVENT_IOC_RESET: c_ulong = 0x2403;
+
+    /// `struct perf_event_attr` up to `PERF_ATTR_SIZE_VER5`.
+    #[repr(C)]
+    #[derive(Default)]
+    struct PerfEventAttr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        wakeup_events: u32,
+        bp_type: u32,
+        config1: u64,
+        config2: u64,
+        branch_sample_type: u64,
+        sample_regs_user: u64,
+        sample_stack_user: u32,
+        clockid: i32,
+        sample_regs_intr: u64,
+        aux_watermark: u32,
+        sample_max_stack: u16,
+        reserved: u16,
+    }
+
+    unsafe extern "C" {
+        fn syscall(number: c_long, ...) -> c_long;
+        fn ioctl(fd: i32, request: c_ulong, ...) -> i32;
+    }
+
+    fn open_event(config: u64) -> Result<File, CounterError> {
+        let attr = PerfEventAttr {
+            kind: PERF_TYPE_HARDWARE,
+            size: core::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            flags: FLAG_DISABLED | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+            ..Default::default()
+        };
+        // SAFETY: `attr` is a valid perf_event_attr; pid 0 / cpu -1 is the calling thread.
+        let fd = unsafe {
+            syscall(
+                SYS_PERF_EVENT_OPEN,
+                &attr as *const PerfEventAttr,
+                0,
+                -1,
+                -1,
+                0,
+            )
+        };
+        if fd < 0 {
+            let errno = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+            return Err(CounterError::Os(errno));
+        }
+        // SAFETY: the kernel returned a fresh, owned file descriptor.
+        Ok(unsafe { File::from_raw_fd(fd as i32) })
+    }
+
+    fn control(file: &File, request: c_ulong) {
+        // SAFETY: perf ioctls take no argument besides the flag word.
+        unsafe { ioctl(file.as_raw_fd(), request, 0) };
+    }
+
+    fn read_count(file: &mut File) -> Option<u64> {
+        let mut bytes = [0u8; 8];
+        file.read_exact(&mut bytes).ok()?;
+        Some(u64::from_ne_bytes(bytes))
+    }
+
+    pub struct Counters {
+        cycles: File,
+        instructions: Option<File>,
+        cache_misses: Option<File>,
+        branch_misses: Option<File>,
+    }
+
+    impl Counters {
+        /// Cycles are required; the remaining events are best-effort.
+        pub fn open() -> Result<Self, CounterError> {
+            Ok(Self {
+                cycles: open_event(PERF_COUNT_HW_CPU_CYCLES)?,
+                instructions: open_event(PERF_COUNT_HW_INSTRUCTIONS).ok(),
+                cache_misses: open_event(PERF_COUNT_HW_CACHE_MISSES).ok(),
+                branch_misses: open_event(PERF_COUNT_HW_BRANCH_MISSES).ok(),
+            })
+        }
+
+        fn files(&self) -> impl Iterator<Item = &File> {
+            core::iter::once(&self.cycles)
+                .chain(self.instructions.as_ref())
+                .chain(self.cache_misses.as_ref())
+                .chain(self.branch_misses.as_ref())
+        }
+
+        pub fn start(&mut self) {
+            for file in self.files() {
+                control(file, PERF_EVENT_IOC_RESET);
+            }
+            for file in self.files() {
+                control(file, PERF_EVENT_IOC_ENABLE);
+            }
+        }
+
+        pub fn stop(&mut self) -> CounterDelta {
+            for file in self.files() {
+                control(file, PERF_EVENT_IOC_DISABLE);
+            }
+            CounterDelta {
+                cycles: read_count(&mut self.cycles),
+                instructions: self.instructions.as_mut().and_then(read_count),
+                cache_misses: self.cache_misses.as_mut().and_then(read_count),
+                branch_misses: self.branch_misses.as_mut().and_then(read_count),
+            }
+        }
+    }
+}
+
+#[cfg(all(
+    target_arch = "arm",
+    not(all(
+        feature = "std",
+        target_os = "linux",
+        any(
+            target_arch = "x86_64",
+            target_arch = "x86",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv64"
+        )
+    ))
+))]
+mod imp {
+    use super::{CounterDelta, CounterError};
+    use core::ptr::{read_volatile, write_volatile};
+
+    const DEMCR: *mut u32 = 0xE000_EDFC as *mut u32;
+    const DWT_CTRL: *mut u32 = 0xE000_1000 as *mut u32;
+    const DWT_CYCCNT: *mut u32 = 0xE000_1004 as *mut u32;
+    const DWT_CPICNT: *mut u32 = 0xE000_1008 as *mut u32;
+    const DWT_EXCCNT: *mut u32 = 0xE000_100C as *mut u32;
+    const DWT_SLEEPCNT: *mut u32 = 0xE000_1010 as *mut u32;
+    const DWT_LSUCNT: *mut u32 = 0xE000_1014 as *mut u32;
+    const DWT_FOLDCNT: *mut u32 = 0xE000_1018 as *mut u32;
+
+    const DEMCR_TRCENA: u32 = 1 << 24;
+    /// CYCCNTENA plus CPI, EXC, SLEEP, LSU and FOLD event enables.
+    const DWT_CTRL_ENABLE: u32 = 1 | (0x1F << 17);
+
+    pub struct Counters {
+        start: [u32; 6],
+    }
+
+    fn sample() -> [u32; 6] {
+        // SAFETY: DWT registers are always mapped on Cortex-M3 and later.
+        unsafe {
+            [
+                read_volatile(DWT_CYCCNT),
+                read_volatile(DWT_CPICNT) & 0xFF,
+                read_volatile(DWT_EXCCNT) & 0xFF,
+                read_volatile(DWT_SLEEPCNT) & 0xFF,
+                read_volatile(DWT_LSUCNT) & 0xFF,
+                read_volatile(DWT_FOLDCNT) & 0xFF,
+            ]
+        }
+    }
+
+    impl Counters {
+        pub fn open() -> Result<Self, CounterError> {
+            // SAFETY: enabling trace and DWT counters has no side effects on program state.
+            unsafe {
+                write_volatile(DEMCR, read_volatile(DEMCR) | DEMCR_TRCENA);
+                write_volatile(DWT_CTRL, read_volatile(DWT_CTRL) | DWT_CTRL_ENABLE);
+            }
+            Ok(Self { start: [0; 6] })
+        }
+
+        pub fn start(&mut self) {
+            self.start = sample();
+        }
+
+        /// The 8-bit event counters wrap after 256 events; keep regions short.
+        pub fn stop(&mut self) -> CounterDelta {
+            let end = sample();
+            let cycles = end[0].wrapping_sub(self.start[0]) as u64;
+            let event = |i: usize| (end[i].wrapping_sub(self.start[i]) & 0xFF) as u64;
+            // Cortex-M TRM: instructions = cycles - CPI - EXC - SLEEP - LSU + FOLD.
+            let stalls = event(1) + event(2) + event(3) + event(4);
+            CounterDelta {
+                cycles: Some(cycles),
+                instructions: Some((cycles + event(5)).saturating_sub(stalls)),
+                cache_misses: None,
+                branch_misses: None,
+            }
+        }
+    }
+}
+
+#[cfg(not(any(
+    all(
+        feature = "std",
+        target_os = "linux",
+        any(
+            target_arch = "x86_64",
+            target_arch = "x86",
+            target_arch = "arm",
+            target_arch = "aarch64",
+            target_arch = "riscv64"
+        )
+    ),
+    target_arch = "arm"
+)))]
+mod imp {
+    use super::{CounterDelta, CounterError};
+
+    pub struct Counters;
+
+    impl Counters {
+        pub fn open() -> Result<Self, CounterError> {
+            Err(CounterError::Unsupported)
+        }
+
+        pub fn start(&mut self) {}
+
+        pub fn stop(&mut self) -> CounterDelta {
+            CounterDelta::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    #[test]
+    fn test_ipc() {
+        let delta = CounterDelta {
+            cycles: Some(200),
+            instructions: Some(100),
+            ..Default::default()
+        };
+        assert_eq!(delta.ipc(), Some(0.5));
+        assert_eq!(CounterDelta::default().ipc(), None);
+    }
+
+    #[test]
+    fn test_measure_returns_result_or_error() {
+        // Counters are often unavailable in containers; both outcomes are valid.
+        match measure(|| (0..1000u64).map(core::hint::black_box).sum::<u64>()) {
+            Ok((sum, delta)) => {
+                assert_eq!(sum, 499_500);
+                assert!(delta.cycles.is_some());
+            }
+            Err(CounterError::Os(errno)) => assert!(errno > 0),
+            Err(CounterError::Unsupported) => {}
+        }
+    }
+
+    #[test]
+    fn test_error_display() {
+        let message = std::format!("{}", CounterError::Os(13));
+        assert!(message.contains("perf_event_paranoid"));
+    }
+}
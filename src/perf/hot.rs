@@ -0,0 +1,49 @@
+//! Stable numeric IDs for `mark_hot!` functions.
+//!
+//! The ID of a hot function is the 32-bit FNV-1a hash of its path
+//! (`module_path!()::name`, as in the record), computed at compile time.
+//! It stays the same across builds as long as the function keeps its path,
+//! fits a `perf::trace::TraceEvent` id, and can be recomputed from the
+//! record name (`HotEntry::id` in the verifier), so traces, metrics and
+//! verification reports can refer to a function without carrying strings.
+
+use crate::const_str;
+
+/// ID of the hot function at `path`, e.g. `"base::dsp::mix"`.
+pub const fn hot_id(path: &str) -> u32 {
+    const_str::fnv1a_32(path)
+}
+
+/// `.hot_funcs` record written by `mark_hot!`: the `;`-separated record
+/// string the verifier reads from the IR, and the ID of its path.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotRecord {
+    pub record: &'static str,
+    pub id: u32,
+}
+
+impl HotRecord {
+    /// Path of the function: the record up to the first `;`.
+    pub fn path(&self) -> &'static str {
+        self.record.split(';').next().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hot_id() {
+        const ID: u32 = hot_id("base::dsp::mix");
+        assert_eq!(ID, const_str::fnv1a_32("base::dsp::mix"));
+        assert_ne!(hot_id("base::dsp::mix"), hot_id("base::dsp::mux"));
+        let record = HotRecord {
+            record: "base::dsp::mix;allow=division,;file=src/dsp.rs\0",
+            id: ID,
+        };
+        assert_eq!(record.path(), "base::dsp::mix");
+        assert_eq!(hot_id(record.path()), record.id);
+    }
+}
@@ -0,0 +1,382 @@
+//! Memory layout of hot structs.
+//!
+//! `mark_hot_type!` places a `TypeLayout` (size, alignment and the offset
+//! and size of every field, from `core::mem` probes) in the `hot_types` link
+//! section. The analysis here finds padding holes and the cache-line
+//! problems that cost loads in a hot loop: a frequently-accessed field that
+//! straddles two lines, and hot fields spread over more lines than their
+//! size needs. Offsets are relative to the start of the value; a value that
+//! is not line-aligned can move every boundary, so raise the alignment
+//! (`#[repr(align(64))]`) of types whose layout matters this much.
+//!
+//! `linked` enumerates the records of the final binary like
+//! `perf::metrics::registry` (ELF start/stop symbols, Linux and bare-metal).
+
+use core::fmt;
+
+/// Cache line size assumed when the caller has no better figure.
+pub const CACHE_LINE: usize = 64;
+
+/// One field of a `TypeLayout`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+    /// Named in `mark_hot_type!(.., hot(..))`, or every field if no list
+    /// was given.
+    pub hot: bool,
+}
+
+impl FieldLayout {
+    fn end(&self) -> usize {
+        self.offset + self.size
+    }
+
+    /// Whether the field crosses a `line`-byte boundary.
+    pub fn straddles(&self, line: usize) -> bool {
+        self.size > 0 && self.offset / line != (self.end() - 1) / line
+    }
+}
+
+/// Section record produced by `mark_hot_type!`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeLayout {
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    /// In declaration order; empty for `mark_hot_type!(T)`.
+    pub fields: &'static [FieldLayout],
+}
+
+/// Unused bytes between fields or at the end of the type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hole {
+    pub offset: usize,
+    pub bytes: usize,
+    /// Field before the hole; `None` for a hole at offset 0.
+    pub after: Option<&'static str>,
+}
+
+/// Cache-line problem of a hot type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutWarning<'a> {
+    /// A hot field crosses a line boundary: every access touches two lines.
+    Straddle {
+        ty: &'a TypeLayout,
+        field: &'a FieldLayout,
+    },
+    /// The hot fields touch `lines` lines where `minimum` would hold them.
+    HotSpan {
+        ty: &'a TypeLayout,
+        lines: usize,
+        minimum: usize,
+    },
+}
+
+impl fmt::Display for LayoutWarning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutWarning::Straddle { ty, field } => write!(
+                f,
+                "{}: hot field `{}` (bytes {}..{}) straddles a cache line",
+                ty.name,
+                field.name,
+                field.offset,
+                field.end()
+            ),
+            LayoutWarning::HotSpan { ty, lines, minimum } => write!(
+                f,
+                "{}: hot fields span {} cache lines, {} would hold them; group them together",
+                ty.name, lines, minimum
+            ),
+        }
+    }
+}
+
+impl TypeLayout {
+    /// Fields ordered by offset (larger first at equal offsets, so a
+    /// zero-sized field never hides the one it shares an offset with).
+    pub fn fields_by_offset(&self) -> impl Iterator<Item = &FieldLayout> + '_ {
+        let key = |f: &FieldLayout| (f.offset, usize::MAX - f.size);
+        let first = self.fields.iter().min_by_key(|f| key(f));
+        core::iter::successors(first, move |current| {
+            self.fields
+                .iter()
+                .filter(|f| key(f) > key(current))
+                .min_by_key(|f| key(f))
+        })
+    }
+
+    /// Bytes not covered by any field.
+    pub fn padding(&self) -> usize {
+        let used: usize = self.fields.iter().map(|f| f.size).sum();
+        self.size.saturating_sub(used)
+    }
+
+    /// Padding holes in offset order, including tail padding.
+    pub fn holes(&self) -> impl Iterator<Item = Hole> + '_ {
+        let mut end = 0;
+        let mut after = None;
+        self.fields_by_offset()
+            .map(Some)
+            .chain([None])
+            .filter_map(move |field| {
+                let (start, next_end, name) = match field {
+                    Some(f) => (f.offset, f.end().max(end), Some(f.name)),
+                    None => (self.size, self.size, None),
+                };
+                let hole = (start > end).then_some(Hole {
+                    offset: end,
+                    bytes: start - end,
+                    after,
+                });
+                end = next_end;
+                after = name.or(after);
+                hole
+            })
+    }
+
+    /// Cache lines touched by the hot fields, and how few could hold them.
+    pub fn hot_lines(&self, line: usize) -> (usize, usize) {
+        let hot = || self.fields.iter().filter(|f| f.hot && f.size > 0);
+        let (Some(first), Some(last)) = (
+            hot().map(|f| f.offset).min(),
+            hot().map(FieldLayout::end).max(),
+        ) else {
+            return (0, 0);
+        };
+        let bytes: usize = hot().map(|f| f.size).sum();
+        ((last - 1) / line - first / line + 1, bytes.div_ceil(line))
+    }
+
+    /// Straddling hot fields, then a `HotSpan` if the hot fields could
+    /// share fewer lines.
+    pub fn warnings(&self, line: usize) -> impl Iterator<Item = LayoutWarning<'_>> {
+        let (lines, minimum) = self.hot_lines(line);
+        self.fields
+            .iter()
+            .filter(move |f| f.hot && f.straddles(line))
+            .map(|field| LayoutWarning::Straddle { ty: self, field })
+            .chain((lines > minimum).then_some(LayoutWarning::HotSpan {
+                ty: self,
+                lines,
+                minimum,
+            }))
+    }
+}
+
+impl fmt::Display for TypeLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} bytes, align {}, {} bytes padding",
+            self.name,
+            self.size,
+            self.align,
+            self.padding()
+        )?;
+        for field in self.fields_by_offset() {
+            write!(
+                f,
+                "\n  {:>6} {:>6}  {}{}",
+                field.offset,
+                field.size,
+                field.name,
+                if field.hot { " (hot)" } else { "" }
+            )?;
+        }
+        for hole in self.holes() {
+            write!(f, "\n  {:>6} {:>6}  <padding>", hole.offset, hole.bytes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Size of the field `probe` projects to, for `mark_hot_type!`.
+#[doc(hidden)]
+pub const fn field_size<T, F>(_probe: fn(&T) -> &F) -> usize {
+    core::mem::size_of::<F>()
+}
+
+/// Whether `field` is in `hot`; an empty list makes every field hot.
+#[doc(hidden)]
+pub const fn is_hot(hot: &[&str], field: &str) -> bool {
+    if hot.is_empty() {
+        return true;
+    }
+    let mut i = 0;
+    while i < hot.len() {
+        if crate::const_str::eq(hot[i], field) {
+            return true;
+        }
+        i += 1;
+    }
+    false
+}
+
+// Keeps the section (and its start/stop symbols) present in every binary.
+#[used]
+#[cfg_attr(
+    any(target_os = "linux", target_os = "none"),
+    unsafe(link_section = "hot_types")
+)]
+static SENTINEL: TypeLayout = TypeLayout {
+    name: "",
+    size: 0,
+    align: 1,
+    fields: &[],
+};
+
+#[cfg(any(target_os = "linux", target_os = "none"))]
+unsafe extern "C" {
+    static __start_hot_types: u8;
+    static __stop_hot_types: u8;
+}
+
+/// Every `mark_hot_type!` record in the final binary, in link order.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn linked() -> impl Iterator<Item = &'static TypeLayout> {
+    // SAFETY: the linker places every `hot_types` record contiguously
+    // between these symbols; records are `repr(C)` statics of one type.
+    let records = unsafe {
+        let start = core::ptr::addr_of!(__start_hot_types) as *const TypeLayout;
+        let stop = core::ptr::addr_of!(__stop_hot_types) as *const TypeLayout;
+        let len = (stop as usize - start as usize) / core::mem::size_of::<TypeLayout>();
+        core::slice::from_raw_parts(start, len)
+    };
+    records.iter().filter(|record| !record.name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::string::ToString;
+    use std::vec::Vec;
+
+    #[allow(dead_code)]
+    struct Voice {
+        phase: f32,
+        name: [u8; 60],
+        freq: f32,
+        gain: f32,
+        active: bool,
+    }
+
+    crate::mark_hot_type!(
+        Voice {
+            phase,
+            name,
+            freq,
+            gain,
+            active
+        },
+        hot(phase, freq, gain)
+    );
+
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct Packed {
+        tag: u8,
+        value: u64,
+        flag: u8,
+    }
+
+    crate::mark_hot_type!(Packed { tag, value, flag });
+
+    const fn field(name: &'static str, offset: usize, size: usize, hot: bool) -> FieldLayout {
+        FieldLayout {
+            name,
+            offset,
+            size,
+            hot,
+        }
+    }
+
+    #[test]
+    fn test_holes_and_padding() {
+        static FIELDS: [FieldLayout; 3] = [
+            field("tag", 0, 1, true),
+            field("value", 8, 8, true),
+            field("flag", 16, 1, true),
+        ];
+        let layout = TypeLayout {
+            name: "Packed",
+            size: 24,
+            align: 8,
+            fields: &FIELDS,
+        };
+        assert_eq!(layout.padding(), 14);
+        let holes: Vec<Hole> = layout.holes().collect();
+        assert_eq!(
+            holes,
+            [
+                Hole {
+                    offset: 1,
+                    bytes: 7,
+                    after: Some("tag")
+                },
+                Hole {
+                    offset: 17,
+                    bytes: 7,
+                    after: Some("flag")
+                },
+            ]
+        );
+        assert!(
+            layout
+                .to_string()
+                .starts_with("Packed: 24 bytes, align 8, 14 bytes padding")
+        );
+    }
+
+    #[test]
+    fn test_cache_line_warnings() {
+        static FIELDS: [FieldLayout; 4] = [
+            field("count", 0, 4, true),
+            field("name", 4, 58, false),
+            field("sample", 62, 4, true),
+            field("rate", 130, 4, true),
+        ];
+        let layout = TypeLayout {
+            name: "Voice",
+            size: 136,
+            align: 2,
+            fields: &FIELDS,
+        };
+        assert_eq!(layout.hot_lines(CACHE_LINE), (3, 1));
+        let warnings: Vec<std::string::String> =
+            layout.warnings(CACHE_LINE).map(|w| w.to_string()).collect();
+        assert_eq!(
+            warnings,
+            [
+                "Voice: hot field `sample` (bytes 62..66) straddles a cache line",
+                "Voice: hot fields span 3 cache lines, 1 would hold them; group them together",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mark_hot_type_records() {
+        let voice = linked().find(|t| t.name.ends_with("::Voice")).unwrap();
+        assert_eq!(voice.size, core::mem::size_of::<Voice>());
+        assert_eq!(voice.align, 4);
+        assert_eq!(voice.fields.len(), 5);
+        let hot: Vec<&str> = voice
+            .fields
+            .iter()
+            .filter(|f| f.hot)
+            .map(|f| f.name)
+            .collect();
+        assert_eq!(hot, ["phase", "freq", "gain"]);
+        let name = voice.fields.iter().find(|f| f.name == "name").unwrap();
+        assert_eq!(name.size, 60);
+
+        let packed = linked().find(|t| t.name.ends_with("::Packed")).unwrap();
+        assert!(packed.fields.iter().all(|f| f.hot));
+        assert_eq!(packed.holes().map(|h| h.bytes).sum::<usize>(), 14);
+    }
+}
@@ -0,0 +1,203 @@
+//! Named counters and gauges collected through a link section.
+//!
+//! `metric!` defines a static `Metric` (or an array of them, one per core or
+//! instance) and places a `MetricEntry` describing it in the `hot_metrics`
+//! section. Hot code bumps its own cell with a relaxed load and store, so
+//! each cell must have a single writer (hence per-core arrays). The non-RT
+//! side walks the section between the linker-provided
+//! `__start_hot_metrics`/`__stop_hot_metrics` symbols and snapshots every
+//! registered metric without any registration call or allocation.
+//!
+//! Enumeration relies on the ELF linker's start/stop symbols and is available
+//! on Linux and bare-metal (`target_os = "none"`) targets; bare-metal linker
+//! scripts must `KEEP(*(hot_metrics))`.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// One single-writer metric cell.
+#[repr(transparent)]
+#[derive(Debug, Default)]
+pub struct Metric(AtomicU32);
+
+impl Metric {
+    pub const fn new() -> Self {
+        Self(AtomicU32::new(0))
+    }
+
+    /// Adds `n`, wrapping (counter use). Single writer only.
+    #[inline(always)]
+    pub fn add(&self, n: u32) {
+        let value = self.0.load(Ordering::Relaxed);
+        self.0.store(value.wrapping_add(n), Ordering::Relaxed);
+    }
+
+    /// Adds `n` with a read-modify-write, for cells updated from several
+    /// contexts off the hot path (e.g. allocator statistics).
+    #[inline]
+    pub fn add_shared(&self, n: u32) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Overwrites the value (gauge use).
+    #[inline(always)]
+    pub fn set(&self, value: u32) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn get(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How a metric's cells combine in a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MetricKind {
+    /// Monotonic count; cells are summed.
+    Counter,
+    /// Last value; cells report the maximum.
+    Gauge,
+}
+
+/// Section record describing one registered metric.
+#[repr(C)]
+#[derive(Debug)]
+pub struct MetricEntry {
+    pub name: &'static str,
+    pub kind: MetricKind,
+    pub cells: &'static [Metric],
+}
+
+impl MetricEntry {
+    /// Combined value across all cells (sum for counters, max for gauges).
+    pub fn value(&self) -> u64 {
+        match self.kind {
+            MetricKind::Counter => self.cells.iter().map(|c| c.get() as u64).sum(),
+            MetricKind::Gauge => self.cells.iter().map(|c| c.get() as u64).max().unwrap_or(0),
+        }
+    }
+}
+
+/// Point-in-time value of one metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricSample {
+    pub name: &'static str,
+    pub kind: MetricKind,
+    pub value: u64,
+}
+
+// Also guarantees the section (and its start/stop symbols) exists in every binary.
+crate::metric!(static SNAPSHOTS: counter);
+
+#[cfg(any(target_os = "linux", target_os = "none"))]
+unsafe extern "C" {
+    static __start_hot_metrics: u8;
+    static __stop_hot_metrics: u8;
+}
+
+/// All metrics registered with `metric!` in the final binary, in link order.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn registry() -> &'static [MetricEntry] {
+    // SAFETY: the linker places every `hot_metrics` record contiguously between
+    // these symbols; records are `repr(C)` statics of identical size and alignment.
+    unsafe {
+        let start = core::ptr::addr_of!(__start_hot_metrics) as *const MetricEntry;
+        let stop = core::ptr::addr_of!(__stop_hot_metrics) as *const MetricEntry;
+        let len = (stop as usize - start as usize) / core::mem::size_of::<MetricEntry>();
+        core::slice::from_raw_parts(start, len)
+    }
+}
+
+/// Finds a metric by name.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn find(name: &str) -> Option<&'static MetricEntry> {
+    registry().iter().find(|entry| entry.name == name)
+}
+
+/// Writes up to `out.len()` samples; returns how many metrics exist in total.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn snapshot(out: &mut [MetricSample]) -> usize {
+    SNAPSHOTS.inc();
+    let entries = registry();
+    for (slot, entry) in out.iter_mut().zip(entries) {
+        *slot = MetricSample {
+            name: entry.name,
+            kind: entry.kind,
+            value: entry.value(),
+        };
+    }
+    entries.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::metric!(static FRAMES: counter);
+    crate::metric!(pub(crate) static QUEUE_DEPTH: gauge);
+    crate::metric!(static CORE_TICKS: counter[4]);
+
+    #[test]
+    fn test_metric_cells() {
+        let m = Metric::new();
+        m.inc();
+        m.add(u32::MAX);
+        assert_eq!(m.get(), 0);
+        m.set(9);
+        assert_eq!(m.get(), 9);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_registry_enumerates_section() {
+        FRAMES.add(3);
+        QUEUE_DEPTH.set(17);
+        CORE_TICKS[0].add(5);
+        CORE_TICKS[3].add(2);
+
+        let frames = find(concat!(module_path!(), "::FRAMES")).expect("registered");
+        assert_eq!(frames.kind, MetricKind::Counter);
+        assert!(frames.value() >= 3);
+        assert_eq!(
+            find(concat!(module_path!(), "::CORE_TICKS"))
+                .unwrap()
+                .cells
+                .len(),
+            4
+        );
+        assert!(find("no::such::metric").is_none());
+
+        let mut samples = [MetricSample {
+            name: "",
+            kind: MetricKind::Counter,
+            value: 0,
+        }; 16];
+        let total = snapshot(&mut samples);
+        assert!(total >= 4);
+        assert!(find("base::perf::metrics::SNAPSHOTS").unwrap().value() >= 1);
+        let depth = samples[..total.min(16)]
+            .iter()
+            .find(|s| s.name.ends_with("::QUEUE_DEPTH"))
+            .unwrap();
+        assert_eq!((depth.kind, depth.value), (MetricKind::Gauge, 17));
+    }
+
+    #[test]
+    fn test_gauge_reports_max() {
+        static CELLS: [Metric; 3] = [const { Metric::new() }; 3];
+        let entry = MetricEntry {
+            name: "depth",
+            kind: MetricKind::Gauge,
+            cells: &CELLS,
+        };
+        CELLS[1].set(4);
+        CELLS[2].set(2);
+        assert_eq!(entry.value(), 4);
+    }
+}
@@ -0,0 +1,278 @@
+//! Real-time thread setup for hosted (Linux) targets.
+//!
+//! `RtThreadConfig` bundles the usual steps for a hot thread: SCHED_FIFO with a
+//! priority, CPU pinning, `mlockall()` and stack pre-faulting. `apply()` runs
+//! them in order and reports which step failed with the OS error.
+//!
+//! The libc entry points are declared here directly since std already links libc.
+
+use std::fmt;
+use std::io;
+
+const SCHED_FIFO: i32 = 1;
+const MCL_CURRENT: i32 = 1;
+const MCL_FUTURE: i32 = 2;
+const CPU_SET_BYTES: usize = 128;
+
+#[repr(C)]
+struct SchedParam {
+    sched_priority: i32,
+}
+
+unsafe extern "C" {
+    fn sched_setscheduler(pid: i32, policy: i32, param: *const SchedParam) -> i32;
+    fn sched_get_priority_min(policy: i32) -> i32;
+    fn sched_get_priority_max(policy: i32) -> i32;
+    fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u8) -> i32;
+    fn mlockall(flags: i32) -> i32;
+}
+
+/// Setup step that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtStep {
+    Scheduler,
+    Affinity,
+    LockMemory,
+    PrefaultStack,
+}
+
+impl fmt::Display for RtStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            RtStep::Scheduler => "set SCHED_FIFO priority",
+            RtStep::Affinity => "pin thread to CPU",
+            RtStep::LockMemory => "lock memory (mlockall)",
+            RtStep::PrefaultStack => "pre-fault stack",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Failure of one setup step with the underlying OS error and a hint.
+#[derive(Debug)]
+pub struct RtSetupError {
+    pub step: RtStep,
+    pub source: io::Error,
+}
+
+impl RtSetupError {
+    fn last_os_error(step: RtStep) -> Self {
+        Self {
+            step,
+            source: io::Error::last_os_error(),
+        }
+    }
+
+    fn invalid(step: RtStep, message: &str) -> Self {
+        Self {
+            step,
+            source: io::Error::new(io::ErrorKind::InvalidInput, message.to_string()),
+        }
+    }
+
+    fn hint(&self) -> Option<&'static str> {
+        match (self.step, self.source.kind()) {
+            (RtStep::Scheduler, io::ErrorKind::PermissionDenied) => {
+                Some("needs CAP_SYS_NICE or an rtprio limit in /etc/security/limits.conf")
+            }
+            (RtStep::LockMemory, _) if self.source.raw_os_error() == Some(12) => {
+                Some("raise RLIMIT_MEMLOCK (ulimit -l) or grant CAP_IPC_LOCK")
+            }
+            (RtStep::LockMemory, io::ErrorKind::PermissionDenied) => {
+                Some("needs CAP_IPC_LOCK or a memlock limit in /etc/security/limits.conf")
+            }
+            (RtStep::Affinity, io::ErrorKind::InvalidInput) => {
+                Some("CPU index is offline, isolated from this cpuset, or out of range")
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RtSetupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to {}: {}", self.step, self.source)?;
+        if let Some(hint) = self.hint() {
+            write!(f, " ({})", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RtSetupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Sets SCHED_FIFO with `priority` for the calling thread.
+pub fn set_fifo_priority(priority: i32) -> Result<(), RtSetupError> {
+    // SAFETY: plain libc queries without pointers.
+    let (min, max) = unsafe {
+        (
+            sched_get_priority_min(SCHED_FIFO),
+            sched_get_priority_max(SCHED_FIFO),
+        )
+    };
+    if priority < min || priority > max {
+        return Err(RtSetupError::invalid(
+            RtStep::Scheduler,
+            &format!(
+                "priority {} outside SCHED_FIFO range {}..={}",
+                priority, min, max
+            ),
+        ));
+    }
+    let param = SchedParam {
+        sched_priority: priority,
+    };
+    // SAFETY: `param` outlives the call; pid 0 targets the calling thread.
+    if unsafe { sched_setscheduler(0, SCHED_FIFO, &param) } != 0 {
+        return Err(RtSetupError::last_os_error(RtStep::Scheduler));
+    }
+    Ok(())
+}
+
+/// Pins the calling thread to `cpu`.
+pub fn pin_to_cpu(cpu: usize) -> Result<(), RtSetupError> {
+    if cpu >= CPU_SET_BYTES * 8 {
+        return Err(RtSetupError::invalid(
+            RtStep::Affinity,
+            &format!("cpu {} exceeds cpu_set_t size", cpu),
+        ));
+    }
+    let mut mask = [0u8; CPU_SET_BYTES];
+    mask[cpu / 8] |= 1 << (cpu % 8);
+    // SAFETY: `mask` is a valid cpu_set_t-sized buffer.
+    if unsafe { sched_setaffinity(0, mask.len(), mask.as_ptr()) } != 0 {
+        return Err(RtSetupError::last_os_error(RtStep::Affinity));
+    }
+    Ok(())
+}
+
+/// Locks current and future pages of the process into RAM.
+pub fn lock_memory() -> Result<(), RtSetupError> {
+    // SAFETY: no pointers involved.
+    if unsafe { mlockall(MCL_CURRENT | MCL_FUTURE) } != 0 {
+        return Err(RtSetupError::last_os_error(RtStep::LockMemory));
+    }
+    Ok(())
+}
+
+/// Touches `bytes` of stack below the caller so later use doesn't page-fault.
+#[inline(never)]
+pub fn prefault_stack(bytes: usize) -> Result<(), RtSetupError> {
+    const CHUNK: usize = 4096;
+    #[inline(never)]
+    fn touch(remaining: usize) {
+        let page = [0u8; CHUNK];
+        core::hint::black_box(&page);
+        if remaining > CHUNK {
+            touch(remaining - CHUNK);
+        }
+    }
+    if bytes == 0 {
+        return Err(RtSetupError::invalid(
+            RtStep::PrefaultStack,
+            "stack size must be non-zero",
+        ));
+    }
+    touch(bytes);
+    Ok(())
+}
+
+/// Real-time configuration for the calling thread.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RtThreadConfig {
+    priority: Option<i32>,
+    cpu: Option<usize>,
+    lock_memory: bool,
+    prefault_stack: Option<usize>,
+}
+
+impl RtThreadConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fifo_priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    pub fn with_cpu(mut self, cpu: usize) -> Self {
+        self.cpu = Some(cpu);
+        self
+    }
+
+    pub fn with_locked_memory(mut self) -> Self {
+        self.lock_memory = true;
+        self
+    }
+
+    pub fn with_prefaulted_stack(mut self, bytes: usize) -> Self {
+        self.prefault_stack = Some(bytes);
+        self
+    }
+
+    /// Applies the configuration to the calling thread, stopping at the first failure.
+    ///
+    /// Memory is locked before the stack is pre-faulted so the touched pages stay resident.
+    pub fn apply(&self) -> Result<(), RtSetupError> {
+        if let Some(cpu) = self.cpu {
+            pin_to_cpu(cpu)?;
+        }
+        if self.lock_memory {
+            lock_memory()?;
+        }
+        if let Some(bytes) = self.prefault_stack {
+            prefault_stack(bytes)?;
+        }
+        if let Some(priority) = self.priority {
+            set_fifo_priority(priority)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_is_noop() {
+        assert!(RtThreadConfig::new().apply().is_ok());
+    }
+
+    #[test]
+    fn test_prefault_stack() {
+        assert!(prefault_stack(64 * 1024).is_ok());
+        let err = prefault_stack(0).unwrap_err();
+        assert_eq!(err.step, RtStep::PrefaultStack);
+    }
+
+    #[test]
+    fn test_priority_out_of_range() {
+        let err = set_fifo_priority(1000).unwrap_err();
+        assert_eq!(err.step, RtStep::Scheduler);
+        assert_eq!(err.source.kind(), io::ErrorKind::InvalidInput);
+        assert!(err.to_string().contains("outside SCHED_FIFO range"));
+    }
+
+    #[test]
+    fn test_cpu_out_of_range() {
+        let err = pin_to_cpu(CPU_SET_BYTES * 8).unwrap_err();
+        assert_eq!(err.step, RtStep::Affinity);
+    }
+
+    #[test]
+    fn test_error_display_includes_hint() {
+        let err = RtSetupError {
+            step: RtStep::Scheduler,
+            source: io::Error::from(io::ErrorKind::PermissionDenied),
+        };
+        let message = err.to_string();
+        assert!(message.starts_with("failed to set SCHED_FIFO priority"));
+        assert!(message.contains("CAP_SYS_NICE"));
+    }
+}
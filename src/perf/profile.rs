@@ -0,0 +1,351 @@
+//! Verifying several IR artifacts of one crate under different policies.
+//!
+//! Debug IR is full of non-inlined calls, so holding it to release standards
+//! is pointless, but it still shows what the optimizer is being relied on
+//! for. `ProfileMatrix` verifies every hot function in each artifact (one
+//! per build profile) with that artifact's `Policy` and returns one report
+//! whose entries are labelled with the profile they came from.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use crate::perf::artifacts::IrQuery;
+use crate::perf::verify_hot_path::{
+    HotEntry, HotPathVerifier, Outcome, Severity, find_hot_entries_from_ir,
+};
+
+/// What makes an artifact fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Any error or warning fails (release).
+    Clean,
+    /// Errors fail, warnings are reported.
+    DenyErrors,
+    /// Everything is reported, nothing fails (debug).
+    Advisory,
+}
+
+impl Policy {
+    fn is_fatal(self, severity: Severity) -> bool {
+        match self {
+            Policy::Clean => true,
+            Policy::DenyErrors => severity.is_failure(),
+            Policy::Advisory => false,
+        }
+    }
+}
+
+/// Cargo build profile to emit IR for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildProfile {
+    Debug,
+    Release,
+}
+
+impl BuildProfile {
+    pub fn name(self) -> &'static str {
+        match self {
+            BuildProfile::Debug => "debug",
+            BuildProfile::Release => "release",
+        }
+    }
+
+    /// The usual policy: release must be clean, debug is advisory.
+    pub fn default_policy(self) -> Policy {
+        match self {
+            BuildProfile::Debug => Policy::Advisory,
+            BuildProfile::Release => Policy::Clean,
+        }
+    }
+}
+
+/// Builds the library at `manifest_dir` with `profile` and returns its LLVM IR.
+///
+/// Uses a private target directory (`target/hot-ir`) so it never contends
+/// with the build that is running the test.
+pub fn crate_ir(
+    manifest_dir: &Path,
+    crate_name: &str,
+    profile: BuildProfile,
+) -> io::Result<String> {
+    let target = manifest_dir.join("target").join("hot-ir");
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command
+        .current_dir(manifest_dir)
+        .args(["rustc", "--lib", "--quiet", "--target-dir"])
+        .arg(&target);
+    if profile == BuildProfile::Release {
+        command.arg("--release");
+    }
+    let status = command
+        .args(["--", "--emit=llvm-ir", "-Ccodegen-units=1"])
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "cargo rustc ({}) failed: {}",
+            profile.name(),
+            status
+        )));
+    }
+    let newest = IrQuery::new(&target, crate_name)
+        .profile(profile.name())
+        .locate()
+        .map_err(io::Error::other)?;
+    std::fs::read_to_string(newest)
+}
+
+/// One finding, labelled with its artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileViolation {
+    pub profile: String,
+    pub function: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Where the `mark_hot!` marker lives, if the record carries it.
+    pub location: Option<String>,
+    /// Whether the artifact's policy turns this into a failure.
+    pub fatal: bool,
+}
+
+impl fmt::Display for ProfileViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match (self.fatal, self.severity) {
+            (true, _) => "error",
+            (false, Severity::Error | Severity::Critical) => "advisory",
+            (false, severity) => severity.name(),
+        };
+        write!(
+            f,
+            "[{}] {} {}: {}",
+            self.profile, level, self.function, self.message
+        )?;
+        if let Some(location) = &self.location {
+            write!(f, " [{}]", location)?;
+        }
+        Ok(())
+    }
+}
+
+/// Results of a `ProfileMatrix` run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatrixReport {
+    pub violations: Vec<ProfileViolation>,
+    /// `(profile, hot functions verified)` per artifact.
+    pub verified: Vec<(String, usize)>,
+}
+
+impl MatrixReport {
+    /// True if no artifact's policy was violated.
+    pub fn is_ok(&self) -> bool {
+        !self.violations.iter().any(|v| v.fatal)
+    }
+
+    /// Fatal findings count as errors, the rest as warnings.
+    pub fn outcome(&self) -> Outcome {
+        self.violations
+            .iter()
+            .map(|v| {
+                if v.fatal {
+                    Outcome::Errors
+                } else {
+                    Outcome::Warnings
+                }
+            })
+            .max()
+            .unwrap_or(Outcome::Clean)
+    }
+
+    pub fn for_profile<'a>(
+        &'a self,
+        profile: &'a str,
+    ) -> impl Iterator<Item = &'a ProfileViolation> {
+        self.violations.iter().filter(move |v| v.profile == profile)
+    }
+
+    pub fn fatal(&self) -> impl Iterator<Item = &ProfileViolation> {
+        self.violations.iter().filter(|v| v.fatal)
+    }
+}
+
+impl fmt::Display for MatrixReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "{}", violation)?;
+        }
+        for (profile, count) in &self.verified {
+            let fatal = self.fatal().filter(|v| &v.profile == profile).count();
+            writeln!(
+                f,
+                "[{}] {} hot function(s), {} failure(s)",
+                profile, count, fatal
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct Artifact {
+    profile: String,
+    ir: String,
+    policy: Policy,
+}
+
+/// IR artifacts to verify together, each with its own policy.
+pub struct ProfileMatrix {
+    verifier: HotPathVerifier,
+    artifacts: Vec<Artifact>,
+}
+
+impl ProfileMatrix {
+    pub fn new(verifier: HotPathVerifier) -> Self {
+        Self {
+            verifier,
+            artifacts: Vec::new(),
+        }
+    }
+
+    /// Adds the IR for `profile` (any label) under `policy`.
+    pub fn with_artifact(
+        mut self,
+        profile: impl Into<String>,
+        ir: impl Into<String>,
+        policy: Policy,
+    ) -> Self {
+        self.artifacts.push(Artifact {
+            profile: profile.into(),
+            ir: ir.into(),
+            policy,
+        });
+        self
+    }
+
+    /// Builds the crate in both profiles and adds each with its default policy.
+    pub fn with_build_profiles(
+        mut self,
+        manifest_dir: &Path,
+        crate_name: &str,
+        profiles: &[BuildProfile],
+    ) -> io::Result<Self> {
+        for &profile in profiles {
+            let ir = crate_ir(manifest_dir, crate_name, profile)?;
+            self = self.with_artifact(profile.name(), ir, profile.default_policy());
+        }
+        Ok(self)
+    }
+
+    pub fn run(&self) -> MatrixReport {
+        let mut report = MatrixReport::default();
+        for artifact in &self.artifacts {
+            let entries = find_hot_entries_from_ir(&artifact.ir);
+            let mut push = |entry: &HotEntry, severity: Severity, message: String| {
+                let function = entry.name.as_str();
+                // Verifier messages are prefixed with the function name.
+                let message = message
+                    .strip_prefix(function)
+                    .and_then(|m| m.strip_prefix(": "))
+                    .map(str::to_string)
+                    .unwrap_or(message);
+                report.violations.push(ProfileViolation {
+                    profile: artifact.profile.clone(),
+                    function: function.to_string(),
+                    severity,
+                    message,
+                    location: entry.location(),
+                    fatal: artifact.policy.is_fatal(severity),
+                });
+            };
+            for entry in &entries {
+                match self.verifier.verify_entry(&artifact.ir, entry) {
+                    Ok(warnings) => {
+                        for warning in warnings {
+                            push(entry, Severity::Warning, warning);
+                        }
+                    }
+                    Err(error) => push(entry, Severity::Error, error),
+                }
+            }
+            report
+                .verified
+                .push((artifact.profile.clone(), entries.len()));
+        }
+        report
+    }
+}
+
+impl Default for ProfileMatrix {
+    fn default() -> Self {
+        Self::new(HotPathVerifier::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOT: &str = r#"
+@alloc_k = private unnamed_addr constant [7 x i8] c"kernel\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\06\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+"#;
+
+    fn artifact(body: &str) -> String {
+        format!(
+            "{}define i32 @kernel(i32 %a) {{\n{}\n  ret i32 %a\n}}\n",
+            HOT, body
+        )
+    }
+
+    #[test]
+    fn test_policies_per_profile() {
+        let release = artifact("  %1 = udiv i32 %a, 3");
+        let debug = artifact("  %1 = call i32 @helper(i32 %a)");
+        let report = ProfileMatrix::default()
+            .with_artifact("release", release, Policy::Clean)
+            .with_artifact("debug", debug, Policy::Advisory)
+            .run();
+
+        assert!(!report.is_ok());
+        assert_eq!(report.outcome(), Outcome::Errors);
+        let release: Vec<_> = report.for_profile("release").collect();
+        assert_eq!(release.len(), 1);
+        assert!(release[0].fatal);
+        assert_eq!(release[0].severity, Severity::Warning);
+        let debug: Vec<_> = report.for_profile("debug").collect();
+        assert_eq!(debug[0].severity, Severity::Error);
+        assert!(!debug[0].fatal);
+        assert!(debug[0].message.starts_with("contains function call"));
+
+        let text = report.to_string();
+        assert!(text.contains("[release] error kernel: division/modulo"));
+        assert!(text.contains("[debug] advisory kernel: contains function call"));
+        assert!(text.contains("[release] 1 hot function(s), 1 failure(s)"));
+    }
+
+    #[test]
+    fn test_deny_errors_allows_warnings() {
+        let report = ProfileMatrix::default()
+            .with_artifact(
+                "release",
+                artifact("  %1 = udiv i32 %a, 3"),
+                Policy::DenyErrors,
+            )
+            .run();
+        assert!(report.is_ok());
+        assert_eq!(report.outcome(), Outcome::Warnings);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.verified, [("release".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_missing_function_is_reported() {
+        let report = ProfileMatrix::default()
+            .with_artifact("release", HOT, Policy::Clean)
+            .run();
+        assert!(!report.is_ok());
+        assert!(report.violations[0].message.contains("not found"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Deterministic token-bucket rate limiter on the cycle counter.
+//!
+//! Refill uses only shifts, multiplies and adds: elapsed cycles are counted in
+//! periods of `2^shift` cycles and tokens are kept in 16.16 fixed point, so the
+//! hot path contains no division. The division needed to turn a rate in
+//! tokens per second into per-period increments happens once in `per_second()`.
+
+use crate::perf::clock;
+
+const FRACTION_BITS: u32 = 16;
+
+/// Token bucket for pacing events such as telemetry emission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBucket {
+    /// Fixed-point tokens currently available.
+    tokens: u64,
+    /// Fixed-point bucket size.
+    capacity: u64,
+    /// Fixed-point tokens added per period.
+    increment: u64,
+    /// Period length as a power of two of cycles.
+    shift: u32,
+    last: u64,
+    started: bool,
+}
+
+impl TokenBucket {
+    /// Adds `tokens_per_period` tokens every `2^shift` cycles, holding at most `capacity`.
+    ///
+    /// The bucket starts full.
+    pub const fn new(capacity: u32, tokens_per_period: u32, shift: u32) -> Self {
+        Self::with_increment(capacity, (tokens_per_period as u64) << FRACTION_BITS, shift)
+    }
+
+    /// Paces `rate` tokens per second for a counter running at `clock_hz`.
+    pub const fn per_second(capacity: u32, rate: u32, clock_hz: u64) -> Self {
+        // Aim for ~1/16 token per period so the fixed-point increment keeps precision.
+        let cycles_per_token = if rate == 0 {
+            u64::MAX
+        } else {
+            clock_hz / rate as u64
+        };
+        let mut shift = 0;
+        while shift < 48 && (1u64 << (shift + 4)) < cycles_per_token {
+            shift += 1;
+        }
+        let increment = if rate == 0 {
+            0
+        } else {
+            ((rate as u64) << (FRACTION_BITS + shift)) / clock_hz
+        };
+        Self::with_increment(capacity, increment, shift)
+    }
+
+    const fn with_increment(capacity: u32, increment: u64, shift: u32) -> Self {
+        let capacity = (capacity as u64) << FRACTION_BITS;
+        Self {
+            tokens: capacity,
+            capacity,
+            increment,
+            shift,
+            last: 0,
+            started: false,
+        }
+    }
+
+    #[inline(always)]
+    fn refill(&mut self, now: u64) {
+        if !self.started {
+            self.last = now;
+            self.started = true;
+            return;
+        }
+        let periods = now.wrapping_sub(self.last) >> self.shift;
+        if periods == 0 {
+            return;
+        }
+        self.last = self.last.wrapping_add(periods << self.shift);
+        let added = periods.saturating_mul(self.increment);
+        let tokens = self.tokens.saturating_add(added);
+        self.tokens = if tokens > self.capacity {
+            self.capacity
+        } else {
+            tokens
+        };
+    }
+
+    /// Takes `n` tokens at time `now` if available.
+    #[inline]
+    pub fn try_acquire_at(&mut self, now: u64, n: u32) -> bool {
+        self.refill(now);
+        let cost = (n as u64) << FRACTION_BITS;
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Takes `n` tokens now if available.
+    #[inline]
+    pub fn try_acquire(&mut self, n: u32) -> bool {
+        self.try_acquire_at(clock::cycles(), n)
+    }
+
+    /// Whole tokens currently available (without refilling).
+    pub fn available(&self) -> u32 {
+        (self.tokens >> FRACTION_BITS) as u32
+    }
+
+    /// Fixed-point tokens added per `2^shift` cycles and the shift itself.
+    pub fn increment_per_period(&self) -> (u64, u32) {
+        (self.increment, self.shift)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_then_refill() {
+        // One token every 2^4 = 16 cycles, burst of 3.
+        let mut bucket = TokenBucket::new(3, 1, 4);
+        assert!(bucket.try_acquire_at(0, 1));
+        assert!(bucket.try_acquire_at(1, 1));
+        assert!(bucket.try_acquire_at(2, 1));
+        assert!(!bucket.try_acquire_at(3, 1));
+        assert!(!bucket.try_acquire_at(15, 1));
+        assert!(bucket.try_acquire_at(16, 1));
+        assert!(!bucket.try_acquire_at(17, 1));
+    }
+
+    #[test]
+    fn test_refill_is_capped() {
+        let mut bucket = TokenBucket::new(2, 1, 0);
+        assert!(bucket.try_acquire_at(0, 2));
+        assert!(bucket.try_acquire_at(1_000_000, 2));
+        assert!(!bucket.try_acquire_at(1_000_000, 1));
+    }
+
+    #[test]
+    fn test_partial_periods_carry_over() {
+        let mut bucket = TokenBucket::new(1, 1, 4);
+        assert!(bucket.try_acquire_at(0, 1));
+        for now in (10..100).step_by(10) {
+            bucket.try_acquire_at(now, 0);
+        }
+        // 96 cycles = 6 full periods, but the bucket holds at most one token.
+        assert_eq!(bucket.available(), 1);
+    }
+
+    #[test]
+    fn test_per_second() {
+        // 1000 tokens/s on a 1 MHz counter: one token per 1000 cycles.
+        let mut bucket = TokenBucket::per_second(2, 1000, 1_000_000);
+        let (increment, shift) = bucket.increment_per_period();
+        assert_eq!(shift, 6);
+        assert_eq!(increment, (1000u64 << (16 + 6)) / 1_000_000);
+        assert!(bucket.try_acquire_at(0, 2));
+        assert!(!bucket.try_acquire_at(900, 1));
+        assert!(bucket.try_acquire_at(1100, 1));
+
+        let mut granted = 0;
+        for now in (2000..1_002_000).step_by(100) {
+            if bucket.try_acquire_at(now, 1) {
+                granted += 1;
+            }
+        }
+        assert!((995..=1000).contains(&granted), "granted {}", granted);
+    }
+
+    #[test]
+    fn test_zero_rate_never_refills() {
+        let mut bucket = TokenBucket::per_second(1, 0, 1_000_000);
+        assert!(bucket.try_acquire_at(0, 1));
+        assert!(!bucket.try_acquire_at(u64::MAX / 2, 1));
+    }
+}
@@ -0,0 +1,370 @@
+//! Global allocator with real-time zones.
+//!
+//! Install `RtZoneAlloc` as the global allocator and bracket RT sections
+//! with `enter_rt()`/`exit_rt()`. Outside a zone calls pass through to the
+//! wrapped allocator. Inside, `RtPolicy` decides: count them, deny them
+//! (the allocation fails, which aborts through `handle_alloc_error`), or
+//! serve them from a pre-reserved `RtPool`. Blocks from the pool go back to
+//! it whichever thread frees them.
+//!
+//! This is the production counterpart of `alloc_guard` and the runtime
+//! complement to `AllocationCheck`. Counters are `metric!`s, so they show up
+//! in `metrics::snapshot` as `base::perf::rt_alloc::*`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, UnsafeCell};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::validate::{const_validate_nonzero, const_validate_range};
+
+crate::metric!(pub static RT_ALLOCATIONS: counter);
+crate::metric!(pub static RT_DENIED: counter);
+crate::metric!(pub static RT_POOL_ALLOCATIONS: counter);
+crate::metric!(pub static RT_POOL_EXHAUSTED: counter);
+
+thread_local! {
+    static DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Marks the current thread as inside an RT zone. Zones nest.
+pub fn enter_rt() {
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+}
+
+/// Leaves the innermost RT zone.
+pub fn exit_rt() {
+    DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+}
+
+/// Whether the current thread is inside an RT zone.
+pub fn in_rt() -> bool {
+    DEPTH.try_with(|depth| depth.get() > 0).unwrap_or(false)
+}
+
+/// What happens to allocator calls inside an RT zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtPolicy {
+    /// Pass through and count (`RT_ALLOCATIONS`).
+    Count,
+    /// Fail the allocation and count it (`RT_DENIED`).
+    Deny,
+    /// Serve from the pool; fail when it cannot (`RT_POOL_EXHAUSTED`).
+    Pool,
+}
+
+/// Pre-reserved memory for allocations inside RT zones.
+pub trait RtPool: Sync {
+    /// A block for `layout`, or null.
+    fn allocate(&self, layout: Layout) -> *mut u8;
+    /// Whether `ptr` came from this pool.
+    fn owns(&self, ptr: *mut u8) -> bool;
+    /// Returns a block obtained from `allocate`.
+    fn release(&self, ptr: *mut u8);
+}
+
+#[repr(C, align(16))]
+struct Block<const SIZE: usize>([u8; SIZE]);
+
+/// `N` blocks of `SIZE` bytes (16-byte aligned, so a `SIZE` that is not a
+/// multiple of 16 is padded) tracked in a bitmap of `WORDS` words; `N` must
+/// fit, i.e. `N <= 64 * WORDS`. Allocation and
+/// release are a bounded scan and a compare-and-swap, with no lock.
+pub struct BlockPool<const SIZE: usize, const N: usize, const WORDS: usize> {
+    blocks: UnsafeCell<[Block<SIZE>; N]>,
+    used: [AtomicU64; WORDS],
+}
+
+// SAFETY: a block is only handed out after its bit was claimed atomically.
+unsafe impl<const SIZE: usize, const N: usize, const WORDS: usize> Sync
+    for BlockPool<SIZE, N, WORDS>
+{
+}
+
+impl<const SIZE: usize, const N: usize, const WORDS: usize> BlockPool<SIZE, N, WORDS> {
+    pub const fn new() -> Self {
+        const {
+            const_validate_nonzero(SIZE, "BlockPool needs SIZE > 0");
+            const_validate_range(N, 1, 64 * WORDS, "BlockPool needs 1 <= N <= 64 * WORDS");
+        };
+        Self {
+            blocks: UnsafeCell::new([const { Block([0; SIZE]) }; N]),
+            used: [const { AtomicU64::new(0) }; WORDS],
+        }
+    }
+
+    /// Blocks currently handed out.
+    pub fn in_use(&self) -> usize {
+        self.used
+            .iter()
+            .map(|word| word.load(Ordering::Relaxed).count_ones() as usize)
+            .sum()
+    }
+
+    /// Distance between blocks: `SIZE` rounded up to the 16-byte alignment.
+    const STRIDE: usize = size_of::<Block<SIZE>>();
+
+    fn base(&self) -> *mut u8 {
+        self.blocks.get() as *mut u8
+    }
+}
+
+impl<const SIZE: usize, const N: usize, const WORDS: usize> Default for BlockPool<SIZE, N, WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const SIZE: usize, const N: usize, const WORDS: usize> RtPool for BlockPool<SIZE, N, WORDS> {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        if layout.size() > SIZE || layout.align() > 16 {
+            return core::ptr::null_mut();
+        }
+        for (index, word) in self.used.iter().enumerate() {
+            // Words past the last block (`WORDS` larger than needed) are unused.
+            let valid = N.saturating_sub(index * 64).min(64);
+            if valid == 0 {
+                break;
+            }
+            let mask = if valid == 64 {
+                u64::MAX
+            } else {
+                (1 << valid) - 1
+            };
+            let mut current = word.load(Ordering::Relaxed);
+            while current & mask != mask {
+                let bit = (!current).trailing_zeros() as usize;
+                match word.compare_exchange_weak(
+                    current,
+                    current | 1 << bit,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    // SAFETY: `index * 64 + bit < N`, so the block is in bounds.
+                    Ok(_) => return unsafe { self.base().add((index * 64 + bit) * Self::STRIDE) },
+                    Err(actual) => current = actual,
+                }
+            }
+        }
+        core::ptr::null_mut()
+    }
+
+    fn owns(&self, ptr: *mut u8) -> bool {
+        let start = self.base() as usize;
+        (start..start + N * Self::STRIDE).contains(&(ptr as usize))
+    }
+
+    fn release(&self, ptr: *mut u8) {
+        let block = (ptr as usize - self.base() as usize) / Self::STRIDE;
+        self.used[block / 64].fetch_and(!(1 << (block % 64)), Ordering::Release);
+    }
+}
+
+/// Global allocator wrapper enforcing `RtPolicy` inside RT zones.
+pub struct RtZoneAlloc<A = System> {
+    inner: A,
+    policy: RtPolicy,
+    pool: Option<&'static dyn RtPool>,
+}
+
+impl RtZoneAlloc<System> {
+    pub const fn system(policy: RtPolicy) -> Self {
+        Self::new(System, policy)
+    }
+}
+
+impl<A> RtZoneAlloc<A> {
+    pub const fn new(inner: A, policy: RtPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            pool: None,
+        }
+    }
+
+    /// Pool used by `RtPolicy::Pool`; without one that policy denies.
+    pub const fn with_pool(mut self, pool: &'static dyn RtPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    fn pooled(&self, ptr: *mut u8) -> Option<&'static dyn RtPool> {
+        self.pool.filter(|pool| pool.owns(ptr))
+    }
+}
+
+impl<A: GlobalAlloc> RtZoneAlloc<A> {
+    /// Policy decision for a call inside an RT zone; `None` passes through.
+    fn rt_alloc(&self, layout: Layout) -> Option<*mut u8> {
+        if !in_rt() {
+            return None;
+        }
+        RT_ALLOCATIONS.add_shared(1);
+        match self.policy {
+            RtPolicy::Count => None,
+            RtPolicy::Deny => {
+                RT_DENIED.add_shared(1);
+                Some(core::ptr::null_mut())
+            }
+            RtPolicy::Pool => {
+                let ptr = self
+                    .pool
+                    .map_or(core::ptr::null_mut(), |pool| pool.allocate(layout));
+                if ptr.is_null() {
+                    RT_POOL_EXHAUSTED.add_shared(1);
+                } else {
+                    RT_POOL_ALLOCATIONS.add_shared(1);
+                }
+                Some(ptr)
+            }
+        }
+    }
+}
+
+// SAFETY: calls either go to the wrapped allocator unchanged or are served
+// by the pool, whose blocks satisfy the layouts it accepts; `dealloc` and
+// `realloc` route pool blocks back to the pool.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for RtZoneAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match self.rt_alloc(layout) {
+            Some(ptr) => ptr,
+            None => unsafe { self.inner.alloc(layout) },
+        }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        match self.rt_alloc(layout) {
+            Some(ptr) => {
+                if !ptr.is_null() {
+                    // SAFETY: the pool block holds at least `layout.size()` bytes.
+                    unsafe { ptr.write_bytes(0, layout.size()) };
+                }
+                ptr
+            }
+            None => unsafe { self.inner.alloc_zeroed(layout) },
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if self.pooled(ptr).is_none() && (!in_rt() || self.policy == RtPolicy::Count) {
+            if in_rt() {
+                RT_ALLOCATIONS.add_shared(1);
+            }
+            return unsafe { self.inner.realloc(ptr, layout, new_size) };
+        }
+        // SAFETY: `new_size` with the old alignment is valid per the
+        // `GlobalAlloc::realloc` contract.
+        let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+        let new = unsafe { self.alloc(new_layout) };
+        if !new.is_null() {
+            // SAFETY: both blocks are live and hold at least the copied length.
+            unsafe {
+                core::ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+                self.dealloc(ptr, layout);
+            }
+        }
+        new
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        match self.pooled(ptr) {
+            Some(pool) => pool.release(ptr),
+            None => unsafe { self.inner.dealloc(ptr, layout) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static POOL: BlockPool<64, 70, 2> = BlockPool::new();
+
+    fn layout(size: usize) -> Layout {
+        Layout::from_size_align(size, 8).unwrap()
+    }
+
+    #[test]
+    fn test_zones_nest() {
+        assert!(!in_rt());
+        enter_rt();
+        enter_rt();
+        exit_rt();
+        assert!(in_rt());
+        exit_rt();
+        assert!(!in_rt());
+    }
+
+    #[test]
+    fn test_deny_and_pass_through() {
+        let alloc = RtZoneAlloc::system(RtPolicy::Deny);
+        unsafe {
+            let outside = alloc.alloc(layout(32));
+            assert!(!outside.is_null());
+            let denied_before = RT_DENIED.get();
+            enter_rt();
+            let inside = alloc.alloc(layout(32));
+            // Freeing is always allowed.
+            alloc.dealloc(outside, layout(32));
+            exit_rt();
+            assert!(inside.is_null());
+            assert!(RT_DENIED.get() > denied_before);
+        }
+    }
+
+    #[test]
+    fn test_pool_serves_rt_zone() {
+        let alloc = RtZoneAlloc::system(RtPolicy::Pool).with_pool(&POOL);
+        unsafe {
+            enter_rt();
+            let a = alloc.alloc(layout(48));
+            let b = alloc.alloc_zeroed(layout(64));
+            let too_big = alloc.alloc(layout(65));
+            exit_rt();
+            assert!(POOL.owns(a) && POOL.owns(b));
+            assert_eq!(*b, 0);
+            assert!(too_big.is_null());
+            assert_eq!(POOL.in_use(), 2);
+
+            // A pool block freed or grown outside the zone leaves the pool.
+            a.write(7);
+            let grown = alloc.realloc(a, layout(48), 256);
+            assert!(!POOL.owns(grown));
+            assert_eq!(*grown, 7);
+            alloc.dealloc(grown, layout(256));
+            alloc.dealloc(b, layout(64));
+            assert_eq!(POOL.in_use(), 0);
+        }
+    }
+
+    #[test]
+    fn test_pool_exhaustion() {
+        static SMALL: BlockPool<16, 3, 1> = BlockPool::new();
+        let blocks: Vec<*mut u8> = (0..4).map(|_| SMALL.allocate(layout(8))).collect();
+        assert!(blocks[..3].iter().all(|b| !b.is_null()));
+        assert!(blocks[3].is_null());
+        SMALL.release(blocks[1]);
+        assert_eq!(SMALL.allocate(layout(8)), blocks[1]);
+
+        // More bitmap words than blocks need.
+        static SPARE: BlockPool<16, 2, 2> = BlockPool::new();
+        let blocks: Vec<*mut u8> = (0..3).map(|_| SPARE.allocate(layout(8))).collect();
+        assert!(blocks[2].is_null());
+        assert_eq!(SPARE.in_use(), 2);
+    }
+
+    #[test]
+    fn test_pool_block_alignment() {
+        static ODD: BlockPool<24, 4, 1> = BlockPool::new();
+        let aligned = Layout::from_size_align(24, 16).unwrap();
+        let blocks: Vec<*mut u8> = (0..4).map(|_| ODD.allocate(aligned)).collect();
+        assert!(
+            blocks
+                .iter()
+                .all(|b| !b.is_null() && (*b as usize).is_multiple_of(16))
+        );
+        assert!(blocks.iter().all(|b| ODD.owns(*b)));
+        ODD.release(blocks[3]);
+        assert_eq!(ODD.in_use(), 3);
+        assert_eq!(ODD.allocate(aligned), blocks[3]);
+    }
+}
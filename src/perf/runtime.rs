@@ -0,0 +1,251 @@
+//! Jitter and deadline-miss tracking for periodic real-time loops.
+//!
+//! The hot side owns a `JitterTracker` and calls `begin()`/`end()` with
+//! timestamps from any monotonic tick source. Results land in a `LoopStats`
+//! made of fixed-size counters that the non-RT side reads via `snapshot()`.
+//!
+//! Counters are written by a single context with plain relaxed loads and
+//! stores (no read-modify-write), so the tracker passes `AtomicCheck`.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// Number of log2 buckets in the jitter histogram.
+pub const JITTER_BUCKETS: usize = 16;
+
+/// Shared loop statistics, written by the RT side and read by the non-RT side.
+pub struct LoopStats {
+    iterations: AtomicU32,
+    missed_deadlines: AtomicU32,
+    max_jitter: AtomicU32,
+    last_exec: AtomicU32,
+    max_exec: AtomicU32,
+    min_exec: AtomicU32,
+    jitter_histogram: [AtomicU32; JITTER_BUCKETS],
+}
+
+/// Point-in-time copy of `LoopStats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopSnapshot {
+    pub iterations: u32,
+    pub missed_deadlines: u32,
+    pub max_jitter: u32,
+    pub last_exec: u32,
+    pub max_exec: u32,
+    pub min_exec: u32,
+    /// Bucket `i` counts start jitter in `[2^i - 1, 2^(i+1) - 1)` ticks.
+    pub jitter_histogram: [u32; JITTER_BUCKETS],
+}
+
+impl LoopStats {
+    pub const fn new() -> Self {
+        Self {
+            iterations: AtomicU32::new(0),
+            missed_deadlines: AtomicU32::new(0),
+            max_jitter: AtomicU32::new(0),
+            last_exec: AtomicU32::new(0),
+            max_exec: AtomicU32::new(0),
+            min_exec: AtomicU32::new(u32::MAX),
+            jitter_histogram: [const { AtomicU32::new(0) }; JITTER_BUCKETS],
+        }
+    }
+
+    /// Reads all counters. Individual fields are consistent, the set is not.
+    pub fn snapshot(&self) -> LoopSnapshot {
+        let mut jitter_histogram = [0; JITTER_BUCKETS];
+        for (out, bucket) in jitter_histogram.iter_mut().zip(&self.jitter_histogram) {
+            *out = bucket.load(Ordering::Relaxed);
+        }
+        LoopSnapshot {
+            iterations: self.iterations.load(Ordering::Relaxed),
+            missed_deadlines: self.missed_deadlines.load(Ordering::Relaxed),
+            max_jitter: self.max_jitter.load(Ordering::Relaxed),
+            last_exec: self.last_exec.load(Ordering::Relaxed),
+            max_exec: self.max_exec.load(Ordering::Relaxed),
+            min_exec: self.min_exec.load(Ordering::Relaxed),
+            jitter_histogram,
+        }
+    }
+}
+
+impl Default for LoopStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Single-writer increment; only valid from the owning RT context.
+#[inline(always)]
+fn bump(counter: &AtomicU32) {
+    let value = counter.load(Ordering::Relaxed);
+    counter.store(value.saturating_add(1), Ordering::Relaxed);
+}
+
+#[inline(always)]
+fn store_max(counter: &AtomicU32, value: u32) {
+    if value > counter.load(Ordering::Relaxed) {
+        counter.store(value, Ordering::Relaxed);
+    }
+}
+
+#[inline(always)]
+fn store_min(counter: &AtomicU32, value: u32) {
+    if value < counter.load(Ordering::Relaxed) {
+        counter.store(value, Ordering::Relaxed);
+    }
+}
+
+#[inline(always)]
+fn saturate(ticks: u64) -> u32 {
+    if ticks > u32::MAX as u64 {
+        u32::MAX
+    } else {
+        ticks as u32
+    }
+}
+
+#[inline(always)]
+fn histogram_bucket(jitter: u32) -> usize {
+    let bucket = (u32::BITS - (jitter.saturating_add(1)).leading_zeros() - 1) as usize;
+    if bucket < JITTER_BUCKETS {
+        bucket
+    } else {
+        JITTER_BUCKETS - 1
+    }
+}
+
+/// Hot-side tracker for a loop with a fixed period and relative deadline (in ticks).
+pub struct JitterTracker<'a> {
+    stats: &'a LoopStats,
+    period: u64,
+    deadline: u64,
+    release: u64,
+    start: u64,
+    started: bool,
+}
+
+impl<'a> JitterTracker<'a> {
+    pub const fn new(stats: &'a LoopStats, period: u64, deadline: u64) -> Self {
+        Self {
+            stats,
+            period,
+            deadline,
+            release: 0,
+            start: 0,
+            started: false,
+        }
+    }
+
+    /// Records the start of an iteration. The first call anchors the release grid.
+    #[inline]
+    pub fn begin(&mut self, now: u64) {
+        if !self.started {
+            self.release = now;
+            self.started = true;
+        } else {
+            self.release = self.release.wrapping_add(self.period);
+        }
+
+        self.start = now;
+        let jitter = saturate(now.abs_diff(self.release));
+        store_max(&self.stats.max_jitter, jitter);
+        bump(&self.stats.jitter_histogram[histogram_bucket(jitter)]);
+    }
+
+    /// Records the end of the iteration started by the last `begin()`.
+    #[inline]
+    pub fn end(&mut self, now: u64) {
+        let exec = saturate(now.wrapping_sub(self.start));
+        self.stats.last_exec.store(exec, Ordering::Relaxed);
+        store_max(&self.stats.max_exec, exec);
+        store_min(&self.stats.min_exec, exec);
+
+        if now.wrapping_sub(self.release) > self.deadline {
+            bump(&self.stats.missed_deadlines);
+        }
+        bump(&self.stats.iterations);
+    }
+
+    /// Re-anchors the release grid at the next `begin()`, e.g. after a pause.
+    pub fn resync(&mut self) {
+        self.started = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_time_loop() {
+        let stats = LoopStats::new();
+        let mut tracker = JitterTracker::new(&stats, 100, 100);
+        for i in 0..10 {
+            let start = i * 100;
+            tracker.begin(start);
+            tracker.end(start + 20);
+        }
+        let snap = stats.snapshot();
+        assert_eq!(snap.iterations, 10);
+        assert_eq!(snap.missed_deadlines, 0);
+        assert_eq!(snap.max_jitter, 0);
+        assert_eq!(snap.max_exec, 20);
+        assert_eq!(snap.min_exec, 20);
+        assert_eq!(snap.jitter_histogram[0], 10);
+    }
+
+    #[test]
+    fn test_jitter_measured_against_grid() {
+        let stats = LoopStats::new();
+        let mut tracker = JitterTracker::new(&stats, 100, 100);
+        tracker.begin(0);
+        tracker.end(10);
+        tracker.begin(107);
+        tracker.end(120);
+        tracker.begin(198);
+        tracker.end(210);
+        let snap = stats.snapshot();
+        assert_eq!(snap.max_jitter, 7);
+        assert_eq!(snap.jitter_histogram[0], 1);
+        assert_eq!(snap.jitter_histogram[1], 1);
+        assert_eq!(snap.jitter_histogram[3], 1);
+    }
+
+    #[test]
+    fn test_missed_deadline() {
+        let stats = LoopStats::new();
+        let mut tracker = JitterTracker::new(&stats, 100, 50);
+        tracker.begin(0);
+        tracker.end(40);
+        tracker.begin(130);
+        tracker.end(160);
+        let snap = stats.snapshot();
+        assert_eq!(snap.iterations, 2);
+        assert_eq!(snap.missed_deadlines, 1);
+        assert_eq!(snap.last_exec, 30);
+        assert_eq!(snap.min_exec, 30);
+        assert_eq!(snap.max_exec, 40);
+    }
+
+    #[test]
+    fn test_resync() {
+        let stats = LoopStats::new();
+        let mut tracker = JitterTracker::new(&stats, 100, 100);
+        tracker.begin(0);
+        tracker.end(10);
+        tracker.resync();
+        tracker.begin(5000);
+        tracker.end(5010);
+        let snap = stats.snapshot();
+        assert_eq!(snap.max_jitter, 0);
+        assert_eq!(snap.missed_deadlines, 0);
+    }
+
+    #[test]
+    fn test_histogram_bucket() {
+        assert_eq!(histogram_bucket(0), 0);
+        assert_eq!(histogram_bucket(1), 1);
+        assert_eq!(histogram_bucket(2), 1);
+        assert_eq!(histogram_bucket(3), 2);
+        assert_eq!(histogram_bucket(u32::MAX), JITTER_BUCKETS - 1);
+    }
+}
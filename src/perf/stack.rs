@@ -0,0 +1,169 @@
+//! Stack high-water-mark measurement by painting and scanning.
+//!
+//! The region is filled with `PAINT` before the code under test runs and
+//! scanned afterwards; the lowest overwritten word marks the deepest point
+//! the stack reached. Assumes a descending stack.
+//!
+//! Bare metal: call `paint_stack()` early on the region between the linker's
+//! stack limit and the current stack pointer, then `stack_high_water()` from
+//! a monitor task. Hosted: `measure_stack_usage()` does both around a closure.
+
+/// Fill pattern for unused stack words.
+pub const PAINT: u32 = 0xCDCD_CDCD;
+
+/// Fills `words` 32-bit words starting at `bottom` with `PAINT`.
+///
+/// # Safety
+/// The region must be valid for writes and not in use by any live frame.
+pub unsafe fn paint_stack(bottom: *mut u32, words: usize) {
+    for i in 0..words {
+        unsafe { core::ptr::write_volatile(bottom.add(i), PAINT) };
+    }
+}
+
+/// Returns the bytes used of a painted region of `words` words starting at `bottom`.
+///
+/// # Safety
+/// The region must be valid for reads and have been painted by `paint_stack()`.
+pub unsafe fn stack_high_water(bottom: *const u32, words: usize) -> usize {
+    let mut untouched = 0;
+    while untouched < words && unsafe { core::ptr::read_volatile(bottom.add(untouched)) } == PAINT {
+        untouched += 1;
+    }
+    (words - untouched) * core::mem::size_of::<u32>()
+}
+
+/// Default depth painted below the caller by `measure_stack_usage()`.
+#[cfg(feature = "std")]
+pub const DEFAULT_MEASURE_DEPTH: usize = 64 * 1024;
+
+/// Bytes left between the measuring frame and the painted region.
+#[cfg(feature = "std")]
+const FRAME_MARGIN: usize = 512;
+
+#[cfg(feature = "std")]
+#[inline(always)]
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    core::hint::black_box(&marker) as *const u8 as usize
+}
+
+/// Paints `depth` bytes ending `FRAME_MARGIN` below `top`.
+///
+/// # Safety
+/// The region must lie within the current thread's stack mapping.
+#[cfg(feature = "std")]
+#[inline(never)]
+unsafe fn paint_below(top: usize, depth: usize) -> *mut u32 {
+    let start = FRAME_MARGIN
+        .checked_add(depth)
+        .and_then(|below| top.checked_sub(below))
+        .expect("paint depth exceeds the address space below the stack pointer");
+    let bottom = (start & !(core::mem::align_of::<u32>() - 1)) as *mut u32;
+    // SAFETY: the area lies below every live frame and, per the caller,
+    // within the thread's stack mapping.
+    unsafe { paint_stack(bottom, depth / core::mem::size_of::<u32>()) };
+    bottom
+}
+
+#[cfg(feature = "std")]
+#[inline(never)]
+fn run<R>(f: impl FnOnce() -> R) -> R {
+    core::hint::black_box(f())
+}
+
+/// Runs `f` and returns its result plus the approximate peak stack bytes it used.
+///
+/// Paints `DEFAULT_MEASURE_DEPTH` bytes below the caller; usage beyond that saturates.
+/// Threads with less stack to spare need `measure_stack_usage_with`.
+///
+/// # Safety
+/// As for `measure_stack_usage_with(DEFAULT_MEASURE_DEPTH, f)`: that many
+/// bytes plus the margin below the caller's frame must be within the
+/// current thread's stack.
+#[cfg(feature = "std")]
+pub unsafe fn measure_stack_usage<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    // SAFETY: forwarded to the caller.
+    unsafe { measure_stack_usage_with(DEFAULT_MEASURE_DEPTH, f) }
+}
+
+/// Like `measure_stack_usage()` with an explicit paint depth in bytes.
+///
+/// # Safety
+/// `depth` bytes plus a 512-byte margin below the caller's frame must be
+/// within the current thread's stack; anything mapped below it (a guard
+/// page, another thread's stack, the heap) would be overwritten.
+///
+/// # Panics
+/// If `depth` reaches below address zero.
+#[cfg(feature = "std")]
+#[inline(never)]
+pub unsafe fn measure_stack_usage_with<R>(depth: usize, f: impl FnOnce() -> R) -> (R, usize) {
+    let top = stack_pointer();
+    let words = depth / core::mem::size_of::<u32>();
+    // SAFETY: the caller guarantees the region is within the stack.
+    let bottom = unsafe { paint_below(top, depth) };
+    let result = run(f);
+    // SAFETY: the region was painted above and is still within the stack mapping.
+    let used = unsafe { stack_high_water(bottom, words) };
+    let overhead = if used == 0 { 0 } else { FRAME_MARGIN };
+    (result, used + overhead)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paint_and_scan() {
+        let mut region = [0u32; 64];
+        unsafe { paint_stack(region.as_mut_ptr(), region.len()) };
+        assert_eq!(
+            unsafe { stack_high_water(region.as_ptr(), region.len()) },
+            0
+        );
+
+        // Stack grows down: the top words are touched first.
+        region[60] = 0;
+        assert_eq!(
+            unsafe { stack_high_water(region.as_ptr(), region.len()) },
+            4 * 4
+        );
+        region[10] = 0;
+        assert_eq!(
+            unsafe { stack_high_water(region.as_ptr(), region.len()) },
+            54 * 4
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(never)]
+    fn use_stack(bytes: usize) -> u8 {
+        let buffer = [1u8; 8192];
+        let buffer = core::hint::black_box(buffer);
+        buffer[..bytes]
+            .iter()
+            .fold(0u8, |acc, b| acc.wrapping_add(*b))
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_measure_stack_usage() {
+        // SAFETY: the test thread's stack (2 MiB by default) has room for
+        // `DEFAULT_MEASURE_DEPTH` below this frame.
+        let (small, small_used) = unsafe { measure_stack_usage(|| core::hint::black_box(1 + 1)) };
+        let (big, big_used) = unsafe { measure_stack_usage(|| use_stack(8192)) };
+        assert_eq!(small, 2);
+        assert_eq!(big, 0);
+        assert!(big_used >= 8192, "measured {big_used}");
+        assert!(big_used > small_used);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "paint depth exceeds")]
+    fn test_measure_depth_below_zero() {
+        // Panics before anything is written.
+        let _ = unsafe { measure_stack_usage_with(usize::MAX - 64, || ()) };
+    }
+}
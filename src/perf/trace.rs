@@ -0,0 +1,269 @@
+//! Hot-region tracing into lock-free SPSC rings.
+//!
+//! Each core (or hot thread) owns one `TraceRing`. The hot side records
+//! `(id, timestamp)` events through a `Tracer` with `trace_enter!`/`trace_exit!`;
+//! the non-RT side drains the ring through a `TraceDrain` and rebuilds nested
+//! spans with `TimelineDecoder`. Recording is a cycle-counter read plus one
+//! slot write, so it stays clear of the allocation and atomic checks.
+
+use crate::perf::clock;
+use crate::sync::{Consumer, Producer, SpscQueue};
+
+/// Ring buffer holding trace events for one producer context.
+pub type TraceRing<const N: usize> = SpscQueue<TraceEvent, N>;
+
+/// Whether an event opens or closes a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKind {
+    Enter,
+    Exit,
+}
+
+/// Single trace record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceEvent {
+    pub id: u32,
+    pub kind: TraceKind,
+    pub timestamp: u64,
+}
+
+/// Hot-side handle for recording into a `TraceRing`.
+pub struct Tracer<'a, const N: usize> {
+    producer: Producer<'a, TraceEvent, N>,
+    dropped: u32,
+}
+
+impl<'a, const N: usize> Tracer<'a, N> {
+    pub fn new(producer: Producer<'a, TraceEvent, N>) -> Self {
+        Self {
+            producer,
+            dropped: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn record(&mut self, id: u32, kind: TraceKind, timestamp: u64) {
+        if self
+            .producer
+            .push(TraceEvent {
+                id,
+                kind,
+                timestamp,
+            })
+            .is_err()
+        {
+            self.dropped = self.dropped.saturating_add(1);
+        }
+    }
+
+    #[inline(always)]
+    pub fn enter(&mut self, id: u32) {
+        self.record(id, TraceKind::Enter, clock::cycles());
+    }
+
+    #[inline(always)]
+    pub fn exit(&mut self, id: u32) {
+        self.record(id, TraceKind::Exit, clock::cycles());
+    }
+
+    /// Number of events lost because the ring was full.
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+/// Non-RT handle for draining a `TraceRing`.
+pub struct TraceDrain<'a, const N: usize> {
+    consumer: Consumer<'a, TraceEvent, N>,
+}
+
+impl<'a, const N: usize> TraceDrain<'a, N> {
+    pub fn new(consumer: Consumer<'a, TraceEvent, N>) -> Self {
+        Self { consumer }
+    }
+}
+
+impl<const N: usize> Iterator for TraceDrain<'_, N> {
+    type Item = TraceEvent;
+
+    fn next(&mut self) -> Option<TraceEvent> {
+        self.consumer.pop()
+    }
+}
+
+/// Splits a ring into its hot-side tracer and non-RT drain.
+pub fn split<const N: usize>(ring: &mut TraceRing<N>) -> (Tracer<'_, N>, TraceDrain<'_, N>) {
+    let (producer, consumer) = ring.split();
+    (Tracer::new(producer), TraceDrain::new(consumer))
+}
+
+/// Completed region reconstructed from an enter/exit pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub id: u32,
+    pub start: u64,
+    pub end: u64,
+    /// Nesting depth, 0 for top-level regions.
+    pub depth: usize,
+}
+
+impl Span {
+    pub fn duration(&self) -> u64 {
+        self.end.wrapping_sub(self.start)
+    }
+}
+
+/// Decoding problems that indicate a dropped or mismatched event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// Exit without a matching enter.
+    UnmatchedExit(TraceEvent),
+    /// Exit for a region other than the innermost open one.
+    MismatchedExit { expected: u32, found: TraceEvent },
+    /// More nested regions than the decoder can track.
+    TooDeep(TraceEvent),
+}
+
+/// Rebuilds nested spans from an event stream, tracking up to `DEPTH` open regions.
+pub struct TimelineDecoder<const DEPTH: usize> {
+    open: [(u32, u64); DEPTH],
+    depth: usize,
+}
+
+impl<const DEPTH: usize> TimelineDecoder<DEPTH> {
+    pub const fn new() -> Self {
+        Self {
+            open: [(0, 0); DEPTH],
+            depth: 0,
+        }
+    }
+
+    /// Number of regions currently open.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Feeds one event, returning a span when a region closes.
+    pub fn feed(&mut self, event: TraceEvent) -> Result<Option<Span>, DecodeError> {
+        match event.kind {
+            TraceKind::Enter => {
+                if self.depth == DEPTH {
+                    return Err(DecodeError::TooDeep(event));
+                }
+                self.open[self.depth] = (event.id, event.timestamp);
+                self.depth += 1;
+                Ok(None)
+            }
+            TraceKind::Exit => {
+                if self.depth == 0 {
+                    return Err(DecodeError::UnmatchedExit(event));
+                }
+                let (id, start) = self.open[self.depth - 1];
+                if id != event.id {
+                    return Err(DecodeError::MismatchedExit {
+                        expected: id,
+                        found: event,
+                    });
+                }
+                self.depth -= 1;
+                Ok(Some(Span {
+                    id,
+                    start,
+                    end: event.timestamp,
+                    depth: self.depth,
+                }))
+            }
+        }
+    }
+
+    /// Discards all open regions, e.g. after a decode error.
+    pub fn reset(&mut self) {
+        self.depth = 0;
+    }
+}
+
+impl<const DEPTH: usize> Default for TimelineDecoder<DEPTH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: u32, kind: TraceKind, timestamp: u64) -> TraceEvent {
+        TraceEvent {
+            id,
+            kind,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_record_and_drain() {
+        let mut ring: TraceRing<8> = TraceRing::new();
+        let (mut tracer, drain) = split(&mut ring);
+        tracer.enter(1);
+        tracer.exit(1);
+        let events: [TraceEvent; 2] = {
+            let mut it = drain;
+            [it.next().unwrap(), it.next().unwrap()]
+        };
+        assert_eq!(events[0].id, 1);
+        assert_eq!(events[0].kind, TraceKind::Enter);
+        assert_eq!(events[1].kind, TraceKind::Exit);
+        assert!(events[1].timestamp >= events[0].timestamp);
+    }
+
+    #[test]
+    fn test_full_ring_counts_drops() {
+        let mut ring: TraceRing<2> = TraceRing::new();
+        let (mut tracer, _drain) = split(&mut ring);
+        tracer.enter(1);
+        tracer.enter(2);
+        tracer.enter(3);
+        assert_eq!(tracer.dropped(), 1);
+    }
+
+    #[test]
+    fn test_decode_nested() {
+        let mut decoder: TimelineDecoder<4> = TimelineDecoder::new();
+        assert_eq!(decoder.feed(event(1, TraceKind::Enter, 10)), Ok(None));
+        assert_eq!(decoder.feed(event(2, TraceKind::Enter, 20)), Ok(None));
+        let inner = decoder
+            .feed(event(2, TraceKind::Exit, 25))
+            .unwrap()
+            .unwrap();
+        assert_eq!(inner.id, 2);
+        assert_eq!(inner.depth, 1);
+        assert_eq!(inner.duration(), 5);
+        let outer = decoder
+            .feed(event(1, TraceKind::Exit, 40))
+            .unwrap()
+            .unwrap();
+        assert_eq!(outer.depth, 0);
+        assert_eq!(outer.duration(), 30);
+        assert_eq!(decoder.depth(), 0);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut decoder: TimelineDecoder<1> = TimelineDecoder::new();
+        let exit = event(1, TraceKind::Exit, 0);
+        assert_eq!(decoder.feed(exit), Err(DecodeError::UnmatchedExit(exit)));
+
+        decoder.feed(event(1, TraceKind::Enter, 0)).unwrap();
+        let deep = event(2, TraceKind::Enter, 1);
+        assert_eq!(decoder.feed(deep), Err(DecodeError::TooDeep(deep)));
+
+        let wrong = event(3, TraceKind::Exit, 2);
+        assert_eq!(
+            decoder.feed(wrong),
+            Err(DecodeError::MismatchedExit {
+                expected: 1,
+                found: wrong
+            })
+        );
+    }
+}
@@ -3,18 +3,132 @@
 //! Verifies functions marked with `mark_hot!` by analyzing LLVM IR for
 //! real-time safety violations and performance issues.
 //!
+//! C code can join the hot set by placing a name string, a pointer to one,
+//! or a pointer to the function itself in a `hot_funcs` section; symbols
+//! with no record at all are added with `HotPathVerifier::add_function`.
+//!
 //! Use `HotPathVerifier` with custom checks or `verify_hot_function()` for defaults.
 
-use std::collections::HashSet;
+pub mod alias;
+pub mod asm;
+pub mod attestation;
+pub mod batch;
+pub mod blocks;
+pub mod cache;
+pub mod calls;
+pub mod cost;
+pub mod coverage;
+pub mod debug_info;
+pub mod explain;
+pub mod flycheck;
+pub mod future;
+pub mod fuzz;
+pub mod gpu;
+pub mod index;
+pub mod loops;
+pub mod observer;
+pub mod outcome;
+pub mod overhead;
+pub mod parallel;
+pub mod parse_error;
+pub mod perf_data;
+pub mod policy;
+pub mod registry;
+pub mod server;
+pub mod sharing;
+pub mod symbol_map;
+pub mod target;
+pub mod trend;
+pub mod wasm;
+pub mod watch;
+
+pub use alias::{PointerParam, noalias_advisories, pointer_params};
+pub use asm::{
+    Arch, CodeSize, CodeSizeCheck, RegisterPressure, code_sizes, register_pressure,
+    verify_register_pressure,
+};
+pub use attestation::Attestation;
+pub use batch::BatchReport;
+pub use cache::{CacheStats, VerifyCache};
+pub use calls::{CallReport, Callee, demangle};
+pub use cost::{FunctionCost, cost_report};
+pub use coverage::{FunctionCoverage, unexercised};
+pub use debug_info::{InlinedFrame, SourceLocation};
+pub use explain::{CheckDoc, explain};
+pub use future::poll_functions;
+pub use gpu::{GpuIsa, kernel_entries, verify_gpu};
+pub use loops::{LoopInfo, LoopReport, loop_report};
+pub use observer::VerifyObserver;
+pub use outcome::Outcome;
+pub use overhead::verify_zero_overhead;
+pub use parse_error::{ParseError, parse_errors};
+pub use perf_data::{HotCandidate, SymbolSamples};
+pub use policy::FunctionPolicy;
+pub use registry::{CheckRegistration, CheckRegistry};
+pub use server::Server;
+pub use sharing::{FalseSharingCheck, StaticWrite};
+pub use symbol_map::{SymbolEntry, SymbolMap};
+pub use target::TargetProfile;
+pub use trend::{Regression, TrendRecord};
+pub use wasm::{WasmFunction, verify_wasm};
+pub use watch::{Delta, IrWatcher};
+
+use std::boxed::Box;
+use std::collections::{HashMap, HashSet};
 use std::string::{String, ToString};
 use std::vec::Vec;
-use std::boxed::Box;
 
-/// Check severity: Error (hard fail) or Warning (performance note).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Check severity, ordered from advisory to fatal so thresholds compare
+/// with `>=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Severity {
-    Error,
+    /// Advisory analysis (layout, branch weights); never fails a run.
+    Info,
+    /// Performance note.
     Warning,
+    /// Hard fail.
+    Error,
+    /// Hard fail that outranks errors, for violations that must never ship.
+    Critical,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Critical => "critical",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Severity::Info,
+            Severity::Warning,
+            Severity::Error,
+            Severity::Critical,
+        ]
+        .into_iter()
+        .find(|severity| severity.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Whether a violation of this severity fails verification.
+    pub fn is_failure(self) -> bool {
+        self >= Severity::Error
+    }
+}
+
+/// One check firing on one instruction of a hot function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub function: String,
+    /// `HotPathCheck::name()`, or `"missing"` if the function is not in the IR.
+    pub check: String,
+    pub severity: Severity,
+    pub message: String,
+    /// From the instruction's debug location, else the `mark_hot!` marker.
+    pub location: Option<SourceLocation>,
 }
 
 /// Trait for hot path verification checks.
@@ -22,6 +136,39 @@ pub trait HotPathCheck: Send + Sync {
     fn name(&self) -> &str;
     fn severity(&self) -> Severity;
     fn check_line(&self, line: &str) -> Option<String>;
+
+    /// Whole-body analysis returning `(line index, message)` pairs. Checks
+    /// that need control flow override this; the default runs `check_line`
+    /// on every line.
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        body.lines()
+            .enumerate()
+            .filter_map(|(index, line)| self.check_line(line).map(|message| (index, message)))
+            .collect()
+    }
+
+    /// Analysis that needs the function's own symbol (as written after `@`,
+    /// unquoted), e.g. to recognise calls back into it. The default ignores
+    /// the symbol and runs `check_body`.
+    fn check_function(&self, symbol: &str, body: &str) -> Vec<(usize, String)> {
+        let _ = symbol;
+        self.check_body(body)
+    }
+
+    /// Name of a more general check whose finding on `line` this check
+    /// replaces, e.g. `"function_call"` for a call it reports with a more
+    /// specific hint. The verifier drops that finding when both checks are
+    /// registered, also where this one is allowed.
+    fn supersedes(&self, symbol: &str, line: &str) -> Option<&str> {
+        let _ = (symbol, line);
+        None
+    }
+
+    /// Distinguishes differently configured instances of one check; part of
+    /// the `VerifyCache` key.
+    fn config(&self) -> &str {
+        ""
+    }
 }
 
 /// Check for memory allocations.
@@ -49,6 +196,41 @@ impl HotPathCheck for AllocationCheck {
     }
 }
 
+/// Check for heap deallocation.
+///
+/// Freeing is as unbounded as allocating: the allocator may coalesce, take
+/// a lock or return pages to the OS. Besides direct calls to `free` and
+/// `__rust_dealloc`, an out-of-line `drop_in_place` of an `alloc` type
+/// (`Box`, `Vec`, `String`, `Rc`, ...) is reported, since that is where a
+/// dropped owner frees its storage.
+pub struct DeallocationCheck;
+impl DeallocationCheck {
+    const CALLEES: [&'static str; 4] = ["free", "cfree", "__rust_dealloc", "alloc::alloc::dealloc"];
+
+    fn is_deallocation(line: &str) -> bool {
+        direct_callee(line).is_some_and(|callee| {
+            Self::CALLEES.contains(&callee.as_str())
+                || callee.contains("drop_in_place<alloc::")
+                || callee.contains("drop_in_place::<alloc::")
+        })
+    }
+}
+impl HotPathCheck for DeallocationCheck {
+    fn name(&self) -> &str {
+        "deallocation"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        Self::is_deallocation(line)
+            .then(|| "contains deallocation (real-time violation)".to_string())
+    }
+    fn supersedes(&self, _symbol: &str, line: &str) -> Option<&str> {
+        Self::is_deallocation(line).then_some("function_call")
+    }
+}
+
 /// Check for atomic operations.
 pub struct AtomicCheck;
 impl HotPathCheck for AtomicCheck {
@@ -85,16 +267,74 @@ impl HotPathCheck for IndirectionCheck {
     }
 }
 
+/// Which `llvm.*` intrinsic calls `FunctionCallCheck` accepts.
+///
+/// Most intrinsics become inline instructions, but some are lowered to
+/// library calls depending on target and operands (`llvm.pow`, `llvm.exp`,
+/// long `llvm.memcpy`). Names are given without `llvm.` and match whole
+/// dot-separated components: `pow` covers `llvm.pow.f32` but not
+/// `llvm.powi.f32`. Intrinsics that emit no code (`dbg`, `lifetime`,
+/// `assume`, `expect`) are always accepted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum IntrinsicPolicy {
+    /// Every intrinsic is accepted.
+    #[default]
+    Allow,
+    /// Every intrinsic except the listed ones is accepted.
+    AllowExcept(Vec<String>),
+    /// Only the listed intrinsics are accepted.
+    DenyExcept(Vec<String>),
+}
+impl IntrinsicPolicy {
+    const NO_CODE: [&'static str; 4] = ["dbg", "lifetime", "assume", "expect"];
+
+    /// Whether `intrinsic` (a callee without `llvm.`) is accepted.
+    pub fn allows(&self, intrinsic: &str) -> bool {
+        match self {
+            IntrinsicPolicy::Allow => true,
+            IntrinsicPolicy::AllowExcept(denied) => !Self::listed(denied, intrinsic),
+            IntrinsicPolicy::DenyExcept(allowed) => {
+                Self::listed(allowed, intrinsic) || Self::listed(&Self::NO_CODE, intrinsic)
+            }
+        }
+    }
+
+    fn listed(names: &[impl AsRef<str>], intrinsic: &str) -> bool {
+        names.iter().any(|name| {
+            let name = name.as_ref();
+            let name = name.strip_prefix("llvm.").unwrap_or(name);
+            intrinsic == name
+                || intrinsic
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+}
+
 /// Check for non-inlined function calls.
+///
+/// `llvm.*` intrinsics are accepted; `FunctionCallCheck::with_intrinsics`
+/// gives a check that accepts only those its `IntrinsicPolicy` allows.
 pub struct FunctionCallCheck;
-impl HotPathCheck for FunctionCallCheck {
-    fn name(&self) -> &str {
-        "function_call"
-    }
-    fn severity(&self) -> Severity {
-        Severity::Error
+impl FunctionCallCheck {
+    /// The check with `intrinsics` deciding which `llvm.*` calls pass.
+    pub fn with_intrinsics(intrinsics: IntrinsicPolicy) -> FunctionCallCheckWith {
+        let config = match &intrinsics {
+            IntrinsicPolicy::Allow => String::new(),
+            policy => format!("{:?}", policy),
+        };
+        FunctionCallCheckWith { intrinsics, config }
     }
-    fn check_line(&self, line: &str) -> Option<String> {
+
+    fn call(line: &str, intrinsics: &IntrinsicPolicy) -> Option<String> {
+        if let Some(intrinsic) = callee_symbol(line).and_then(|c| c.strip_prefix("llvm.")) {
+            return (!intrinsics.allows(intrinsic)).then(|| {
+                format!(
+                    "calls intrinsic llvm.{} (may be lowered to a library call)",
+                    intrinsic
+                )
+            });
+        }
         if line.contains("call") && !line.contains("@llvm.") {
             // Skip if it's an allocation (handled by AllocationCheck)
             if line.contains("@malloc")
@@ -104,12 +344,236 @@ impl HotPathCheck for FunctionCallCheck {
             {
                 return None;
             }
+            if is_indirect_call(line) {
+                return Some(
+                    "contains indirect function call through a pointer (InlineFn/dyn Fn dispatch cannot be inlined)"
+                        .to_string(),
+                );
+            }
             Some("contains function call (not inlined)".to_string())
         } else {
             None
         }
     }
 }
+impl HotPathCheck for FunctionCallCheck {
+    fn name(&self) -> &str {
+        "function_call"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        Self::call(line, &IntrinsicPolicy::Allow)
+    }
+}
+
+/// `FunctionCallCheck` with an `IntrinsicPolicy`, from
+/// `FunctionCallCheck::with_intrinsics(..)`; reported under the same name.
+pub struct FunctionCallCheckWith {
+    intrinsics: IntrinsicPolicy,
+    config: String,
+}
+impl HotPathCheck for FunctionCallCheckWith {
+    fn name(&self) -> &str {
+        FunctionCallCheck.name()
+    }
+    fn severity(&self) -> Severity {
+        FunctionCallCheck.severity()
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        FunctionCallCheck::call(line, &self.intrinsics)
+    }
+    fn config(&self) -> &str {
+        &self.config
+    }
+}
+
+/// Check for direct recursion: calls from a function back into itself.
+///
+/// Each level takes another stack frame, and the number of levels depends
+/// on the input, so neither stack depth nor worst-case execution time has a
+/// static bound. Self tail calls that LLVM turned into a loop no longer
+/// appear as calls and are not reported. Recursion through other functions
+/// is not detected.
+pub struct RecursionCheck;
+impl HotPathCheck for RecursionCheck {
+    fn name(&self) -> &str {
+        "recursion"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
+    }
+    fn check_function(&self, symbol: &str, body: &str) -> Vec<(usize, String)> {
+        body.lines()
+            .enumerate()
+            .filter(|(_, line)| callee_symbol(line) == Some(symbol))
+            .map(|(index, _)| {
+                (
+                    index,
+                    "calls itself (direct recursion: stack depth and WCET are unbounded)"
+                        .to_string(),
+                )
+            })
+            .collect()
+    }
+    fn supersedes(&self, symbol: &str, line: &str) -> Option<&str> {
+        (callee_symbol(line) == Some(symbol)).then_some("function_call")
+    }
+}
+
+/// Check for libm calls: transcendental functions, `pow`, `sqrt` and
+/// relatives, called directly (`@sinf`) or through an `llvm.*` intrinsic
+/// that the target lowers to one (`llvm.pow.f32` becomes `powf`).
+///
+/// Without hardware support each is a software routine of tens to hundreds
+/// of cycles, often with data-dependent branches. `llvm.sqrt` is a single
+/// instruction on cores with an FPU; allow the check per function there.
+/// An error, as these calls were when `FunctionCallCheck` reported them;
+/// wrap it in `WithSeverity` to make it a warning.
+pub struct LibmCheck;
+impl LibmCheck {
+    const FUNCTIONS: [&'static str; 28] = [
+        "sin", "cos", "tan", "asin", "acos", "atan", "atan2", "sinh", "cosh", "tanh", "asinh",
+        "acosh", "atanh", "sincos", "exp", "exp2", "exp10", "expm1", "log", "log2", "log10",
+        "log1p", "pow", "sqrt", "cbrt", "hypot", "fmod", "erf",
+    ];
+
+    /// libm function `line` calls, as `sinf` or `llvm.pow.f32`.
+    fn function(line: &str) -> Option<&str> {
+        let callee = callee_symbol(line)?;
+        let known = match callee.strip_prefix("llvm.") {
+            Some(intrinsic) => {
+                let name = intrinsic.split('.').next()?;
+                name != "powi" && Self::FUNCTIONS.contains(&name)
+            }
+            // `sinf`, `sinl` and `sin`; `erff` is `erf` in single precision.
+            None => {
+                let base = callee
+                    .strip_suffix(['f', 'l'])
+                    .filter(|base| Self::FUNCTIONS.contains(base));
+                base.is_some() || Self::FUNCTIONS.contains(&callee)
+            }
+        };
+        known.then_some(callee)
+    }
+}
+impl HotPathCheck for LibmCheck {
+    fn name(&self) -> &str {
+        "libm"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        let function = Self::function(line)?;
+        Some(if function.starts_with("llvm.") {
+            format!(
+                "{} (lowered to a libm call without hardware support, tens to hundreds of cycles)",
+                function
+            )
+        } else {
+            format!(
+                "calls libm {} (software routine, tens to hundreds of cycles)",
+                function
+            )
+        })
+    }
+    fn supersedes(&self, _symbol: &str, line: &str) -> Option<&str> {
+        Self::function(line).map(|_| "function_call")
+    }
+}
+
+/// Check for comparison and length scans lowered to libc calls.
+///
+/// Slice and `str` equality become `bcmp`/`memcmp` when the length is not a
+/// small constant, and C-string handling calls `strlen`. These are reported
+/// separately from other calls because the fix is in the data layout.
+pub struct LibcCompareCheck;
+impl LibcCompareCheck {
+    const CALLEES: [(&'static str, &'static str); 4] = [
+        (
+            "@bcmp(",
+            "slice equality lowered to bcmp (compare fixed-size arrays or u64 chunks so it inlines)",
+        ),
+        (
+            "@memcmp(",
+            "ordering comparison lowered to memcmp (compare fixed-size arrays or u64 chunks; memcmp is not constant-time)",
+        ),
+        (
+            "@strlen(",
+            "C string length scan via strlen (carry the length, e.g. &str or a fixed buffer with a len field)",
+        ),
+        (
+            "@strcmp(",
+            "C string comparison via strcmp (compare known-length byte slices instead)",
+        ),
+    ];
+
+    fn callee(line: &str) -> Option<&'static str> {
+        if !line.contains("call ") {
+            return None;
+        }
+        Self::CALLEES
+            .iter()
+            .find(|(symbol, _)| line.contains(symbol))
+            .map(|(_, hint)| *hint)
+    }
+}
+impl HotPathCheck for LibcCompareCheck {
+    fn name(&self) -> &str {
+        "libc_compare"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        Self::callee(line).map(str::to_string)
+    }
+    fn supersedes(&self, _symbol: &str, line: &str) -> Option<&str> {
+        Self::callee(line).map(|_| "function_call")
+    }
+}
+
+/// `call <ty> %reg(...)`: the callee is a value, not a symbol.
+fn is_indirect_call(line: &str) -> bool {
+    let Some(pos) = line.find("call ") else {
+        return false;
+    };
+    line[pos + 5..]
+        .split(['(', '@'])
+        .next()
+        .is_some_and(|callee| callee.contains('%'))
+}
+
+/// Demangled symbol called by a `call`/`invoke` line; `None` for other
+/// lines and indirect calls.
+fn direct_callee(line: &str) -> Option<String> {
+    callee_symbol(line).map(demangle)
+}
+
+/// Symbol called by a `call`/`invoke` line as written, without `@` and
+/// quotes.
+fn callee_symbol(line: &str) -> Option<&str> {
+    let code = line.split_once(" = ").map_or(line, |(_, code)| code).trim();
+    let is_call = [
+        "call ",
+        "tail call ",
+        "musttail call ",
+        "notail call ",
+        "invoke ",
+    ]
+    .iter()
+    .any(|prefix| code.starts_with(prefix));
+    if !is_call {
+        return None;
+    }
+    let symbol = code.split('@').nth(1)?.split('(').next()?;
+    Some(symbol.trim_matches('"'))
+}
 
 /// Check for volatile loads.
 pub struct VolatileLoadCheck;
@@ -205,100 +669,2069 @@ impl HotPathCheck for NonInboundsGepCheck {
     }
 }
 
-/// Verifier for hot path functions with configurable checks.
-pub struct HotPathVerifier {
-    checks: Vec<Box<dyn HotPathCheck>>,
+/// Advisory: conditional branches into panic blocks without branch weights.
+///
+/// Without `!prof` metadata the block layout is left to LLVM's heuristics;
+/// `unlikely!`/`cold_path!` make the hot fallthrough explicit. Not part of
+/// any profile; add it with `with_check` or pick it up via the registry.
+pub struct BranchHintCheck;
+impl HotPathCheck for BranchHintCheck {
+    fn name(&self) -> &str {
+        "branch_hint"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let blocks = blocks::split(body);
+        let panics: HashSet<&str> = blocks
+            .iter()
+            .filter(|block| blocks::is_panic_block(block))
+            .map(|block| block.label)
+            .collect();
+        let mut hits = Vec::new();
+        for block in &blocks {
+            let Some(offset) = block.lines.iter().rposition(|line| {
+                let line = line.trim();
+                line.starts_with("br i1 ") && !line.contains("!prof")
+            }) else {
+                continue;
+            };
+            let targets = blocks::labels(block.lines[offset]);
+            if let Some(cold) = targets.iter().find(|label| panics.contains(*label)) {
+                hits.push((
+                    block.first + offset,
+                    format!(
+                        "conditional branch to panic block %{} has no branch weights (use unlikely!/cold_path!)",
+                        cold
+                    ),
+                ));
+            }
+        }
+        hits
+    }
 }
 
-impl HotPathVerifier {
-    pub fn new() -> Self {
-        Self { checks: Vec::new() }
+/// Check for `alloca` inside loop bodies.
+///
+/// Entry-block allocas are part of the fixed frame; one in a loop (usually a
+/// variable-length buffer or a scope the optimizer could not hoist) grows
+/// the stack on every iteration until the function returns.
+pub struct AllocaInLoopCheck;
+impl HotPathCheck for AllocaInLoopCheck {
+    fn name(&self) -> &str {
+        "alloca_in_loop"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
     }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let loops = loops::analyze(body, &debug_info::DebugInfo::parse(""));
+        let mut hits = Vec::new();
+        for block in blocks::split(body) {
+            let Some(header) = loops
+                .iter()
+                .filter(|l| l.contains(block.label))
+                .max_by_key(|l| l.depth)
+                .map(|l| l.header.as_str())
+            else {
+                continue;
+            };
+            for (offset, line) in block.lines.iter().enumerate() {
+                if line.contains(" = alloca ") {
+                    hits.push((
+                        block.first + offset,
+                        format!(
+                            "alloca inside loop %{} grows the stack every iteration",
+                            header
+                        ),
+                    ));
+                }
+            }
+        }
+        hits
+    }
+}
 
-    pub fn with_check(mut self, check: Box<dyn HotPathCheck>) -> Self {
-        self.checks.push(check);
-        self
+/// Check for trapping paths: `llvm.trap`, `abort`, and noreturn calls
+/// followed by `unreachable`.
+///
+/// These usually come from overflow checks, bounds checks or asserts that
+/// were meant to be compiled out. When the trapping block has a single
+/// conditional predecessor, the message names the branch condition.
+pub struct TrapCheck;
+impl TrapCheck {
+    const TRAPS: [&'static str; 4] = [
+        "@llvm.trap(",
+        "@llvm.ubsantrap(",
+        "@abort(",
+        "@llvm.debugtrap(",
+    ];
+
+    /// What the block traps with, if anything.
+    fn trap(block: &blocks::Block<'_>) -> Option<(usize, String)> {
+        let code: Vec<(usize, &str)> = block
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(offset, line)| (offset, line.trim()))
+            .filter(|(_, l)| !l.is_empty() && !l.starts_with(';') && !l.starts_with("#dbg"))
+            .collect();
+        if let Some((offset, line)) = code
+            .iter()
+            .find(|(_, line)| Self::TRAPS.iter().any(|trap| line.contains(trap)))
+        {
+            let name = Self::TRAPS
+                .iter()
+                .find(|trap| line.contains(*trap))
+                .map(|trap| trap.trim_matches(['@', '(']))
+                .unwrap_or_default();
+            return Some((*offset, name.to_string()));
+        }
+        match code.as_slice() {
+            [.., (offset, call), (_, "unreachable")] if call.contains("call ") => {
+                let callee = call
+                    .split('@')
+                    .nth(1)
+                    .and_then(|rest| rest.split('(').next())
+                    .unwrap_or("indirect callee");
+                Some((*offset, format!("noreturn call to {}", demangle(callee))))
+            }
+            _ => None,
+        }
     }
 
-    pub fn with_default_checks(self) -> Self {
-        self.with_check(Box::new(IndirectionCheck))
-            .with_check(Box::new(AllocationCheck))
-            .with_check(Box::new(FunctionCallCheck))
-            .with_check(Box::new(AtomicCheck))
-            .with_check(Box::new(VolatileLoadCheck))
-            .with_check(Box::new(VolatileStoreCheck))
-            .with_check(Box::new(DivisionCheck))
-            .with_check(Box::new(UnalignedAccessCheck))
-            .with_check(Box::new(NonInboundsGepCheck))
+    /// `%cond is true|false (<definition>)` for the single branch into `label`.
+    fn condition(body: &str, blocks: &[blocks::Block<'_>], label: &str) -> Option<String> {
+        let mut branches = blocks.iter().filter_map(|block| {
+            let terminator = block.terminator()?;
+            let targets = blocks::labels(terminator);
+            targets.contains(&label).then_some((terminator, targets))
+        });
+        let (terminator, targets) = branches.next()?;
+        if branches.next().is_some() {
+            return None;
+        }
+        let condition = terminator
+            .strip_prefix("br i1 %")?
+            .split(',')
+            .next()?
+            .trim();
+        let taken = if targets.first() == Some(&label) {
+            "true"
+        } else {
+            "false"
+        };
+        let definition = body.lines().find_map(|line| {
+            line.trim()
+                .strip_prefix(&format!("%{} = ", condition))
+                .map(str::trim)
+        });
+        Some(match definition {
+            Some(definition) => format!("%{} is {} ({})", condition, taken, definition),
+            None => format!("%{} is {}", condition, taken),
+        })
+    }
+}
+impl HotPathCheck for TrapCheck {
+    fn name(&self) -> &str {
+        "trap"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
     }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let blocks = blocks::split(body);
+        blocks
+            .iter()
+            .filter_map(|block| {
+                let (offset, what) = Self::trap(block)?;
+                let message = match Self::condition(body, &blocks, block.label) {
+                    Some(condition) => format!("reaches {} when {}", what, condition),
+                    None => format!("reaches {}", what),
+                };
+                Some((block.first + offset, message))
+            })
+            .collect()
+    }
+}
 
-    pub fn verify(&self, ir: &str, func_name: &str) -> Result<Vec<String>, String> {
-        let body = find_function_body(ir, func_name)?;
-        let mut warnings = Vec::new();
+/// Check for compiler-inserted arithmetic overflow checks.
+///
+/// `overflow-checks = true` (the debug default) turns `+`, `-` and `*` into
+/// `llvm.*.with.overflow` followed by a branch to a panic. Checked arithmetic
+/// written on purpose (`checked_add`, `overflowing_mul`) uses the same
+/// intrinsics but branches to ordinary code, and is not reported.
+pub struct OverflowCheck;
+impl OverflowCheck {
+    const INTRINSICS: [&'static str; 6] = ["sadd", "uadd", "ssub", "usub", "smul", "umul"];
 
-        for line in body.lines() {
-            for check in &self.checks {
-                if let Some(violation) = check.check_line(line) {
-                    match check.severity() {
-                        Severity::Error => {
-                            return Err(format!("{}: {}", func_name, violation));
-                        }
-                        Severity::Warning => {
-                            warnings.push(format!("{}: {}", func_name, violation));
+    fn intrinsic(line: &str) -> Option<&'static str> {
+        Self::INTRINSICS
+            .iter()
+            .find(|op| line.contains(&format!("@llvm.{}.with.overflow", op)))
+            .copied()
+    }
+}
+impl HotPathCheck for OverflowCheck {
+    fn name(&self) -> &str {
+        "overflow_check"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let blocks = blocks::split(body);
+        let panics: HashSet<&str> = blocks
+            .iter()
+            .filter(|block| blocks::is_panic_block(block))
+            .map(|block| block.label)
+            .collect();
+        let mut hits = Vec::new();
+        for (index, line) in body.lines().enumerate() {
+            let Some((result, rhs)) = line.trim().split_once(" = ") else {
+                continue;
+            };
+            let Some(op) = Self::intrinsic(rhs) else {
+                continue;
+            };
+            // `%flag = extractvalue {..} %result, 1` feeding `br i1 %flag` into a panic.
+            let flags: Vec<&str> = body
+                .lines()
+                .filter_map(|l| {
+                    let (flag, rhs) = l.trim().split_once(" = ")?;
+                    (rhs.starts_with("extractvalue ") && rhs.ends_with(&format!("{}, 1", result)))
+                        .then_some(flag)
+                })
+                .collect();
+            let panics_on_overflow = blocks.iter().any(|block| {
+                block.terminator().is_some_and(|terminator| {
+                    flags
+                        .iter()
+                        .any(|flag| terminator.starts_with(&format!("br i1 {},", flag)))
+                        && blocks::labels(terminator)
+                            .iter()
+                            .any(|label| panics.contains(label))
+                })
+            });
+            if panics_on_overflow {
+                hits.push((
+                    index,
+                    format!(
+                        "{} overflow check branches to a panic: overflow-checks is enabled for this profile (use wrapping_*/checked_* explicitly if intended)",
+                        op
+                    ),
+                ));
+            }
+        }
+        hits
+    }
+}
+
+/// Advisory: computations and stores in optimized IR that have no effect.
+///
+/// Flags side-effect-free values that are never used, stores overwritten in
+/// the same block before any possible read, and empty `asm sideeffect`
+/// barriers (`black_box`) that pin values the optimizer would otherwise drop.
+pub struct DeadCodeCheck;
+impl DeadCodeCheck {
+    const PURE: [&'static str; 27] = [
+        "add",
+        "sub",
+        "mul",
+        "fadd",
+        "fsub",
+        "fmul",
+        "fneg",
+        "shl",
+        "lshr",
+        "ashr",
+        "and",
+        "or",
+        "xor",
+        "icmp",
+        "fcmp",
+        "select",
+        "getelementptr",
+        "zext",
+        "sext",
+        "trunc",
+        "bitcast",
+        "fpext",
+        "fptrunc",
+        "extractelement",
+        "insertelement",
+        "shufflevector",
+        "phi",
+    ];
+
+    /// `%names` referenced by an instruction, excluding its own definition.
+    fn uses(line: &str) -> impl Iterator<Item = &str> {
+        let operands = line.split_once(" = ").map_or(line, |(_, rhs)| rhs);
+        operands.match_indices('%').filter_map(move |(pos, _)| {
+            let rest = &operands[pos + 1..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '-' | '$')))
+                .unwrap_or(rest.len());
+            (end > 0).then(|| &rest[..end])
+        })
+    }
+}
+impl HotPathCheck for DeadCodeCheck {
+    fn name(&self) -> &str {
+        "dead_code"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        line.contains("asm sideeffect \"\"").then(|| {
+            "optimization barrier (black_box) keeps otherwise dead values alive".to_string()
+        })
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let mut hits: Vec<(usize, String)> = body
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| self.check_line(line).map(|m| (index, m)))
+            .collect();
+
+        let used: HashSet<&str> = body.lines().flat_map(Self::uses).collect();
+        for (index, line) in body.lines().enumerate() {
+            let Some((name, rhs)) = line.trim().split_once(" = ") else {
+                continue;
+            };
+            let Some(name) = name.strip_prefix('%') else {
+                continue;
+            };
+            let op = rhs.split_whitespace().next().unwrap_or_default();
+            if Self::PURE.contains(&op) && !used.contains(name) {
+                hits.push((index, format!("value %{} is computed but never used", name)));
+            }
+        }
+
+        for block in blocks::split(body) {
+            // Pointer operand -> line of a store not yet observed.
+            let mut pending: Vec<(&str, usize)> = Vec::new();
+            for (offset, line) in block.lines.iter().enumerate() {
+                let line = line.trim();
+                if let Some(store) = line.strip_prefix("store ") {
+                    if store.starts_with("volatile") || store.starts_with("atomic") {
+                        pending.clear();
+                        continue;
+                    }
+                    let pointer = store
+                        .split(", ptr ")
+                        .nth(1)
+                        .and_then(|rest| rest.split(',').next())
+                        .map(str::trim);
+                    if let Some(pointer) = pointer {
+                        if let Some(pos) = pending.iter().position(|(p, _)| *p == pointer) {
+                            let (_, earlier) = pending.remove(pos);
+                            hits.push((
+                                earlier,
+                                format!("store to {} is overwritten before it is read", pointer),
+                            ));
                         }
+                        pending.push((pointer, block.first + offset));
                     }
+                } else if line.contains("load ")
+                    || line.contains("call ")
+                    || line.contains("invoke ")
+                    || line.contains("fence")
+                    || line.contains("atomicrmw")
+                    || line.contains("cmpxchg")
+                {
+                    pending.clear();
                 }
             }
         }
-
-        Ok(warnings)
+        hits.sort_by_key(|&(index, _)| index);
+        hits
     }
 }
 
-impl Default for HotPathVerifier {
-    fn default() -> Self {
-        Self::new().with_default_checks()
+/// Advisory: operations the configured core has no instruction for.
+///
+/// On ARMv6-M, `ctlz`/`cttz` become `__clzsi2`-style libcalls, 64-bit
+/// variable shifts become `__aeabi_llsl` and friends, and 64-bit multiplies
+/// become `__aeabi_lmul`. Division and floating point have their own checks
+/// (`DivisionCheck`, `SoftFloatCheck`).
+pub struct TargetLoweringCheck {
+    target: TargetProfile,
+}
+impl TargetLoweringCheck {
+    pub fn new(target: TargetProfile) -> Self {
+        Self { target }
+    }
+}
+impl HotPathCheck for TargetLoweringCheck {
+    fn name(&self) -> &str {
+        "target_lowering"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        let target = &self.target;
+        let code = line.trim();
+        let rhs = code.split_once(" = ").map_or(code, |(_, rhs)| rhs);
+        for (intrinsic, available, what) in [
+            ("@llvm.ctlz", target.has_clz, "count-leading-zeros"),
+            ("@llvm.cttz", target.has_ctz, "count-trailing-zeros"),
+            ("@llvm.ctpop", target.has_popcount, "population count"),
+        ] {
+            if !available && rhs.contains(intrinsic) {
+                return Some(format!(
+                    "{} has no instruction on {} (lowers to a libcall or loop)",
+                    what, target.name
+                ));
+            }
+        }
+        let mut words = rhs.split_whitespace();
+        let op = words.next()?;
+        match op {
+            "shl" | "lshr" | "ashr" if cost::is_emulated(op, rhs, target) => {
+                let amount = rhs.rsplit(',').next()?.trim();
+                amount.starts_with('%').then(|| {
+                    format!(
+                        "variable shift wider than {} bits lowers to a libcall on {}",
+                        target.word_bits, target.name
+                    )
+                })
+            }
+            "mul" if cost::is_emulated(op, rhs, target) => Some(format!(
+                "multiply wider than {} bits lowers to a libcall on {}",
+                target.word_bits, target.name
+            )),
+            _ => None,
+        }
+    }
+    fn config(&self) -> &str {
+        self.target.name
     }
 }
 
-/// Verifies hot path functions from LLVM IR content using default checks.
-pub fn verify_hot_path_functions(ir: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let verifier = HotPathVerifier::default();
-    let hot_funcs = find_hot_functions_from_ir(ir);
+/// Check for floating-point operations the configured core does in software.
+///
+/// Without an FPU (`thumbv6m`, `thumbv7m`) each `fadd`, `fmul`, `fdiv`,
+/// `fcmp` and conversion becomes a compiler-rt call of tens of cycles; a
+/// single-precision FPU (Cortex-M4F) still leaves `double` to software.
+/// `fneg` only flips the sign bit and is not reported. Not part of any
+/// profile: add it with `HotPathVerifier::with_soft_float` for the builds
+/// of such targets.
+pub struct SoftFloatCheck {
+    target: TargetProfile,
+}
+impl SoftFloatCheck {
+    pub fn new(target: TargetProfile) -> Self {
+        Self { target }
+    }
 
-    for func in hot_funcs {
-        verifier.verify(ir, &func).map_err(|e| e)?;
+    /// Whether `ty` (or the element type of a vector) has no FPU support.
+    fn soft(&self, ty: &str) -> bool {
+        match ty {
+            "half" | "bfloat" | "float" => !self.target.has_fpu_f32,
+            "double" => !self.target.has_fpu_f64,
+            "fp128" | "x86_fp80" | "ppc_fp128" => true,
+            _ => false,
+        }
     }
 
-    Ok(())
+    /// First type in `words`, past fast-math flags; `<4 x float>` gives
+    /// `float`.
+    fn operand_type<'a>(mut words: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+        let ty = words.find(|w| {
+            !matches!(
+                *w,
+                "fast" | "nnan" | "ninf" | "nsz" | "arcp" | "contract" | "afn" | "reassoc"
+            )
+        })?;
+        if ty.starts_with('<') {
+            words.nth(1).map(|element| element.trim_end_matches('>'))
+        } else {
+            Some(ty)
+        }
+    }
 }
-
-/// Discovers hot functions from LLVM IR `.hot_funcs` section.
-pub fn find_hot_functions_from_ir(ir: &str) -> HashSet<String> {
-    use regex::Regex;
-    let mut hot_funcs = HashSet::new();
-
-    // Find .hot_funcs entries with ptr @alloc_*
-    let re_ptr = Regex::new(r#"ptr\s+(@alloc_\w+).*section\s+"\.hot_funcs""#).unwrap();
-
-    for cap in re_ptr.captures_iter(ir) {
-        if let Some(alloc_ref) = cap.get(1) {
-            // Find allocation: @alloc_HASH = ... c"function_name\00"
-            let alloc_pattern = format!(
-                r#"{}\s*=.*?c"([^"]+)\\00""#,
-                regex::escape(alloc_ref.as_str())
-            );
-            if let Ok(re_alloc) = Regex::new(&alloc_pattern) {
-                if let Some(alloc_cap) = re_alloc.captures(ir) {
-                    if let Some(func_name) = alloc_cap.get(1) {
-                        hot_funcs.insert(func_name.as_str().to_string());
-                    }
+impl HotPathCheck for SoftFloatCheck {
+    fn name(&self) -> &str {
+        "soft_float"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        let code = line.trim();
+        let rhs = code.split_once(" = ").map_or(code, |(_, rhs)| rhs);
+        let mut words = rhs.split_whitespace();
+        let op = words.next()?;
+        let ty = match op {
+            "fadd" | "fsub" | "fmul" | "fdiv" | "frem" | "fptosi" | "fptoui" => {
+                Self::operand_type(words)?
+            }
+            "fcmp" => {
+                let types = words.clone();
+                let predicate = Self::operand_type(words)?;
+                if matches!(predicate, "true" | "false") {
+                    return None;
                 }
+                Self::operand_type(types.skip_while(|w| *w != predicate).skip(1))?
             }
-        }
+            "sitofp" | "uitofp" => {
+                Self::operand_type(rhs.split_once(" to ")?.1.split_whitespace())?
+            }
+            "fpext" | "fptrunc" => {
+                let from = Self::operand_type(words)?;
+                let to = Self::operand_type(rhs.split_once(" to ")?.1.split_whitespace())?;
+                if self.soft(from) { from } else { to }
+            }
+            _ => return None,
+        };
+        self.soft(ty).then(|| {
+            format!(
+                "`{}` on {} is a compiler-rt call on {} (no FPU support)",
+                op, ty, self.target.name
+            )
+        })
+    }
+    fn config(&self) -> &str {
+        self.target.name
+    }
+}
+
+/// Requirement: floating-point multiply-adds were fused.
+///
+/// Enabled per function with `mark_hot!(f, require(fma))`. Flags every
+/// `fmul` whose result feeds an `fadd`/`fsub` unless both carry `contract`
+/// (or `fast`), in which case the backend may still fuse them. A kernel
+/// written with `mul_add` shows up as `llvm.fma`/`llvm.fmuladd` instead, so
+/// a finding usually means a `-C target-feature=+fma` or fast-math setting
+/// was lost.
+pub struct FmaCheck;
+impl FmaCheck {
+    fn contractable(rhs: &str) -> bool {
+        rhs.split_whitespace()
+            .take_while(|word| !word.starts_with('%'))
+            .any(|flag| matches!(flag, "contract" | "fast"))
     }
+}
+impl HotPathCheck for FmaCheck {
+    fn name(&self) -> &str {
+        "fma"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let products: Vec<(usize, &str, bool)> = body
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let (name, rhs) = line.trim().split_once(" = ")?;
+                let rhs = rhs.strip_prefix("fmul ")?;
+                Some((index, name, Self::contractable(rhs)))
+            })
+            .collect();
+        let mut hits = Vec::new();
+        for (index, product, contract) in products {
+            let feeds_add = body.lines().find_map(|line| {
+                let rhs = line.trim().split_once(" = ")?.1;
+                let operands = rhs
+                    .strip_prefix("fadd ")
+                    .or_else(|| rhs.strip_prefix("fsub "))?;
+                let uses = operands.split([' ', ',']).any(|operand| operand == product);
+                uses.then_some(Self::contractable(operands))
+            });
+            if let Some(add_contract) = feeds_add
+                && !(contract && add_contract)
+            {
+                hits.push((
+                    index,
+                    format!(
+                        "{} feeds an fadd/fsub but was not fused into llvm.fma (missing +fma target feature or contraction)",
+                        product
+                    ),
+                ));
+            }
+        }
+        hits
+    }
+}
+
+/// Opt-in checks by `require(..)` name.
+fn required_check(name: &str) -> Option<Box<dyn HotPathCheck>> {
+    match name {
+        "fma" => Some(Box::new(FmaCheck)),
+        _ => None,
+    }
+}
+
+/// Check for `requires!`/`ensures!` machinery left in the function.
+///
+/// In release builds contracts lower to `llvm.assume` hints and leave no
+/// code; a call to the contract panic path means the function was built
+/// with `debug_assertions` or the condition could not be folded away.
+pub struct ContractCheck;
+impl ContractCheck {
+    fn is_violation_call(line: &str) -> bool {
+        line.contains("call") && line.contains("8contract8violated")
+    }
+}
+impl HotPathCheck for ContractCheck {
+    fn name(&self) -> &str {
+        "contract"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        Self::is_violation_call(line)
+            .then(|| "contains a contract check (built with debug_assertions?)".to_string())
+    }
+    fn supersedes(&self, _symbol: &str, line: &str) -> Option<&str> {
+        Self::is_violation_call(line).then_some("function_call")
+    }
+}
+
+/// Check for calls into the panic machinery.
+///
+/// `core::panicking::*` (`panic`, `panic_fmt`, `panic_bounds_check`, the
+/// `panic_const_*` family), `Option`/`Result` `expect_failed` and
+/// `unwrap_failed`: any of them pulls message formatting and unwinding onto
+/// the hot path. Reported once per call site, so a bounds check that could
+/// not be hoisted shows up next to the slice access it guards.
+pub struct PanicPathCheck;
+impl PanicPathCheck {
+    const FAILURES: [&'static str; 4] = [
+        "expect_failed",
+        "unwrap_failed",
+        "begin_panic",
+        "rust_begin_unwind",
+    ];
+
+    /// Demangled callee if `line` calls panic machinery.
+    fn callee(line: &str) -> Option<String> {
+        let callee = direct_callee(line)?;
+        let panicking =
+            callee.starts_with("core::panicking::") || callee.starts_with("std::panicking::");
+        let last = callee.rsplit("::").next().unwrap_or(&callee);
+        (panicking || Self::FAILURES.contains(&last)).then_some(callee)
+    }
+
+    /// Whether `callee` is the panic of a coroutine resumed after it
+    /// completed (`_panic` and `_drop` variants included).
+    fn is_resumed(callee: &str) -> bool {
+        const RESUMED: [&str; 4] = [
+            "panic_const_async_fn_resumed",
+            "panic_const_async_gen_fn_resumed",
+            "panic_const_gen_fn_none",
+            "panic_const_coroutine_resumed",
+        ];
+        let last = callee.rsplit("::").next().unwrap_or(callee);
+        RESUMED.iter().any(|resumed| last.starts_with(resumed))
+    }
+}
+impl HotPathCheck for PanicPathCheck {
+    fn name(&self) -> &str {
+        "panic_path"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        Self::callee(line).map(|callee| {
+            format!(
+                "calls panic machinery {} (formatting and unwinding on the hot path)",
+                callee
+            )
+        })
+    }
+    fn supersedes(&self, _symbol: &str, line: &str) -> Option<&str> {
+        Self::callee(line).map(|_| "function_call")
+    }
+}
+
+/// `PanicPathCheck` for `async fn` state machines, as in `Profile::Async`.
+///
+/// Every state machine panics when it is polled again after completing
+/// (`panic_const_async_fn_resumed` and its `gen`/coroutine forms); that
+/// call is accepted. Other panic machinery is reported as by
+/// `PanicPathCheck`, under the same name.
+pub struct AsyncPanicPathCheck;
+impl HotPathCheck for AsyncPanicPathCheck {
+    fn name(&self) -> &str {
+        PanicPathCheck.name()
+    }
+    fn severity(&self) -> Severity {
+        PanicPathCheck.severity()
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        let callee = PanicPathCheck::callee(line)?;
+        if PanicPathCheck::is_resumed(&callee) {
+            return None;
+        }
+        PanicPathCheck.check_line(line)
+    }
+    fn supersedes(&self, symbol: &str, line: &str) -> Option<&str> {
+        PanicPathCheck.supersedes(symbol, line)
+    }
+    fn config(&self) -> &str {
+        "async"
+    }
+}
+
+/// Check for floating-point arithmetic and conversions.
+///
+/// In an interrupt handler the first FP instruction makes a Cortex-M core
+/// with lazy stacking save the FP context; without an FPU the operation is
+/// a soft-float call instead.
+pub struct FloatingPointCheck;
+impl HotPathCheck for FloatingPointCheck {
+    fn name(&self) -> &str {
+        "floating_point"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        const OPS: &[&str] = &[
+            "fadd", "fsub", "fmul", "fdiv", "frem", "fneg", "fcmp", "fptrunc", "fpext", "fptoui",
+            "fptosi", "uitofp", "sitofp",
+        ];
+        let rhs = line.split_once(" = ").map_or(line.trim(), |(_, rhs)| rhs);
+        let op = rhs.split_whitespace().next()?;
+        if OPS.contains(&op) {
+            return Some(format!("contains floating-point `{}`", op));
+        }
+        (line.contains("call") && (line.contains(".f32(") || line.contains(".f64(")))
+            .then(|| "contains floating-point intrinsic".to_string())
+    }
+}
+
+/// Check for any call, including allocation, libc and memory intrinsics.
+///
+/// Stricter than `FunctionCallCheck`: nothing may leave the function. Only
+/// intrinsics that never become code (debug info, lifetimes, assumptions)
+/// are accepted.
+pub struct CallCheck;
+impl HotPathCheck for CallCheck {
+    fn name(&self) -> &str {
+        "call"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        const FREE: &[&str] = &[
+            "@llvm.dbg.",
+            "@llvm.lifetime.",
+            "@llvm.assume",
+            "@llvm.expect",
+        ];
+        let rhs = line.split_once(" = ").map_or(line.trim(), |(_, rhs)| rhs);
+        let is_call = [
+            "call ",
+            "tail call ",
+            "musttail call ",
+            "notail call ",
+            "invoke ",
+        ]
+        .iter()
+        .any(|prefix| rhs.starts_with(prefix));
+        if !is_call || FREE.iter().any(|intrinsic| line.contains(intrinsic)) {
+            return None;
+        }
+        let callee = line
+            .split('@')
+            .nth(1)
+            .and_then(|rest| rest.split('(').next())
+            .unwrap_or("through a pointer");
+        Some(format!("calls {}", callee))
+    }
+}
+
+/// Check that a body stays within an instruction budget.
+///
+/// Bounds the work done per call (or per `poll` of a state machine) where a
+/// worst case matters more than an average.
+pub struct InstructionCountCheck {
+    limit: usize,
+    config: String,
+}
+impl InstructionCountCheck {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            config: limit.to_string(),
+        }
+    }
+}
+impl HotPathCheck for InstructionCountCheck {
+    fn name(&self) -> &str {
+        "instruction_count"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let count = body.lines().filter(|l| cost::is_instruction(l)).count();
+        if count > self.limit {
+            std::vec![(
+                0,
+                format!("body has {} IR instructions (limit {})", count, self.limit),
+            )]
+        } else {
+            Vec::new()
+        }
+    }
+    fn config(&self) -> &str {
+        &self.config
+    }
+}
+
+/// Check for single stack allocations larger than `limit` bytes.
+///
+/// Interrupt and signal stacks are small (often 1-2 KiB) and are not
+/// guarded, so one large local array overflows them silently. Each `alloca`
+/// is sized from its IR type; one with a runtime element count is reported
+/// too, since its size has no bound at all. For the whole frame use
+/// `FunctionPolicy::stack_limit`.
+pub struct StackAllocaSizeCheck {
+    limit: u64,
+    config: String,
+}
+impl StackAllocaSizeCheck {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            config: limit.to_string(),
+        }
+    }
+}
+impl HotPathCheck for StackAllocaSizeCheck {
+    fn name(&self) -> &str {
+        "stack_alloca_size"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        let (ty, count) = policy::alloca(line)?;
+        let name = line.split(" = ").next().unwrap_or_default().trim();
+        let Some(count) = count else {
+            return Some(format!(
+                "dynamic alloca {} of {} elements has no size bound (limit {} bytes)",
+                name, ty, self.limit
+            ));
+        };
+        // `type_size` saturates, so an overflowing size is over any limit.
+        let size = policy::type_size(ty)?.saturating_mul(count);
+        if size == u64::MAX {
+            return Some(format!(
+                "alloca {} is larger than a 64-bit size can hold (limit {} bytes)",
+                name, self.limit
+            ));
+        }
+        (size > self.limit).then(|| {
+            format!(
+                "alloca {} of {} bytes exceeds the {}-byte limit",
+                name, size, self.limit
+            )
+        })
+    }
+    fn config(&self) -> &str {
+        &self.config
+    }
+}
+
+/// Check for `llvm.memcpy`, `llvm.memmove` and `llvm.memset`.
+///
+/// `FunctionCallCheck` accepts these intrinsics by default, but above a few
+/// dozen bytes the backend turns them into a copy loop or a libc call whose
+/// cost grows with the length. By default copies longer than
+/// `MEM_INLINE_BYTES` are warnings; `error_above` makes copies over a given
+/// size errors and leaves shorter ones alone. A length known only at run
+/// time is reported either way.
+pub struct MemIntrinsicCheck {
+    limit: Option<u64>,
+    config: String,
+}
+impl Default for MemIntrinsicCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MemIntrinsicCheck {
+    const INTRINSICS: [&'static str; 3] = ["memcpy", "memmove", "memset"];
+
+    pub fn new() -> Self {
+        Self {
+            limit: None,
+            config: String::new(),
+        }
+    }
+
+    /// Reports copies longer than `bytes` as errors instead.
+    pub fn error_above(bytes: u64) -> Self {
+        Self {
+            limit: Some(bytes),
+            config: bytes.to_string(),
+        }
+    }
+
+    /// Intrinsic name and length operand (`None` if not a constant) of a
+    /// `call @llvm.mem*` line.
+    fn intrinsic(line: &str) -> Option<(&str, Option<u64>)> {
+        let callee = callee_symbol(line)?.strip_prefix("llvm.")?;
+        let name = callee.split('.').next()?;
+        if !Self::INTRINSICS.contains(&name) {
+            return None;
+        }
+        // `(dst, src | value, length, is_volatile)`, after the callee.
+        let args = line.split_once(callee)?.1.strip_prefix('(')?;
+        let mut depth = 0;
+        let mut operands = Vec::new();
+        let mut start = 0;
+        for (at, c) in args.char_indices() {
+            match c {
+                '(' | '<' | '[' | '{' => depth += 1,
+                ')' | '>' | ']' | '}' if depth == 0 => break,
+                ')' | '>' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    operands.push(&args[start..at]);
+                    start = at + 1;
+                }
+                _ => {}
+            }
+        }
+        let length = operands.get(2)?.split_whitespace().last()?;
+        Some((name, length.parse().ok()))
+    }
+}
+impl HotPathCheck for MemIntrinsicCheck {
+    fn name(&self) -> &str {
+        "mem_intrinsic"
+    }
+    fn severity(&self) -> Severity {
+        if self.limit.is_some() {
+            Severity::Error
+        } else {
+            Severity::Warning
+        }
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        let (name, length) = Self::intrinsic(line)?;
+        let Some(length) = length else {
+            return Some(format!(
+                "llvm.{} with a length known only at run time (cost grows with the length)",
+                name
+            ));
+        };
+        match self.limit {
+            Some(limit) => (length > limit).then(|| {
+                format!(
+                    "llvm.{} of {} bytes exceeds the {}-byte limit",
+                    name, length, limit
+                )
+            }),
+            None => (length > MEM_INLINE_BYTES).then(|| {
+                format!(
+                    "llvm.{} of {} bytes (a copy loop or libc call, not a few moves)",
+                    name, length
+                )
+            }),
+        }
+    }
+    fn config(&self) -> &str {
+        &self.config
+    }
+}
+
+/// Check for long chains of dependent loads: a load whose address comes
+/// from a previous load (through `getelementptr`, `phi` or `select`), as
+/// in linked-list and tree walks. Each step waits for the previous one, so
+/// a chain of N misses costs N full memory latencies; per-line checks
+/// cannot see it. Chains longer than `depth` are reported once per body,
+/// and a chain carried around a loop (`node = node->next`) always is.
+pub struct PointerChaseCheck {
+    depth: usize,
+    config: String,
+}
+impl PointerChaseCheck {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth,
+            config: depth.to_string(),
+        }
+    }
+}
+impl HotPathCheck for PointerChaseCheck {
+    fn name(&self) -> &str {
+        "pointer_chase"
+    }
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+    fn check_line(&self, _line: &str) -> Option<String> {
+        None
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        let chains = load_chains(body);
+        let loads = chains.len();
+        let Some(&(line, depth)) = chains
+            .iter()
+            .max_by_key(|(line, depth)| (*depth, usize::MAX - line))
+        else {
+            return Vec::new();
+        };
+        // An acyclic chain cannot be longer than the number of loads.
+        if depth > loads {
+            std::vec![(
+                line,
+                "pointer chase carried around a loop: each load's address comes from the previous iteration's load".to_string(),
+            )]
+        } else if depth > self.depth {
+            std::vec![(
+                line,
+                format!("chain of {} dependent loads (limit {})", depth, self.depth),
+            )]
+        } else {
+            Vec::new()
+        }
+    }
+    fn config(&self) -> &str {
+        &self.config
+    }
+}
+
+/// `(line index, chain length)` of every load in `body`. Values flowing
+/// through `phi` are iterated to a fixed point, capped one past the number
+/// of loads so loop-carried chains show up as over-long.
+fn load_chains(body: &str) -> Vec<(usize, usize)> {
+    let lines: Vec<&str> = body.lines().map(str::trim).collect();
+    let cap = lines.iter().filter(|l| l.contains(" = load ")).count() + 1;
+    // Loads already behind each SSA pointer.
+    let mut depth: HashMap<&str, usize> = HashMap::new();
+    let mut chains = Vec::new();
+    fn value(operand: &str) -> Option<&str> {
+        let name = operand.split_whitespace().last()?;
+        name.starts_with('%').then_some(name)
+    }
+    for _ in 0..=cap {
+        let mut changed = false;
+        chains.clear();
+        for (index, line) in lines.iter().enumerate() {
+            let Some((result, code)) = line.split_once(" = ") else {
+                continue;
+            };
+            let of = |name: Option<&str>| name.and_then(|n| depth.get(n)).copied().unwrap_or(0);
+            let new = if let Some(load) = code.strip_prefix("load ") {
+                let load = load
+                    .trim_start_matches("atomic ")
+                    .trim_start_matches("volatile ");
+                let (_, pointer) = policy::split_type(load);
+                let (pointer, _) = policy::split_type(pointer);
+                let chain = (of(value(pointer)) + 1).min(cap);
+                chains.push((index, chain));
+                chain
+            } else if let Some(gep) = code.strip_prefix("getelementptr ") {
+                let (_, rest) = policy::split_type(gep);
+                of(value(policy::split_type(rest).0))
+            } else if code.starts_with("phi ptr") {
+                code.split('[')
+                    .skip(1)
+                    .map(|incoming| of(value(incoming.split(',').next().unwrap_or(""))))
+                    .max()
+                    .unwrap_or(0)
+            } else if let Some(select) = code.strip_prefix("select ") {
+                let (_, rest) = policy::split_type(select);
+                let (a, rest) = policy::split_type(rest);
+                of(value(a)).max(of(value(policy::split_type(rest).0)))
+            } else {
+                continue;
+            };
+            if new > 0 && depth.insert(result, new) != Some(new) {
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    chains
+}
+
+/// Runs `C` with a different severity, e.g. division as an error.
+pub struct WithSeverity<C>(pub C, pub Severity);
+impl<C: HotPathCheck> HotPathCheck for WithSeverity<C> {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+    fn severity(&self) -> Severity {
+        self.1
+    }
+    fn check_line(&self, line: &str) -> Option<String> {
+        self.0.check_line(line)
+    }
+    fn check_body(&self, body: &str) -> Vec<(usize, String)> {
+        self.0.check_body(body)
+    }
+    fn check_function(&self, symbol: &str, body: &str) -> Vec<(usize, String)> {
+        self.0.check_function(symbol, body)
+    }
+    fn supersedes(&self, symbol: &str, line: &str) -> Option<&str> {
+        self.0.supersedes(symbol, line)
+    }
+    fn config(&self) -> &str {
+        self.0.config()
+    }
+}
+
+/// IR instruction budget of one `poll` body under `Profile::Async`.
+pub const ASYNC_POLL_LIMIT: usize = 256;
+
+/// IR instruction budget of a body under `Profile::InterruptHandler`.
+pub const ISR_INSTRUCTION_LIMIT: usize = 128;
+
+/// Largest single stack allocation, in bytes, under `Profile::InterruptHandler`.
+pub const ISR_ALLOCA_LIMIT: u64 = 512;
+
+/// Longest `llvm.memcpy`/`memmove`/`memset` that `MemIntrinsicCheck`
+/// accepts by default; backends expand shorter ones into register moves.
+pub const MEM_INLINE_BYTES: u64 = 64;
+
+/// Named check sets for common use cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Audio/control loops: no allocation, calls (libm included), recursion,
+    /// unwinding, atomics, traps, overflow checks or allocas in loops; memory
+    /// and arithmetic costs (including long `llvm.memcpy`s) are warnings,
+    /// dead code an `Info` note. Same as
+    /// `with_default_checks`.
+    RealTime,
+    /// Crypto kernels: variable-latency division and calls into unverified
+    /// code are errors. Memory-cost checks are off.
+    ConstTime,
+    /// Small code: allocation, unwinding and the panic machinery pull in
+    /// large runtime pieces, as do libm routines; division and unaligned
+    /// access may become libcalls or byte sequences. Recursion is an error
+    /// here too, as its stack use has no bound.
+    CodeSize,
+    /// Cortex-M class targets: real-time rules plus division (no divider on
+    /// v6-M), unaligned access (faults) and atomics as errors. Volatile
+    /// access is expected (MMIO) and not checked.
+    Embedded,
+    /// `async fn` state machines: no allocation or recursion and a bounded
+    /// `poll` body.
+    /// Calls (sub-futures that were not inlined) and indirect or atomic
+    /// waker operations are warnings; the "resumed after completion" panic
+    /// every state machine carries is not reported (`AsyncPanicPathCheck`).
+    Async,
+    /// Interrupt service routines: on top of `RealTime`'s rules, no floating
+    /// point, no calls of any kind, a bounded instruction count and no
+    /// stack allocation over `ISR_ALLOCA_LIMIT` bytes. Usually
+    /// selected per function with `mark_hot!(f, tier = isr)`.
+    InterruptHandler,
+}
+
+impl Profile {
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::RealTime => "realtime",
+            Profile::ConstTime => "consttime",
+            Profile::CodeSize => "codesize",
+            Profile::Embedded => "embedded",
+            Profile::Async => "async",
+            Profile::InterruptHandler => "isr",
+        }
+    }
+
+    /// Inverse of `name`, case-insensitive.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Profile::RealTime,
+            Profile::ConstTime,
+            Profile::CodeSize,
+            Profile::Embedded,
+            Profile::Async,
+            Profile::InterruptHandler,
+        ]
+        .into_iter()
+        .find(|profile| profile.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Checks enabled by the profile, with its severities.
+    pub fn checks(self) -> Vec<Box<dyn HotPathCheck>> {
+        use Severity::{Error, Warning};
+        match self {
+            Profile::RealTime => std::vec![
+                Box::new(IndirectionCheck),
+                Box::new(AllocationCheck),
+                Box::new(DeallocationCheck),
+                Box::new(FunctionCallCheck),
+                Box::new(RecursionCheck),
+                Box::new(LibcCompareCheck),
+                Box::new(MemIntrinsicCheck::new()),
+                Box::new(LibmCheck),
+                Box::new(ContractCheck),
+                Box::new(PanicPathCheck),
+                Box::new(AtomicCheck),
+                Box::new(AllocaInLoopCheck),
+                Box::new(TrapCheck),
+                Box::new(OverflowCheck),
+                Box::new(VolatileLoadCheck),
+                Box::new(VolatileStoreCheck),
+                Box::new(DivisionCheck),
+                Box::new(UnalignedAccessCheck),
+                Box::new(NonInboundsGepCheck),
+                Box::new(DeadCodeCheck),
+            ],
+            Profile::ConstTime => std::vec![
+                Box::new(IndirectionCheck),
+                Box::new(AllocationCheck),
+                Box::new(DeallocationCheck),
+                Box::new(FunctionCallCheck),
+                Box::new(RecursionCheck),
+                Box::new(LibcCompareCheck),
+                Box::new(LibmCheck),
+                Box::new(ContractCheck),
+                Box::new(PanicPathCheck),
+                Box::new(WithSeverity(DivisionCheck, Error)),
+                Box::new(WithSeverity(AtomicCheck, Warning)),
+            ],
+            Profile::CodeSize => std::vec![
+                Box::new(IndirectionCheck),
+                Box::new(AllocationCheck),
+                Box::new(DeallocationCheck),
+                Box::new(RecursionCheck),
+                Box::new(LibmCheck),
+                Box::new(PanicPathCheck),
+                Box::new(DivisionCheck),
+                Box::new(UnalignedAccessCheck),
+                Box::new(DeadCodeCheck),
+            ],
+            Profile::Embedded => std::vec![
+                Box::new(IndirectionCheck),
+                Box::new(AllocationCheck),
+                Box::new(DeallocationCheck),
+                Box::new(FunctionCallCheck),
+                Box::new(RecursionCheck),
+                Box::new(LibcCompareCheck),
+                Box::new(MemIntrinsicCheck::new()),
+                Box::new(LibmCheck),
+                Box::new(ContractCheck),
+                Box::new(PanicPathCheck),
+                Box::new(AtomicCheck),
+                Box::new(AllocaInLoopCheck),
+                Box::new(TrapCheck),
+                Box::new(OverflowCheck),
+                Box::new(WithSeverity(DivisionCheck, Error)),
+                Box::new(WithSeverity(UnalignedAccessCheck, Error)),
+                Box::new(NonInboundsGepCheck),
+            ],
+            Profile::Async => std::vec![
+                Box::new(AllocationCheck),
+                Box::new(DeallocationCheck),
+                Box::new(RecursionCheck),
+                Box::new(LibcCompareCheck),
+                Box::new(AllocaInLoopCheck),
+                Box::new(OverflowCheck),
+                Box::new(InstructionCountCheck::new(ASYNC_POLL_LIMIT)),
+                Box::new(WithSeverity(IndirectionCheck, Warning)),
+                Box::new(WithSeverity(FunctionCallCheck, Warning)),
+                Box::new(WithSeverity(LibmCheck, Warning)),
+                Box::new(WithSeverity(ContractCheck, Warning)),
+                Box::new(WithSeverity(AsyncPanicPathCheck, Warning)),
+                Box::new(WithSeverity(AtomicCheck, Warning)),
+                Box::new(DivisionCheck),
+            ],
+            Profile::InterruptHandler => std::vec![
+                Box::new(IndirectionCheck),
+                Box::new(AllocationCheck),
+                Box::new(DeallocationCheck),
+                Box::new(CallCheck),
+                Box::new(RecursionCheck),
+                Box::new(FloatingPointCheck),
+                Box::new(AtomicCheck),
+                Box::new(AllocaInLoopCheck),
+                Box::new(TrapCheck),
+                Box::new(OverflowCheck),
+                Box::new(WithSeverity(DivisionCheck, Error)),
+                Box::new(UnalignedAccessCheck),
+                Box::new(InstructionCountCheck::new(ISR_INSTRUCTION_LIMIT)),
+                Box::new(StackAllocaSizeCheck::new(ISR_ALLOCA_LIMIT)),
+            ],
+        }
+    }
+}
+
+/// Verifier for hot path functions with configurable checks.
+pub struct HotPathVerifier {
+    checks: Vec<Box<dyn HotPathCheck>>,
+    policies: Vec<(String, FunctionPolicy)>,
+    /// Hot functions added with `add_function`.
+    functions: Vec<HotEntry>,
+    /// Date that `until` expiries are compared with; the system clock if unset.
+    today: Option<String>,
+    /// Findings below this severity are dropped.
+    min_severity: Severity,
+}
+
+impl HotPathVerifier {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            policies: Vec::new(),
+            functions: Vec::new(),
+            today: None,
+            min_severity: Severity::Info,
+        }
+    }
+
+    /// Evaluates `until` expiries of allow lists as of `date` (`YYYY-MM-DD`)
+    /// instead of today, for reproducible runs.
+    pub fn with_date(mut self, date: &str) -> Self {
+        self.today = Some(date.to_string());
+        self
+    }
+
+    /// Drops findings below `severity`, e.g. `Severity::Warning` to keep
+    /// advisory `Info` analyses out of a CI log.
+    pub fn with_min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    fn today(&self) -> String {
+        self.today.clone().unwrap_or_else(policy::today)
+    }
+
+    /// Verifies `symbol` as if it carried a `.hot_funcs` record, for code
+    /// that cannot emit one (C objects, assembly, vendor libraries). Takes
+    /// the record syntax, so `"c_mix_kernel;allow=division"` works too.
+    pub fn add_function(&mut self, symbol: &str) -> &mut Self {
+        let entry = HotEntry::parse(symbol);
+        self.functions.retain(|e| e.name != entry.name);
+        self.functions.push(entry);
+        self
+    }
+
+    /// Hot functions of `ir` plus those added with `add_function`, by name;
+    /// an added function replaces a record of the same name.
+    pub fn entries(&self, ir: &str) -> Vec<HotEntry> {
+        let mut entries = find_hot_entries_from_ir(ir);
+        entries.retain(|e| self.functions.iter().all(|f| f.name != e.name));
+        entries.extend(self.functions.iter().cloned());
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
+    /// Overrides for the function at `path` (as in `mark_hot!` records,
+    /// e.g. `"base::dsp::mix"`); other functions keep the global checks.
+    /// Calling it again for the same path extends the policy.
+    pub fn with_function_policy(
+        mut self,
+        path: &str,
+        build: impl FnOnce(FunctionPolicy) -> FunctionPolicy,
+    ) -> Self {
+        match self.policies.iter_mut().find(|(p, _)| p == path) {
+            Some((_, policy)) => *policy = build(core::mem::take(policy)),
+            None => self
+                .policies
+                .push((path.to_string(), build(FunctionPolicy::new()))),
+        }
+        self
+    }
+
+    fn policy(&self, function: &str) -> Option<&FunctionPolicy> {
+        self.policies
+            .iter()
+            .find(|(path, _)| path == function)
+            .map(|(_, policy)| policy)
+    }
+
+    pub fn with_check(mut self, check: Box<dyn HotPathCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    pub fn with_default_checks(self) -> Self {
+        self.with_profile(Profile::RealTime)
+    }
+
+    /// Adds the profile's checks; ones already present by name are kept as is.
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        for check in profile.checks() {
+            if !self.checks.iter().any(|c| c.name() == check.name()) {
+                self.checks.push(check);
+            }
+        }
+        self
+    }
+
+    /// Adds `TargetLoweringCheck` for `target` (see `TargetProfile::from_ir`).
+    pub fn with_target(self, target: TargetProfile) -> Self {
+        self.with_check(Box::new(TargetLoweringCheck::new(target)))
+    }
+
+    /// Adds `SoftFloatCheck` for `target`, e.g.
+    /// `TargetProfile::from_triple("thumbv6m-none-eabi")`.
+    pub fn with_soft_float(self, target: TargetProfile) -> Self {
+        self.with_check(Box::new(SoftFloatCheck::new(target)))
+    }
+
+    /// Adds every check in `registry` whose name is not already present.
+    pub fn with_registry(mut self, registry: CheckRegistry) -> Self {
+        for check in registry.into_checks() {
+            if !self.checks.iter().any(|c| c.name() == check.name()) {
+                self.checks.push(check);
+            }
+        }
+        self
+    }
+
+    /// Default checks plus every check registered with `register_check!` in
+    /// this binary (see `registry`).
+    #[cfg(any(target_os = "linux", target_os = "none"))]
+    pub fn with_linked_checks() -> Self {
+        Self::default().with_registry(CheckRegistry::linked())
+    }
+
+    pub fn verify(&self, ir: &str, func_name: &str) -> Result<Vec<String>, String> {
+        self.verify_allowing(ir, func_name, &[])
+    }
+
+    /// Every violation in every `mark_hot!` function of `ir`, honouring allow
+    /// lists. Hot functions missing from the IR are reported as `missing` errors.
+    pub fn verify_entries(&self, ir: &str) -> Vec<Violation> {
+        self.verify_entries_observed(ir, None, &mut ())
+    }
+
+    /// `verify_entries` (or `verify_entries_cached` with a cache) that
+    /// reports progress and violations to `observer` as it goes.
+    pub fn verify_entries_observed(
+        &self,
+        ir: &str,
+        mut cache: Option<&mut VerifyCache>,
+        observer: &mut dyn VerifyObserver,
+    ) -> Vec<Violation> {
+        let debug = debug_info::DebugInfo::parse(ir);
+        let functions = index::FunctionIndex::new(ir);
+        let entries = self.entries(ir);
+        let mut violations = Vec::new();
+        for (index, entry) in entries.iter().enumerate() {
+            observer.on_function_start(index, entries.len(), entry);
+            let before = violations.len();
+            let cache = cache.as_deref_mut().map(cache::CacheRef::Owned);
+            let policy = self.policy(&entry.name);
+            self.collect(&functions, &debug, entry, policy, cache, &mut violations);
+            for violation in &violations[before..] {
+                observer.on_violation(violation);
+            }
+            observer.on_function_done(entry, violations.len() - before);
+        }
+        violations
+    }
+
+    /// Like `verify_entries`, restricted to markers from the named crates
+    /// (e.g. dependencies whose code ends up in an LTO module). Names are
+    /// matched with `-` and `_` treated alike.
+    pub fn verify_entries_for_crates(&self, ir: &str, crates: &[&str]) -> Vec<Violation> {
+        let normalize = |name: &str| name.replace('-', "_");
+        let crates: Vec<String> = crates.iter().map(|c| normalize(c)).collect();
+        let debug = debug_info::DebugInfo::parse(ir);
+        let functions = index::FunctionIndex::new(ir);
+        let mut violations = Vec::new();
+        for entry in self.entries(ir) {
+            if crates.contains(&normalize(entry.crate_of())) {
+                let policy = self.policy(&entry.name);
+                self.collect(&functions, &debug, &entry, policy, None, &mut violations);
+            }
+        }
+        violations
+    }
+
+    /// Like `verify_entries`, but reuses `cache` results for functions whose
+    /// IR, allow list and this verifier's checks are unchanged.
+    pub fn verify_entries_cached(&self, ir: &str, cache: &mut VerifyCache) -> Vec<Violation> {
+        self.verify_entries_observed(ir, Some(cache), &mut ())
+    }
+
+    /// All violations in one function, with source locations where available.
+    pub fn violations(&self, ir: &str, entry: &HotEntry) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let debug = debug_info::DebugInfo::parse(ir);
+        let functions = index::FunctionIndex::new(ir);
+        let policy = self.policy(&entry.name);
+        self.collect(&functions, &debug, entry, policy, None, &mut violations);
+        violations
+    }
+
+    /// Stable hash of the checks and function policies, recorded in
+    /// attestations so a result can be tied to the configuration that
+    /// produced it.
+    pub fn config_hash(&self) -> u64 {
+        let mut hash = self.fingerprint();
+        for (path, policy) in &self.policies {
+            hash.write(path.as_bytes())
+                .write(format!("{:?}", policy).as_bytes());
+        }
+        for entry in &self.functions {
+            hash.write(format!("{:?}", entry).as_bytes());
+        }
+        hash.finish()
+    }
+
+    /// Hash of the check configuration (names and severities, in order).
+    fn fingerprint(&self) -> cache::Fnv {
+        let mut hash = cache::Fnv::new();
+        hash.write(&[self.min_severity as u8]);
+        for check in &self.checks {
+            hash.write(check.name().as_bytes())
+                .write(check.config().as_bytes())
+                .write(&[check.severity() as u8]);
+        }
+        hash
+    }
+
+    /// `(line, check, message)` for every enabled check, ordered by line and
+    /// then by check registration order.
+    fn hits(
+        &self,
+        symbol: &str,
+        body: &str,
+        allowed: impl Fn(&str) -> bool,
+        required: &[String],
+        tier: &[Box<dyn HotPathCheck>],
+    ) -> Vec<cache::Finding> {
+        let mut unknown = Vec::new();
+        let required: Vec<Box<dyn HotPathCheck>> = required
+            .iter()
+            .filter_map(|name| {
+                let check = required_check(name);
+                if check.is_none() {
+                    unknown.push(cache::Finding {
+                        line: 0,
+                        check: "require".to_string(),
+                        severity: Severity::Error,
+                        message: format!("unknown requirement `{}`", name),
+                    });
+                }
+                check
+            })
+            .collect();
+        let checks: Vec<&dyn HotPathCheck> = self
+            .checks
+            .iter()
+            .chain(tier)
+            .chain(&required)
+            .map(|check| check.as_ref())
+            .collect();
+        let lines: Vec<&str> = body.lines().collect();
+        let superseded = |line: usize, name: &str| {
+            lines.get(line).is_some_and(|text| {
+                checks
+                    .iter()
+                    .any(|check| check.supersedes(symbol, text) == Some(name))
+            })
+        };
+        let mut hits = Vec::new();
+        for (order, check) in checks.iter().enumerate() {
+            if allowed(check.name()) {
+                continue;
+            }
+            for (line, message) in check.check_function(symbol, body) {
+                if !superseded(line, check.name()) {
+                    hits.push((line, order, *check, message));
+                }
+            }
+        }
+        hits.sort_by_key(|&(line, order, _, _)| (line, order));
+        unknown
+            .into_iter()
+            .chain(
+                hits.into_iter()
+                    .map(|(line, _, check, message)| cache::Finding {
+                        line,
+                        check: check.name().to_string(),
+                        severity: check.severity(),
+                        message,
+                    }),
+            )
+            .collect()
+    }
+
+    /// Findings in one body under `policy` (usually `self.policy(..)` of
+    /// the entry).
+    fn findings(
+        &self,
+        symbol: &str,
+        body: &str,
+        entry: &HotEntry,
+        policy: Option<&FunctionPolicy>,
+    ) -> Vec<cache::Finding> {
+        let mut required = entry.require.clone();
+        if let Some(policy) = policy {
+            required.extend(policy.require.iter().cloned());
+        }
+        // The tiers' checks are layered on this verifier's; same-named ones
+        // already enabled are kept as configured, as in `with_profile`.
+        let mut tier: Vec<Box<dyn HotPathCheck>> = Vec::new();
+        let tiers = entry
+            .tier
+            .as_deref()
+            .and_then(Profile::from_name)
+            .into_iter()
+            .chain(policy.and_then(|policy| policy.tier));
+        for check in tiers.flat_map(Profile::checks) {
+            let enabled = self.checks.iter().chain(&tier);
+            if !enabled.into_iter().any(|own| own.name() == check.name()) {
+                tier.push(check);
+            }
+        }
+        // Exemptions past their `until` date stop applying and fail the function.
+        let today = self.today();
+        let entry_expired = entry
+            .allow_until
+            .as_deref()
+            .filter(|until| policy::expired(until, &today));
+        let policy_expired: Vec<(&str, &str)> = policy
+            .map(|policy| {
+                policy
+                    .until
+                    .iter()
+                    .filter(|(_, until)| policy::expired(until, &today))
+                    .map(|(check, until)| (check.as_str(), until.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let expired = entry_expired
+            .into_iter()
+            .flat_map(|until| entry.allow.iter().map(move |check| (check.as_str(), until)))
+            .chain(policy_expired.iter().copied());
+        let mut findings: Vec<cache::Finding> = expired
+            .map(|(check, until)| cache::Finding {
+                line: 0,
+                check: "allow_expired".to_string(),
+                severity: Severity::Error,
+                message: if policy::is_date(until) {
+                    format!(
+                        "allow({}) expired on {}; the check applies again",
+                        check, until
+                    )
+                } else {
+                    format!(
+                        "allow({}) has an invalid expiry `{}` (expected YYYY-MM-DD)",
+                        check, until
+                    )
+                },
+            })
+            .collect();
+        findings.extend(self.hits(
+            symbol,
+            body,
+            |name| {
+                (entry_expired.is_none() && entry.allow.iter().any(|allowed| allowed == name))
+                    || policy.is_some_and(|policy| {
+                        policy.allows(name)
+                            && !policy_expired.iter().any(|&(check, _)| check == name)
+                    })
+            },
+            &required,
+            &tier,
+        ));
+        if let Some(limit) = policy.and_then(|policy| policy.stack_limit) {
+            let frame = policy::frame_size(body);
+            if frame > limit {
+                findings.push(cache::Finding {
+                    line: 0,
+                    check: "stack_limit".to_string(),
+                    severity: Severity::Error,
+                    message: format!(
+                        "static frame of {} bytes exceeds the {}-byte limit",
+                        frame, limit
+                    ),
+                });
+            }
+        }
+        findings.retain(|finding| finding.severity >= self.min_severity);
+        findings
+    }
+
+    fn collect(
+        &self,
+        functions: &index::FunctionIndex<'_>,
+        debug: &debug_info::DebugInfo<'_>,
+        entry: &HotEntry,
+        policy: Option<&FunctionPolicy>,
+        cache: Option<cache::CacheRef<'_>>,
+        out: &mut Vec<Violation>,
+    ) {
+        let marker = entry.file.as_ref().map(|file| SourceLocation {
+            file: file.clone(),
+            line: entry.line.unwrap_or(1),
+            column: 1,
+        });
+        let found = match functions.find(&entry.name) {
+            Ok(found) => found,
+            Err(message) => {
+                out.push(Violation {
+                    function: entry.name.clone(),
+                    check: "missing".to_string(),
+                    severity: Severity::Error,
+                    message,
+                    location: marker,
+                });
+                return;
+            }
+        };
+        let mut cache = cache;
+        let targets = std::iter::once((
+            entry.name.clone(),
+            found.symbol.to_string(),
+            functions.body(&found).to_string(),
+        ))
+        .chain(future::indexed_poll_bodies(functions, &entry.name));
+        for (function, symbol, body) in targets {
+            let findings = match cache.as_mut() {
+                Some(cache) => {
+                    let mut hash = self.fingerprint();
+                    for allowed in &entry.allow {
+                        hash.write(allowed.as_bytes());
+                    }
+                    for required in &entry.require {
+                        hash.write(b"+").write(required.as_bytes());
+                    }
+                    if let Some(tier) = &entry.tier {
+                        hash.write(b"@").write(tier.as_bytes());
+                    }
+                    if let Some(until) = &entry.allow_until {
+                        hash.write(b"~").write(until.as_bytes());
+                    }
+                    if entry.allow_until.is_some()
+                        || policy.is_some_and(|policy| !policy.until.is_empty())
+                    {
+                        hash.write(b"=").write(self.today().as_bytes());
+                    }
+                    if let Some(policy) = policy {
+                        hash.write(format!("{:?}", policy).as_bytes());
+                    }
+                    let hash = hash
+                        .write(symbol.as_bytes())
+                        .write(body.as_bytes())
+                        .finish();
+                    cache.get_or_insert_with(&function, hash, || {
+                        self.findings(&symbol, &body, entry, policy)
+                    })
+                }
+                None => self.findings(&symbol, &body, entry, policy),
+            };
+            let lines: Vec<&str> = body.lines().collect();
+            for finding in findings {
+                let line = lines.get(finding.line);
+                let location = line.and_then(|line| debug.location_of(line));
+                let mut message = finding.message;
+                // Name the inlined helper the instruction came from, not
+                // only the line in the hot function that called it.
+                if let Some((inner, outer)) = line
+                    .map(|line| debug.inlined_frames(line))
+                    .as_deref()
+                    .and_then(<[_]>::split_first)
+                {
+                    message.push_str(&format!(
+                        " (inlined from `{}` at {}:{}:{}",
+                        inner.function,
+                        inner.location.file,
+                        inner.location.line,
+                        inner.location.column.max(1)
+                    ));
+                    for frame in outer {
+                        message.push_str(&format!(" via `{}`", frame.function));
+                    }
+                    message.push(')');
+                }
+                out.push(Violation {
+                    function: function.clone(),
+                    check: finding.check,
+                    severity: finding.severity,
+                    message,
+                    location: location.or_else(|| marker.clone()),
+                });
+            }
+        }
+    }
+
+    /// Like `verify`, but skips the checks named in `allowed` (see `mark_hot!(f, allow(..))`).
+    pub fn verify_allowing(
+        &self,
+        ir: &str,
+        func_name: &str,
+        allowed: &[&str],
+    ) -> Result<Vec<String>, String> {
+        let entry = HotEntry {
+            name: func_name.to_string(),
+            allow: allowed.iter().map(|check| check.to_string()).collect(),
+            ..HotEntry::default()
+        };
+        self.verify_entry(ir, &entry)
+    }
+
+    /// Verifies `entry` with its allow list and `require(..)` checks. For an
+    /// `async fn` its coroutine bodies are verified too.
+    pub fn verify_entry(&self, ir: &str, entry: &HotEntry) -> Result<Vec<String>, String> {
+        let found = find_function(ir, &entry.name)?;
+        let policy = self.policy(&entry.name);
+        let findings = self.findings(found.symbol, &ir[found.body], entry, policy);
+        let mut warnings = Self::split(&entry.name, findings)?;
+        for (function, symbol, body) in future::poll_bodies(ir, &entry.name) {
+            warnings.extend(Self::split(
+                &function,
+                self.findings(&symbol, &body, entry, policy),
+            )?);
+        }
+        Ok(warnings)
+    }
+
+    /// First failure, or all warnings; `Info` findings are left out (see
+    /// `violations` for them).
+    fn split(func_name: &str, findings: Vec<cache::Finding>) -> Result<Vec<String>, String> {
+        let mut warnings = Vec::new();
+        for finding in findings {
+            match finding.severity {
+                Severity::Error | Severity::Critical => {
+                    return Err(format!("{}: {}", func_name, finding.message));
+                }
+                Severity::Warning => {
+                    warnings.push(format!("{}: {}", func_name, finding.message));
+                }
+                Severity::Info => {}
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+impl Default for HotPathVerifier {
+    fn default() -> Self {
+        Self::new().with_default_checks()
+    }
+}
+
+/// Verifies hot path functions from LLVM IR content using default checks.
+///
+/// Checks listed in a function's `mark_hot!(f, allow(..))` are skipped for it.
+pub fn verify_hot_path_functions(ir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let verifier = HotPathVerifier::default();
+
+    for entry in find_hot_entries_from_ir(ir) {
+        verifier
+            .verify_entry(ir, &entry)
+            .map_err(|error| match entry.location() {
+                Some(location) => format!("{} [{}]", error, location),
+                None => error,
+            })?;
+    }
+
+    Ok(())
+}
+
+/// One `.hot_funcs` record: the function path, checks it opts out of, and
+/// where the marker lives. Location fields are `None` for bare records.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct HotEntry {
+    pub name: String,
+    pub allow: Vec<String>,
+    /// Expiry of `allow` from `mark_hot!(f, allow(.., until = "YYYY-MM-DD"))`;
+    /// from the next day on the list no longer applies.
+    pub allow_until: Option<String>,
+    /// Opt-in checks from `mark_hot!(f, require(..))`.
+    pub require: Vec<String>,
+    /// Profile name from `mark_hot!(f, tier = ..)` whose checks are added
+    /// for this function, e.g. `isr`.
+    pub tier: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub crate_name: Option<String>,
+    pub crate_version: Option<String>,
+}
+
+impl HotEntry {
+    /// Parses the `mark_hot!` encoding
+    /// `path::func[;allow=check,check,[;until=YYYY-MM-DD]][;require=check,][;tier=T][;file=F;line=N;crate=C;version=V]`.
+    /// Unknown keys are ignored.
+    pub fn parse(raw: &str) -> Self {
+        let mut parts = raw.split(';');
+        let mut entry = Self {
+            name: parts.next().unwrap_or_default().to_string(),
+            ..Self::default()
+        };
+        for part in parts {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            match key {
+                "allow" => entry.allow.extend(
+                    value
+                        .split(',')
+                        .filter(|check| !check.is_empty())
+                        .map(str::to_string),
+                ),
+                "require" => entry.require.extend(
+                    value
+                        .split(',')
+                        .filter(|check| !check.is_empty())
+                        .map(str::to_string),
+                ),
+                "until" => entry.allow_until = Some(value.to_string()),
+                "tier" => entry.tier = Some(value.to_string()),
+                "file" => entry.file = Some(value.to_string()),
+                "line" => entry.line = value.parse().ok(),
+                "crate" => entry.crate_name = Some(value.to_string()),
+                "version" => entry.crate_version = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        entry
+    }
+
+    /// `perf::hot_id` of the function, as `mark_hot!` stores it next to the
+    /// record.
+    pub fn id(&self) -> u32 {
+        crate::perf::hot_id(&self.name)
+    }
+
+    /// Crate the marker lives in: the recorded name, else the first path segment.
+    pub fn crate_of(&self) -> &str {
+        self.crate_name
+            .as_deref()
+            .unwrap_or_else(|| self.name.split("::").next().unwrap_or_default())
+    }
+
+    /// `file:line (crate version)`, or whichever parts the record carries.
+    pub fn location(&self) -> Option<String> {
+        let site = match (&self.file, self.line) {
+            (Some(file), Some(line)) => Some(format!("{}:{}", file, line)),
+            (Some(file), None) => Some(file.clone()),
+            _ => None,
+        };
+        let krate = match (&self.crate_name, &self.crate_version) {
+            (Some(name), Some(version)) => Some(format!("{} {}", name, version)),
+            (Some(name), None) => Some(name.clone()),
+            _ => None,
+        };
+        match (site, krate) {
+            (Some(site), Some(krate)) => Some(format!("{} ({})", site, krate)),
+            (site, krate) => site.or(krate),
+        }
+    }
+}
+
+/// Decodes LLVM `c"..."` escapes (`\XX` hex bytes, e.g. `\5C` for a backslash).
+fn unescape_ir_string(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(hex) = raw.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Discovers hot functions from LLVM IR `.hot_funcs` section.
+pub fn find_hot_functions_from_ir(ir: &str) -> HashSet<String> {
+    find_hot_entries_from_ir(ir)
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect()
+}
+
+/// Discovers `.hot_funcs` records including their allow lists, sorted by name.
+pub fn find_hot_entries_from_ir(ir: &str) -> Vec<HotEntry> {
+    let hot_funcs: HashSet<HotEntry> = hot_records(ir)
+        .into_iter()
+        .filter_map(|(_, _, name)| Some(HotEntry::parse(&name?)))
+        .collect();
+    let mut entries: Vec<HotEntry> = hot_funcs.into_iter().collect();
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+/// Each `.hot_funcs` record as (byte offset, text, recorded name); the name
+/// is `None` if the record points at nothing readable.
+fn hot_records(ir: &str) -> Vec<(usize, &str, Option<String>)> {
+    use regex::Regex;
+
+    // Records in `.hot_funcs` (or `hot_funcs`, `__DATA,hot_funcs`: C toolchains
+    // differ in how they spell the section).
+    let re_record =
+        Regex::new(r#"(?m)^[ \t]*@[^\n]*section\s+"(?:[^"]*,)?\.?hot_funcs"[^\n]*$"#).unwrap();
+    let re_ref = Regex::new(r#"ptr\s+(@[\w.$]+|@"[^"]+")"#).unwrap();
+    let re_inline = Regex::new(r#"=[^"]*\[\d+ x i8\] c"([^"]+?)(?:\\00)+""#).unwrap();
+
+    let mut records = Vec::new();
+    for found in re_record.find_iter(ir) {
+        let record = found.as_str();
+        // The first pointer is the name: a string global (`@alloc_*` from
+        // rustc, `@.str.N` from clang) or the function itself.
+        let name = re_ref
+            .captures(record)
+            .and_then(|cap| record_name(ir, cap.get(1)?.as_str()))
+            // A C `const char[]` placed in the section directly.
+            .or_else(|| {
+                re_inline
+                    .captures(record)
+                    .map(|cap| unescape_ir_string(&cap[1]))
+            });
+        records.push((found.start(), record, name));
+    }
+    records
+}
 
-    hot_funcs
+/// Name a `.hot_funcs` record points to: the contents of string global
+/// `global`, or the symbol itself if `global` is a function.
+fn record_name(ir: &str, global: &str) -> Option<String> {
+    use regex::Regex;
+    let escaped = regex::escape(global);
+    let string = Regex::new(&format!(r#"(?m)^[ \t]*{}\s*=.*?c"([^"]+)\\00""#, escaped)).ok()?;
+    if let Some(cap) = string.captures(ir) {
+        return Some(unescape_ir_string(&cap[1]));
+    }
+    let function = Regex::new(&format!(
+        r#"(?m)^[ \t]*(?:define|declare)[^@\n]*{}\("#,
+        escaped
+    ))
+    .ok()?;
+    function
+        .is_match(ir)
+        .then(|| global[1..].trim_matches('"').to_string())
 }
 
 /// Converts Rust path (a::b::c) to LLVM mangled format (1a1b1c).
@@ -310,7 +2743,33 @@ fn mangle_rust_path(path: &str) -> String {
 }
 
 /// Extracts function body from LLVM IR.
+///
+/// Crate-qualified paths (`dep::module::f`, as recorded by `mark_hot!`) are
+/// first matched as a whole symbol path under legacy (`_ZN..17h<hash>E`) or
+/// v0 (`_R..C[s<disambiguator>_]..`) mangling, so a dependency's
+/// `dep::dsp::process` is not confused with the top-level `dsp::process` in
+/// an LTO module. Unmangled symbols (`#[no_mangle]`, `extern "C"`
+/// callbacks) are then matched exactly by the path's last segment.
+/// Anything else falls back to a substring match.
 fn find_function_body(ir: &str, func_name: &str) -> Result<String, String> {
+    find_function(ir, func_name).map(|found| ir[found.body].to_string())
+}
+
+/// Parameter list, attributes, then the body up to the closing brace.
+const FUNCTION_BODY: &str = r"\([^\)]*\)[^\{]*\{(?<body>(?s:.*?))\n\}";
+
+/// Where a function's definition sits in the IR.
+#[derive(Clone)]
+pub(crate) struct FunctionMatch<'a> {
+    /// Symbol as written after `@`, without quotes.
+    pub symbol: &'a str,
+    /// From `define` to the closing brace.
+    pub define: core::ops::Range<usize>,
+    pub body: core::ops::Range<usize>,
+}
+
+/// Locates `func_name`'s definition; see `find_function_body`.
+pub(crate) fn find_function<'a>(ir: &'a str, func_name: &str) -> Result<FunctionMatch<'a>, String> {
     use regex::Regex;
 
     // Mangle Rust paths (a::b::c) for matching in IR
@@ -320,21 +2779,43 @@ fn find_function_body(ir: &str, func_name: &str) -> Result<String, String> {
         func_name.to_string()
     };
 
-    let pattern = format!(
-        r"define[^@]*@[^\s]*{}[^\(]*\([^\)]*\)[^\{{]*\{{(.*?)\n\}}",
-        regex::escape(&search_name)
-    );
-    let re = Regex::new(&pattern).unwrap();
-
-    let body = re
-        .captures(ir)
-        .ok_or_else(|| format!("Function {} not found in IR", func_name))?
-        .get(1)
-        .unwrap()
-        .as_str()
-        .to_string();
+    let mut patterns = Vec::new();
+    if func_name.contains("::") {
+        patterns.push(format!(
+            r#"define[^@]*@"?(?<symbol>(?:_ZN|_R[A-Za-z]*?C(?:s[0-9A-Za-z]*_)?){}(?:17h[0-9a-f]{{16}}E)?(?:\.[^\s("]*)?)"?{}"#,
+            regex::escape(&search_name),
+            FUNCTION_BODY
+        ));
+    }
+    let unmangled = func_name.rsplit("::").next().unwrap_or(func_name);
+    patterns.push(format!(
+        r#"define[^@]*@"?(?<symbol>{})"?{}"#,
+        regex::escape(unmangled),
+        FUNCTION_BODY
+    ));
+    patterns.push(format!(
+        r#"define[^@]*@"?(?<symbol>[^\s"]*{}[^\("]*)"?{}"#,
+        regex::escape(&search_name),
+        FUNCTION_BODY
+    ));
 
-    Ok(body)
+    for pattern in patterns {
+        // Only a name too long for the regex size limit fails to compile.
+        let re = Regex::new(&pattern).map_err(|_| {
+            format!(
+                "Function name of {} bytes is too long to look up in IR",
+                func_name.len()
+            )
+        })?;
+        if let Some(captures) = re.captures(ir) {
+            return Ok(FunctionMatch {
+                symbol: captures.name("symbol").unwrap().as_str(),
+                define: captures.get(0).unwrap().range(),
+                body: captures.name("body").unwrap().range(),
+            });
+        }
+    }
+    Err(format!("Function {} not found in IR", func_name))
 }
 
 /// Verifies a single hot function using default checks.
@@ -384,6 +2865,15 @@ mod tests {
         assert!(result.unwrap_err().contains("function call"));
     }
 
+    #[test]
+    fn test_detect_indirect_call() {
+        let ir = "define float @test_func(ptr %f) {  %1 = load ptr, ptr %f, align 8  %2 = call float %1(ptr %f, float 1.0)  ret float %2\n}";
+        let err = verify_hot_function(ir, "test_func").unwrap_err();
+        assert!(err.contains("indirect function call"), "{}", err);
+
+        assert!(!is_indirect_call("%1 = call i32 @other_function(ptr %x)"));
+    }
+
     #[test]
     fn test_detect_atomic_operation() {
         let ir = "define i32 @test_func(ptr %ptr) {  %1 = atomicrmw add ptr %ptr, i32 1 seq_cst  ret i32 %1\n}";
@@ -496,6 +2986,17 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_multiline_function_body() {
+        let ir = "define void @test_func(ptr %p) {\nstart:\n  %1 = call i32 @other()\n  ret void\n}\n\ndefine void @next() {\n  ret void\n}";
+        assert!(
+            verify_hot_function(ir, "test_func")
+                .unwrap_err()
+                .contains("function call")
+        );
+        assert!(verify_hot_function(ir, "next").is_ok());
+    }
+
     #[test]
     fn test_function_not_found() {
         let ir = "define i32 @other_func() { ret i32 0\n}";
@@ -563,6 +3064,1161 @@ mod tests {
         assert!(funcs.contains("process"));
     }
 
+    #[test]
+    fn test_find_hot_functions_from_c() {
+        let ir = r#"
+@.str = private unnamed_addr constant [13 x i8] c"c_mix_kernel\00", align 1
+@c_mix_kernel_hot = internal global ptr @.str, section ".hot_funcs", align 8
+@c_gain_hot = internal global ptr @c_gain, section "hot_funcs", align 8
+@c_clip_hot = dso_local constant [7 x i8] c"c_clip\00", section "__DATA,hot_funcs", align 1
+@not_hot = internal global ptr @.str, section ".data", align 8
+define dso_local void @c_gain(ptr %x) {
+  ret void
+}
+"#;
+        let funcs = find_hot_functions_from_ir(ir);
+        assert_eq!(funcs.len(), 3);
+        for name in ["c_mix_kernel", "c_gain", "c_clip"] {
+            assert!(funcs.contains(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_pointer_chase() {
+        let chain = "
+  %a = getelementptr inbounds i8, ptr %root, i64 8
+  %b = load ptr, ptr %a, align 8
+  %c = getelementptr inbounds i8, ptr %b, i64 16
+  %d = load ptr, ptr %c, align 8
+  %e = load i32, ptr %d, align 4
+  %f = load i32, ptr %root, align 4
+";
+        assert_eq!(
+            PointerChaseCheck::new(2).check_body(chain),
+            [(5, "chain of 3 dependent loads (limit 2)".to_string())]
+        );
+        assert!(PointerChaseCheck::new(3).check_body(chain).is_empty());
+
+        let walk = "
+entry:
+  br label %loop
+loop:
+  %node = phi ptr [ %head, %entry ], [ %next, %loop ]
+  %link = getelementptr inbounds i8, ptr %node, i64 8
+  %next = load ptr, ptr %link, align 8
+  %done = icmp eq ptr %next, null
+  br i1 %done, label %exit, label %loop
+";
+        let found = PointerChaseCheck::new(8).check_body(walk);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 6);
+        assert!(
+            found[0]
+                .1
+                .starts_with("pointer chase carried around a loop")
+        );
+    }
+
+    #[test]
+    fn test_add_function() {
+        let ir = r#"
+            @alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+            @HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @k(i32 %a) {
+  ret i32 %a
+}
+define i32 @c_mix_kernel(i32 %a) {
+  %1 = udiv i32 %a, 3
+  ret i32 %1
+}
+"#;
+        let mut verifier = HotPathVerifier::default();
+        assert!(verifier.verify_entries(ir).is_empty());
+        let before = verifier.config_hash();
+        verifier.add_function("c_mix_kernel");
+        assert_ne!(verifier.config_hash(), before);
+        let names: Vec<String> = verifier.entries(ir).into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["c_mix_kernel", "k"]);
+        let violations = verifier.verify_entries(ir);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].function, "c_mix_kernel");
+
+        verifier.add_function("c_mix_kernel;allow=division");
+        assert!(verifier.verify_entries(ir).is_empty());
+    }
+
+    #[test]
+    fn test_hot_entry_allow_list() {
+        let entry = HotEntry::parse("dev::poll;allow=volatile_load,volatile_store,");
+        assert_eq!(entry.name, "dev::poll");
+        assert_eq!(entry.allow, ["volatile_load", "volatile_store"]);
+        assert!(HotEntry::parse("dev::poll").allow.is_empty());
+        assert_eq!(entry.id(), crate::perf::hot_id("dev::poll"));
+    }
+
+    #[test]
+    fn test_hot_entry_location() {
+        let entry = HotEntry::parse(
+            "dev::poll;allow=atomic,;file=src/dev.rs;line=42;crate=dev;version=1.2.0",
+        );
+        assert_eq!(entry.allow, ["atomic"]);
+        assert_eq!(entry.file.as_deref(), Some("src/dev.rs"));
+        assert_eq!(entry.line, Some(42));
+        assert_eq!(entry.location().unwrap(), "src/dev.rs:42 (dev 1.2.0)");
+        assert_eq!(HotEntry::parse("dev::poll").location(), None);
+        assert_eq!(unescape_ir_string(r"src\5Cdev.rs"), r"src\dev.rs");
+    }
+
+    #[test]
+    fn test_location_in_error() {
+        let ir = r#"
+            @alloc_f = private unnamed_addr constant [50 x i8] c"f;file=src\5Cf.rs;line=7;crate=app;version=0.3.1\00", align 1
+            @HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_f, [8 x i8] c"\07\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @f() {
+  %1 = call i32 @g()
+  ret i32 %1
+}
+"#;
+        let err = verify_hot_path_functions(ir).unwrap_err().to_string();
+        assert!(err.ends_with(r"[src\f.rs:7 (app 0.3.1)]"), "{}", err);
+    }
+
+    #[test]
+    fn test_allow_list_suppresses_checks() {
+        let ir = r#"
+            @alloc_poll = private unnamed_addr constant [40 x i8] c"poll;allow=volatile_load,atomic,\00", align 1
+            @HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_poll, [8 x i8] c"\07\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @poll(ptr %reg) {
+  %1 = load volatile i32, ptr %reg, align 4
+  %2 = atomicrmw add ptr %reg, i32 1 monotonic
+  ret i32 %1
+}
+"#;
+        assert!(find_hot_functions_from_ir(ir).contains("poll"));
+        verify_hot_path_functions(ir).unwrap();
+        let verifier = HotPathVerifier::default();
+        assert!(verifier.verify(ir, "poll").is_err());
+        assert_eq!(
+            verifier
+                .verify_allowing(ir, "poll", &["atomic"])
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_profiles() {
+        let div = "define i32 @f(i32 %a) {\n  %1 = udiv i32 %a, 3\n  ret i32 %1\n}";
+        assert_eq!(
+            HotPathVerifier::default().verify(div, "f").unwrap().len(),
+            1
+        );
+        for profile in [Profile::ConstTime, Profile::Embedded] {
+            let verifier = HotPathVerifier::new().with_profile(profile);
+            assert!(verifier.verify(div, "f").is_err(), "{:?}", profile);
+        }
+
+        let mmio =
+            "define void @f(ptr %r) {\n  store volatile i32 1, ptr %r, align 4\n  ret void\n}";
+        let embedded = HotPathVerifier::new().with_profile(Profile::Embedded);
+        assert!(embedded.verify(mmio, "f").unwrap().is_empty());
+
+        let call = "define void @f() {\n  call void @g()\n  ret void\n}";
+        let size = HotPathVerifier::new().with_profile(Profile::CodeSize);
+        assert!(size.verify(call, "f").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_entries_collects_all() {
+        let ir = r#"
+            @alloc_f = private unnamed_addr constant [30 x i8] c"f;file=src/f.rs;line=3\00", align 1
+            @HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_f, [8 x i8] c"\07\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+            @alloc_g = private unnamed_addr constant [2 x i8] c"g\00", align 1
+            @HOT_FUNC2 = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_g, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @f(i32 %a) !dbg !7 {
+  %1 = udiv i32 %a, 3, !dbg !9
+  %2 = call i32 @h(i32 %1)
+  ret i32 %2
+}
+!7 = distinct !DISubprogram(name: "f", file: !8, line: 2)
+!8 = !DIFile(filename: "src/f.rs", directory: "/work")
+!9 = !DILocation(line: 5, column: 13, scope: !7)
+"#;
+        let violations = HotPathVerifier::default().verify_entries(ir);
+        let checks: Vec<&str> = violations.iter().map(|v| v.check.as_str()).collect();
+        assert_eq!(checks, ["division", "function_call", "missing"]);
+        let at = |v: &Violation| v.location.as_ref().map(|l| (l.line, l.column));
+        assert_eq!(at(&violations[0]), Some((5, 13)));
+        assert_eq!(at(&violations[1]), Some((3, 1)));
+        assert_eq!(violations[2].function, "g");
+        assert_eq!(at(&violations[2]), None);
+    }
+
+    #[test]
+    fn test_inlined_attribution() {
+        let ir = r#"
+define i32 @f(i32 %a) !dbg !7 {
+  %1 = udiv i32 %a, 3, !dbg !12
+  ret i32 %1
+}
+!7 = distinct !DISubprogram(name: "f", file: !8, line: 2)
+!8 = !DIFile(filename: "src/f.rs", directory: "/work")
+!9 = !DILocation(line: 5, column: 13, scope: !7)
+!10 = distinct !DISubprogram(name: "scale", linkageName: "_ZN3app4util5scale17h0123456789abcdefE", file: !11, line: 20)
+!11 = !DIFile(filename: "src/util.rs", directory: "/work")
+!12 = !DILocation(line: 21, column: 7, scope: !13, inlinedAt: !14)
+!13 = distinct !DISubprogram(name: "ratio", file: !11, line: 30)
+!14 = !DILocation(line: 24, column: 5, scope: !10, inlinedAt: !9)
+"#;
+        let mut verifier = HotPathVerifier::default();
+        verifier.add_function("f");
+        let violations = verifier.verify_entries(ir);
+        assert_eq!(violations.len(), 1);
+        assert!(
+            violations[0]
+                .message
+                .ends_with(" (inlined from `ratio` at src/util.rs:21:7 via `app::util::scale`)"),
+            "{}",
+            violations[0].message
+        );
+        let location = violations[0].location.as_ref().unwrap();
+        assert_eq!((location.file.as_str(), location.line), ("src/f.rs", 5));
+    }
+
+    #[test]
+    fn test_dependency_paths_are_anchored() {
+        let ir = r#"
+            @alloc_a = private unnamed_addr constant [40 x i8] c"dsp::process;crate=app\00", align 1
+            @HOT_A = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_a, [8 x i8] c"\0C\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+            @alloc_b = private unnamed_addr constant [40 x i8] c"filt::dsp::process;crate=filt-core\00", align 1
+            @HOT_B = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_b, [8 x i8] c"\12\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+            @alloc_c = private unnamed_addr constant [40 x i8] c"filt::mix::gain\00", align 1
+            @HOT_C = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_c, [8 x i8] c"\0F\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define void @_ZN4filt3dsp7process17h0123456789abcdefE(ptr %x) {
+  %1 = call i32 @helper()
+  ret void
+}
+define void @_ZN3dsp7process17hfedcba9876543210E(ptr %x) {
+  ret void
+}
+define internal float @_RNvNtCs9aB3_4filt3mix4gain(float %a) unnamed_addr {
+  %1 = fdiv float %a, 2.0
+  ret float %1
+}
+"#;
+        let verifier = HotPathVerifier::default();
+        let top = find_function_body(ir, "dsp::process").unwrap();
+        assert!(!top.contains("call"), "{}", top);
+        assert!(
+            find_function_body(ir, "filt::dsp::process")
+                .unwrap()
+                .contains("call")
+        );
+        assert!(
+            find_function_body(ir, "filt::mix::gain")
+                .unwrap()
+                .contains("fdiv")
+        );
+
+        let deps = verifier.verify_entries_for_crates(ir, &["filt_core", "filt"]);
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].function, "filt::dsp::process");
+        assert!(verifier.verify_entries_for_crates(ir, &["app"]).is_empty());
+        assert_eq!(HotEntry::parse("filt::mix::gain").crate_of(), "filt");
+    }
+
+    #[test]
+    fn test_branch_hint_advisory() {
+        let ir = "define i32 @f(ptr %a, i64 %i, i64 %n) {
+start:
+  %c = icmp ult i64 %i, %n
+  br i1 %c, label %ok, label %panic
+ok:                                               ; preds = %start
+  %d = icmp eq i64 %i, 0
+  br i1 %d, label %zero, label %done, !prof !3
+zero:
+  call void @_ZN4core9panicking5panic17h0123456789abcdefE()
+  unreachable
+done:
+  ret i32 0
+panic:                                            ; preds = %start
+  call void @_ZN4core9panicking18panic_bounds_check17h0123456789abcdefE(i64 %i)
+  unreachable
+}";
+        let verifier = HotPathVerifier::new().with_check(Box::new(BranchHintCheck));
+        // Advisory: reported as `Info`, which `verify` leaves out.
+        assert!(verifier.verify(ir, "f").unwrap().is_empty());
+
+        let entry = HotEntry::parse("f");
+        let violations = verifier.violations(ir, &entry);
+        assert_eq!(violations[0].check, "branch_hint");
+        assert_eq!(violations[0].severity, Severity::Info);
+        assert!(
+            violations[0].message.contains("panic block %panic"),
+            "{}",
+            violations[0].message
+        );
+        let quiet = HotPathVerifier::new()
+            .with_check(Box::new(BranchHintCheck))
+            .with_min_severity(Severity::Warning);
+        assert!(quiet.violations(ir, &entry).is_empty());
+        let body = find_function_body(ir, "f").unwrap();
+        let (index, _) = BranchHintCheck.check_body(&body)[0];
+        assert!(body.lines().nth(index).unwrap().contains("br i1 %c"));
+    }
+
+    #[test]
+    fn test_dead_code_advisory() {
+        let ir = "define void @f(ptr %p, ptr %q, i32 %a) {
+start:
+  %unused = mul i32 %a, 3
+  %kept = add i32 %a, 1
+  store i32 %kept, ptr %p, align 4
+  store i32 0, ptr %q, align 4
+  store i32 %a, ptr %p, align 4
+  %v = load i32, ptr %q, align 4
+  store i32 %v, ptr %q, align 4
+  call void asm sideeffect \"\", \"r,~{memory}\"(ptr %p)
+  ret void
+}";
+        let body = find_function_body(ir, "f").unwrap();
+        let hits = DeadCodeCheck.check_body(&body);
+        let messages: Vec<&str> = hits.iter().map(|(_, m)| m.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "value %unused is computed but never used",
+                "store to %p is overwritten before it is read",
+                "optimization barrier (black_box) keeps otherwise dead values alive",
+            ]
+        );
+        assert!(
+            body.lines()
+                .nth(hits[1].0)
+                .unwrap()
+                .contains("store i32 %kept")
+        );
+        let verifier = HotPathVerifier::new().with_profile(Profile::CodeSize);
+        let violations = verifier.violations(ir, &HotEntry::parse("f"));
+        assert_eq!(violations.len(), 3);
+        assert!(violations.iter().all(|v| v.severity == Severity::Info));
+    }
+
+    #[test]
+    fn test_alloca_in_loop() {
+        let ir = "define void @f(i64 %n) {
+start:
+  %frame = alloca [16 x i8], align 8
+  br label %body
+
+body:
+  %i = phi i64 [ 0, %start ], [ %i.next, %body ]
+  %tmp = alloca i8, i64 %n, align 1
+  %i.next = add i64 %i, 1
+  %done = icmp eq i64 %i.next, %n
+  br i1 %done, label %exit, label %body
+
+exit:
+  ret void
+}";
+        let body = find_function_body(ir, "f").unwrap();
+        let hits = AllocaInLoopCheck.check_body(&body);
+        assert_eq!(hits.len(), 1);
+        assert!(body.lines().nth(hits[0].0).unwrap().contains("%tmp"));
+        assert_eq!(
+            hits[0].1,
+            "alloca inside loop %body grows the stack every iteration"
+        );
+        let err = verify_hot_function(ir, "f").unwrap_err();
+        assert!(err.contains("alloca inside loop"), "{}", err);
+    }
+
+    #[test]
+    fn test_trap_reports_condition() {
+        let ir = "define i32 @get(ptr %p, i64 %i) {
+start:
+  %oob = icmp uge i64 %i, 8
+  br i1 %oob, label %panic, label %ok
+
+ok:
+  %v = load i32, ptr %p, align 4
+  %sum = call { i32, i1 } @llvm.sadd.with.overflow.i32(i32 %v, i32 1)
+  %ovf = extractvalue { i32, i1 } %sum, 1
+  br i1 %ovf, label %trap, label %done
+
+trap:
+  call void @llvm.trap()
+  unreachable
+
+panic:
+  call void @_ZN4core9panicking18panic_bounds_check17h0123456789abcdefE(i64 %i, i64 8)
+  unreachable
+
+done:
+  %r = extractvalue { i32, i1 } %sum, 0
+  ret i32 %r
+}";
+        let body = find_function_body(ir, "get").unwrap();
+        let messages: Vec<String> = TrapCheck
+            .check_body(&body)
+            .into_iter()
+            .map(|(_, m)| m)
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "reaches llvm.trap when %ovf is true (extractvalue { i32, i1 } %sum, 1)",
+                "reaches noreturn call to core::panicking::panic_bounds_check when %oob is true (icmp uge i64 %i, 8)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_severity_levels() {
+        assert!(Severity::Info < Severity::Warning && Severity::Error < Severity::Critical);
+        assert!(Severity::Critical.is_failure() && !Severity::Warning.is_failure());
+        assert_eq!(Severity::from_name("CRITICAL"), Some(Severity::Critical));
+        assert_eq!(Severity::Info.name(), "info");
+
+        let ir = "define i32 @mix(i32 %a) {
+  %q = udiv i32 %a, 3
+  ret i32 %q
+}";
+        let critical = HotPathVerifier::new()
+            .with_check(Box::new(WithSeverity(DivisionCheck, Severity::Critical)));
+        assert_eq!(
+            critical.verify(ir, "mix").unwrap_err(),
+            "mix: division/modulo operation (10-40 cycles, not pipelined)"
+        );
+        let violations = critical.violations(ir, &HotEntry::parse("mix"));
+        assert_eq!(Outcome::from_violations(&violations), Outcome::Errors);
+        let info = HotPathVerifier::new()
+            .with_check(Box::new(WithSeverity(DivisionCheck, Severity::Info)));
+        let violations = info.violations(ir, &HotEntry::parse("mix"));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(Outcome::from_violations(&violations), Outcome::Clean);
+    }
+
+    #[test]
+    fn test_stack_alloca_size() {
+        let ir = "define void @on_dma(i64 %n) {
+start:
+  %samples = alloca [4096 x i8], align 16
+  %coeffs = alloca float, i64 64, align 4
+  %state = alloca { i32, [2 x ptr] }, align 8
+  %scratch = alloca i8, i64 %n, align 1
+  %huge = alloca [4294967296 x i64], i64 4294967296, align 8
+  ret void
+}";
+        let body = find_function_body(ir, "on_dma").unwrap();
+        let messages: Vec<String> = body
+            .lines()
+            .filter_map(|line| StackAllocaSizeCheck::new(128).check_line(line))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "alloca %samples of 4096 bytes exceeds the 128-byte limit",
+                "alloca %coeffs of 256 bytes exceeds the 128-byte limit",
+                "dynamic alloca %scratch of i8 elements has no size bound (limit 128 bytes)",
+                "alloca %huge is larger than a 64-bit size can hold (limit 128 bytes)",
+            ]
+        );
+        // Part of the interrupt-handler profile; a warning where configured so.
+        let isr = HotPathVerifier::new().with_profile(Profile::InterruptHandler);
+        assert_eq!(
+            isr.verify(ir, "on_dma").unwrap_err(),
+            "on_dma: alloca %samples of 4096 bytes exceeds the 512-byte limit"
+        );
+        let lenient = HotPathVerifier::new().with_check(Box::new(WithSeverity(
+            StackAllocaSizeCheck::new(8192),
+            Severity::Warning,
+        )));
+        assert_eq!(lenient.verify(ir, "on_dma").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_deallocation() {
+        let ir = "define void @drain(ptr %v, ptr %b) {
+start:
+  call void @_ZN4core3ptr47drop_in_place$LT$alloc..vec..Vec$LT$f32$GT$$GT$17h0123456789abcdefE(ptr %v)
+  call void @__rust_dealloc(ptr %b, i64 64, i64 8)
+  tail call void @free(ptr %b)
+  call void @_ZN4core3ptr33drop_in_place$LT$synth..Voice$GT$17h0123456789abcdefE(ptr %v)
+  ret void
+}";
+        let body = find_function_body(ir, "drain").unwrap();
+        let flagged: Vec<bool> = body
+            .lines()
+            .filter(|line| line.contains("call "))
+            .map(|line| DeallocationCheck.check_line(line).is_some())
+            .collect();
+        assert_eq!(flagged, [true, true, true, false]);
+        // Reported as deallocation, not also as plain calls.
+        let violations = HotPathVerifier::default().violations(ir, &HotEntry::parse("drain"));
+        let checks: Vec<&str> = violations.iter().map(|v| v.check.as_str()).collect();
+        assert_eq!(
+            checks,
+            [
+                "deallocation",
+                "deallocation",
+                "deallocation",
+                "function_call"
+            ]
+        );
+        // Without `DeallocationCheck` they are still reported, as calls.
+        let calls = HotPathVerifier::new()
+            .with_check(Box::new(FunctionCallCheck))
+            .violations(ir, &HotEntry::parse("drain"));
+        assert_eq!(calls.len(), 4);
+        assert!(calls.iter().all(|v| v.check == "function_call"));
+    }
+
+    #[test]
+    fn test_panic_path() {
+        let ir = "define i32 @get(ptr %p, i64 %i) {
+start:
+  %oob = icmp uge i64 %i, 8
+  br i1 %oob, label %panic, label %ok
+
+ok:
+  %v = load i32, ptr %p, align 4
+  %o = call i32 @_ZN4core6option13expect_failed17h0123456789abcdefE(ptr @msg)
+  %d = call i32 @_ZN5synth3dsp3mix17h0123456789abcdefE(i32 %v)
+  ret i32 %d
+
+panic:
+  call void @_ZN4core9panicking18panic_bounds_check17h0123456789abcdefE(i64 %i, i64 8)
+  unreachable
+}";
+        let body = find_function_body(ir, "get").unwrap();
+        let messages: Vec<String> = body
+            .lines()
+            .filter_map(|line| PanicPathCheck.check_line(line))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "calls panic machinery core::option::expect_failed (formatting and unwinding on the hot path)",
+                "calls panic machinery core::panicking::panic_bounds_check (formatting and unwinding on the hot path)",
+            ]
+        );
+        // On by default, and not reported a second time as a plain call.
+        let violations = HotPathVerifier::default().violations(ir, &HotEntry::parse("get"));
+        let checks: Vec<&str> = violations.iter().map(|v| v.check.as_str()).collect();
+        assert_eq!(checks.iter().filter(|c| **c == "panic_path").count(), 2);
+        assert_eq!(checks.iter().filter(|c| **c == "function_call").count(), 1);
+        // Without `PanicPathCheck` they are still reported, as calls.
+        let calls = HotPathVerifier::new()
+            .with_check(Box::new(FunctionCallCheck))
+            .violations(ir, &HotEntry::parse("get"));
+        assert_eq!(calls.len(), 3);
+        assert!(calls.iter().all(|v| v.check == "function_call"));
+
+        // The other profiles that care about these calls have `PanicPathCheck` too.
+        for (profile, severity) in [
+            (Profile::ConstTime, Severity::Error),
+            (Profile::CodeSize, Severity::Error),
+            (Profile::Async, Severity::Warning),
+        ] {
+            let verifier = HotPathVerifier::new().with_profile(profile);
+            let panics: Vec<Severity> = verifier
+                .violations(ir, &HotEntry::parse("get"))
+                .iter()
+                .filter(|v| v.check == "panic_path")
+                .map(|v| v.severity)
+                .collect();
+            assert_eq!(panics, [severity, severity], "{:?}", profile);
+        }
+    }
+
+    #[test]
+    fn test_recursion() {
+        let ir = "define i64 @_ZN5synth4tree5depth17h0123456789abcdefE(ptr %node) {
+start:
+  %leaf = icmp eq ptr %node, null
+  br i1 %leaf, label %done, label %walk
+
+walk:
+  %child = load ptr, ptr %node, align 8
+  %d = call i64 @_ZN5synth4tree5depth17h0123456789abcdefE(ptr %child)
+  %w = tail call i64 @_ZN5synth4tree5width17h0123456789abcdefE(ptr %child)
+  %n = add i64 %d, 1
+  ret i64 %n
+
+done:
+  ret i64 0
+}";
+        let found = find_function(ir, "synth::tree::depth").unwrap();
+        let body = &ir[found.body];
+        let hits = RecursionCheck.check_function(found.symbol, body);
+        assert_eq!(hits.len(), 1);
+        assert!(body.lines().nth(hits[0].0).unwrap().contains("%d = call"));
+        assert!(RecursionCheck.check_body(body).is_empty());
+        assert!(
+            RecursionCheck
+                .check_function("_ZN5synth4tree5width17h0123456789abcdefE", body)
+                .iter()
+                .all(|(line, _)| body.lines().nth(*line).unwrap().contains("%w ="))
+        );
+
+        // An error by default; the other call is still a plain call.
+        let violations =
+            HotPathVerifier::default().violations(ir, &HotEntry::parse("synth::tree::depth"));
+        let checks: Vec<(&str, Severity)> = violations
+            .iter()
+            .map(|v| (v.check.as_str(), v.severity))
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                ("recursion", Severity::Error),
+                ("function_call", Severity::Error)
+            ]
+        );
+        assert!(
+            HotPathVerifier::default()
+                .violations(ir, &HotEntry::parse("synth::tree::depth;allow=recursion"))
+                .iter()
+                .all(|v| v.check == "function_call")
+        );
+        // Without `RecursionCheck` the call is still reported, as a call.
+        let calls = HotPathVerifier::new()
+            .with_check(Box::new(FunctionCallCheck))
+            .violations(ir, &HotEntry::parse("synth::tree::depth"));
+        assert_eq!(calls.len(), 2);
+        assert!(calls.iter().all(|v| v.check == "function_call"));
+    }
+
+    #[test]
+    fn test_mem_intrinsics() {
+        let ir = "define void @tick(ptr %dst, ptr %src, i64 %n) {
+start:
+  call void @llvm.memcpy.p0.p0.i64(ptr align 4 %dst, ptr align 4 %src, i64 4096, i1 false)
+  call void @llvm.memmove.p0.p0.i64(ptr align 8 %dst, ptr align 8 getelementptr inbounds (i8, ptr @buf, i64 8), i64 48, i1 false)
+  call void @llvm.memset.p0.i64(ptr align 1 %dst, i8 0, i64 %n, i1 false)
+  call void @llvm.memcpy.inline.p0.p0.i64(ptr %dst, ptr %src, i64 256, i1 false)
+  call void @llvm.lifetime.start.p0(i64 4096, ptr %dst)
+  ret void
+}";
+        let body = find_function_body(ir, "tick").unwrap();
+        let messages: Vec<String> = body
+            .lines()
+            .filter_map(|line| MemIntrinsicCheck::new().check_line(line))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "llvm.memcpy of 4096 bytes (a copy loop or libc call, not a few moves)",
+                "llvm.memset with a length known only at run time (cost grows with the length)",
+                "llvm.memcpy of 256 bytes (a copy loop or libc call, not a few moves)",
+            ]
+        );
+        let strict = MemIntrinsicCheck::error_above(1024);
+        assert_eq!(strict.severity(), Severity::Error);
+        let hits = strict.check_body(&body);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            hits[0].1,
+            "llvm.memcpy of 4096 bytes exceeds the 1024-byte limit"
+        );
+
+        // A warning by default; the intrinsics are still not plain calls.
+        let violations = HotPathVerifier::default().violations(ir, &HotEntry::parse("tick"));
+        assert_eq!(violations.len(), 3);
+        assert!(
+            violations
+                .iter()
+                .all(|v| v.check == "mem_intrinsic" && v.severity == Severity::Warning)
+        );
+    }
+
+    #[test]
+    fn test_intrinsic_policy() {
+        let ir = "define float @gain(float %x, ptr %d, ptr %s) {
+start:
+  call void @llvm.dbg.declare(metadata ptr %d, metadata !1, metadata !DIExpression())
+  %p = call float @llvm.pow.f32(float %x, float 2.0)
+  %q = call float @llvm.powi.f32.i32(float %x, i32 3)
+  %f = call float @llvm.fabs.f32(float %p)
+  call void @llvm.memcpy.p0.p0.i64(ptr %d, ptr %s, i64 16, i1 false)
+  ret float %f
+}";
+        let body = find_function_body(ir, "gain").unwrap();
+        let flagged = |check: &dyn HotPathCheck| -> Vec<String> {
+            body.lines()
+                .filter_map(|line| check.check_line(line))
+                .collect()
+        };
+        assert!(flagged(&FunctionCallCheck).is_empty());
+        assert_eq!(
+            flagged(&FunctionCallCheck::with_intrinsics(
+                IntrinsicPolicy::AllowExcept(std::vec![
+                    "pow".to_string(),
+                    "llvm.memcpy".to_string()
+                ])
+            )),
+            [
+                "calls intrinsic llvm.pow.f32 (may be lowered to a library call)",
+                "calls intrinsic llvm.memcpy.p0.p0.i64 (may be lowered to a library call)",
+            ]
+        );
+        let strict = FunctionCallCheck::with_intrinsics(IntrinsicPolicy::DenyExcept(std::vec![
+            "fabs".to_string()
+        ]));
+        assert_eq!(flagged(&strict).len(), 3);
+        // An intrinsic `LibmCheck` reports is reported once, as libm.
+        let verifier = HotPathVerifier::new()
+            .with_check(Box::new(FunctionCallCheck::with_intrinsics(
+                IntrinsicPolicy::AllowExcept(std::vec!["pow".to_string()]),
+            )))
+            .with_check(Box::new(LibmCheck));
+        let checks: Vec<String> = verifier
+            .violations(ir, &HotEntry::parse("gain"))
+            .into_iter()
+            .map(|v| v.check)
+            .collect();
+        assert_eq!(checks, ["libm"]);
+        assert!(IntrinsicPolicy::DenyExcept(std::vec![]).allows("lifetime.start.p0"));
+        assert!(!IntrinsicPolicy::DenyExcept(std::vec![]).allows("dbgx"));
+
+        // Configured instances do not share cache entries.
+        assert_eq!(FunctionCallCheck.config(), "");
+        assert_ne!(
+            FunctionCallCheck::with_intrinsics(IntrinsicPolicy::AllowExcept(std::vec![
+                "pow".to_string()
+            ]))
+            .config(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_libm() {
+        let ir = "define float @osc(float %x) {
+start:
+  %s = call float @sinf(float %x)
+  %p = call float @llvm.pow.f32(float %s, float 2.5)
+  %i = call float @llvm.powi.f32.i32(float %s, i32 2)
+  %e = tail call float @erff(float %p)
+  %l = call float @logistic(float %e)
+  %r = call float @llvm.sqrt.f32(float %l)
+  ret float %r
+}";
+        let body = find_function_body(ir, "osc").unwrap();
+        let messages: Vec<String> = body
+            .lines()
+            .filter_map(|line| LibmCheck.check_line(line))
+            .collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            messages[0],
+            "calls libm sinf (software routine, tens to hundreds of cycles)"
+        );
+        assert!(messages[1].starts_with("llvm.pow.f32 (lowered to a libm call"));
+        assert!(messages[2].contains("erff"));
+        assert!(messages[3].starts_with("llvm.sqrt.f32"));
+
+        // An error by default, as a plain call was, and not reported again
+        // as one; configurable with `WithSeverity`.
+        let entry = HotEntry::parse("osc");
+        let violations = HotPathVerifier::default().violations(ir, &entry);
+        let checks: Vec<(&str, Severity)> = violations
+            .iter()
+            .map(|v| (v.check.as_str(), v.severity))
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                ("libm", Severity::Error),
+                ("libm", Severity::Error),
+                ("libm", Severity::Error),
+                ("function_call", Severity::Error),
+                ("libm", Severity::Error),
+            ]
+        );
+        // Without `LibmCheck` the libm functions are still reported, as calls.
+        let calls = HotPathVerifier::new()
+            .with_check(Box::new(FunctionCallCheck))
+            .violations(ir, &entry);
+        assert_eq!(calls.len(), 3);
+        assert!(calls.iter().all(|v| v.check == "function_call"));
+        let sinf =
+            "define float @f(float %x) {\n  %s = call float @sinf(float %x)\n  ret float %s\n}";
+        assert!(verify_hot_function(sinf, "f").is_err());
+        let lenient =
+            HotPathVerifier::new().with_check(Box::new(WithSeverity(LibmCheck, Severity::Warning)));
+        assert_eq!(lenient.verify(ir, "osc").unwrap().len(), 4);
+
+        // The other profiles that care about these calls have `LibmCheck` too.
+        for (profile, severity) in [
+            (Profile::ConstTime, Severity::Error),
+            (Profile::CodeSize, Severity::Error),
+            (Profile::Async, Severity::Warning),
+        ] {
+            let verifier = HotPathVerifier::new().with_profile(profile);
+            let violations = verifier.violations(sinf, &HotEntry::parse("f"));
+            assert_eq!(violations.len(), 1, "{:?}", profile);
+            assert_eq!(violations[0].check, "libm");
+            assert_eq!(violations[0].severity, severity);
+        }
+    }
+
+    #[test]
+    fn test_overflow_checks_vs_checked_arithmetic() {
+        let ir = "define i32 @f(i32 %a, i32 %b) {
+start:
+  %0 = call { i32, i1 } @llvm.sadd.with.overflow.i32(i32 %a, i32 %b)
+  %_3.1 = extractvalue { i32, i1 } %0, 1
+  br i1 %_3.1, label %panic, label %bb1
+
+bb1:
+  %1 = call { i32, i1 } @llvm.umul.with.overflow.i32(i32 %a, i32 %b)
+  %2 = extractvalue { i32, i1 } %1, 1
+  br i1 %2, label %none, label %some
+
+some:
+  %3 = extractvalue { i32, i1 } %1, 0
+  ret i32 %3
+
+none:
+  ret i32 0
+
+panic:
+  call void @_ZN4core9panicking11panic_const24panic_const_add_overflow17h0123456789abcdefE(ptr @loc)
+  unreachable
+}";
+        let body = find_function_body(ir, "f").unwrap();
+        let hits = OverflowCheck.check_body(&body);
+        assert_eq!(hits.len(), 1);
+        assert!(
+            hits[0]
+                .1
+                .starts_with("sadd overflow check branches to a panic")
+        );
+        assert!(body.lines().nth(hits[0].0).unwrap().contains("sadd"));
+    }
+
+    #[test]
+    fn test_function_policy() {
+        let ir = "define i32 @mix(i32 %a) {
+  %buf = alloca [512 x i8], align 1
+  %q = udiv i32 %a, 3
+  ret i32 %q
+}
+define i32 @gain(i32 %a) {
+  %q = udiv i32 %a, 3
+  ret i32 %q
+}";
+        let verifier = HotPathVerifier::default()
+            .with_function_policy("mix", |p| p.allow("division"))
+            .with_function_policy("mix", |p| p.stack_limit(256));
+        let err = verifier.verify(ir, "mix").unwrap_err();
+        assert_eq!(
+            err,
+            "mix: static frame of 512 bytes exceeds the 256-byte limit"
+        );
+        // The global policy still applies to other functions.
+        assert_eq!(verifier.verify(ir, "gain").unwrap().len(), 1);
+
+        let relaxed = HotPathVerifier::default()
+            .with_function_policy("mix", |p| p.allow("division").stack_limit(1024));
+        assert!(relaxed.verify(ir, "mix").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_allow_until() {
+        let ir = "define i32 @mix(i32 %a) {
+  %q = udiv i32 %a, 3
+  ret i32 %q
+}";
+        let entry = HotEntry::parse("mix;allow=division,;until=2025-09-01;file=src/dsp.rs");
+        assert_eq!(entry.allow, ["division"]);
+        assert_eq!(entry.allow_until.as_deref(), Some("2025-09-01"));
+        let on = |date: &str| HotPathVerifier::default().with_date(date);
+        assert!(on("2025-09-01").violations(ir, &entry).is_empty());
+
+        let violations = on("2025-09-02").violations(ir, &entry);
+        let checks: Vec<&str> = violations.iter().map(|v| v.check.as_str()).collect();
+        assert_eq!(checks, ["allow_expired", "division"]);
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert_eq!(
+            violations[0].message,
+            "allow(division) expired on 2025-09-01; the check applies again"
+        );
+
+        let typo = HotEntry::parse("mix;allow=division,;until=2025-9-1");
+        assert!(
+            on("2000-01-01").violations(ir, &typo)[0]
+                .message
+                .contains("invalid expiry `2025-9-1`")
+        );
+
+        // Policy exemptions expire per check; a permanent one stays.
+        let verifier = on("2026-01-01")
+            .with_function_policy("mix", |p| p.allow_until("division", "2025-12-31"));
+        assert!(verifier.verify(ir, "mix").is_err());
+        let verifier =
+            verifier.with_function_policy("mix", |p| p.allow_until("atomic", "2026-06-30"));
+        assert_eq!(
+            verifier.verify(ir, "mix").unwrap_err(),
+            "mix: allow(division) expired on 2025-12-31; the check applies again"
+        );
+        let relaxed = on("2026-01-01")
+            .with_function_policy("mix", |p| p.allow_until("division", "2026-06-30"));
+        assert!(relaxed.verify(ir, "mix").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fma_requirement() {
+        let split = "define float @k(float %a, float %b, float %c) {
+  %p = fmul float %a, %b
+  %s = fadd float %p, %c
+  ret float %s
+}";
+        let fused = "define float @k(float %a, float %b, float %c) {
+  %s = call float @llvm.fma.f32(float %a, float %b, float %c)
+  %q = fmul contract float %a, %b
+  %r = fadd contract float %q, %s
+  ret float %r
+}";
+        let entry = HotEntry::parse("k;require=fma,");
+        assert_eq!(entry.require, ["fma"]);
+        let verifier = HotPathVerifier::new();
+        let err = verifier.verify_entry(split, &entry).unwrap_err();
+        assert!(err.contains("%p feeds an fadd/fsub"), "{}", err);
+        assert!(verifier.verify_entry(fused, &entry).is_ok());
+        // Without the requirement the split pair is fine.
+        assert!(verifier.verify(split, "k").is_ok());
+
+        let unknown = HotEntry::parse("k;require=sve,");
+        assert!(
+            verifier
+                .verify_entry(fused, &unknown)
+                .unwrap_err()
+                .contains("unknown requirement `sve`")
+        );
+    }
+
+    #[test]
+    fn test_contract_machinery() {
+        let ir = "define i32 @k(i32 %x) {
+start:
+  %ok = icmp ult i32 %x, 1024
+  br i1 %ok, label %bb2, label %bb1
+
+bb1:
+  call void @_ZN4base6macros8contract8violated17h0123456789abcdefE(ptr @anon.0, i64 12, ptr @anon.1, i64 9) #7
+  unreachable
+
+bb2:
+  %r = shl i32 %x, 2
+  ret i32 %r
+}";
+        let violations = HotPathVerifier::default().violations(ir, &HotEntry::parse("k"));
+        let checks: Vec<&str> = violations.iter().map(|v| v.check.as_str()).collect();
+        assert_eq!(checks, ["contract", "trap"]);
+        // Every profile that leaves the call to `ContractCheck` has it.
+        for (profile, severity) in [
+            (Profile::ConstTime, Severity::Error),
+            (Profile::Async, Severity::Warning),
+        ] {
+            let verifier = HotPathVerifier::new().with_profile(profile);
+            let violations = verifier.violations(ir, &HotEntry::parse("k"));
+            assert_eq!(violations.len(), 1, "{:?}", profile);
+            assert_eq!(violations[0].check, "contract");
+            assert_eq!(violations[0].severity, severity);
+        }
+        // Without `ContractCheck` the call is still reported, as a call.
+        let calls = HotPathVerifier::new()
+            .with_check(Box::new(FunctionCallCheck))
+            .violations(ir, &HotEntry::parse("k"));
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].check, "function_call");
+
+        let release = "define i32 @k(i32 %x) {
+  %ok = icmp ult i32 %x, 1024
+  tail call void @llvm.assume(i1 %ok)
+  %r = shl i32 %x, 2
+  ret i32 %r
+}";
+        assert!(
+            HotPathVerifier::default()
+                .verify(release, "k")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_isr_tier() {
+        let ir = "define void @on_tick(ptr %s, float %g) {
+  %1 = load float, ptr %s, align 4
+  %2 = fmul float %1, %g
+  store float %2, ptr %s, align 4
+  call void @llvm.lifetime.start.p0(i64 4, ptr %s)
+  call void @llvm.memset.p0.i64(ptr %s, i8 0, i64 64, i1 false)
+  ret void
+}";
+        let entry = HotEntry::parse("on_tick;allow=floating_point,;tier=isr");
+        assert_eq!(entry.tier.as_deref(), Some("isr"));
+        let verifier = HotPathVerifier::default();
+        assert!(verifier.verify(ir, "on_tick").is_ok());
+        let err = verifier.verify_entry(ir, &entry).unwrap_err();
+        assert_eq!(err, "on_tick: calls llvm.memset.p0.i64");
+
+        let checks: Vec<String> = HotPathVerifier::new()
+            .with_profile(Profile::InterruptHandler)
+            .violations(ir, &HotEntry::parse("on_tick"))
+            .into_iter()
+            .map(|v| v.check)
+            .collect();
+        assert_eq!(checks, ["floating_point", "call"]);
+        assert_eq!(Profile::from_name("ISR"), Some(Profile::InterruptHandler));
+    }
+
+    #[test]
+    fn test_libc_compare_has_hint() {
+        let ir = "define i1 @eq(ptr %a, ptr %b, i64 %n) {
+  %1 = tail call i32 @bcmp(ptr %a, ptr %b, i64 %n)
+  %2 = call i64 @strlen(ptr %a)
+  %3 = icmp eq i32 %1, 0
+  ret i1 %3
+}";
+        let err = verify_hot_function(ir, "eq").unwrap_err();
+        assert!(err.contains("slice equality lowered to bcmp"), "{}", err);
+        assert!(!err.contains("not inlined"));
+        assert!(
+            LibcCompareCheck
+                .check_line("  %2 = call i64 @strlen(ptr %a)")
+                .unwrap()
+                .contains("carry the length")
+        );
+        assert_eq!(
+            LibcCompareCheck.check_line("  %x = call i32 @memcmp(ptr %a, ptr %b, i64 4)"),
+            Some(LibcCompareCheck::CALLEES[1].1.to_string())
+        );
+
+        // Without `LibcCompareCheck` the calls are still reported, as calls.
+        let entry = HotEntry::parse("eq");
+        let checks = |verifier: HotPathVerifier| -> Vec<String> {
+            verifier
+                .violations(ir, &entry)
+                .into_iter()
+                .map(|v| v.check)
+                .collect()
+        };
+        assert_eq!(
+            checks(HotPathVerifier::new().with_check(Box::new(FunctionCallCheck))),
+            ["function_call", "function_call"]
+        );
+        assert_eq!(
+            checks(HotPathVerifier::default()),
+            ["libc_compare", "libc_compare"]
+        );
+    }
+
+    #[test]
+    fn test_target_lowering_advisory() {
+        let lines = [
+            "  %z = call i32 @llvm.ctlz.i32(i32 %a, i1 false)",
+            "  %s = shl i64 %x, %n",
+            "  %c = shl i64 %x, 3",
+            "  %m = mul nuw i64 %x, %y",
+            "  %w = mul i32 %a, %b",
+        ];
+        let hits = |target| {
+            let check = TargetLoweringCheck::new(target);
+            lines
+                .iter()
+                .filter_map(|line| check.check_line(line))
+                .collect::<Vec<_>>()
+        };
+        let m0 = hits(TargetProfile::CORTEX_M0);
+        assert_eq!(m0.len(), 3);
+        assert!(m0[0].starts_with("count-leading-zeros has no instruction on cortex-m0"));
+        assert!(m0[1].starts_with("variable shift wider than 32 bits"));
+        assert!(m0[2].starts_with("multiply wider than 32 bits"));
+        assert_eq!(hits(TargetProfile::CORTEX_M3).len(), 1);
+        assert!(hits(TargetProfile::X86_64).is_empty());
+
+        let ir = "define i64 @f(i64 %x, i64 %n) {\n  %s = lshr i64 %x, %n\n  ret i64 %s\n}";
+        let verifier = HotPathVerifier::new().with_target(TargetProfile::CORTEX_M3);
+        assert_eq!(verifier.verify(ir, "f").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_soft_float() {
+        let lines = [
+            "  %a = fadd float %x, %y",
+            "  %b = fmul fast double %x, %y",
+            "  %c = fcmp olt double %x, %y",
+            "  %d = fdiv nnan <4 x float> %x, %y",
+            "  %e = fneg float %x",
+            "  %f = sitofp i32 %n to double",
+            "  %g = fpext float %x to double",
+            "  %h = fcmp true float %x, %y",
+            "  %i = add i32 %n, 1",
+        ];
+        let hits = |target| {
+            let check = SoftFloatCheck::new(target);
+            lines
+                .iter()
+                .filter_map(|line| check.check_line(line))
+                .collect::<Vec<_>>()
+        };
+        let m0 = hits(TargetProfile::from_triple("thumbv6m-none-eabi"));
+        assert_eq!(m0.len(), 6);
+        assert_eq!(
+            m0[0],
+            "`fadd` on float is a compiler-rt call on cortex-m0 (no FPU support)"
+        );
+        assert!(m0[2].starts_with("`fcmp` on double"));
+        assert!(m0[3].starts_with("`fdiv` on float"));
+        let m4f = hits(TargetProfile::CORTEX_M4F);
+        assert_eq!(m4f.len(), 4);
+        assert!(m4f.iter().all(|hit| hit.contains("on double")));
+        assert!(hits(TargetProfile::X86_64).is_empty());
+
+        let ir = "define float @f(float %x) {\n  %y = fmul float %x, %x\n  ret float %y\n}";
+        assert!(HotPathVerifier::new().verify(ir, "f").unwrap().is_empty());
+        let verifier = HotPathVerifier::new()
+            .with_soft_float(TargetProfile::from_triple("thumbv6m-none-eabi"));
+        assert_eq!(
+            verifier.verify(ir, "f").unwrap_err(),
+            "f: `fmul` on float is a compiler-rt call on cortex-m0 (no FPU support)"
+        );
+    }
+
+    #[test]
+    fn test_no_mangle_extern_c_functions() {
+        let ir = r#"
+@alloc_cb = private unnamed_addr constant [19 x i8] c"app::audio::on_dma\00", align 1
+@HOT_FUNC_ON_DMA = internal constant <{ ptr, [8 x i8] }> <{ ptr @alloc_cb, [8 x i8] c"\12\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define void @_ZN3app5audio13on_dma_helper17h0123456789abcdefE() unnamed_addr #1 {
+  %1 = udiv i32 7, 3
+  ret void
+}
+; Function Attrs: nounwind
+define dso_local void @on_dma(ptr noalias noundef nonnull align 4 dereferenceable(8) %ctx, i32 noundef %n) unnamed_addr #0 personality ptr @rust_eh_personality {
+start:
+  store i32 %n, ptr %ctx, align 4
+  ret void
+}
+define void @"on_dma.cold"() {
+  call void @abort()
+  unreachable
+}
+"#;
+        let entries = find_hot_entries_from_ir(ir);
+        assert_eq!(entries[0].name, "app::audio::on_dma");
+        let body = find_function_body(ir, "app::audio::on_dma").unwrap();
+        assert!(body.contains("store i32 %n"), "{}", body);
+        assert!(verify_hot_path_functions(ir).is_ok());
+        assert!(find_function_body(ir, "on_dma").unwrap().contains("store"));
+    }
+
     #[test]
     fn test_mangle_rust_path() {
         assert_eq!(mangle_rust_path("foo"), "3foo");
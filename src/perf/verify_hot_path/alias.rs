@@ -0,0 +1,287 @@
+//! Missing `noalias` on hot function parameters.
+//!
+//! rustc marks `&mut T` and (freeze) `&T` parameters `noalias`; raw
+//! pointers, `&Cell<T>` and `&UnsafeCell<T>` get nothing. Without it LLVM
+//! must assume a store through one parameter may change what another points
+//! to, so a value read before the store is read again after it. This pass
+//! reports those reloads: the same address loaded twice in a block with a
+//! store through a possibly aliasing pointer in between, and a
+//! loop-invariant address loaded on every iteration of a loop that stores.
+//! The advisory (`Severity::Info`) names both pointers; the fix is in the
+//! Rust signature.
+
+use std::collections::HashMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::policy::split_type;
+use super::{HotEntry, Severity, Violation, blocks, find_function};
+
+/// A pointer parameter of a `define`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerParam {
+    /// SSA name including `%`.
+    pub name: String,
+    pub noalias: bool,
+}
+
+/// Pointer parameters of the function defined by `define` (header and body).
+pub fn pointer_params(define: &str) -> Vec<PointerParam> {
+    let header = &define[..define.find('{').unwrap_or(define.len())];
+    let Some(open) = header
+        .find('@')
+        .and_then(|at| header[at..].find('(').map(|p| at + p + 1))
+    else {
+        return Vec::new();
+    };
+    let close = header.rfind(')').unwrap_or(header.len()).max(open);
+    let mut params = Vec::new();
+    let mut rest = &header[open..close];
+    while !rest.trim().is_empty() {
+        let (param, tail) = split_type(rest);
+        rest = tail;
+        let words: Vec<&str> = param.split_whitespace().collect();
+        if words.first() == Some(&"ptr")
+            && let Some(name) = words.last().filter(|w| w.starts_with('%'))
+        {
+            params.push(PointerParam {
+                name: name.to_string(),
+                noalias: words.contains(&"noalias"),
+            });
+        }
+    }
+    params
+}
+
+/// Pointer operand of a load or store line, and whether it is a store.
+fn access(line: &str) -> Option<(&str, bool)> {
+    let code = line.split_once(" = ").map_or(line, |(_, code)| code).trim();
+    let (operands, store) = if let Some(load) = code.strip_prefix("load ") {
+        (split_type(load).1, false)
+    } else if let Some(store) = code.strip_prefix("store ") {
+        (split_type(store).1, true)
+    } else {
+        return None;
+    };
+    let operands = operands
+        .trim_start()
+        .trim_start_matches("atomic ")
+        .trim_start_matches("volatile ");
+    let pointer = split_type(operands).0.strip_prefix("ptr ")?;
+    Some((pointer.split_whitespace().next()?, store))
+}
+
+/// The pointer `pointer` is derived from through `base`.
+fn root<'a>(base: &HashMap<&'a str, &'a str>, pointer: &'a str) -> &'a str {
+    base.get(pointer).copied().unwrap_or(pointer)
+}
+
+/// Whether a store through `store` may change memory read through `load`.
+fn may_alias(load: &str, store: &str, params: &[PointerParam], locals: &[&str]) -> bool {
+    if load == store || locals.contains(&store) {
+        return false;
+    }
+    let param = |name: &str| params.iter().find(|p| p.name == name);
+    match (param(load), param(store)) {
+        (Some(l), Some(s)) => !l.noalias && !s.noalias,
+        (Some(l), None) => !l.noalias,
+        _ => false,
+    }
+}
+
+/// Advisories for hot functions in `entries` with reloads caused by missing
+/// `noalias`. Entries allowing `noalias` are skipped.
+pub fn noalias_advisories(ir: &str, entries: &[HotEntry]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for entry in entries {
+        if entry.allow.iter().any(|a| a == "noalias") {
+            continue;
+        }
+        let Ok(found) = find_function(ir, &entry.name) else {
+            continue;
+        };
+        let params = pointer_params(&ir[found.define]);
+        if params.iter().all(|p| p.noalias) {
+            continue;
+        }
+        let body = &ir[found.body];
+        // SSA pointer -> the parameter (or other root) it is derived from.
+        let mut base: HashMap<&str, &str> = HashMap::new();
+        let mut locals = Vec::new();
+        for line in body.lines().map(str::trim) {
+            let Some((result, code)) = line.split_once(" = ") else {
+                continue;
+            };
+            if code.starts_with("alloca ") {
+                locals.push(result);
+            } else if let Some(gep) = code.strip_prefix("getelementptr ") {
+                let (_, rest) = split_type(gep);
+                if let Some(pointer) = split_type(rest).0.split_whitespace().last() {
+                    let root = base.get(pointer).copied().unwrap_or(pointer);
+                    base.insert(result, root);
+                }
+            }
+        }
+        let root = |pointer| root(&base, pointer);
+        let mut reported = Vec::new();
+        let mut report = |loaded: &str, (from, to): (&str, &str), message: String| {
+            if !reported.contains(&loaded.to_string()) {
+                reported.push(loaded.to_string());
+                violations.push(Violation {
+                    function: entry.name.clone(),
+                    check: "noalias".to_string(),
+                    severity: Severity::Info,
+                    message: format!(
+                        "{}: {} and {} may alias; take `&mut`/`&` instead of raw pointers or `Cell`s, or read into a local before the stores",
+                        message, from, to
+                    ),
+                    location: None,
+                });
+            }
+        };
+        for block in blocks::split(body) {
+            let accesses: Vec<(&str, bool)> =
+                block.lines.iter().filter_map(|l| access(l)).collect();
+            // Same address loaded again after an aliasing store.
+            for (i, &(pointer, store)) in accesses.iter().enumerate() {
+                if store {
+                    continue;
+                }
+                let mut clobber = None;
+                for &(other, other_store) in &accesses[i + 1..] {
+                    if other_store && other == pointer {
+                        break;
+                    }
+                    if other_store && may_alias(root(pointer), root(other), &params, &locals) {
+                        clobber = Some(other);
+                    } else if !other_store
+                        && other == pointer
+                        && let Some(stored) = clobber
+                    {
+                        report(
+                            pointer,
+                            (root(pointer), root(stored)),
+                            format!(
+                                "{} is loaded again after a store through {}",
+                                pointer, stored
+                            ),
+                        );
+                        break;
+                    }
+                }
+            }
+            // A loop that reloads an address it never computes itself.
+            if block.successors().contains(&block.label) {
+                let defined = |pointer: &str| {
+                    block
+                        .lines
+                        .iter()
+                        .any(|l| l.trim().starts_with(&format!("{} = ", pointer)))
+                };
+                for &(pointer, _) in accesses.iter().filter(|(_, store)| !store) {
+                    if defined(pointer) {
+                        continue;
+                    }
+                    if let Some(&(stored, _)) = accesses.iter().find(|&&(other, store)| {
+                        store && may_alias(root(pointer), root(other), &params, &locals)
+                    }) {
+                        report(
+                            pointer,
+                            (root(pointer), root(stored)),
+                            format!(
+                                "loop-invariant {} is loaded on every iteration because the loop stores through {}",
+                                pointer, stored
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"define void @scale(ptr noundef %out, ptr noundef %gain, i64 %n) {
+start:
+  %g0 = load float, ptr %gain, align 4
+  store float %g0, ptr %out, align 4
+  %g1 = load float, ptr %gain, align 4
+  %p = getelementptr inbounds i8, ptr %out, i64 4
+  store float %g1, ptr %p, align 4
+  br label %loop
+loop:
+  %i = phi i64 [ 0, %start ], [ %next, %loop ]
+  %len = load i64, ptr %gain, align 8
+  %slot = getelementptr inbounds float, ptr %out, i64 %i
+  store float 0.0, ptr %slot, align 4
+  %next = add i64 %i, 1
+  %done = icmp uge i64 %next, %len
+  br i1 %done, label %exit, label %loop
+exit:
+  ret void
+}
+define void @scale_ref(ptr noalias noundef %out, ptr noalias noundef readonly %gain) {
+start:
+  %g0 = load float, ptr %gain, align 4
+  store float %g0, ptr %out, align 4
+  %g1 = load float, ptr %gain, align 4
+  store float %g1, ptr %out, align 4
+  ret void
+}
+define void @local(ptr noundef %gain) {
+start:
+  %tmp = alloca [4 x i8], align 4
+  %g0 = load float, ptr %gain, align 4
+  store float %g0, ptr %tmp, align 4
+  %g1 = load float, ptr %gain, align 4
+  ret void
+}
+"#;
+
+    #[test]
+    fn test_pointer_params() {
+        let params = pointer_params("define void @f(ptr noalias noundef %a, i64 %n, ptr %b) {\n}");
+        assert_eq!(
+            params,
+            [
+                PointerParam {
+                    name: "%a".to_string(),
+                    noalias: true
+                },
+                PointerParam {
+                    name: "%b".to_string(),
+                    noalias: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_noalias_advisories() {
+        let entries = ["scale", "scale_ref", "local"].map(HotEntry::parse);
+        let violations = noalias_advisories(IR, &entries);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].function, "scale");
+        assert!(
+            violations[0].message.starts_with(
+                "%gain is loaded again after a store through %out: %gain and %out may alias"
+            ),
+            "{}",
+            violations[0].message
+        );
+
+        let only_loop = IR.replace(
+            "  %g1 = load float, ptr %gain, align 4\n  %p",
+            "  %g1 = fadd float %g0, 1.0\n  %p",
+        );
+        let violations = noalias_advisories(&only_loop, &entries[..1]);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].message.starts_with("loop-invariant %gain is loaded on every iteration because the loop stores through %slot"));
+
+        assert!(noalias_advisories(IR, &[HotEntry::parse("scale;allow=noalias")]).is_empty());
+    }
+}
@@ -0,0 +1,493 @@
+//! Assembly backend: what only the final machine code shows.
+//!
+//! Works on `--emit=asm` output for the same build as the IR. Counts the
+//! callee-saved registers each hot function saves in its prologue; a
+//! function that spills most of the callee-saved set is under register
+//! pressure, which IR-level checks cannot see. `CodeSizeCheck` budgets the
+//! machine code of each hot function, for targets where flash and I-cache
+//! matter as much as cycles.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::{HotEntry, Severity, Violation, demangle};
+
+/// Instruction set of the assembly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    X86_64,
+    AArch64,
+    /// ARM/Thumb (Cortex-M, Cortex-A in AArch32).
+    Arm,
+}
+
+impl Arch {
+    pub fn from_triple(triple: &str) -> Option<Self> {
+        let arch = triple.split('-').next().unwrap_or(triple);
+        if arch == "x86_64" {
+            Some(Arch::X86_64)
+        } else if arch.starts_with("aarch64") || arch.starts_with("arm64") {
+            Some(Arch::AArch64)
+        } else if arch.starts_with("thumb") || arch.starts_with("arm") {
+            Some(Arch::Arm)
+        } else {
+            None
+        }
+    }
+
+    /// Guesses from the assembler dialect.
+    pub fn detect(asm: &str) -> Self {
+        if asm.contains(".thumb_func") || asm.contains(".syntax unified") {
+            Arch::Arm
+        } else if asm.contains("stp\tx") || asm.contains("\tstr\tx30") {
+            Arch::AArch64
+        } else {
+            Arch::X86_64
+        }
+    }
+
+    /// Saves above which a function is reported: roughly two thirds of the
+    /// general-purpose callee-saved set.
+    pub fn default_threshold(self) -> usize {
+        match self {
+            Arch::X86_64 => 4,
+            Arch::AArch64 => 6,
+            Arch::Arm => 5,
+        }
+    }
+
+    /// Callee-saved registers that indicate pressure. Frame pointer and link
+    /// register saves happen regardless and are not counted.
+    fn is_pressure_register(self, register: &str) -> bool {
+        let number = |prefix: &str| {
+            register
+                .strip_prefix(prefix)
+                .and_then(|n| n.parse::<u32>().ok())
+        };
+        match self {
+            Arch::X86_64 => matches!(register, "rbx" | "r12" | "r13" | "r14" | "r15"),
+            Arch::AArch64 => {
+                number("x").is_some_and(|n| (19..=28).contains(&n))
+                    || number("d").is_some_and(|n| (8..=15).contains(&n))
+            }
+            Arch::Arm => {
+                number("r").is_some_and(|n| (4..=11).contains(&n) && n != 7)
+                    || number("d").is_some_and(|n| (8..=15).contains(&n))
+                    || number("s").is_some_and(|n| (16..=31).contains(&n))
+            }
+        }
+    }
+}
+
+/// Callee-saved registers one hot function saves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterPressure {
+    pub function: String,
+    /// In prologue order.
+    pub saved: Vec<String>,
+}
+
+/// Body of the function whose label demangles to `name`, up to its end marker.
+pub fn function_body<'a>(asm: &'a str, name: &str) -> Option<&'a str> {
+    let mut offset = 0;
+    for line in asm.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some(label) = line.trim_end().strip_suffix(':') else {
+            continue;
+        };
+        if label.starts_with('.') || label.contains(char::is_whitespace) {
+            continue;
+        }
+        if label != name && demangle(label.trim_matches('"')) != name {
+            continue;
+        }
+        let rest = &asm[start..];
+        let end = rest
+            .find(".Lfunc_end")
+            .or_else(|| rest.find(".cfi_endproc"))
+            .or_else(|| rest.find("\t.size"))
+            .unwrap_or(rest.len());
+        return Some(&rest[..end]);
+    }
+    None
+}
+
+/// Registers listed in `{r4-r6, r8}` style lists.
+fn register_list(list: &str) -> Vec<String> {
+    let mut registers = Vec::new();
+    for item in list.split(',').map(str::trim) {
+        match item.split_once('-') {
+            Some((first, last)) => {
+                let prefix = first.trim_end_matches(|c: char| c.is_ascii_digit());
+                let from = first[prefix.len()..].parse::<u32>();
+                let to = last.trim_start_matches(prefix).parse::<u32>();
+                if let (Ok(from), Ok(to)) = (from, to) {
+                    registers.extend((from..=to).map(|n| format!("{}{}", prefix, n)));
+                }
+            }
+            None if !item.is_empty() => registers.push(item.to_string()),
+            None => {}
+        }
+    }
+    registers
+}
+
+/// Callee-saved registers `body` saves, in order.
+pub fn saved_registers(body: &str, arch: Arch) -> Vec<String> {
+    let mut saved = Vec::new();
+    for line in body.lines() {
+        let line = line.trim();
+        let (mnemonic, operands) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(m, o)| (m, o.trim()));
+        let candidates: Vec<String> = match (arch, mnemonic) {
+            (Arch::X86_64, "pushq" | "push") => {
+                std::vec![operands.trim_start_matches('%').to_string()]
+            }
+            (Arch::AArch64, "stp" | "str") if operands.contains("[sp") => operands
+                .split(", [")
+                .next()
+                .unwrap_or("")
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .collect(),
+            (Arch::Arm, "push" | "push.w" | "vpush" | "stmdb" | "stmdb.w")
+                if operands.contains('{') =>
+            {
+                let list = operands
+                    .split('{')
+                    .nth(1)
+                    .and_then(|l| l.split('}').next())
+                    .unwrap_or("");
+                register_list(list)
+            }
+            _ => Vec::new(),
+        };
+        for register in candidates {
+            if arch.is_pressure_register(&register) && !saved.contains(&register) {
+                saved.push(register);
+            }
+        }
+    }
+    saved
+}
+
+/// Saved-register counts for each entry found in `asm`.
+pub fn register_pressure(asm: &str, entries: &[HotEntry], arch: Arch) -> Vec<RegisterPressure> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let body = function_body(asm, &entry.name)?;
+            Some(RegisterPressure {
+                function: entry.name.clone(),
+                saved: saved_registers(body, arch),
+            })
+        })
+        .collect()
+}
+
+/// `register_pressure` warnings for functions saving more than `threshold`
+/// registers. Entries that allow `register_pressure` are skipped.
+pub fn verify_register_pressure(
+    asm: &str,
+    entries: &[HotEntry],
+    arch: Arch,
+    threshold: usize,
+) -> Vec<Violation> {
+    let entries: Vec<HotEntry> = entries
+        .iter()
+        .filter(|entry| !entry.allow.iter().any(|a| a == "register_pressure"))
+        .cloned()
+        .collect();
+    register_pressure(asm, &entries, arch)
+        .into_iter()
+        .filter(|pressure| pressure.saved.len() > threshold)
+        .map(|pressure| Violation {
+            check: "register_pressure".to_string(),
+            severity: Severity::Warning,
+            message: format!(
+                "saves {} callee-saved registers ({}); hot loop values are likely spilled",
+                pressure.saved.len(),
+                pressure.saved.join(", ")
+            ),
+            location: None,
+            function: pressure.function,
+        })
+        .collect()
+}
+
+/// Instruction lines of `body`: no labels, directives or comments.
+fn instructions(body: &str) -> impl Iterator<Item = &str> {
+    body.lines().map(str::trim).filter(|line| {
+        !line.is_empty() && !line.ends_with(':') && !line.starts_with(['.', '@', '#', ';', '/'])
+    })
+}
+
+/// Encoded size of one instruction. Thumb-2 has 16- and 32-bit encodings;
+/// x86-64 lengths vary, so 4 bytes is used as an average there.
+fn instruction_bytes(line: &str, arch: Arch) -> usize {
+    const THUMB_WIDE: &[&str] = &[
+        "bl", "mla", "mls", "umull", "smull", "umlal", "smlal", "sdiv", "udiv", "ldrd", "strd",
+        "bfi", "bfc", "ubfx", "sbfx", "clz", "rbit", "movw", "movt", "tbb", "tbh",
+    ];
+    match arch {
+        Arch::X86_64 | Arch::AArch64 => 4,
+        Arch::Arm => {
+            let mnemonic = line.split_whitespace().next().unwrap_or("");
+            if mnemonic.ends_with(".w")
+                || mnemonic.starts_with('v')
+                || THUMB_WIDE.contains(&mnemonic)
+            {
+                4
+            } else {
+                2
+            }
+        }
+    }
+}
+
+fn is_control_flow(line: &str, arch: Arch) -> bool {
+    let mnemonic = line.split_whitespace().next().unwrap_or("");
+    match arch {
+        Arch::X86_64 => {
+            mnemonic.starts_with('j') || mnemonic.starts_with("call") || mnemonic.starts_with("ret")
+        }
+        Arch::AArch64 | Arch::Arm => {
+            let branch = mnemonic.starts_with('b')
+                && !["bic", "bfi", "bfc", "bfxil"]
+                    .iter()
+                    .any(|bit_op| mnemonic.starts_with(bit_op));
+            branch
+                || mnemonic.starts_with("cb")
+                || mnemonic.starts_with("tb")
+                || mnemonic == "ret"
+                || (mnemonic.starts_with("pop") && line.contains("pc"))
+        }
+    }
+}
+
+/// Machine-code size of one hot function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeSize {
+    pub function: String,
+    /// Estimated from the instruction encodings (see `SymbolMap::with_nm`
+    /// for exact sizes from the final object).
+    pub bytes: usize,
+    pub instructions: usize,
+    /// Longest run of instructions without a branch: a fully unrolled loop
+    /// or a deep chain of inlined callees shows up as one long run.
+    pub straight_line: usize,
+}
+
+/// Size of `body`.
+pub fn code_size(function: &str, body: &str, arch: Arch) -> CodeSize {
+    let (mut bytes, mut count, mut run, mut straight_line) = (0, 0, 0, 0);
+    for line in instructions(body) {
+        bytes += instruction_bytes(line, arch);
+        count += 1;
+        run = if is_control_flow(line, arch) {
+            0
+        } else {
+            run + 1
+        };
+        straight_line = straight_line.max(run);
+    }
+    CodeSize {
+        function: function.to_string(),
+        bytes,
+        instructions: count,
+        straight_line,
+    }
+}
+
+/// Sizes of each entry found in `asm`.
+pub fn code_sizes(asm: &str, entries: &[HotEntry], arch: Arch) -> Vec<CodeSize> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            Some(code_size(
+                &entry.name,
+                function_body(asm, &entry.name)?,
+                arch,
+            ))
+        })
+        .collect()
+}
+
+/// Per-function machine-code budget.
+///
+/// A function over `budget` bytes is an error; a straight-line run longer
+/// than the straight-line limit is a warning even within budget, since it
+/// is where unrolling or inlining grew the code. Entries that allow
+/// `code_size` are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeSizeCheck {
+    pub budget: usize,
+    pub straight_line: usize,
+}
+
+impl CodeSizeCheck {
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            straight_line: 64,
+        }
+    }
+
+    pub fn with_straight_line_limit(mut self, instructions: usize) -> Self {
+        self.straight_line = instructions;
+        self
+    }
+
+    pub fn verify(&self, asm: &str, entries: &[HotEntry], arch: Arch) -> Vec<Violation> {
+        let entries: Vec<HotEntry> = entries
+            .iter()
+            .filter(|entry| !entry.allow.iter().any(|a| a == "code_size"))
+            .cloned()
+            .collect();
+        let mut violations = Vec::new();
+        for size in code_sizes(asm, &entries, arch) {
+            let mut violation = |severity, message| {
+                violations.push(Violation {
+                    function: size.function.clone(),
+                    check: "code_size".to_string(),
+                    severity,
+                    message,
+                    location: None,
+                })
+            };
+            if size.bytes > self.budget {
+                violation(
+                    Severity::Error,
+                    format!(
+                        "{} bytes of machine code ({} instructions) exceed the {}-byte budget",
+                        size.bytes, size.instructions, self.budget
+                    ),
+                );
+            }
+            if size.straight_line > self.straight_line {
+                violation(
+                    Severity::Warning,
+                    format!(
+                        "{} instructions without a branch; unrolled loop or inlined callees",
+                        size.straight_line
+                    ),
+                );
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X86: &str = "\t.section\t.text._ZN4base3dsp3mix17h0123456789abcdefE,\"ax\",@progbits
+_ZN4base3dsp3mix17h0123456789abcdefE:
+\t.cfi_startproc
+\tpushq\t%rbp
+\tpushq\t%r15
+\tpushq\t%r14
+\tpushq\t%r13
+\tpushq\t%r12
+\tpushq\t%rbx
+\tretq
+.Lfunc_end0:
+_ZN4base3dsp4gain17h0123456789abcdefE:
+\tpushq\t%rbx
+\tretq
+.Lfunc_end1:
+";
+
+    fn entries(names: &[&str]) -> Vec<HotEntry> {
+        names.iter().map(|name| HotEntry::parse(name)).collect()
+    }
+
+    #[test]
+    fn test_x86_pressure() {
+        let report = register_pressure(
+            X86,
+            &entries(&["base::dsp::mix", "base::dsp::gain"]),
+            Arch::X86_64,
+        );
+        assert_eq!(report[0].saved, ["r15", "r14", "r13", "r12", "rbx"]);
+        assert_eq!(report[1].saved, ["rbx"]);
+
+        let warnings = verify_register_pressure(
+            X86,
+            &entries(&["base::dsp::mix", "base::dsp::gain"]),
+            Arch::X86_64,
+            Arch::X86_64.default_threshold(),
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].function, "base::dsp::mix");
+        assert!(
+            warnings[0]
+                .message
+                .starts_with("saves 5 callee-saved registers")
+        );
+        assert!(
+            verify_register_pressure(
+                X86,
+                &entries(&["base::dsp::mix;allow=register_pressure,"]),
+                Arch::X86_64,
+                4
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_arm_and_aarch64_prologues() {
+        let arm = "\t.syntax unified\n\t.thumb_func\nk:\n\tpush\t{r4, r5, r6, r7, lr}\n\tpush.w\t{r8, r9, r10, r11}\n\tvpush\t{d8-d9}\n\tbx\tlr\n.Lfunc_end0:\n";
+        assert_eq!(Arch::detect(arm), Arch::Arm);
+        let saved = saved_registers(function_body(arm, "k").unwrap(), Arch::Arm);
+        assert_eq!(
+            saved,
+            ["r4", "r5", "r6", "r8", "r9", "r10", "r11", "d8", "d9"]
+        );
+
+        let a64 = "k:\n\tstp\tx29, x30, [sp, #-48]!\n\tstp\tx22, x21, [sp, #16]\n\tstr\td8, [sp, #32]\n\tret\n.Lfunc_end0:\n";
+        assert_eq!(Arch::detect(a64), Arch::AArch64);
+        let saved = saved_registers(function_body(a64, "k").unwrap(), Arch::AArch64);
+        assert_eq!(saved, ["x22", "x21", "d8"]);
+        assert_eq!(Arch::from_triple("thumbv7em-none-eabihf"), Some(Arch::Arm));
+        assert_eq!(Arch::from_triple("riscv32imac-unknown-none-elf"), None);
+    }
+
+    #[test]
+    fn test_code_size_budget() {
+        let mut arm = String::from("\t.thumb_func\nk:\n\tpush\t{r4, lr}\n");
+        for _ in 0..8 {
+            arm.push_str("\tldr\tr2, [r0], #4\n\tmla\tr1, r2, r3, r1\n");
+        }
+        arm.push_str("\tpop\t{r4, pc}\n.Lfunc_end0:\nm:\n\tadds\tr0, #1\n\tbx\tlr\n.Lfunc_end1:\n");
+
+        let sizes = code_sizes(&arm, &entries(&["k", "m"]), Arch::Arm);
+        assert_eq!(
+            (
+                sizes[0].bytes,
+                sizes[0].instructions,
+                sizes[0].straight_line
+            ),
+            (2 + 8 * 6 + 2, 18, 17)
+        );
+        assert_eq!((sizes[1].bytes, sizes[1].straight_line), (4, 1));
+
+        let check = CodeSizeCheck::new(48).with_straight_line_limit(16);
+        let violations = check.verify(&arm, &entries(&["k", "m"]), Arch::Arm);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.function.as_str(), v.severity))
+            .collect();
+        assert_eq!(found, [("k", Severity::Error), ("k", Severity::Warning)]);
+        assert!(violations[0].message.starts_with("52 bytes"));
+        assert!(
+            check
+                .verify(&arm, &entries(&["k;allow=code_size,"]), Arch::Arm)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Verification evidence embedded in the shipped binary.
+//!
+//! An `Attestation` records the verifier version, the configuration hash
+//! (`HotPathVerifier::config_hash`), a policy label and the outcome of every
+//! hot function. `to_rust` renders it as a `#[used]` static in the
+//! `.hot_verified` section; a build script writes that to `OUT_DIR` and the
+//! firmware includes it:
+//!
+//! ```text
+//! include!(concat!(env!("OUT_DIR"), "/hot_verified.rs"));
+//! ```
+//!
+//! The payload is the `render` text, so `objcopy --dump-section
+//! .hot_verified=out.txt` on an image followed by `parse` gives it back.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::{HotPathVerifier, Outcome, Violation, find_hot_entries_from_ir};
+
+const HEADER: &str = "hot-verified 1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attestation {
+    /// `crate version` of the verifier that produced it.
+    pub verifier: String,
+    pub config_hash: u64,
+    /// Free-form label, e.g. the `Profile` name.
+    pub policy: String,
+    /// Outcome per hot function, in marker order.
+    pub functions: Vec<(String, Outcome)>,
+}
+
+impl Attestation {
+    /// Empty attestation for `verifier`'s configuration.
+    pub fn new(verifier: &HotPathVerifier, policy: &str) -> Self {
+        Self {
+            verifier: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            config_hash: verifier.config_hash(),
+            policy: policy.to_string(),
+            functions: Vec::new(),
+        }
+    }
+
+    /// Verifies every hot function in `ir` and records the result.
+    pub fn from_ir(verifier: &HotPathVerifier, ir: &str, policy: &str) -> Self {
+        let mut attestation = Self::new(verifier, policy);
+        attestation.record(ir, &verifier.verify_entries(ir));
+        attestation
+    }
+
+    /// Adds the hot functions of `ir`, given the violations already found in it.
+    pub fn record(&mut self, ir: &str, violations: &[Violation]) {
+        for entry in find_hot_entries_from_ir(ir) {
+            let own: Vec<Violation> = violations
+                .iter()
+                .filter(|v| v.function == entry.name)
+                .cloned()
+                .collect();
+            self.functions
+                .push((entry.name, Outcome::from_violations(&own)));
+        }
+    }
+
+    /// Worst outcome over all functions.
+    pub fn outcome(&self) -> Outcome {
+        self.functions
+            .iter()
+            .map(|(_, outcome)| *outcome)
+            .max()
+            .unwrap_or(Outcome::Clean)
+    }
+
+    /// Line-oriented text form.
+    pub fn render(&self) -> String {
+        let mut text = format!(
+            "{}\nverifier={}\nconfig={:016x}\npolicy={}\n",
+            HEADER, self.verifier, self.config_hash, self.policy
+        );
+        for (function, outcome) in &self.functions {
+            text.push_str(&format!("fn {} {}\n", outcome.name(), function));
+        }
+        text
+    }
+
+    /// Parses `render` output (trailing NULs from section padding are ignored).
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.trim_end_matches('\0').lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+        let verifier = lines.next()?.strip_prefix("verifier=")?.to_string();
+        let config_hash = u64::from_str_radix(lines.next()?.strip_prefix("config=")?, 16).ok()?;
+        let policy = lines.next()?.strip_prefix("policy=")?.to_string();
+        let functions = lines
+            .map(|line| {
+                let (outcome, function) = line.strip_prefix("fn ")?.split_once(' ')?;
+                Some((function.to_string(), Outcome::from_name(outcome)?))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            verifier,
+            config_hash,
+            policy,
+            functions,
+        })
+    }
+
+    /// Rust source for a `#[used]` static holding `render()` in `.hot_verified`.
+    pub fn to_rust(&self) -> String {
+        let text = self.render();
+        let escaped: String = text
+            .bytes()
+            .flat_map(core::ascii::escape_default)
+            .map(char::from)
+            .collect();
+        format!(
+            "#[used]\n#[unsafe(link_section = \".hot_verified\")]\nstatic HOT_VERIFIED: [u8; {}] = *b\"{}\";\n",
+            text.len(),
+            escaped
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::Profile;
+
+    const IR: &str = r#"
+@alloc_a = private unnamed_addr constant [2 x i8] c"a\00", align 1
+@HOT_FUNC_A = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_a, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+@alloc_b = private unnamed_addr constant [2 x i8] c"b\00", align 1
+@HOT_FUNC_B = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_b, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @a(i32 %x) {
+  %1 = udiv i32 %x, 3
+  ret i32 %1
+}
+define i32 @b(i32 %x) {
+  ret i32 %x
+}
+"#;
+
+    #[test]
+    fn test_round_trip() {
+        let verifier = HotPathVerifier::new().with_profile(Profile::RealTime);
+        let attestation = Attestation::from_ir(&verifier, IR, "realtime");
+        assert_eq!(
+            attestation.functions,
+            [
+                ("a".to_string(), Outcome::Warnings),
+                ("b".to_string(), Outcome::Clean)
+            ]
+        );
+        assert_eq!(attestation.outcome(), Outcome::Warnings);
+        assert_eq!(attestation.config_hash, verifier.config_hash());
+        assert_ne!(
+            attestation.config_hash,
+            HotPathVerifier::new()
+                .with_profile(Profile::Embedded)
+                .config_hash()
+        );
+
+        let text = attestation.render();
+        assert!(text.contains("policy=realtime\nfn warnings a\nfn clean b\n"));
+        let mut padded = text.clone();
+        padded.push_str("\0\0");
+        assert_eq!(Attestation::parse(&padded), Some(attestation.clone()));
+        assert_eq!(Attestation::parse("hot-verified 2\n"), None);
+
+        let source = attestation.to_rust();
+        assert!(source.contains("#[unsafe(link_section = \".hot_verified\")]"));
+        assert!(source.contains(&format!("[u8; {}] = *b\"hot-verified 1\\n", text.len())));
+    }
+}
@@ -0,0 +1,122 @@
+//! Verifying a list of functions, each under its own policy.
+//!
+//! A build that checks ISRs and soft real-time code from one module would
+//! otherwise configure a verifier per function or call `verify_entry` in a
+//! loop, parsing the module's debug info and `.hot_funcs` records and
+//! searching it for each definition every time. `verify_batch` reads all
+//! three once and returns one report per requested function.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::index::FunctionIndex;
+use super::{FunctionPolicy, HotEntry, HotPathVerifier, Outcome, Violation, debug_info};
+
+/// Result for one function of a `verify_batch` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchReport {
+    /// Name as passed in.
+    pub function: String,
+    /// Findings of the function and its `async` poll bodies; a function
+    /// not in the IR has a single `missing` error.
+    pub violations: Vec<Violation>,
+}
+
+impl BatchReport {
+    pub fn outcome(&self) -> Outcome {
+        Outcome::from_violations(&self.violations)
+    }
+}
+
+impl HotPathVerifier {
+    /// Verifies each `(name, policy)` pair with this verifier's checks plus
+    /// `policy`, which takes the place of a `with_function_policy` policy
+    /// for that name. A name with a `.hot_funcs` record keeps the record's
+    /// allow list, requirements and tier; other names are verified as if
+    /// added with `add_function`. Reports are in the order of `functions`.
+    pub fn verify_batch(&self, ir: &str, functions: &[(&str, FunctionPolicy)]) -> Vec<BatchReport> {
+        let debug = debug_info::DebugInfo::parse(ir);
+        let index = FunctionIndex::new(ir);
+        let entries = self.entries(ir);
+        functions
+            .iter()
+            .map(|(name, policy)| {
+                let entry = entries
+                    .iter()
+                    .find(|entry| entry.name == *name)
+                    .cloned()
+                    .unwrap_or_else(|| HotEntry::parse(name));
+                let mut violations = Vec::new();
+                self.collect(&index, &debug, &entry, Some(policy), None, &mut violations);
+                BatchReport {
+                    function: name.to_string(),
+                    violations,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::Profile;
+
+    const IR: &str = r#"@alloc_isr = private unnamed_addr constant [17 x i8] c"app::irq::on_dma\00", align 1
+@HOT_FUNC.0 = internal constant <{ ptr, [8 x i8] }> <{ ptr @alloc_isr, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+
+define void @_ZN3app3irq6on_dma17h0123456789abcdefE(ptr %buf) {
+  %v = load float, ptr %buf, align 4
+  %w = fmul float %v, 2.0
+  store float %w, ptr %buf, align 4
+  ret void
+}
+
+define i32 @mix(i32 %a, i32 %b) {
+  %q = udiv i32 %a, %b
+  ret i32 %q
+}
+"#;
+
+    #[test]
+    fn test_verify_batch() {
+        let verifier = HotPathVerifier::default();
+        let reports = verifier.verify_batch(
+            IR,
+            &[
+                (
+                    "app::irq::on_dma",
+                    FunctionPolicy::new().tier(Profile::InterruptHandler),
+                ),
+                ("mix", FunctionPolicy::new()),
+                ("mix", FunctionPolicy::new().allow("division")),
+                ("gone", FunctionPolicy::new()),
+            ],
+        );
+        let checks: Vec<Vec<&str>> = reports
+            .iter()
+            .map(|r| r.violations.iter().map(|v| v.check.as_str()).collect())
+            .collect();
+        assert_eq!(
+            checks,
+            [
+                std::vec!["floating_point"],
+                std::vec!["division"],
+                std::vec![],
+                std::vec!["missing"],
+            ]
+        );
+        assert_eq!(reports[0].function, "app::irq::on_dma");
+        assert_eq!(reports[1].outcome(), Outcome::Warnings);
+        assert_eq!(reports[2].outcome(), Outcome::Clean);
+        assert_eq!(reports[3].outcome(), Outcome::Errors);
+
+        // Without the tier the ISR body is plain real-time code.
+        let plain = verifier.verify_batch(IR, &[("app::irq::on_dma", FunctionPolicy::new())]);
+        assert!(plain[0].violations.is_empty());
+        assert_eq!(
+            plain[0].violations,
+            verifier.violations(IR, &HotEntry::parse("app::irq::on_dma"))
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Basic-block view of a function body, for checks that need control flow.
+
+use std::vec::Vec;
+
+/// One basic block: its label (empty for an unnamed entry block) and the
+/// body line range `first..end` holding its instructions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block<'a> {
+    pub label: &'a str,
+    pub first: usize,
+    pub end: usize,
+    pub lines: Vec<&'a str>,
+}
+
+impl Block<'_> {
+    /// The terminator instruction (last non-empty, non-comment line).
+    pub fn terminator(&self) -> Option<&str> {
+        self.lines
+            .iter()
+            .rev()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty() && !line.starts_with(';'))
+    }
+
+    /// Successor labels named in the terminator, in order.
+    pub fn successors(&self) -> Vec<&str> {
+        self.terminator().map(labels).unwrap_or_default()
+    }
+}
+
+/// `label %name` operands of an instruction.
+pub fn labels(instruction: &str) -> Vec<&str> {
+    instruction
+        .match_indices("label %")
+        .map(|(pos, _)| {
+            let rest = &instruction[pos + 7..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '-'))
+                .unwrap_or(rest.len());
+            &rest[..end]
+        })
+        .collect()
+}
+
+/// `name` of a `name:` label line, ignoring `; preds = ...` comments.
+fn label_of(line: &str) -> Option<&str> {
+    let code = line.split(';').next()?.trim_end();
+    let label = code.strip_suffix(':')?;
+    (!label.is_empty() && !label.starts_with(' ') && !label.contains(' ')).then_some(label)
+}
+
+/// Splits a body (as returned by `find_function_body`) into basic blocks.
+pub fn split(body: &str) -> Vec<Block<'_>> {
+    let mut blocks: Vec<Block<'_>> = Vec::new();
+    let mut current = Block {
+        label: "",
+        first: 0,
+        end: 0,
+        lines: Vec::new(),
+    };
+    for (index, line) in body.lines().enumerate() {
+        if let Some(label) = label_of(line) {
+            if !current.lines.iter().all(|l| l.trim().is_empty()) {
+                blocks.push(current);
+            }
+            current = Block {
+                label,
+                first: index + 1,
+                end: index + 1,
+                lines: Vec::new(),
+            };
+        } else {
+            current.lines.push(line);
+            current.end = index + 1;
+        }
+    }
+    if !current.lines.iter().all(|l| l.trim().is_empty()) {
+        blocks.push(current);
+    }
+    blocks
+}
+
+/// Whether a block only leads to a panic: it calls a panic/abort entry point
+/// or consists of nothing but `unreachable`.
+pub fn is_panic_block(block: &Block<'_>) -> bool {
+    const PANIC: [&str; 6] = [
+        "panic",
+        "unwrap_failed",
+        "expect_failed",
+        "_fail",
+        "abort",
+        "begin_unwind",
+    ];
+    let calls_panic = block.lines.iter().any(|line| {
+        line.contains("call ")
+            && line.split('@').nth(1).is_some_and(|callee| {
+                PANIC
+                    .iter()
+                    .any(|p| callee.split('(').next().unwrap_or("").contains(p))
+            })
+    });
+    let code: Vec<&str> = block
+        .lines
+        .iter()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty() && !l.starts_with(';') && !l.starts_with("#dbg"))
+        .collect();
+    calls_panic || code == ["unreachable"]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let body = "\nstart:\n  %c = icmp ult i64 %i, %n\n  br i1 %c, label %bb1, label %panic\n\nbb1:                 ; preds = %start\n  ret i32 0\n\npanic:               ; preds = %start\n  tail call void @_ZN4core9panicking18panic_bounds_check17h0000000000000000E(i64 %i)\n  unreachable";
+        let blocks = split(body);
+        let names: Vec<&str> = blocks.iter().map(|b| b.label).collect();
+        assert_eq!(names, ["start", "bb1", "panic"]);
+        assert_eq!(blocks[0].successors(), ["bb1", "panic"]);
+        assert_eq!(blocks[1].terminator(), Some("ret i32 0"));
+        assert!(!is_panic_block(&blocks[1]));
+        assert!(is_panic_block(&blocks[2]));
+        assert_eq!(
+            body.lines().nth(blocks[0].first).unwrap().trim(),
+            "%c = icmp ult i64 %i, %n"
+        );
+    }
+
+    #[test]
+    fn test_unnamed_entry_block() {
+        let blocks = split("\n  %1 = add i32 %a, 1\n  ret i32 %1");
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].label, "");
+    }
+}
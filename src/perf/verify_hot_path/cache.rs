@@ -0,0 +1,289 @@
+//! Incremental verification: skip hot functions whose IR did not change.
+//!
+//! Each function's result is keyed by an FNV-1a hash of its IR body, its
+//! allow list and the verifier's check configuration (names and severities).
+//! Findings are stored by line index within the body; source locations are
+//! resolved from the current IR on every run, because metadata numbering
+//! shifts when unrelated code changes. The file is plain text, versioned,
+//! and silently discarded if it does not parse.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::sync::{Mutex, PoisonError};
+use std::vec::Vec;
+
+use super::Severity;
+
+const HEADER: &str = "hot-verify-cache 1";
+
+/// A check hit at line `line` of a function body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Finding {
+    pub line: usize,
+    pub check: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// 64-bit FNV-1a, stable across runs and toolchains (unlike `DefaultHasher`).
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Fnv(u64);
+
+impl Fnv {
+    pub fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub fn write(&mut self, bytes: &[u8]) -> &mut Self {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        // Field separator so ("ab", "c") and ("a", "bc") differ.
+        self.0 = (self.0 ^ 0xff).wrapping_mul(0x0000_0100_0000_01b3);
+        self
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Hit/miss counts of one run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+struct Entry {
+    hash: u64,
+    findings: Vec<Finding>,
+}
+
+/// Per-function verification results from previous runs.
+#[derive(Default)]
+pub struct VerifyCache {
+    entries: HashMap<String, Entry>,
+    stats: CacheStats,
+}
+
+impl VerifyCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `target/hot-verify/cache.txt` under `target_dir`.
+    pub fn default_path(target_dir: &Path) -> PathBuf {
+        target_dir.join("hot-verify").join("cache.txt")
+    }
+
+    /// Loads `path`; a missing, outdated or corrupt file yields an empty cache.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != HEADER {
+            return None;
+        }
+        let mut cache = Self::new();
+        let mut current: Option<&mut Entry> = None;
+        for line in lines {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                ["F", function, hash] => {
+                    let hash = u64::from_str_radix(hash, 16).ok()?;
+                    let entry = cache
+                        .entries
+                        .entry(function.to_string())
+                        .insert_entry(Entry {
+                            hash,
+                            findings: Vec::new(),
+                        })
+                        .into_mut();
+                    current = Some(entry);
+                }
+                ["V", line, check, severity, message] => {
+                    current.as_mut()?.findings.push(Finding {
+                        line: line.parse().ok()?,
+                        check: check.to_string(),
+                        severity: match *severity {
+                            "E" => Severity::Error,
+                            "W" => Severity::Warning,
+                            "I" => Severity::Info,
+                            "C" => Severity::Critical,
+                            _ => return None,
+                        },
+                        message: message.to_string(),
+                    });
+                }
+                _ => return None,
+            }
+        }
+        Some(cache)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let clean = |s: &str| s.replace(['\t', '\n'], " ");
+        let mut functions: Vec<_> = self.entries.iter().collect();
+        functions.sort_by(|a, b| a.0.cmp(b.0));
+        let mut out = String::from(HEADER);
+        out.push('\n');
+        for (function, entry) in functions {
+            out.push_str(&format!("F\t{}\t{:016x}\n", clean(function), entry.hash));
+            for finding in &entry.findings {
+                let severity = match finding.severity {
+                    Severity::Error => "E",
+                    Severity::Warning => "W",
+                    Severity::Info => "I",
+                    Severity::Critical => "C",
+                };
+                out.push_str(&format!(
+                    "V\t{}\t{}\t{}\t{}\n",
+                    finding.line,
+                    clean(&finding.check),
+                    severity,
+                    clean(&finding.message)
+                ));
+            }
+        }
+        std::fs::write(path, out)
+    }
+
+    /// Cached findings for `function` if `hash` matches; counts the hit or miss.
+    fn lookup(&mut self, function: &str, hash: u64) -> Option<Vec<Finding>> {
+        match self.entries.get(function) {
+            Some(entry) if entry.hash == hash => {
+                self.stats.hits += 1;
+                Some(entry.findings.clone())
+            }
+            _ => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn store(&mut self, function: &str, hash: u64, findings: Vec<Finding>) {
+        self.entries
+            .insert(function.to_string(), Entry { hash, findings });
+    }
+
+    /// Hits and misses since this cache was created or loaded.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The cache of one verification run: owned by it, or shared by worker
+/// threads (see `HotPathVerifier::verify_entries_parallel`).
+pub(super) enum CacheRef<'a> {
+    Owned(&'a mut VerifyCache),
+    Shared(&'a Mutex<VerifyCache>),
+}
+
+impl CacheRef<'_> {
+    /// Cached findings for `function` if `hash` matches, else computes and
+    /// stores them. A shared cache is not locked while computing.
+    pub fn get_or_insert_with(
+        &mut self,
+        function: &str,
+        hash: u64,
+        compute: impl FnOnce() -> Vec<Finding>,
+    ) -> Vec<Finding> {
+        match self {
+            CacheRef::Owned(cache) => cache.lookup(function, hash).unwrap_or_else(|| {
+                let findings = compute();
+                cache.store(function, hash, findings.clone());
+                findings
+            }),
+            CacheRef::Shared(cache) => {
+                // A panicking worker cannot leave an entry half written.
+                let lock = || cache.lock().unwrap_or_else(PoisonError::into_inner);
+                if let Some(findings) = lock().lookup(function, hash) {
+                    return findings;
+                }
+                let findings = compute();
+                lock().store(function, hash, findings.clone());
+                findings
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{HotPathVerifier, Profile};
+    use super::*;
+
+    const IR: &str = r#"
+@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @k(i32 %a) {
+  %1 = udiv i32 %a, 3
+  ret i32 %1
+}
+"#;
+
+    #[test]
+    fn test_hits_and_invalidation() {
+        let verifier = HotPathVerifier::default();
+        let mut cache = VerifyCache::new();
+        let first = verifier.verify_entries_cached(IR, &mut cache);
+        let second = verifier.verify_entries_cached(IR, &mut cache);
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 1);
+        assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+
+        let changed = IR.replace("udiv i32 %a, 3", "add i32 %a, 3");
+        assert!(
+            verifier
+                .verify_entries_cached(&changed, &mut cache)
+                .is_empty()
+        );
+        assert_eq!(cache.stats().misses, 2);
+
+        // A different check configuration must not reuse results.
+        let embedded = HotPathVerifier::new().with_profile(Profile::Embedded);
+        let strict = embedded.verify_entries_cached(IR, &mut cache);
+        assert_eq!(strict[0].severity, Severity::Error);
+        assert_eq!(cache.stats().misses, 3);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir()
+            .join(format!("hot-verify-cache-{}", std::process::id()))
+            .join("cache.txt");
+        let verifier = HotPathVerifier::default();
+        let mut cache = VerifyCache::new();
+        let expected = verifier.verify_entries_cached(IR, &mut cache);
+        cache.save(&path).unwrap();
+
+        let mut loaded = VerifyCache::load(&path);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(verifier.verify_entries_cached(IR, &mut loaded), expected);
+        assert_eq!(loaded.stats(), CacheStats { hits: 1, misses: 0 });
+
+        std::fs::write(&path, "garbage").unwrap();
+        assert!(VerifyCache::load(&path).is_empty());
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,294 @@
+//! Residual-call metrics for hot functions.
+//!
+//! For each `mark_hot!` function: how many calls survive optimization, to
+//! which callees (demangled), and whether each callee is itself hot and
+//! verified. With a depth above one, callees whose bodies are in the module
+//! are followed and their calls attributed to the hot function at the
+//! corresponding depth. Intended as a burn-down metric ("calls remaining in
+//! the hot set") tracked across releases.
+
+use std::collections::{BTreeMap, HashSet};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::{
+    HotEntry, HotPathVerifier, find_function_body, find_hot_entries_from_ir, is_indirect_call,
+};
+
+/// Symbol used for calls through a pointer.
+pub const INDIRECT: &str = "<indirect>";
+
+/// One distinct callee of a hot function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Callee {
+    /// Raw IR symbol (without `@`), or `INDIRECT`.
+    pub symbol: String,
+    /// Demangled path, hash suffix removed.
+    pub name: String,
+    /// Call sites at the shallowest depth this callee was seen.
+    pub count: usize,
+    /// 1 for direct calls, 2 for calls made by a direct callee, ...
+    pub depth: usize,
+    /// The callee is itself marked with `mark_hot!`.
+    pub hot: bool,
+    /// Hot and free of error-severity violations.
+    pub verified: bool,
+}
+
+/// Call metrics of one hot function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallReport {
+    pub function: String,
+    /// Direct call sites, intrinsics excluded.
+    pub calls: usize,
+    pub callees: Vec<Callee>,
+}
+
+impl CallReport {
+    /// Direct callees that are not hot-and-verified.
+    pub fn unverified(&self) -> impl Iterator<Item = &Callee> {
+        self.callees.iter().filter(|c| c.depth == 1 && !c.verified)
+    }
+}
+
+/// Callee symbol of a `call`/`invoke` line, `INDIRECT` for pointer calls,
+/// `None` for intrinsics and non-calls.
+fn callee_of(line: &str) -> Option<String> {
+    let pos = line.find("call ").or_else(|| line.find("invoke "))?;
+    if is_indirect_call(line) {
+        return Some(INDIRECT.to_string());
+    }
+    let rest = &line[pos..];
+    let at = rest.find('@')?;
+    let symbol = &rest[at + 1..];
+    let symbol = if let Some(quoted) = symbol.strip_prefix('"') {
+        &quoted[..quoted.find('"')?]
+    } else {
+        &symbol[..symbol.find(['(', ' ', ','])?]
+    };
+    (!symbol.starts_with("llvm.")).then(|| symbol.to_string())
+}
+
+/// Demangles legacy (`_ZN..E`) and plain-path v0 (`_R..`) Rust symbols;
+/// other symbols are returned unchanged.
+pub fn demangle(symbol: &str) -> String {
+    let symbol = symbol.split(".llvm.").next().unwrap_or(symbol);
+    let segments = if let Some(legacy) = symbol.strip_prefix("_ZN") {
+        legacy_segments(legacy)
+    } else if let Some(v0) = symbol.strip_prefix("_R") {
+        v0_segments(v0)
+    } else {
+        None
+    };
+    match segments {
+        Some(segments) if !segments.is_empty() => segments.join("::"),
+        _ => symbol.to_string(),
+    }
+}
+
+fn legacy_segments(mut rest: &str) -> Option<Vec<String>> {
+    let mut segments = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let len: usize = rest[..digits].parse().ok()?;
+        let end = digits.checked_add(len)?;
+        let ident = rest.get(digits..end)?;
+        rest = &rest[end..];
+        let is_hash = ident.len() == 17
+            && ident.starts_with('h')
+            && ident[1..].bytes().all(|b| b.is_ascii_hexdigit());
+        if !is_hash {
+            segments.push(unescape_legacy(ident));
+        }
+    }
+    Some(segments)
+}
+
+fn unescape_legacy(ident: &str) -> String {
+    const ESCAPES: [(&str, &str); 9] = [
+        ("$LT$", "<"),
+        ("$GT$", ">"),
+        ("$RF$", "&"),
+        ("$BP$", "*"),
+        ("$C$", ","),
+        ("$u20$", " "),
+        ("$u27$", "'"),
+        ("$u7b$", "{"),
+        ("$u7d$", "}"),
+    ];
+    // A leading `$` escape is prefixed with `_` to form a valid identifier.
+    let ident = ident.strip_prefix("_$").map_or(ident, |_| &ident[1..]);
+    let mut out = ident.replace("..", "::");
+    for (from, to) in ESCAPES {
+        out = out.replace(from, to);
+    }
+    out
+}
+
+fn v0_segments(rest: &str) -> Option<Vec<String>> {
+    let bytes = rest.as_bytes();
+    let mut segments = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            let len: usize = rest[start..i].parse().ok()?;
+            if bytes.get(i) == Some(&b'_') {
+                i += 1;
+            }
+            let end = i.checked_add(len)?;
+            segments.push(rest.get(i..end)?.to_string());
+            i = end;
+        } else if b == b's' && i > 0 && bytes[i - 1].is_ascii_uppercase() {
+            // Disambiguator `s<base62>_`.
+            i += rest[i..].find('_')? + 1;
+        } else {
+            i += 1;
+        }
+    }
+    Some(segments)
+}
+
+impl HotPathVerifier {
+    /// Call metrics for every hot function, following callees up to `depth`
+    /// levels (at least 1).
+    pub fn call_metrics(&self, ir: &str, depth: usize) -> Vec<CallReport> {
+        let entries = find_hot_entries_from_ir(ir);
+        let hot: HashSet<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        let verified: HashSet<&str> = entries
+            .iter()
+            .filter(|entry| self.is_verified(ir, entry))
+            .map(|e| e.name.as_str())
+            .collect();
+
+        let mut reports = Vec::new();
+        for entry in &entries {
+            let Ok(body) = find_function_body(ir, &entry.name) else {
+                continue;
+            };
+            let mut callees: BTreeMap<String, Callee> = BTreeMap::new();
+            let mut frontier = std::vec![body];
+            let mut expanded: HashSet<String> = HashSet::new();
+            let mut calls = 0;
+            for level in 1..=depth.max(1) {
+                let mut next = Vec::new();
+                for body in &frontier {
+                    for symbol in body.lines().filter_map(callee_of) {
+                        if level == 1 {
+                            calls += 1;
+                        }
+                        let name = demangle(&symbol);
+                        let callee = callees.entry(symbol.clone()).or_insert_with(|| Callee {
+                            hot: hot.contains(name.as_str()),
+                            verified: verified.contains(name.as_str()),
+                            symbol: symbol.clone(),
+                            name,
+                            count: 0,
+                            depth: level,
+                        });
+                        if callee.depth == level {
+                            callee.count += 1;
+                        }
+                        if level < depth
+                            && symbol != INDIRECT
+                            && expanded.insert(symbol.clone())
+                            && let Ok(inner) = find_function_body(ir, &symbol)
+                        {
+                            next.push(inner);
+                        }
+                    }
+                }
+                frontier = next;
+            }
+            let mut callees: Vec<Callee> = callees.into_values().collect();
+            callees.sort_by(|a, b| (a.depth, &a.name).cmp(&(b.depth, &b.name)));
+            reports.push(CallReport {
+                function: entry.name.clone(),
+                calls,
+                callees,
+            });
+        }
+        reports
+    }
+
+    fn is_verified(&self, ir: &str, entry: &HotEntry) -> bool {
+        !self
+            .violations(ir, entry)
+            .iter()
+            .any(|v| v.severity.is_failure())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demangle() {
+        assert_eq!(
+            demangle("_ZN4base3dsp3fir9fir_block17h0123456789abcdefE"),
+            "base::dsp::fir::fir_block"
+        );
+        assert_eq!(
+            demangle(
+                "_ZN50_$LT$T$u20$as$u20$core..convert..Into$LT$U$GT$$GT$4into17h0123456789abcdefE"
+            ),
+            "<T as core::convert::Into<U>>::into"
+        );
+        assert_eq!(demangle("_RNvNtCs9aB3_4filt3mix4gain"), "filt::mix::gain");
+        assert_eq!(demangle("memcpy"), "memcpy");
+        // Lengths past the end of the symbol leave it as is.
+        for symbol in ["_ZN18446744073709551615aE", "_R18446744073709551615_a"] {
+            assert_eq!(demangle(symbol), symbol);
+        }
+    }
+
+    #[test]
+    fn test_call_metrics() {
+        let ir = r#"
+            @alloc_a = private unnamed_addr constant [2 x i8] c"a\00", align 1
+            @HOT_A = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_a, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+            @alloc_b = private unnamed_addr constant [2 x i8] c"b\00", align 1
+            @HOT_B = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_b, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define void @a(ptr %f) {
+  call void @b()
+  call void @b()
+  call void @helper()
+  %1 = call float %f(float 1.0)
+  call void @llvm.memcpy.p0.p0.i64(ptr %f, ptr %f, i64 4, i1 false)
+  ret void
+}
+define void @b() {
+  ret void
+}
+define void @helper() {
+  call void @deep()
+  ret void
+}
+"#;
+        let verifier = HotPathVerifier::default();
+        let reports = verifier.call_metrics(ir, 1);
+        let a = &reports[0];
+        assert_eq!((a.function.as_str(), a.calls), ("a", 4));
+        let names: Vec<(&str, usize, bool)> = a
+            .callees
+            .iter()
+            .map(|c| (c.name.as_str(), c.count, c.verified))
+            .collect();
+        assert_eq!(
+            names,
+            [(INDIRECT, 1, false), ("b", 2, true), ("helper", 1, false)]
+        );
+        assert_eq!(a.unverified().count(), 2);
+        assert_eq!(reports[1].calls, 0);
+
+        let deep = verifier.call_metrics(ir, 2);
+        let last = deep[0].callees.last().unwrap();
+        assert_eq!((last.name.as_str(), last.depth), ("deep", 2));
+        assert_eq!(deep[0].calls, 4);
+    }
+}
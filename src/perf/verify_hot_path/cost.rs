@@ -0,0 +1,198 @@
+//! Static cycle estimates for hot functions.
+//!
+//! Each instruction is classified (ALU, multiply, divide, memory, branch,
+//! call, emulated on the target) and priced from the `TargetProfile`.
+//! Loops whose trip count is known (see `loops`) are multiplied out; other
+//! loops count once. The result is a relative figure for tracking a
+//! function across changes, not a prediction of real latency.
+
+use std::string::String;
+use std::vec::Vec;
+
+use super::debug_info::DebugInfo;
+use super::loops;
+use super::target::TargetProfile;
+use super::{blocks, find_function_body, find_hot_entries_from_ir};
+
+/// Estimate for one function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCost {
+    pub function: String,
+    /// Instructions in the body (labels, comments and debug records excluded).
+    pub instructions: usize,
+    /// Estimated cycles for one call.
+    pub cycles: u64,
+}
+
+/// Whether `op` on `ty` is done in software on `target`.
+pub(super) fn is_emulated(op: &str, line: &str, target: &TargetProfile) -> bool {
+    let wide = |ty: &str| {
+        ty.strip_prefix('i')
+            .and_then(|bits| bits.parse::<u32>().ok())
+            .is_some_and(|bits| bits > target.word_bits)
+    };
+    let ty = line
+        .split_once(&format!("{} ", op))
+        .map(|(_, rest)| {
+            rest.split_whitespace()
+                .find(|w| !matches!(*w, "nuw" | "nsw" | "exact" | "fast" | "nnan" | "ninf"))
+                .unwrap_or("")
+        })
+        .unwrap_or("");
+    match op {
+        "udiv" | "sdiv" | "urem" | "srem" => !target.has_hw_divide || wide(ty),
+        "mul" => wide(ty) && !target.has_long_multiply,
+        "shl" | "lshr" | "ashr" => wide(ty) && target.word_bits < 64,
+        "fadd" | "fsub" | "fmul" | "fdiv" | "frem" | "fcmp" | "fneg" => match ty {
+            "float" => !target.has_fpu_f32,
+            "double" => !target.has_fpu_f64,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Cycles for one instruction line.
+fn instruction_cost(line: &str, target: &TargetProfile) -> u64 {
+    let code = line.trim();
+    let rhs = code.split_once(" = ").map_or(code, |(_, rhs)| rhs);
+    let op = rhs.split_whitespace().next().unwrap_or("");
+    if is_emulated(op, rhs, target) {
+        return target.cost_emulated as u64;
+    }
+    let cost = match op {
+        "mul" | "fmul" => target.cost_mul,
+        "udiv" | "sdiv" | "urem" | "srem" | "fdiv" | "frem" => target.cost_div,
+        "load" | "store" | "atomicrmw" | "cmpxchg" => target.cost_mem,
+        "br" | "switch" | "indirectbr" => target.cost_branch,
+        "call" | "tail" | "invoke" => {
+            if rhs.contains("@llvm.") {
+                if (rhs.contains("@llvm.ctlz") && !target.has_clz)
+                    || (rhs.contains("@llvm.cttz") && !target.has_ctz)
+                    || (rhs.contains("@llvm.ctpop") && !target.has_popcount)
+                {
+                    target.cost_emulated
+                } else {
+                    target.cost_alu
+                }
+            } else {
+                // Call overhead only; the callee is costed separately if hot.
+                target.cost_branch * 2 + target.cost_mem
+            }
+        }
+        "phi" | "ret" | "unreachable" | "bitcast" => 0,
+        _ => target.cost_alu,
+    };
+    cost as u64
+}
+
+pub(super) fn is_instruction(line: &str) -> bool {
+    let code = line.trim();
+    !code.is_empty()
+        && !code.starts_with(';')
+        && !code.starts_with("#dbg")
+        && !code
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim_end()
+            .ends_with(':')
+}
+
+/// Estimate for one function body.
+pub fn estimate(body: &str, metadata: &DebugInfo<'_>, target: &TargetProfile) -> (usize, u64) {
+    let blocks = blocks::split(body);
+    let loops = loops::analyze(body, metadata);
+    let mut instructions = 0;
+    let mut cycles = 0u64;
+    for block in &blocks {
+        // Multiply by the trip counts of every known-count loop containing the block.
+        let weight: u64 = loops
+            .iter()
+            .filter(|l| l.contains(block.label))
+            .map(|l| l.trip_count.unwrap_or(1).max(1))
+            .product();
+        for line in block.lines.iter().filter(|l| is_instruction(l)) {
+            instructions += 1;
+            cycles = cycles.saturating_add(instruction_cost(line, target).saturating_mul(weight));
+        }
+    }
+    (instructions, cycles)
+}
+
+/// Estimates for every `mark_hot!` function in `ir`.
+pub fn cost_report(ir: &str, target: &TargetProfile) -> Vec<FunctionCost> {
+    let metadata = DebugInfo::parse(ir);
+    find_hot_entries_from_ir(ir)
+        .into_iter()
+        .filter_map(|entry| {
+            let body = find_function_body(ir, &entry.name).ok()?;
+            let (instructions, cycles) = estimate(&body, &metadata, target);
+            Some(FunctionCost {
+                function: entry.name,
+                instructions,
+                cycles,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"
+@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @k(ptr %p, i32 %a) {
+start:
+  br label %body
+
+body:
+  %i = phi i64 [ 0, %start ], [ %i.next, %body ]
+  %x = load i32, ptr %p, align 4
+  %y = udiv i32 %x, %a
+  store i32 %y, ptr %p, align 4
+  %i.next = add nuw nsw i64 %i, 1
+  %done = icmp eq i64 %i.next, 4
+  br i1 %done, label %exit, label %body
+
+exit:
+  ret i32 0
+}
+"#;
+
+    #[test]
+    fn test_estimate_scales_with_target_and_trip_count() {
+        let m3 = cost_report(IR, &TargetProfile::CORTEX_M3);
+        let m0 = cost_report(IR, &TargetProfile::CORTEX_M0);
+        assert_eq!(m3[0].instructions, 9);
+        // Loop body: load 2 + div 12 + store 2 + add 1 + icmp 1 + br 3 = 21, x4; plus entry br and ret.
+        assert_eq!(m3[0].cycles, 3 + 21 * 4);
+        assert_eq!(m0[0].cycles, 3 + (21 - 12 + 40) * 4);
+    }
+
+    #[test]
+    fn test_emulation() {
+        let m0 = TargetProfile::CORTEX_M0;
+        assert!(is_emulated("shl", "shl i64 %a, %n", &m0));
+        assert!(!is_emulated("shl", "shl i32 %a, %n", &m0));
+        assert!(is_emulated("mul", "mul nuw i64 %a, %b", &m0));
+        assert!(!is_emulated(
+            "mul",
+            "mul nuw i64 %a, %b",
+            &TargetProfile::CORTEX_M3
+        ));
+        assert!(is_emulated("fmul", "fmul float %a, %b", &m0));
+        assert!(!is_emulated(
+            "fmul",
+            "fmul float %a, %b",
+            &TargetProfile::CORTEX_M4F
+        ));
+        assert!(is_emulated(
+            "fmul",
+            "fmul double %a, %b",
+            &TargetProfile::CORTEX_M4F
+        ));
+    }
+}
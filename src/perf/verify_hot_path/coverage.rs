@@ -0,0 +1,214 @@
+//! Cross-check of hot functions against test coverage.
+//!
+//! A hot function the test suite never runs can pass every check and still
+//! be wrong about the path that matters: nothing shows it is reached with
+//! the inputs the checks assume. This module reads per-function execution
+//! counts from LLVM source-based coverage (`-C instrument-coverage`) and
+//! reports the hot functions whose count is zero.
+//!
+//! Accepted inputs:
+//!
+//! - `llvm-cov export` JSON (`data[].functions[]` with `name` and `count`),
+//! - `llvm-profdata show --all-functions` text,
+//! - `.profraw`/`.profdata` files, which `load` passes through
+//!   `llvm-profdata show` (`$LLVM_PROFDATA`, else from `PATH`).
+//!
+//! Functions without a coverage record (another crate, or not instrumented)
+//! are not reported; only a recorded count of zero is.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::perf_data::{annotated, clean};
+use super::server::Json;
+use super::{HotEntry, Severity, Violation};
+
+/// Execution count of one function, summed over its instantiations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCoverage {
+    /// Demangled path without hash.
+    pub function: String,
+    pub count: u64,
+}
+
+/// Name as recorded by the instrumentation: local symbols carry a
+/// `file.rs:` prefix.
+fn coverage_name(name: &str) -> String {
+    clean(name.rsplit_once(".rs:").map_or(name, |(_, symbol)| symbol))
+}
+
+fn export_json(json: &Json) -> Vec<(String, u64)> {
+    let mut functions = Vec::new();
+    let Some(Json::Array(data)) = json.get("data") else {
+        return functions;
+    };
+    for export in data {
+        let Some(Json::Array(records)) = export.get("functions") else {
+            continue;
+        };
+        for record in records {
+            if let (Some(name), Some(Json::Number(count))) = (
+                record.get("name").and_then(Json::as_str),
+                record.get("count"),
+            ) {
+                functions.push((coverage_name(name), *count as u64));
+            }
+        }
+    }
+    functions
+}
+
+fn profdata_show(text: &str) -> Vec<(String, u64)> {
+    let mut functions = Vec::new();
+    let mut name = None;
+    for line in text.lines().map(str::trim) {
+        if let Some(count) = line.strip_prefix("Function count:") {
+            if let (Some(name), Ok(count)) = (name.take(), count.trim().parse()) {
+                functions.push((coverage_name(name), count));
+            }
+        } else if let Some(symbol) = line.strip_suffix(':')
+            && !symbol.contains(": ")
+            && symbol != "Counters"
+        {
+            name = Some(symbol);
+        }
+    }
+    functions
+}
+
+/// Execution counts in `text` (export JSON or `llvm-profdata show`).
+pub fn parse(text: &str) -> Result<Vec<FunctionCoverage>, String> {
+    let raw = if text.trim_start().starts_with('{') {
+        export_json(&Json::parse(text)?)
+    } else {
+        profdata_show(text)
+    };
+    let mut merged: HashMap<String, u64> = HashMap::new();
+    for (function, count) in raw {
+        *merged.entry(function).or_default() += count;
+    }
+    let mut functions: Vec<FunctionCoverage> = merged
+        .into_iter()
+        .map(|(function, count)| FunctionCoverage { function, count })
+        .collect();
+    functions.sort_by(|a, b| a.function.cmp(&b.function));
+    Ok(functions)
+}
+
+/// Reads `path`; raw and indexed profiles go through `llvm-profdata`.
+pub fn load(path: &Path) -> io::Result<Vec<FunctionCoverage>> {
+    let binary = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("profraw" | "profdata")
+    );
+    let text = if binary {
+        let tool = std::env::var("LLVM_PROFDATA").unwrap_or_else(|_| "llvm-profdata".to_string());
+        let output = std::process::Command::new(&tool)
+            .arg("show")
+            .arg("--all-functions")
+            .arg(path)
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "{} show failed: {}",
+                tool,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Warnings for entries whose coverage records all have a count of zero.
+/// Entries allowing `unexercised` are skipped.
+pub fn unexercised(coverage: &[FunctionCoverage], entries: &[HotEntry]) -> Vec<Violation> {
+    entries
+        .iter()
+        .filter(|entry| !entry.allow.iter().any(|a| a == "unexercised"))
+        .filter(|entry| {
+            let mut records = coverage
+                .iter()
+                .filter(|c| annotated(&c.function, core::slice::from_ref(*entry)))
+                .peekable();
+            records.peek().is_some() && records.all(|c| c.count == 0)
+        })
+        .map(|entry| Violation {
+            function: entry.name.clone(),
+            check: "unexercised".to_string(),
+            severity: Severity::Warning,
+            message: "never executed by the test suite; its checks are not backed by any run"
+                .to_string(),
+            location: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        let export = r#"{"data":[{"functions":[
+            {"name":"_ZN5synth3dsp3mix17h0123456789abcdefE","count":120,"filenames":["src/dsp.rs"]},
+            {"name":"src/dsp.rs:_ZN5synth3dsp6filter17h0123456789abcdefE","count":0},
+            {"name":"_ZN5synth3dsp3mix17hfedcba9876543210E","count":3}
+        ]}],"type":"llvm.coverage.json.export","version":"2.0.1"}"#;
+        assert_eq!(
+            parse(export).unwrap(),
+            [
+                FunctionCoverage {
+                    function: "synth::dsp::filter".to_string(),
+                    count: 0
+                },
+                FunctionCoverage {
+                    function: "synth::dsp::mix".to_string(),
+                    count: 123
+                },
+            ]
+        );
+
+        let show = "\
+Counters:
+  _ZN5synth3dsp3mix17h0123456789abcdefE:
+    Hash: 0x00000000000004d2
+    Counters: 3
+    Function count: 42
+  synth::ui::draw:
+    Hash: 0x0000000000000001
+    Counters: 1
+    Function count: 0
+Instrumentation level: Front-end
+Functions shown: 2
+";
+        let functions = parse(show).unwrap();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0].count, 42);
+        assert_eq!(functions[1].function, "synth::ui::draw");
+        assert!(parse("{\"data\": [").is_err());
+    }
+
+    #[test]
+    fn test_unexercised() {
+        let coverage = parse("  synth::dsp::mix:\n    Function count: 9\n  synth::dsp::filter:\n    Function count: 0\n")
+            .unwrap();
+        let entries = ["dsp::mix", "dsp::filter", "dsp::elsewhere"].map(HotEntry::parse);
+        let violations = unexercised(&coverage, &entries);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].function, "dsp::filter");
+        assert_eq!(violations[0].check, "unexercised");
+        assert!(
+            unexercised(
+                &coverage,
+                &[HotEntry::parse("dsp::filter;allow=unexercised")]
+            )
+            .is_empty()
+        );
+    }
+}
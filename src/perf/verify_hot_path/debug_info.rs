@@ -0,0 +1,265 @@
+//! Source locations from LLVM debug metadata (`!dbg` / `!DILocation`).
+//!
+//! IR emitted with `-g` (or `debug = "line-tables-only"`) annotates each
+//! instruction with `!dbg !N`. Locations inlined from other functions chain
+//! through `inlinedAt:`; the outermost link is the line in the hot function
+//! itself, which is where a violation is reported. The inner links name
+//! the inlined callees (`inlined_frames`), so a finding in inlined code can
+//! also point at the helper that introduced it.
+
+use std::collections::HashMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::calls::demangle;
+
+/// A source position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
+    /// 1-based; 0 if unknown.
+    pub column: u32,
+}
+
+/// A function inlined into the hot function, and where in it the
+/// instruction came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlinedFrame {
+    /// Demangled path without hash, else the subprogram's short name.
+    pub function: String,
+    pub location: SourceLocation,
+}
+
+/// Index of the numbered metadata nodes (`!N = ...`) in an IR module.
+pub struct DebugInfo<'a> {
+    nodes: HashMap<u32, &'a str>,
+}
+
+impl<'a> DebugInfo<'a> {
+    pub fn parse(ir: &'a str) -> Self {
+        let mut nodes = HashMap::new();
+        for line in ir.lines() {
+            let Some(rest) = line.strip_prefix('!') else {
+                continue;
+            };
+            let Some((id, node)) = rest.split_once(" = ") else {
+                continue;
+            };
+            if let Ok(id) = id.parse() {
+                nodes.insert(id, node.strip_prefix("distinct ").unwrap_or(node));
+            }
+        }
+        Self { nodes }
+    }
+
+    /// Raw text of metadata node `!id` (without `distinct`).
+    pub fn node(&self, id: u32) -> Option<&'a str> {
+        self.nodes.get(&id).copied()
+    }
+
+    /// Location of an instruction line, resolved to the outermost inlining site.
+    pub fn location_of(&self, instruction: &str) -> Option<SourceLocation> {
+        self.location(dbg_ref(instruction)?)
+    }
+
+    /// Location of the `!DILocation` node `id`, resolved through `inlinedAt`.
+    pub fn location(&self, mut id: u32) -> Option<SourceLocation> {
+        // Bounded so malformed, cyclic metadata cannot loop forever.
+        for _ in 0..256 {
+            let node = self.nodes.get(&id)?;
+            if !node.starts_with("!DILocation(") {
+                return None;
+            }
+            match field_ref(node, "inlinedAt") {
+                Some(outer) => id = outer,
+                None => {
+                    let line = field(node, "line")?.parse().ok()?;
+                    if line == 0 {
+                        return None;
+                    }
+                    return Some(SourceLocation {
+                        file: self.scope_file(field_ref(node, "scope")?)?,
+                        line,
+                        column: field(node, "column")
+                            .and_then(|c| c.parse().ok())
+                            .unwrap_or(0),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Inlined callees an instruction came from, innermost first; empty for
+    /// code of the hot function itself.
+    pub fn inlined_frames(&self, instruction: &str) -> Vec<InlinedFrame> {
+        let mut frames = Vec::new();
+        let Some(mut id) = dbg_ref(instruction) else {
+            return frames;
+        };
+        for _ in 0..256 {
+            let Some(node) = self
+                .nodes
+                .get(&id)
+                .filter(|n| n.starts_with("!DILocation("))
+            else {
+                break;
+            };
+            let Some(outer) = field_ref(node, "inlinedAt") else {
+                break;
+            };
+            let scope = field_ref(node, "scope");
+            let frame = scope.and_then(|scope| {
+                Some(InlinedFrame {
+                    function: self.subprogram_name(scope)?,
+                    location: SourceLocation {
+                        file: self.scope_file(scope)?,
+                        line: field(node, "line")?.parse().ok()?,
+                        column: field(node, "column")
+                            .and_then(|c| c.parse().ok())
+                            .unwrap_or(0),
+                    },
+                })
+            });
+            frames.extend(frame);
+            id = outer;
+        }
+        frames
+    }
+
+    /// Name of the `DISubprogram` enclosing a scope.
+    fn subprogram_name(&self, mut scope: u32) -> Option<String> {
+        for _ in 0..256 {
+            let node = self.nodes.get(&scope)?;
+            if node.starts_with("!DISubprogram(") {
+                if let Some(linkage) = field(node, "linkageName") {
+                    let path = demangle(linkage.trim_matches('"'));
+                    if !path.starts_with('_') {
+                        return Some(path);
+                    }
+                }
+                return Some(field(node, "name")?.trim_matches('"').to_string());
+            }
+            scope = field_ref(node, "scope")?;
+        }
+        None
+    }
+
+    /// File of a scope (`DISubprogram`, `DILexicalBlock`, ...).
+    fn scope_file(&self, scope: u32) -> Option<String> {
+        let file = self
+            .nodes
+            .get(&field_ref(self.nodes.get(&scope)?, "file")?)?;
+        let name = field(file, "filename")?;
+        Some(name.trim_matches('"').to_string())
+    }
+}
+
+/// The `N` of a trailing `!dbg !N` attachment.
+pub fn dbg_ref(instruction: &str) -> Option<u32> {
+    let pos = instruction.rfind("!dbg !")?;
+    let digits = &instruction[pos + 6..];
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+/// Raw value of `key: value` inside a metadata node.
+fn field<'n>(node: &'n str, key: &str) -> Option<&'n str> {
+    let mut search = 0;
+    while let Some(pos) = node[search..].find(key) {
+        let start = search + pos;
+        search = start + key.len();
+        let boundary = start == 0 || matches!(node.as_bytes()[start - 1], b'(' | b' ');
+        let Some(value) = node[search..].strip_prefix(": ") else {
+            continue;
+        };
+        if !boundary {
+            continue;
+        }
+        let end = if let Some(quoted) = value.strip_prefix('"') {
+            quoted.find('"').map_or(value.len(), |p| p + 2)
+        } else {
+            value.find([',', ')']).unwrap_or(value.len())
+        };
+        return Some(&value[..end]);
+    }
+    None
+}
+
+/// `key: !N` inside a metadata node.
+fn field_ref(node: &str, key: &str) -> Option<u32> {
+    field(node, key)?.strip_prefix('!')?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"
+define i32 @f(i32 %a) !dbg !7 {
+  %1 = udiv i32 %a, 3, !dbg !25
+  %2 = call i32 @g(i32 %1), !dbg !144
+  ret i32 %2, !dbg !24
+}
+!7 = distinct !DISubprogram(name: "f", scope: !9, file: !8, line: 2, unit: !5)
+!8 = !DIFile(filename: "src/dsp.rs", directory: "/work")
+!24 = !DILocation(line: 0, scope: !7)
+!25 = !DILocation(line: 12, column: 40, scope: !7)
+!90 = distinct !DILexicalBlock(scope: !91, file: !92, line: 30, column: 5)
+!91 = distinct !DISubprogram(name: "next", linkageName: "_ZN4core4iter4next17h0123456789abcdefE", scope: !9, file: !92, line: 25, unit: !5)
+!92 = !DIFile(filename: "/rustc/core/src/iter.rs", directory: "")
+!101 = !DILocation(line: 14, column: 9, scope: !7)
+!144 = !DILocation(line: 28, column: 9, scope: !90, inlinedAt: !101)
+"#;
+
+    #[test]
+    fn test_locations() {
+        let info = DebugInfo::parse(IR);
+        let lines: std::vec::Vec<&str> = IR.lines().collect();
+        let at = |l: u32, c: u32| SourceLocation {
+            file: "src/dsp.rs".to_string(),
+            line: l,
+            column: c,
+        };
+        assert_eq!(info.location_of(lines[2]), Some(at(12, 40)));
+        // Inlined code reports the call site in the hot function.
+        assert_eq!(info.location_of(lines[3]), Some(at(14, 9)));
+        assert_eq!(info.location_of(lines[4]), None);
+        assert_eq!(info.location_of("  ret void"), None);
+    }
+
+    #[test]
+    fn test_inlined_frames() {
+        let info = DebugInfo::parse(IR);
+        let lines: std::vec::Vec<&str> = IR.lines().collect();
+        assert_eq!(
+            info.inlined_frames(lines[3]),
+            [InlinedFrame {
+                function: "core::iter::next".to_string(),
+                location: SourceLocation {
+                    file: "/rustc/core/src/iter.rs".to_string(),
+                    line: 28,
+                    column: 9,
+                },
+            }]
+        );
+        assert!(info.inlined_frames(lines[2]).is_empty());
+        assert!(info.inlined_frames("  ret void").is_empty());
+    }
+
+    #[test]
+    fn test_fields() {
+        let node = "!DILocation(line: 5, column: 2, scope: !7, inlinedAt: !9)";
+        assert_eq!(field(node, "line"), Some("5"));
+        assert_eq!(field(node, "column"), Some("2"));
+        assert_eq!(field_ref(node, "inlinedAt"), Some(9));
+        assert_eq!(
+            field(r#"!DIFile(filename: "a, b.rs", directory: "")"#, "filename"),
+            Some("\"a, b.rs\"")
+        );
+        assert_eq!(dbg_ref("  ret void, !dbg !12"), Some(12));
+    }
+}
@@ -0,0 +1,615 @@
+//! Stable codes and long-form explanations for the built-in checks.
+//!
+//! Codes never change meaning once assigned; new checks take the next free
+//! number. `explain("HP0008")` (or `cargo hot-verify --explain HP0008`)
+//! returns what the check looks for, why it matters on a hot path and the
+//! usual fixes, for readers who do not know the LLVM IR behind a message.
+
+/// Documentation of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckDoc {
+    pub code: &'static str,
+    /// `HotPathCheck::name()` or `Violation::check`.
+    pub check: &'static str,
+    pub summary: &'static str,
+    pub explanation: &'static str,
+}
+
+impl CheckDoc {
+    /// `HP0001 (allocation): summary`, a blank line, then the explanation.
+    pub fn render(&self) -> std::string::String {
+        format!(
+            "{} ({}): {}\n\n{}\n",
+            self.code, self.check, self.summary, self.explanation
+        )
+    }
+}
+
+/// Every documented check, in code order.
+pub const CHECKS: &[CheckDoc] = &[
+    CheckDoc {
+        code: "HP0001",
+        check: "allocation",
+        summary: "heap allocation in a hot function",
+        explanation: "\
+The function calls the allocator (`__rust_alloc`, `malloc`, ...). Allocator
+latency is unbounded: it can take locks, fault in pages or call into the OS.
+
+Fix: allocate up front and reuse the storage (`Vec::with_capacity` at init,
+`collections::RingBuffer`, `BlockPool`), or pass a buffer in. `String`/`Vec`
+growth, `Box::new`, `format!` and cloning owned collections all allocate.",
+    },
+    CheckDoc {
+        code: "HP0002",
+        check: "atomic",
+        summary: "atomic read-modify-write or fence",
+        explanation: "\
+An `atomicrmw`, `cmpxchg` or `fence` instruction. These serialize the core
+and bounce cache lines between cores; under contention a CAS loop has no
+upper bound.
+
+Fix: give each context its own data (per-core `metric!` cells, SPSC queues
+whose ends only load and store), or batch updates outside the hot loop.",
+    },
+    CheckDoc {
+        code: "HP0003",
+        check: "indirection",
+        summary: "call through a function pointer or vtable",
+        explanation: "\
+An indirect call (`call %ptr(...)`): a `dyn Trait` method, a `fn` pointer or
+a boxed closure. It cannot be inlined and costs a possible branch
+misprediction per call.
+
+Fix: make the callee a generic parameter (`impl Fn`, `F: Trait`) so it is
+monomorphized and inlined, or switch over an `enum` instead of a trait object.",
+    },
+    CheckDoc {
+        code: "HP0004",
+        check: "function_call",
+        summary: "call that was not inlined",
+        explanation: "\
+A direct call survived optimization. Calls cost a branch, spills around the
+call and lost optimization across the boundary; the callee may also do
+anything the verifier cannot see. `llvm.*` intrinsics are accepted unless
+an `IntrinsicPolicy` (`FunctionCallCheck::with_intrinsics`) excludes them,
+for ones like `llvm.pow` that the target lowers to a library call.
+
+Fix: mark small helpers `#[inline]` (across crates) or `#[inline(always)]`,
+enable LTO, or move rare work to a `#[cold]` function and `allow` it. For a
+flagged intrinsic, use an approximation that compiles to plain arithmetic.",
+    },
+    CheckDoc {
+        code: "HP0005",
+        check: "libc_compare",
+        summary: "comparison or length scan lowered to libc",
+        explanation: "\
+Slice or `str` equality became `bcmp`/`memcmp`, or C-string handling became
+`strlen`/`strcmp`. These are out-of-line calls whose time grows with the
+data length.
+
+Fix: compare fixed-size arrays (`[u8; N]`, known at compile time, inlines to
+a few loads), compare hashes or IDs, or store lengths instead of scanning.",
+    },
+    CheckDoc {
+        code: "HP0006",
+        check: "volatile_load",
+        summary: "volatile load",
+        explanation: "\
+`load volatile` cannot be merged, reordered or hoisted out of loops. On
+ordinary memory it is usually a mistake; on MMIO it is required.
+
+Fix: use a plain load for normal data; for device registers accept it with
+`mark_hot!(f, allow(volatile_load))` or the `embedded` profile.",
+    },
+    CheckDoc {
+        code: "HP0007",
+        check: "volatile_store",
+        summary: "volatile store",
+        explanation: "\
+`store volatile`, with the same restrictions as a volatile load: every store
+is emitted, in order, and none can be combined.
+
+Fix: as for HP0006; `allow(volatile_store)` for MMIO writes.",
+    },
+    CheckDoc {
+        code: "HP0008",
+        check: "division",
+        summary: "integer division or remainder",
+        explanation: "\
+`udiv`/`sdiv`/`urem`/`srem` by a value not known at compile time. Hardware
+division takes tens of cycles and is not pipelined; Cortex-M0 has none and
+calls a library routine.
+
+Fix: divide by constants (turned into multiplies), use power-of-two sizes and
+masks (`i & (N - 1)` instead of `i % N`), or precompute a reciprocal.",
+    },
+    CheckDoc {
+        code: "HP0009",
+        check: "unaligned_access",
+        summary: "memory access with alignment 1",
+        explanation: "\
+A load or store marked `align 1` on a multi-byte value, typically from
+`#[repr(packed)]` or byte-buffer casts. Some cores fault, others split it
+into several accesses.
+
+Fix: align the data (`mem::Aligned`, `AlignedBytes`), copy into an aligned
+local first, or avoid `packed` structs on the hot path.",
+    },
+    CheckDoc {
+        code: "HP0010",
+        check: "non_inbounds_gep",
+        summary: "address arithmetic without `inbounds`",
+        explanation: "\
+A `getelementptr` without `inbounds`, usually from `wrapping_add` on
+pointers or integer-to-pointer casts. LLVM then cannot assume the pointer
+stays in its object, which blocks vectorization and alias analysis.
+
+Fix: index slices or use `ptr.add` within the allocation instead of
+wrapping arithmetic.",
+    },
+    CheckDoc {
+        code: "HP0011",
+        check: "branch_hint",
+        summary: "branch to a panic block without weights",
+        explanation: "\
+A conditional branch into a panic path has no `!prof` branch weights, so the
+block layout is left to LLVM's heuristics and the panic side may become the
+fallthrough.
+
+Fix: wrap the condition in `unlikely!` or put `cold_path!()` on the failing
+side, which marks it cold and keeps the hot path straight.",
+    },
+    CheckDoc {
+        code: "HP0012",
+        check: "alloca_in_loop",
+        summary: "stack allocation inside a loop",
+        explanation: "\
+An `alloca` executed on every iteration, so the stack grows with the trip
+count (dynamically sized arrays or values LLVM could not hoist).
+
+Fix: declare the buffer once before the loop and reuse it.",
+    },
+    CheckDoc {
+        code: "HP0013",
+        check: "trap",
+        summary: "reachable trap or panic",
+        explanation: "\
+The function can reach `llvm.trap`, `abort` or a `noreturn` call followed by
+`unreachable`: a panic from indexing, `unwrap`, arithmetic overflow or an
+explicit `panic!`. The message names the condition that leads there.
+
+Fix: prove the condition away (iterate instead of indexing, `get` with a
+fallback, check lengths once up front) or use the `ext` helpers that are
+unchecked in release.",
+    },
+    CheckDoc {
+        code: "HP0014",
+        check: "overflow_check",
+        summary: "arithmetic overflow check that panics",
+        explanation: "\
+A `*.with.overflow` intrinsic whose overflow flag branches to a panic, from
+debug-style overflow checks (`overflow-checks = true`) or `checked_*` with
+`unwrap`.
+
+Fix: use `wrapping_*`/`saturating_*` where wrapping is intended, widen the
+type, or disable overflow checks for the release profile.",
+    },
+    CheckDoc {
+        code: "HP0015",
+        check: "dead_code",
+        summary: "work whose result is never used",
+        explanation: "\
+Side-effect-free values that are never used, stores overwritten before any
+read, or empty `asm sideeffect` barriers (`black_box`) pinning values the
+optimizer would otherwise drop. Usually left over from benchmarking or
+debugging.
+
+Fix: remove the computation or the `black_box` around it.",
+    },
+    CheckDoc {
+        code: "HP0016",
+        check: "target_lowering",
+        summary: "operation the target core must emulate",
+        explanation: "\
+For the selected `--core`, the operation has no single instruction: bit
+counts without CLZ/CTZ/POPCNT, 64-bit shifts or multiplies on a 32-bit core,
+divides without hardware division. Each becomes a loop or library call.
+
+Fix: use types that match the word size, lookup tables for bit counts, or
+a core profile that has the instruction.",
+    },
+    CheckDoc {
+        code: "HP0017",
+        check: "fma",
+        summary: "multiply and add not fused (required by `require(fma)`)",
+        explanation: "\
+An `fmul` feeds an `fadd`/`fsub` without the `contract` flag, so they cannot
+be fused into one rounding step.
+
+Fix: call `f32::mul_add`/`f64::mul_add`, or drop `require(fma)` if separate
+rounding is acceptable.",
+    },
+    CheckDoc {
+        code: "HP0018",
+        check: "contract",
+        summary: "`requires!`/`ensures!` code left in the function",
+        explanation: "\
+A call to the contract panic path. In release builds contracts lower to
+optimizer hints and leave no code, so the function was built with
+`debug_assertions`.
+
+Fix: verify a build without debug assertions.",
+    },
+    CheckDoc {
+        code: "HP0019",
+        check: "floating_point",
+        summary: "floating point in an interrupt handler",
+        explanation: "\
+A floating-point instruction or intrinsic. On Cortex-M with lazy stacking it
+makes the core save the FP context on exception entry; without an FPU it is
+a soft-float call.
+
+Fix: use fixed-point arithmetic in the handler, or defer the computation to
+a task.",
+    },
+    CheckDoc {
+        code: "HP0020",
+        check: "call",
+        summary: "any call in an interrupt handler",
+        explanation: "\
+Stricter than HP0004: memory intrinsics and library routines count too,
+since each may lower to a call with its own stack use and latency.
+
+Fix: inline everything the handler needs or move the work to a task.",
+    },
+    CheckDoc {
+        code: "HP0021",
+        check: "instruction_count",
+        summary: "body over its instruction budget",
+        explanation: "\
+The IR body has more instructions than the profile allows (a `poll` under
+`async`, a handler under `isr`). Long bodies mean long worst-case latency.
+
+Fix: split the work into more states or hand it to a lower-priority context.",
+    },
+    CheckDoc {
+        code: "HP0022",
+        check: "register_pressure",
+        summary: "most callee-saved registers are saved",
+        explanation: "\
+The prologue saves nearly all callee-saved registers, so the loop is likely
+spilling values to the stack.
+
+Fix: keep fewer values live across the loop, split it, or process data in
+smaller chunks.",
+    },
+    CheckDoc {
+        code: "HP0023",
+        check: "code_size",
+        summary: "machine code over budget or unrolled",
+        explanation: "\
+The function's machine code exceeds its byte budget, or has a very long
+straight-line run from unrolling or inlining.
+
+Fix: `#[inline(never)]` on large callees, limit unrolling (a runtime loop
+bound, `opt-level = \"s\"`), or raise the budget.",
+    },
+    CheckDoc {
+        code: "HP0024",
+        check: "stack_limit",
+        summary: "static frame over the function's stack limit",
+        explanation: "\
+The `alloca`s of the function add up to more than the `FunctionPolicy`
+stack limit.
+
+Fix: move large buffers into statics or caller-provided storage.",
+    },
+    CheckDoc {
+        code: "HP0025",
+        check: "missing",
+        summary: "hot function not found in the IR",
+        explanation: "\
+A `mark_hot!` record names a function with no definition in the IR: it was
+inlined into every caller, is generic and never instantiated, or the IR is
+from a different build.
+
+Fix: add `#[inline(never)]` to keep a standalone copy, or verify the IR of
+the build that contains it.",
+    },
+    CheckDoc {
+        code: "HP0026",
+        check: "require",
+        summary: "unknown requirement in `require(..)`",
+        explanation: "\
+`mark_hot!(f, require(name))` or a `FunctionPolicy` names a check that does
+not exist.
+
+Fix: correct the name; `fma` is the currently available requirement.",
+    },
+    CheckDoc {
+        code: "HP0027",
+        check: "host_call",
+        summary: "call into an imported Wasm function",
+        explanation: "\
+Reported by the Wasm backend (`verify_wasm`) when a hot function calls a
+function the module imports. The call leaves the module: the embedder runs
+JavaScript or native host code with no bound on its latency, and some
+engines switch stacks on the way.
+
+Fix: pass the data the host call would produce in as a parameter, or queue
+the request and make the host call outside the hot path.",
+    },
+    CheckDoc {
+        code: "HP0028",
+        check: "f64",
+        summary: "double-precision arithmetic in a GPU kernel",
+        explanation: "\
+Reported by the GPU backend (`verify_gpu`) for f64 instructions in PTX or
+SPIR-V. Consumer GPUs run double precision at 1/32 or 1/64 of the f32
+rate, and some do not support it at all.
+
+Fix: compute in f32, or with compensated f32 arithmetic where the precision
+is needed; check literals and `as f64` casts that promote a whole
+expression.",
+    },
+    CheckDoc {
+        code: "HP0029",
+        check: "unbounded_loop",
+        summary: "GPU loop without a visible bound",
+        explanation: "\
+Reported by the GPU backend for a loop whose trip count the code does not
+bound: any backward branch in PTX, an `OpLoopMerge` without `MaxIterations`
+or `Unroll` in SPIR-V. Lanes that iterate longer hold their whole warp or
+subgroup, and a long-running kernel can trip the driver watchdog.
+
+Fix: iterate over a constant range the compiler unrolls, or cap the
+iteration count explicitly; allow the check where data-dependent loops are
+intended.",
+    },
+    CheckDoc {
+        code: "HP0030",
+        check: "false_sharing",
+        summary: "hot functions on different threads write one cache line",
+        explanation: "\
+Reported by `FalseSharingCheck` when two hot functions mapped to different
+threads write different bytes of the same cache line: two fields of one
+static, or two statics the linker placed next to each other (with `nm`
+addresses). Each write takes the line away from the other core, so both
+threads stall on coherence traffic although they share no data.
+
+Fix: give each thread's data its own line, e.g. `#[repr(align(64))]` on the
+per-thread part or padding between producer and consumer indices; check
+the result with `mark_hot_type!`.",
+    },
+    CheckDoc {
+        code: "HP0031",
+        check: "pointer_chase",
+        summary: "chain of dependent loads",
+        explanation: "\
+Reported by `PointerChaseCheck` when a load's address comes from an earlier
+load more times in a row than the configured depth, or when such a chain
+is carried around a loop (walking a linked list or a tree). The loads
+cannot overlap: each one waits for the previous to return, so a chain of
+cache misses costs one full memory latency per step and no amount of
+instruction-level work hides it.
+
+Fix: flatten the structure (indices into an array, a struct of arrays),
+prefetch the next node while working on the current one, or keep the hot
+part of each node in the node that points to it.",
+    },
+    CheckDoc {
+        code: "HP0032",
+        check: "noalias",
+        summary: "reload through a pointer without noalias",
+        explanation: "\
+Reported by `alias::noalias_advisories` when a hot function loads the same
+address again after a store through another pointer parameter, or reloads
+a loop-invariant address on every iteration of a loop that stores. Raw
+pointers, `&Cell<T>` and `&UnsafeCell<T>` parameters carry no `noalias`
+attribute, so LLVM must assume the store may have changed the value and
+cannot keep it in a register.
+
+Fix: take `&mut T` and `&T` parameters where the borrow rules allow it,
+or read the value into a local before the stores and use the local.",
+    },
+    CheckDoc {
+        code: "HP0033",
+        check: "unexercised",
+        summary: "hot function not run by the tests",
+        explanation: "\
+Reported with `--coverage` when LLVM coverage data (`-C
+instrument-coverage`) records an execution count of zero for a hot
+function. The checks only look at the code; if no test reaches the
+function, nothing confirms that it is the code that runs on the real hot
+path, or that it behaves as intended when it does.
+
+Fix: add a test or benchmark that drives the function with representative
+input, or remove the annotation if the function is no longer hot.",
+    },
+    CheckDoc {
+        code: "HP0034",
+        check: "panic_path",
+        summary: "call into the panic machinery",
+        explanation: "\
+The function calls `core::panicking::*` (`panic`, `panic_fmt`,
+`panic_bounds_check`, the overflow `panic_const_*` functions) or an
+`expect_failed`/`unwrap_failed` of `Option` or `Result`. Even if the call
+is never taken, its arguments are set up on the hot path, and when it is
+taken it formats a message and unwinds, neither of which has a bounded
+cost. `Profile::Async` accepts the panic every state machine has for being
+polled after it completed.
+
+Fix: make the failure impossible to reach (check lengths once before the
+loop, use `get_unchecked` behind a proven bound, iterate instead of
+indexing) or handle it (`unwrap_or`, `checked_*`, `saturating_*`). Allow
+`panic_path` only for failures that abort the program anyway.",
+    },
+    CheckDoc {
+        code: "HP0035",
+        check: "allow_expired",
+        summary: "temporary exemption past its expiry date",
+        explanation: "\
+An exemption was given an end date, with `mark_hot!(f, allow(division,
+until = \"2025-09-01\"))` or `FunctionPolicy::allow_until`, and that date
+has passed (or is not a `YYYY-MM-DD` date). The exempted checks run again
+and their findings are reported as usual; this error makes sure the lapsed
+exemption is noticed even when those findings are only warnings.
+
+Fix: resolve the findings and drop the `allow`, or, if the exemption is
+still needed, move the date and record why.",
+    },
+    CheckDoc {
+        code: "HP0036",
+        check: "deallocation",
+        summary: "heap deallocation in a hot function",
+        explanation: "\
+The function frees heap memory: it calls `__rust_dealloc` or `free`, or an
+out-of-line `drop_in_place` of a `Box`, `Vec`, `String`, `Rc` or other
+`alloc` type. Freeing has the same unbounded latency as allocating; the
+allocator may take a lock, merge free lists or return pages to the OS.
+
+Fix: keep owners alive across calls and reuse them (`clear()` instead of
+dropping a `Vec`), hand finished buffers to a non-real-time thread to drop
+(`collections::RingBuffer`), or use a pool whose release is a list push.",
+    },
+    CheckDoc {
+        code: "HP0037",
+        check: "stack_alloca_size",
+        summary: "stack allocation above the configured size",
+        explanation: "\
+A single `alloca` is larger than the limit given to `StackAllocaSizeCheck`
+(512 bytes in the `isr` profile), or its element count is only known at
+run time. Interrupt and thread stacks on small targets are a few KiB and
+usually unguarded; a large local array overflows them into whatever memory
+lies below, typically without a fault.
+
+Fix: move the buffer into a `static` or a field of the driver state, pass
+it in by reference, or split the work into smaller chunks. Dynamic sizes
+come from `alloca`-style helpers and unsized locals; give them a fixed
+upper bound.",
+    },
+    CheckDoc {
+        code: "HP0038",
+        check: "recursion",
+        summary: "function calls itself",
+        explanation: "\
+The hot function contains a call to its own symbol. Every level of
+recursion adds a stack frame, and how many levels run depends on the input,
+so neither the stack depth nor the worst-case execution time can be bounded
+statically. Only direct recursion is detected; a cycle through other
+functions shows up as ordinary `function_call` findings.
+
+Fix: rewrite the recursion as a loop with an explicit, fixed-size stack
+(`[T; N]` plus a depth counter). Tail recursion becomes a plain `loop`;
+Rust does not guarantee that LLVM eliminates the tail call for you.",
+    },
+    CheckDoc {
+        code: "HP0039",
+        check: "mem_intrinsic",
+        summary: "long or unbounded memcpy/memmove/memset",
+        explanation: "\
+A struct move, array copy, `copy_from_slice` or zeroing became an
+`llvm.memcpy`, `llvm.memmove` or `llvm.memset` longer than the backend
+expands inline (`MEM_INLINE_BYTES`, 64 bytes), or with a length only known
+at run time. It looks like one instruction in the IR but runs as a loop or a
+libc call whose time grows with the length, and on large buffers it also
+evicts the cache lines the rest of the tick needs.
+
+Fix: pass large values by reference instead of moving them, reuse a buffer
+instead of zeroing it every call, or copy only the part that changed. With
+`MemIntrinsicCheck::error_above(bytes)` copies over a budget fail the
+build.",
+    },
+    CheckDoc {
+        code: "HP0040",
+        check: "libm",
+        summary: "call into libm",
+        explanation: "\
+The function calls a libm routine (`sinf`, `powf`, `expf`, ...) or an
+intrinsic the target lowers to one (`llvm.sin`, `llvm.pow`, `llvm.exp`).
+Without hardware support these are software series evaluations of tens to
+hundreds of cycles, and many branch on the input range, so their time also
+varies. On a Cortex-M4 a `powf` costs more than the rest of a typical
+filter tick.
+
+Fix: use the polynomial approximations in `math` (e.g. `math::audio` for
+dB conversions), precompute tables or coefficients outside the hot path,
+or restructure to avoid the function (`x * x` for `powf(x, 2.0)`). Where
+the core has the instruction (`vsqrt` on an FPU), `allow(libm)`.",
+    },
+    CheckDoc {
+        code: "HP0041",
+        check: "soft_float",
+        summary: "floating point without FPU support",
+        explanation: "\
+The function does floating-point arithmetic, a comparison or a conversion
+on a type the target's FPU does not handle: any `float` or `double` on a
+Cortex-M0/M3, `double` on a Cortex-M4F. Each such instruction is a call
+into compiler-rt (`__aeabi_fadd`, `__aeabi_dcmplt`, ...) of tens of cycles
+instead of one. Enabled with `HotPathVerifier::with_soft_float` for the
+targets it applies to.
+
+Fix: use fixed-point arithmetic (`Q15`/`Q31`, integer scaling), keep data
+in `f32` on cores with a single-precision FPU, or move the computation out
+of the hot path.",
+    },
+];
+
+/// Documentation for a code (`HP0008`) or check name (`division`).
+pub fn explain(code_or_check: &str) -> Option<&'static CheckDoc> {
+    CHECKS
+        .iter()
+        .find(|doc| doc.code.eq_ignore_ascii_case(code_or_check) || doc.check == code_or_check)
+}
+
+/// Code of a check name.
+pub fn code(check: &str) -> Option<&'static str> {
+    CHECKS
+        .iter()
+        .find(|doc| doc.check == check)
+        .map(|doc| doc.code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_unique_and_sequential() {
+        for (index, doc) in CHECKS.iter().enumerate() {
+            assert_eq!(doc.code, format!("HP{:04}", index + 1));
+            assert_eq!(CHECKS.iter().filter(|d| d.check == doc.check).count(), 1);
+        }
+    }
+
+    #[test]
+    fn test_every_builtin_check_is_documented() {
+        use crate::perf::verify_hot_path::Profile;
+        for profile in [
+            Profile::RealTime,
+            Profile::ConstTime,
+            Profile::CodeSize,
+            Profile::Embedded,
+            Profile::Async,
+            Profile::InterruptHandler,
+        ] {
+            for check in profile.checks() {
+                assert!(code(check.name()).is_some(), "{}", check.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(code("division"), Some("HP0008"));
+        assert_eq!(explain("hp0008").unwrap().check, "division");
+        assert_eq!(explain("division").unwrap().code, "HP0008");
+        assert!(explain("HP9999").is_none());
+        assert!(
+            explain("HP0001")
+                .unwrap()
+                .render()
+                .starts_with("HP0001 (allocation): heap allocation")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Single-line `path:line:col: severity[check]: message` output.
+//!
+//! The format matches rustc/gcc diagnostics closely enough for vim's
+//! quickfix (`%f:%l:%c: %m`), Emacs `compilation-mode` and VS Code problem
+//! matchers to jump straight to the offending line. Violations without any
+//! location are printed against `<unknown>:1:1` so every line still parses.
+
+use std::fmt::Write;
+use std::string::String;
+
+use super::{Severity, Violation};
+
+/// One violation as a single line (no trailing newline).
+pub fn line(violation: &Violation) -> String {
+    let mut out = String::new();
+    match &violation.location {
+        Some(location) => {
+            let _ = write!(
+                out,
+                "{}:{}:{}",
+                location.file,
+                location.line,
+                location.column.max(1)
+            );
+        }
+        None => out.push_str("<unknown>:1:1"),
+    }
+    let _ = write!(
+        out,
+        ": {}[{}]: {}: {}",
+        violation.severity.name(),
+        violation.check,
+        violation.function,
+        violation.message
+    );
+    out
+}
+
+/// All violations, one per line.
+pub fn render(violations: &[Violation]) -> String {
+    render_at_least(violations, Severity::Info)
+}
+
+/// Violations of at least `min` severity, one per line.
+pub fn render_at_least(violations: &[Violation], min: Severity) -> String {
+    let mut out = String::new();
+    for violation in violations.iter().filter(|v| v.severity >= min) {
+        out.push_str(&line(violation));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::SourceLocation;
+    use std::string::ToString;
+
+    #[test]
+    fn test_render() {
+        let mut violation = Violation {
+            function: "dsp::fir_block".to_string(),
+            check: "division".to_string(),
+            severity: Severity::Warning,
+            message: "division/modulo operation".to_string(),
+            location: Some(SourceLocation {
+                file: "src/dsp/fir.rs".to_string(),
+                line: 41,
+                column: 0,
+            }),
+        };
+        assert_eq!(
+            line(&violation),
+            "src/dsp/fir.rs:41:1: warning[division]: dsp::fir_block: division/modulo operation"
+        );
+        violation.location = None;
+        violation.severity = Severity::Error;
+        let note = Violation {
+            check: "branch_hint".to_string(),
+            severity: Severity::Info,
+            ..violation.clone()
+        };
+        assert_eq!(
+            render_at_least(&[note, violation], Severity::Warning),
+            "<unknown>:1:1: error[division]: dsp::fir_block: division/modulo operation\n"
+        );
+    }
+}
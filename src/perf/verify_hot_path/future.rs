@@ -0,0 +1,219 @@
+//! Hot `async fn`s.
+//!
+//! `mark_hot!` inside an `async fn` names the function that builds the
+//! future, which only moves arguments into the state machine. The work
+//! happens in the generated coroutine body, `f::{{closure}}` (legacy
+//! mangling) or `NC..f0` (v0), which `poll` resumes. `poll_functions`
+//! finds those so the verifier checks them under the marker's name;
+//! `Profile::Async` holds the rules that make sense for them.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::index::FunctionIndex;
+use super::{demangle, find_function_body, mangle_rust_path};
+
+/// Whether `symbol` is a coroutine body of the `async fn` at `path`.
+pub(super) fn is_poll_of(path: &str) -> impl Fn(&str) -> bool {
+    let legacy = format!("{}::{{{{closure}}}}", path);
+    let v0 = format!("{}0", mangle_rust_path(path));
+    move |symbol| {
+        if symbol.starts_with("_ZN") {
+            demangle(symbol).starts_with(&legacy)
+        } else {
+            symbol.starts_with("_RNC") && symbol.contains(&v0)
+        }
+    }
+}
+
+/// Symbols of the coroutine bodies generated for the `async fn` at `path`.
+pub fn poll_functions(ir: &str, path: &str) -> Vec<String> {
+    let is_poll = is_poll_of(path);
+    ir.lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix("define ")?;
+            let symbol = rest.split('@').nth(1)?;
+            let symbol = match symbol.strip_prefix('"') {
+                Some(quoted) => quoted.split('"').next()?,
+                None => symbol.split('(').next()?,
+            };
+            is_poll(symbol).then(|| symbol.to_string())
+        })
+        .collect()
+}
+
+/// `(label, symbol, body)` of each coroutine body of `path`, labelled
+/// `path::{{closure}}` (numbered from the second one on).
+pub(super) fn poll_bodies(ir: &str, path: &str) -> Vec<(String, String, String)> {
+    labelled(
+        path,
+        poll_functions(ir, path).into_iter().filter_map(|symbol| {
+            let body = find_function_body(ir, &symbol).ok()?;
+            Some((symbol, body))
+        }),
+    )
+}
+
+/// `poll_bodies` from a prebuilt index.
+pub(super) fn indexed_poll_bodies(
+    functions: &FunctionIndex<'_>,
+    path: &str,
+) -> Vec<(String, String, String)> {
+    labelled(
+        path,
+        functions.matching(is_poll_of(path)).filter_map(|found| {
+            let body = functions.find(found.symbol).ok()?;
+            Some((found.symbol.to_string(), functions.body(&body).to_string()))
+        }),
+    )
+}
+
+fn labelled(
+    path: &str,
+    bodies: impl Iterator<Item = (String, String)>,
+) -> Vec<(String, String, String)> {
+    bodies
+        .enumerate()
+        .map(|(index, (symbol, body))| {
+            let label = match index {
+                0 => format!("{}::{{{{closure}}}}", path),
+                n => format!("{}::{{{{closure}}}}#{}", path, n),
+            };
+            (label, symbol, body)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::{
+        HotEntry, HotPathVerifier, InstructionCountCheck, PanicPathCheck, Profile, Severity,
+    };
+
+    const IR: &str = r#"
+@alloc_t = private unnamed_addr constant [13 x i8] c"app::io::tick\00", align 1
+@HOT_FUNC_TICK = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_t, [8 x i8] c"\0d\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define void @_ZN3app2io4tick17h7f2ae4ad014cc06dE(ptr sret([24 x i8]) %_0, ptr %s, i32 %n) unnamed_addr #0 {
+start:
+  store ptr %s, ptr %_0, align 8
+  ret void
+}
+define internal { i32, i32 } @"_ZN3app2io4tick28_$u7b$$u7b$closure$u7d$$u7d$17hc558d48e02943f9cE"(ptr %_1, ptr %cx) unnamed_addr #1 {
+start:
+  %state = load i8, ptr %_1, align 8
+  %p = call ptr @__rust_alloc(i64 64, i64 8)
+  ret { i32, i32 } { i32 0, i32 0 }
+}
+define internal void @"_ZN4core3ptr57drop_in_place$LT$app..io..tick..$u7b$$u7b$closure$u7d$$u7d$$GT$17h4c972a61c4b68192E"(ptr %_1) {
+  ret void
+}
+define internal { i32, i32 } @_RNCNvNtCs5a42DAGlD6v_3app2io4tick0B5_(ptr %_1, ptr %cx) {
+  ret { i32, i32 } { i32 0, i32 0 }
+}
+"#;
+
+    #[test]
+    fn test_poll_discovery() {
+        let polls = poll_functions(IR, "app::io::tick");
+        assert_eq!(polls.len(), 2);
+        assert!(polls[0].contains("closure"));
+        assert_eq!(polls[1], "_RNCNvNtCs5a42DAGlD6v_3app2io4tick0B5_");
+        assert!(poll_functions(IR, "app::io::tock").is_empty());
+    }
+
+    #[test]
+    fn test_poll_body_is_verified() {
+        let violations = HotPathVerifier::new()
+            .with_profile(Profile::Async)
+            .verify_entries(IR);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.function.as_str(), v.check.as_str(), v.severity))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("app::io::tick::{{closure}}", "allocation", Severity::Error),
+                (
+                    "app::io::tick::{{closure}}",
+                    "function_call",
+                    Severity::Warning
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resumed_panic_is_accepted() {
+        let ir = r#"
+define void @_ZN3app2io4read17h0123456789abcdefE(ptr sret([16 x i8]) %_0, ptr %s) {
+start:
+  store ptr %s, ptr %_0, align 8
+  ret void
+}
+define internal i32 @"_ZN3app2io4read28_$u7b$$u7b$closure$u7d$$u7d$17h1123456789abcdefE"(ptr %_1, ptr %cx) {
+start:
+  %state = load i8, ptr %_1, align 8
+  switch i8 %state, label %resumed [
+    i8 0, label %first
+  ]
+
+first:
+  %i = load i64, ptr %cx, align 8
+  %oob = icmp uge i64 %i, 4
+  br i1 %oob, label %panic, label %done
+
+done:
+  store i8 1, ptr %_1, align 8
+  ret i32 0
+
+panic:
+  call void @_ZN4core9panicking18panic_bounds_check17h2123456789abcdefE(i64 %i, i64 4, ptr @loc)
+  unreachable
+
+resumed:
+  call void @_ZN4core9panicking11panic_const28panic_const_async_fn_resumed17h3123456789abcdefE(ptr @loc)
+  unreachable
+}
+"#;
+        // Only the bounds check is reported under `Profile::Async`.
+        let warnings = HotPathVerifier::new()
+            .with_profile(Profile::Async)
+            .verify(ir, "app::io::read")
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("panic_bounds_check"),
+            "{}",
+            warnings[0]
+        );
+        // Elsewhere it is panic machinery like any other.
+        let err = HotPathVerifier::default()
+            .verify(ir, "app::io::read")
+            .unwrap_err();
+        assert!(err.contains("panic_bounds_check"), "{}", err);
+        let panics = HotPathVerifier::new()
+            .with_check(Box::new(PanicPathCheck))
+            .violations(ir, &HotEntry::parse("app::io::read"));
+        assert_eq!(panics.len(), 2);
+        assert!(
+            panics[1]
+                .message
+                .contains("panic_const::panic_const_async_fn_resumed"),
+            "{}",
+            panics[1].message
+        );
+    }
+
+    #[test]
+    fn test_poll_body_is_bounded() {
+        let verifier = HotPathVerifier::new().with_check(Box::new(InstructionCountCheck::new(2)));
+        let err = verifier.verify(IR, "app::io::tick").unwrap_err();
+        assert!(
+            err.starts_with("app::io::tick::{{closure}}: body has 3"),
+            "{}",
+            err
+        );
+    }
+}
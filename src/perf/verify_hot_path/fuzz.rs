@@ -0,0 +1,255 @@
+//! Adversarial IR for fuzzing the verifier.
+//!
+//! The verifier reads IR with line-oriented patterns, not a full parser, so
+//! the inputs most likely to break it are almost-valid modules: a function
+//! cut off before its closing brace, a `.hot_funcs` record whose string
+//! global is missing, a metadata node with an out-of-range number. `corpus`
+//! derives such inputs from a well-formed module by truncating, deleting,
+//! duplicating and swapping lines and splicing in hostile fragments. The
+//! output is deterministic for a given seed, so it can prime a fuzz
+//! target's corpus directory or back a plain regression test.
+
+use std::string::String;
+use std::vec::Vec;
+
+/// Well-formed module that touches every parser: `.hot_funcs` records in
+/// string, function-pointer and inline form, debug locations with inlined
+/// frames, a loop, calls, allocas and an `async fn` poll body.
+pub const SEED_IR: &str = r#"@alloc_mix = private unnamed_addr constant [14 x i8] c"app::dsp::mix\00", align 1
+@HOT_FUNC.0 = internal constant <{ ptr, [8 x i8] }> <{ ptr @alloc_mix, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+@alloc_tick = private unnamed_addr constant [37 x i8] c"tick;allow=division;until=2030-01-01\00", align 1
+@HOT_FUNC.1 = internal constant <{ ptr, [8 x i8] }> <{ ptr @alloc_tick, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+@HOT_FUNC.2 = internal constant ptr @c_kernel, section "hot_funcs", align 8
+@hot_name = constant [9 x i8] c"app::run\00", section ".hot_funcs", align 1
+
+define void @_ZN3app3dsp3mix17h0123456789abcdefE(ptr noalias %out, ptr %in, i64 %n) !dbg !4 {
+start:
+  %buf = alloca [64 x i8], align 16
+  br label %loop
+
+loop:
+  %i = phi i64 [ 0, %start ], [ %next, %loop ]
+  %src = getelementptr inbounds float, ptr %in, i64 %i
+  %x = load float, ptr %src, align 4, !dbg !7
+  %y = fmul fast float %x, 5.000000e-01, !dbg !8
+  %dst = getelementptr inbounds float, ptr %out, i64 %i
+  store float %y, ptr %dst, align 4
+  %next = add nuw i64 %i, 1
+  %done = icmp eq i64 %next, %n
+  br i1 %done, label %exit, label %loop, !llvm.loop !9
+
+exit:
+  %len = alloca i8, i64 %n, align 1
+  call void @_ZN4core9panicking5panic17h0123456789abcdefE(ptr @alloc_mix, i64 3)
+  ret void
+}
+
+define i32 @tick(i32 %a, i32 %b) {
+  %q = sdiv i32 %a, %b
+  %p = call ptr @malloc(i64 16)
+  call void @free(ptr %p)
+  ret i32 %q
+}
+
+define void @c_kernel(ptr %p) {
+  %v = load volatile i32, ptr %p, align 4
+  %w = atomicrmw add ptr %p, i32 1 seq_cst
+  ret void
+}
+
+define void @"_ZN103_$LT$app..run..$u7b$$u7b$closure$u7d$$u7d$$u20$as$u20$core..future..future..Future$GT$4poll17h0123456789abcdefE"(ptr %cx) {
+  %f = call float @sqrtf(float 2.0)
+  ret void
+}
+
+define void @_ZN3app3run17h0123456789abcdefE() {
+  ret void
+}
+
+declare ptr @malloc(i64)
+declare void @free(ptr)
+declare float @sqrtf(float)
+declare void @_ZN4core9panicking5panic17h0123456789abcdefE(ptr, i64)
+
+!llvm.dbg.cu = !{!0}
+!0 = distinct !DICompileUnit(language: DW_LANG_Rust, file: !1)
+!1 = !DIFile(filename: "src/dsp.rs", directory: "/app")
+!4 = distinct !DISubprogram(name: "mix", scope: null, file: !1, line: 10, unit: !0)
+!5 = distinct !DISubprogram(name: "gain", scope: null, file: !1, line: 30, unit: !0)
+!6 = distinct !DILocation(line: 14, column: 9, scope: !4)
+!7 = !DILocation(line: 12, column: 17, scope: !4)
+!8 = !DILocation(line: 31, column: 5, scope: !5, inlinedAt: !6)
+!9 = distinct !{!9, !10}
+!10 = !{!"llvm.loop.mustprogress"}
+"#;
+
+/// Fragments spliced into the seed: stray delimiters, unterminated strings
+/// and escapes, dangling references, and numbers no field can hold.
+const FRAGMENTS: &[&str] = &[
+    "define void @",
+    "define i32 @tick(",
+    "{",
+    "}",
+    "\n}\n",
+    "(",
+    ")",
+    "\"",
+    "c\"",
+    "\\",
+    "\\0",
+    "\\00",
+    "!dbg !",
+    "!dbg !99999999999999999999",
+    ", !dbg !8",
+    "!8 = !DILocation(line: 99999999999999999999, column: -1, scope: !8, inlinedAt: !8)\n",
+    "!4 = distinct !DISubprogram(name: \"",
+    "section \".hot_funcs\"",
+    "@HOT_FUNC.9 = constant ptr @missing, section \".hot_funcs\"\n",
+    "@HOT_FUNC.9 = constant ptr @\"",
+    "@x = constant [0 x i8] c\"\\00\", section \".hot_funcs\"\n",
+    "alloca [18446744073709551616 x i8]",
+    "alloca [4294967295 x [4294967295 x i64]]",
+    "alloca i8, i64 -1",
+    "  %z = alloca",
+    "  br label %",
+    "  br i1 %c, label %",
+    "  %p = phi i64 [",
+    "  call void @\"",
+    "  call ",
+    "  invoke void @f() to label %a unwind label %",
+    " = ",
+    "%",
+    "@",
+    "!llvm.loop !",
+    "\u{0}",
+    "\u{7f}",
+    "é",
+    "\u{fffd}",
+    "🦀",
+    "\t",
+    "\r\n",
+];
+
+/// xorshift64*, so the corpus needs no dependency and is the same on every
+/// platform.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform-enough value in `0..n`; `n` must not be zero.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+/// Char boundary at or before `at`.
+fn boundary(text: &str, mut at: usize) -> usize {
+    at = at.min(text.len());
+    while !text.is_char_boundary(at) {
+        at -= 1;
+    }
+    at
+}
+
+fn mutate(text: &mut String, rng: &mut Rng) {
+    let mut lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let line = if lines.is_empty() {
+        0
+    } else {
+        rng.below(lines.len())
+    };
+    let edited = match rng.below(7) {
+        0 => {
+            let at = boundary(text, rng.below(text.len() + 1));
+            text.truncate(at);
+            return;
+        }
+        1 if !lines.is_empty() => {
+            lines.remove(line);
+            lines.concat()
+        }
+        2 if !lines.is_empty() => {
+            let copies = 1 + rng.below(64);
+            for _ in 0..copies {
+                lines.insert(line, lines[line]);
+            }
+            lines.concat()
+        }
+        3 if !lines.is_empty() => {
+            let other = rng.below(lines.len());
+            lines.swap(line, other);
+            lines.concat()
+        }
+        4 => {
+            let at = boundary(text, rng.below(text.len() + 1));
+            let mut edited = String::with_capacity(text.len() + 64);
+            edited.push_str(&text[..at]);
+            edited.push_str(FRAGMENTS[rng.below(FRAGMENTS.len())]);
+            edited.push_str(&text[at..]);
+            edited
+        }
+        5 => {
+            // A delimiter replaced by another, so nesting no longer matches.
+            const DELIMITERS: &[char] = &['{', '}', '(', ')', '[', ']', '<', '>', '"', ','];
+            let positions: Vec<usize> = text.match_indices(DELIMITERS).map(|(at, _)| at).collect();
+            if positions.is_empty() {
+                return;
+            }
+            let at = positions[rng.below(positions.len())];
+            let mut edited = String::with_capacity(text.len());
+            edited.push_str(&text[..at]);
+            edited.push(DELIMITERS[rng.below(DELIMITERS.len())]);
+            edited.push_str(&text[at + 1..]);
+            edited
+        }
+        _ => {
+            // An identifier long enough to strain the name patterns.
+            let at = boundary(text, rng.below(text.len() + 1));
+            let mut edited = String::with_capacity(text.len() + 4096);
+            edited.push_str(&text[..at]);
+            for _ in 0..1 + rng.below(4096) {
+                edited.push('a');
+            }
+            edited.push_str(&text[at..]);
+            edited
+        }
+    };
+    *text = edited;
+}
+
+/// `count` variants of `seed_ir`, each with one to four mutations applied.
+/// Equal arguments give equal output.
+pub fn corpus(seed_ir: &str, seed: u64, count: usize) -> Vec<String> {
+    // xorshift is stuck at zero.
+    let mut rng = Rng(seed | 1);
+    (0..count)
+        .map(|_| {
+            let mut ir = seed_ir.to_string();
+            for _ in 0..1 + rng.below(4) {
+                mutate(&mut ir, &mut rng);
+            }
+            ir
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus() {
+        let inputs = corpus(SEED_IR, 7, 32);
+        assert_eq!(inputs.len(), 32);
+        assert_eq!(inputs, corpus(SEED_IR, 7, 32));
+        assert_ne!(inputs, corpus(SEED_IR, 8, 32));
+        assert!(inputs.iter().all(|ir| ir != SEED_IR));
+        assert_eq!(corpus("", 1, 4).len(), 4);
+    }
+}
@@ -0,0 +1,396 @@
+//! GPU kernel backend (experimental, advisory).
+//!
+//! Applies a reduced check set to PTX (`--emit=asm` for `nvptx64`) or to
+//! SPIR-V disassembly (`spirv-dis` of a rust-gpu shader). On a GPU every
+//! lane of a warp or subgroup executes the same instruction stream, so the
+//! concerns differ from CPU hot paths:
+//!
+//! * `indirection`: an indirect call can diverge the warp per lane.
+//! * `f64`: double precision runs at a fraction of f32 throughput on
+//!   consumer parts.
+//! * `unbounded_loop`: a loop whose trip count the code does not bound.
+//!   PTX keeps no loop metadata, so every backward branch is reported;
+//!   SPIR-V loops pass with a `MaxIterations` or `Unroll` control.
+//!
+//! Findings are warnings in the usual `Violation` form, so text and
+//! flycheck output, `Outcome` and allow lists work unchanged. Kernels come
+//! from `kernel_entries` (entry points of the module) or from `mark_hot!`
+//! records of the host build.
+
+use std::collections::HashSet;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::{HotEntry, Severity, Violation};
+
+/// Instruction set of a kernel module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuIsa {
+    Ptx,
+    /// `spirv-dis` text.
+    SpirV,
+}
+
+impl GpuIsa {
+    pub fn detect(text: &str) -> Option<Self> {
+        if text.contains("OpCapability") || text.contains("OpFunctionEnd") {
+            Some(GpuIsa::SpirV)
+        } else if text.contains(".target") && (text.contains(".entry") || text.contains(".func")) {
+            Some(GpuIsa::Ptx)
+        } else {
+            None
+        }
+    }
+}
+
+/// One function of the module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kernel<'a> {
+    pub name: &'a str,
+    /// Entry point (`.entry`, `OpEntryPoint`) rather than a device function.
+    pub entry: bool,
+    pub body: &'a str,
+}
+
+/// PTX name and body of a `.entry`/`.func` starting at `text`.
+fn ptx_function(text: &str) -> Option<(&str, &str)> {
+    let open = text.find('{')?;
+    let head = &text[..open];
+    // `.func (.param .b32 r) name(`: the name is the word before the
+    // parameter list that is not the return list.
+    let name = head
+        .split('(')
+        .filter_map(|part| part.split_whitespace().last())
+        .find(|word| !word.starts_with('.') && !word.ends_with(')'))?;
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((name, &text[open..open + i + 1]));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn ptx_kernels(text: &str) -> Vec<Kernel<'_>> {
+    let mut kernels = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start();
+        let words: Vec<&str> = trimmed.split_whitespace().take(3).collect();
+        let entry = words.contains(&".entry");
+        if (entry || words.contains(&".func"))
+            && !trimmed.trim_end().ends_with(';')
+            && let Some((name, body)) = ptx_function(&text[start..])
+        {
+            kernels.push(Kernel { name, entry, body });
+        }
+    }
+    kernels
+}
+
+fn spirv_kernels(text: &str) -> Vec<Kernel<'_>> {
+    let mut names = Vec::new();
+    let mut entries = HashSet::new();
+    for line in text.lines().map(str::trim) {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("OpName") => {
+                if let (Some(id), Some(name)) = (words.next(), line.split('"').nth(1)) {
+                    names.push((id, name));
+                }
+            }
+            Some("OpEntryPoint") => {
+                if let (Some(id), Some(name)) = (words.nth(1), line.split('"').nth(1)) {
+                    names.insert(0, (id, name));
+                    entries.insert(id);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut kernels = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some((id, rest)) = line.trim().split_once(" = ") else {
+            continue;
+        };
+        if !rest.starts_with("OpFunction ") {
+            continue;
+        }
+        let end = text[start..]
+            .find("OpFunctionEnd")
+            .map_or(text.len(), |n| start + n);
+        let name = names
+            .iter()
+            .find(|(named, _)| *named == id)
+            .map_or(id.trim_start_matches('%'), |(_, name)| name);
+        kernels.push(Kernel {
+            name,
+            entry: entries.contains(id),
+            body: &text[start..end],
+        });
+    }
+    kernels
+}
+
+/// Every function of the module, for `isa`.
+pub fn kernels(text: &str, isa: GpuIsa) -> Vec<Kernel<'_>> {
+    match isa {
+        GpuIsa::Ptx => ptx_kernels(text),
+        GpuIsa::SpirV => spirv_kernels(text),
+    }
+}
+
+/// An entry for every entry point of the module, to verify all kernels.
+pub fn kernel_entries(text: &str) -> Vec<HotEntry> {
+    let Some(isa) = GpuIsa::detect(text) else {
+        return Vec::new();
+    };
+    kernels(text, isa)
+        .into_iter()
+        .filter(|k| k.entry)
+        .map(|k| HotEntry::parse(k.name))
+        .collect()
+}
+
+/// `(check, message)` for one PTX body.
+fn ptx_findings(body: &str) -> Vec<(&'static str, String)> {
+    let mut found = Vec::new();
+    let mut labels = HashSet::new();
+    for line in body.lines().map(str::trim) {
+        if let Some(label) = line.strip_suffix(':') {
+            labels.insert(label);
+            continue;
+        }
+        // Drop a `@%p1` / `@!%p1` guard.
+        let code = match line.strip_prefix('@') {
+            Some(guarded) => guarded
+                .split_once(char::is_whitespace)
+                .map_or("", |(_, c)| c),
+            None => line,
+        }
+        .trim_start();
+        let opcode = code.split_whitespace().next().unwrap_or("");
+        if opcode == "call" || opcode.starts_with("call.") {
+            // `call (ret), target, (args)`: skip the return list.
+            let operands = code[opcode.len()..].trim_start();
+            let operands = match operands.strip_prefix('(') {
+                Some(rest) => rest.split_once("),").map_or("", |(_, r)| r),
+                None => operands,
+            };
+            let target = operands.trim_start().split([',', ';']).next().unwrap_or("");
+            if target.starts_with('%') {
+                found.push(("indirection", format!("indirect call through {}", target)));
+            }
+        } else if opcode.split('.').any(|part| part == "f64") {
+            found.push(("f64", format!("double-precision `{}`", opcode)));
+        } else if opcode == "bra" || opcode.starts_with("bra.") {
+            let target = code[opcode.len()..].trim().trim_end_matches(';').trim();
+            if labels.contains(target) {
+                found.push((
+                    "unbounded_loop",
+                    format!(
+                        "backward branch to {}; the trip count is not visible",
+                        target
+                    ),
+                ));
+            }
+        }
+    }
+    found
+}
+
+/// `(check, message)` for one SPIR-V body; `doubles` are the ids of f64
+/// scalar and vector types.
+fn spirv_findings(body: &str, doubles: &HashSet<&str>) -> Vec<(&'static str, String)> {
+    let mut found = Vec::new();
+    for line in body.lines().map(str::trim) {
+        let (result, code) = match line.split_once(" = ") {
+            Some((result, code)) => (Some(result), code),
+            None => (None, line),
+        };
+        let mut words = code.split_whitespace();
+        let opcode = words.next().unwrap_or("");
+        match opcode {
+            "OpFunctionPointerCallINTEL" => found.push((
+                "indirection",
+                format!("indirect call through {}", words.nth(1).unwrap_or("?")),
+            )),
+            "OpLoopMerge" => {
+                let control: Vec<&str> = words.skip(2).collect();
+                let bounded = |c: &&str| {
+                    c.contains("MaxIterations")
+                        || (c.contains("Unroll") && !c.contains("DontUnroll"))
+                };
+                if !control.iter().any(bounded) {
+                    found.push((
+                        "unbounded_loop",
+                        "loop without a MaxIterations or Unroll control".to_string(),
+                    ));
+                }
+            }
+            "OpFunction" | "OpFunctionParameter" | "OpVariable" => {}
+            _ if result.is_some() && words.next().is_some_and(|ty| doubles.contains(ty)) => {
+                found.push(("f64", format!("double-precision `{}`", opcode)));
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Ids of `OpTypeFloat 64` and vectors of it.
+fn double_types(text: &str) -> HashSet<&str> {
+    let mut doubles = HashSet::new();
+    for line in text.lines().map(str::trim) {
+        let Some((id, code)) = line.split_once(" = ") else {
+            continue;
+        };
+        let words: Vec<&str> = code.split_whitespace().collect();
+        match words.as_slice() {
+            ["OpTypeFloat", "64", ..] => {
+                doubles.insert(id);
+            }
+            ["OpTypeVector", element, ..] if doubles.contains(element) => {
+                doubles.insert(id);
+            }
+            _ => {}
+        }
+    }
+    doubles
+}
+
+/// Advisory warnings for the kernels named by `entries` (see
+/// `kernel_entries`). Kernels missing from the module are skipped.
+pub fn verify_gpu(text: &str, entries: &[HotEntry]) -> Vec<Violation> {
+    let Some(isa) = GpuIsa::detect(text) else {
+        return Vec::new();
+    };
+    let kernels = kernels(text, isa);
+    let doubles = double_types(text);
+    let mut violations = Vec::new();
+    for entry in entries {
+        let last = entry.name.rsplit("::").next().unwrap_or(&entry.name);
+        let Some(kernel) = kernels
+            .iter()
+            .find(|k| k.name == entry.name)
+            .or_else(|| kernels.iter().find(|k| k.name == last))
+        else {
+            continue;
+        };
+        let found = match isa {
+            GpuIsa::Ptx => ptx_findings(kernel.body),
+            GpuIsa::SpirV => spirv_findings(kernel.body, &doubles),
+        };
+        for (check, message) in found {
+            if !entry.allow.iter().any(|a| a == check) {
+                violations.push(Violation {
+                    function: entry.name.clone(),
+                    check: check.to_string(),
+                    severity: Severity::Warning,
+                    message,
+                    location: None,
+                });
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PTX: &str = r#"//
+.version 7.0
+.target sm_70
+.address_size 64
+
+.func  (.param .b32 func_retval0) helper(
+	.param .b32 helper_param_0
+)
+{
+	ret;
+}
+
+.visible .entry scale(
+	.param .u64 scale_param_0,
+	.param .u32 scale_param_1
+)
+{
+	.reg .pred 	%p<2>;
+	.reg .f64 	%fd<3>;
+$L__BB1_1:
+	add.f64 	%fd2, %fd1, 0d3FF0000000000000;
+	call.uni (retval0), helper, (param0);
+	call (retval0), %rd4, (param0), prototype_0;
+	@%p1 bra 	$L__BB1_1;
+	bra.uni 	$L__BB1_2;
+$L__BB1_2:
+	ret;
+}
+"#;
+
+    const SPIRV: &str = r#"               OpCapability Shader
+               OpCapability Float64
+               OpEntryPoint GLCompute %main "main_cs"
+               OpName %main "main_cs"
+       %void = OpTypeVoid
+     %double = OpTypeFloat 64
+      %float = OpTypeFloat 32
+   %v2double = OpTypeVector %double 2
+       %main = OpFunction %void None %3
+          %5 = OpLabel
+               OpLoopMerge %merge %cont None
+          %6 = OpFAdd %double %a %b
+          %7 = OpFAdd %float %c %d
+          %8 = OpFMul %v2double %e %f
+               OpLoopMerge %merge2 %cont2 MaxIterations 16
+               OpReturn
+               OpFunctionEnd
+"#;
+
+    fn checks(violations: &[Violation]) -> Vec<&str> {
+        violations.iter().map(|v| v.check.as_str()).collect()
+    }
+
+    #[test]
+    fn test_ptx() {
+        assert_eq!(GpuIsa::detect(PTX), Some(GpuIsa::Ptx));
+        let names: Vec<&str> = kernels(PTX, GpuIsa::Ptx).iter().map(|k| k.name).collect();
+        assert_eq!(names, ["helper", "scale"]);
+        let entries = kernel_entries(PTX);
+        assert_eq!(entries, [HotEntry::parse("scale")]);
+        let violations = verify_gpu(PTX, &entries);
+        assert_eq!(
+            checks(&violations),
+            ["f64", "indirection", "unbounded_loop"]
+        );
+        assert_eq!(violations[1].message, "indirect call through %rd4");
+        assert!(violations.iter().all(|v| v.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn test_spirv() {
+        assert_eq!(GpuIsa::detect(SPIRV), Some(GpuIsa::SpirV));
+        let entries = kernel_entries(SPIRV);
+        assert_eq!(entries, [HotEntry::parse("main_cs")]);
+        let violations = verify_gpu(SPIRV, &entries);
+        assert_eq!(checks(&violations), ["unbounded_loop", "f64", "f64"]);
+
+        let allowing = [HotEntry::parse("shaders::main_cs;allow=f64")];
+        assert_eq!(checks(&verify_gpu(SPIRV, &allowing)), ["unbounded_loop"]);
+        assert!(verify_gpu("define void @f() {\n}\n", &entries).is_empty());
+    }
+}
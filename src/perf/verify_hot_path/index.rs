@@ -0,0 +1,116 @@
+//! Function definitions of a module, located once.
+//!
+//! `find_function` searches the whole module for the name it is given,
+//! which suits a single lookup. A run over many functions would repeat that
+//! search for each of them and for their `async` poll bodies, so it builds a
+//! `FunctionIndex` instead: one pass records every definition, and lookups
+//! compare symbols in the order `find_function` tries its patterns.
+
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+use regex::Regex;
+
+use super::{FUNCTION_BODY, FunctionMatch, find_function, mangle_rust_path};
+
+/// Every `define` of a module, in IR order.
+pub(crate) struct FunctionIndex<'a> {
+    ir: &'a str,
+    functions: Vec<FunctionMatch<'a>>,
+}
+
+impl<'a> FunctionIndex<'a> {
+    pub(crate) fn new(ir: &'a str) -> Self {
+        let definition = Regex::new(&format!(
+            r#"\Adefine[^@]*@(?:"(?<quoted>[^"]+)"|(?<plain>[^\s"(]+)){}"#,
+            FUNCTION_BODY
+        ))
+        .unwrap();
+        let functions = ir
+            .match_indices("define")
+            .filter_map(|(start, _)| {
+                let captures = definition.captures(&ir[start..])?;
+                let symbol = captures.name("quoted").or(captures.name("plain"))?;
+                let body = captures.name("body")?.range();
+                Some(FunctionMatch {
+                    symbol: symbol.as_str(),
+                    define: start..start + captures.get(0)?.end(),
+                    body: start + body.start..start + body.end,
+                })
+            })
+            .collect();
+        Self { ir, functions }
+    }
+
+    /// `find_function(ir, func_name)` without searching the module again.
+    /// Names the index cannot resolve go to `find_function`, so errors (and
+    /// any definition the index skipped) are the same.
+    pub(crate) fn find(&self, func_name: &str) -> Result<FunctionMatch<'a>, String> {
+        let search_name = if func_name.contains("::") {
+            mangle_rust_path(func_name)
+        } else {
+            func_name.into()
+        };
+        if func_name.contains("::")
+            && let Ok(mangled) = Regex::new(&format!(
+                r#"\A(?:_ZN|_R[A-Za-z]*?C(?:s[0-9A-Za-z]*_)?){}(?:17h[0-9a-f]{{16}}E)?(?:\.[^\s("]*)?\z"#,
+                regex::escape(&search_name)
+            ))
+            && let Some(found) = self.first(|symbol| mangled.is_match(symbol))
+        {
+            return Ok(found);
+        }
+        let unmangled = func_name.rsplit("::").next().unwrap_or(func_name);
+        self.first(|symbol| symbol == unmangled)
+            .or_else(|| self.first(|symbol| symbol.contains(search_name.as_str())))
+            .map_or_else(|| find_function(self.ir, func_name), Ok)
+    }
+
+    /// Definitions whose symbol satisfies `matches`, in IR order.
+    pub(crate) fn matching(
+        &self,
+        matches: impl Fn(&str) -> bool,
+    ) -> impl Iterator<Item = &FunctionMatch<'a>> {
+        self.functions
+            .iter()
+            .filter(move |found| matches(found.symbol))
+    }
+
+    pub(crate) fn body(&self, found: &FunctionMatch<'_>) -> &'a str {
+        &self.ir[found.body.clone()]
+    }
+
+    fn first(&self, matches: impl Fn(&str) -> bool) -> Option<FunctionMatch<'a>> {
+        self.matching(matches).next().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::fuzz::SEED_IR;
+
+    #[test]
+    fn test_index_matches_find_function() {
+        let index = FunctionIndex::new(SEED_IR);
+        for name in [
+            "app::dsp::mix",
+            "tick",
+            "c_kernel",
+            "app::run",
+            "closure",
+            "mix",
+            "gone",
+            "dsp::gone",
+        ] {
+            let indexed = index.find(name).map(|found| index.body(&found));
+            let searched = find_function(SEED_IR, name).map(|found| &SEED_IR[found.body]);
+            assert_eq!(indexed, searched, "{}", name);
+        }
+        assert_eq!(
+            index.matching(|symbol| symbol.starts_with("_ZN")).count(),
+            3
+        );
+    }
+}
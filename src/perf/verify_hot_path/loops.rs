@@ -0,0 +1,347 @@
+//! Loop structure of hot functions.
+//!
+//! Natural loops are found from back edges of a depth-first walk over the
+//! basic blocks; loops sharing a header are merged. For each loop the report
+//! gives its nesting depth, size, whether it has more than one exiting block
+//! (an early exit such as `break` or a search), a constant trip count when
+//! the exit test compares a unit-step induction variable that starts at a
+//! constant with a constant bound, and whether LLVM marked it vectorized.
+
+use std::collections::{HashMap, HashSet};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::blocks::{self, Block};
+use super::debug_info::DebugInfo;
+use super::{find_function_body, find_hot_entries_from_ir};
+
+/// One natural loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopInfo {
+    /// Label of the header block.
+    pub header: String,
+    /// 1 for outermost loops.
+    pub depth: usize,
+    /// Number of basic blocks in the loop, nested loops included.
+    pub blocks: usize,
+    /// Labels of those blocks, in function order.
+    pub members: Vec<String>,
+    /// Blocks with an edge leaving the loop.
+    pub exiting_blocks: usize,
+    /// More than one exiting block.
+    pub early_exit: bool,
+    /// Constant iteration count, if it could be derived.
+    pub trip_count: Option<u64>,
+    /// `llvm.loop.isvectorized` is set on the latch.
+    pub vectorized: bool,
+}
+
+impl LoopInfo {
+    /// Whether the block labelled `label` is part of this loop.
+    pub fn contains(&self, label: &str) -> bool {
+        self.members.iter().any(|m| m == label)
+    }
+}
+
+/// Loops of one hot function, outermost first, in block order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopReport {
+    pub function: String,
+    pub loops: Vec<LoopInfo>,
+}
+
+impl LoopReport {
+    pub fn max_depth(&self) -> usize {
+        self.loops.iter().map(|l| l.depth).max().unwrap_or(0)
+    }
+}
+
+/// Loop reports for every `mark_hot!` function found in `ir`.
+pub fn loop_report(ir: &str) -> Vec<LoopReport> {
+    let metadata = DebugInfo::parse(ir);
+    find_hot_entries_from_ir(ir)
+        .into_iter()
+        .filter_map(|entry| {
+            let body = find_function_body(ir, &entry.name).ok()?;
+            Some(LoopReport {
+                function: entry.name,
+                loops: analyze(&body, &metadata),
+            })
+        })
+        .collect()
+}
+
+/// Loops of one function body.
+pub fn analyze(body: &str, metadata: &DebugInfo<'_>) -> Vec<LoopInfo> {
+    let blocks = blocks::split(body);
+    if blocks.is_empty() {
+        return Vec::new();
+    }
+    let index: HashMap<&str, usize> = blocks
+        .iter()
+        .enumerate()
+        .map(|(i, block)| (block.label, i))
+        .collect();
+    let successors: Vec<Vec<usize>> = blocks
+        .iter()
+        .map(|block| {
+            block
+                .successors()
+                .iter()
+                .filter_map(|label| index.get(label).copied())
+                .collect()
+        })
+        .collect();
+    let mut predecessors = std::vec![Vec::new(); blocks.len()];
+    for (from, targets) in successors.iter().enumerate() {
+        for &to in targets {
+            predecessors[to].push(from);
+        }
+    }
+
+    // Back edges: targets still on the DFS stack.
+    let mut back_edges = Vec::new();
+    let mut state = std::vec![0u8; blocks.len()]; // 0 new, 1 on stack, 2 done
+    let mut stack = std::vec![(0usize, 0usize)];
+    state[0] = 1;
+    while let Some(&mut (node, ref mut next)) = stack.last_mut() {
+        if let Some(&succ) = successors[node].get(*next) {
+            *next += 1;
+            match state[succ] {
+                0 => {
+                    state[succ] = 1;
+                    stack.push((succ, 0));
+                }
+                1 => back_edges.push((node, succ)),
+                _ => {}
+            }
+        } else {
+            state[node] = 2;
+            stack.pop();
+        }
+    }
+
+    // Natural loop bodies, merged per header.
+    let mut bodies: Vec<(usize, HashSet<usize>, Vec<usize>)> = Vec::new();
+    for (latch, header) in back_edges {
+        let mut body = HashSet::from([header]);
+        let mut work = std::vec![latch];
+        while let Some(node) = work.pop() {
+            if body.insert(node) {
+                work.extend(predecessors[node].iter().copied());
+            }
+        }
+        match bodies.iter_mut().find(|(h, _, _)| *h == header) {
+            Some((_, existing, latches)) => {
+                existing.extend(body);
+                latches.push(latch);
+            }
+            None => bodies.push((header, body, std::vec![latch])),
+        }
+    }
+    bodies.sort_by_key(|(header, _, _)| *header);
+
+    let definitions = definitions(body);
+    bodies
+        .iter()
+        .map(|(header, body, latches)| {
+            let exiting: Vec<usize> = body
+                .iter()
+                .copied()
+                .filter(|&b| successors[b].iter().any(|s| !body.contains(s)))
+                .collect();
+            let trip_count = match exiting.as_slice() {
+                [only] => trip_count(&blocks[*only], &definitions),
+                _ => None,
+            };
+            let mut members: Vec<usize> = body.iter().copied().collect();
+            members.sort_unstable();
+            LoopInfo {
+                header: blocks[*header].label.to_string(),
+                depth: bodies.iter().filter(|(_, b, _)| b.contains(header)).count(),
+                blocks: body.len(),
+                members: members
+                    .iter()
+                    .map(|&b| blocks[b].label.to_string())
+                    .collect(),
+                exiting_blocks: exiting.len(),
+                early_exit: exiting.len() > 1,
+                trip_count,
+                vectorized: latches
+                    .iter()
+                    .any(|&latch| is_vectorized(&blocks[latch], metadata)),
+            }
+        })
+        .collect()
+}
+
+/// `%name` → right-hand side of its definition.
+fn definitions(body: &str) -> HashMap<&str, &str> {
+    body.lines()
+        .filter_map(|line| {
+            let (name, rhs) = line.trim().split_once(" = ")?;
+            Some((name.strip_prefix('%')?, rhs))
+        })
+        .collect()
+}
+
+fn constant(operand: &str) -> Option<i64> {
+    operand.trim().parse().ok()
+}
+
+/// Trip count from the exit test `icmp eq|ne|ult|slt iv(.next), N`.
+fn trip_count(exiting: &Block<'_>, defs: &HashMap<&str, &str>) -> Option<u64> {
+    let branch = exiting.terminator()?;
+    let condition = branch.strip_prefix("br i1 %")?.split(',').next()?;
+    let compare = defs.get(condition)?.strip_prefix("icmp ")?;
+    let mut words = compare.split_whitespace();
+    let predicate = words.next()?;
+    let _ty = words.next()?;
+    let rest: String = words.collect::<Vec<_>>().join(" ");
+    let (lhs, rhs) = rest.split_once(',')?;
+    let bound = constant(rhs)?;
+    let counter = lhs.trim().strip_prefix('%')?;
+
+    // `counter` is either the phi itself or `phi + 1`.
+    let (phi, post_increment) = match defs.get(counter)?.strip_prefix("add ") {
+        Some(add) => {
+            let (head, step) = add.rsplit_once(' ')?;
+            if constant(step)? != 1 {
+                return None;
+            }
+            let base = head.trim_end_matches(',').rsplit(' ').next()?;
+            (base.strip_prefix('%')?, true)
+        }
+        None => (counter, false),
+    };
+    let start = phi_start(defs.get(phi)?, phi, counter)?;
+    let count = match (predicate, post_increment) {
+        ("eq" | "ne" | "ult" | "slt", true) => bound - start,
+        ("ult" | "slt" | "ne", false) => bound - start,
+        _ => return None,
+    };
+    u64::try_from(count).ok()
+}
+
+/// Constant incoming value of `phi <ty> [ c, %a ], [ %next, %b ]`.
+fn phi_start(definition: &str, phi: &str, counter: &str) -> Option<i64> {
+    let incoming = definition.strip_prefix("phi ")?;
+    incoming
+        .split('[')
+        .skip(1)
+        .filter_map(|pair| pair.split(',').next())
+        .map(str::trim)
+        .filter(|value| *value != format!("%{}", counter) && *value != format!("%{}", phi))
+        .find_map(constant)
+}
+
+fn is_vectorized(latch: &Block<'_>, metadata: &DebugInfo<'_>) -> bool {
+    let Some(terminator) = latch.terminator() else {
+        return false;
+    };
+    let Some(pos) = terminator.find("!llvm.loop !") else {
+        return false;
+    };
+    let Some(id) = terminator[pos + 12..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .and_then(|id| id.parse().ok())
+    else {
+        return false;
+    };
+    let Some(node) = metadata.node(id) else {
+        return false;
+    };
+    node.split('!')
+        .filter_map(|part| {
+            part.split(|c: char| !c.is_ascii_digit())
+                .next()?
+                .parse()
+                .ok()
+        })
+        .filter_map(|child| metadata.node(child))
+        .any(|child| child.contains("llvm.loop.isvectorized"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"
+@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define void @k(ptr %a, ptr %b, i64 %n) {
+start:
+  br label %outer
+
+outer:                                            ; preds = %start, %outer.latch
+  %j = phi i64 [ 0, %start ], [ %j.next, %outer.latch ]
+  br label %inner
+
+inner:                                            ; preds = %outer, %inner
+  %i = phi i64 [ 0, %outer ], [ %i.next, %inner ]
+  %i.next = add nuw nsw i64 %i, 1
+  %exitcond = icmp eq i64 %i.next, 8
+  br i1 %exitcond, label %outer.latch, label %inner, !llvm.loop !5
+
+outer.latch:                                      ; preds = %inner
+  %j.next = add nuw nsw i64 %j, 1
+  %done = icmp ult i64 %j.next, %n
+  br i1 %done, label %outer, label %search
+
+search:                                           ; preds = %outer.latch, %next
+  %p = phi i64 [ 0, %outer.latch ], [ %p.next, %next ]
+  %v = load i32, ptr %a, align 4
+  %hit = icmp eq i32 %v, 7
+  br i1 %hit, label %exit, label %next
+
+next:                                             ; preds = %search
+  %p.next = add nuw i64 %p, 1
+  %end = icmp eq i64 %p.next, %n
+  br i1 %end, label %exit, label %search
+
+exit:
+  ret void
+}
+!5 = distinct !{!5, !6}
+!6 = !{!"llvm.loop.isvectorized", i32 1}
+"#;
+
+    #[test]
+    fn test_loop_report() {
+        let reports = loop_report(IR);
+        assert_eq!(reports.len(), 1);
+        let loops = &reports[0].loops;
+        let summary: Vec<_> = loops
+            .iter()
+            .map(|l| {
+                (
+                    l.header.as_str(),
+                    l.depth,
+                    l.blocks,
+                    l.early_exit,
+                    l.trip_count,
+                    l.vectorized,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("outer", 1, 3, false, None, false),
+                ("inner", 2, 1, false, Some(8), true),
+                ("search", 1, 2, true, None, false),
+            ]
+        );
+        assert_eq!(reports[0].max_depth(), 2);
+        assert_eq!(loops[0].members, ["outer", "inner", "outer.latch"]);
+        assert!(loops[2].contains("next") && !loops[2].contains("exit"));
+    }
+
+    #[test]
+    fn test_straight_line_has_no_loops() {
+        let body = "\n  %1 = add i32 %a, 1\n  ret i32 %1";
+        assert!(analyze(body, &DebugInfo::parse("")).is_empty());
+        assert!(analyze("", &DebugInfo::parse("")).is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+//! Callbacks during a verification run.
+//!
+//! `HotPathVerifier::verify_entries_observed` reports each function as it
+//! is verified, so front ends can show progress or stream diagnostics
+//! instead of waiting for the whole report. All methods default to doing
+//! nothing; `()` is the no-op observer.
+
+use super::{HotEntry, Violation};
+
+pub trait VerifyObserver {
+    /// Before function `index` (0-based) of `total` is verified.
+    fn on_function_start(&mut self, _index: usize, _total: usize, _entry: &HotEntry) {}
+
+    /// For every violation, as soon as it is found.
+    fn on_violation(&mut self, _violation: &Violation) {}
+
+    /// After a function, with the number of violations it produced.
+    fn on_function_done(&mut self, _entry: &HotEntry, _violations: usize) {}
+}
+
+impl VerifyObserver for () {}
+
+#[cfg(test)]
+mod tests {
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::perf::verify_hot_path::HotPathVerifier;
+
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl VerifyObserver for Recorder {
+        fn on_function_start(&mut self, index: usize, total: usize, entry: &HotEntry) {
+            self.0
+                .push(format!("start {}/{} {}", index + 1, total, entry.name));
+        }
+        fn on_violation(&mut self, violation: &Violation) {
+            self.0.push(format!("violation {}", violation.check));
+        }
+        fn on_function_done(&mut self, entry: &HotEntry, violations: usize) {
+            self.0.push(format!("done {} {}", entry.name, violations));
+        }
+    }
+
+    #[test]
+    fn test_events_in_order() {
+        let ir = r#"
+@alloc_a = private unnamed_addr constant [2 x i8] c"a\00", align 1
+@HOT_FUNC_A = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_a, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+@alloc_b = private unnamed_addr constant [2 x i8] c"b\00", align 1
+@HOT_FUNC_B = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_b, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @a(i32 %x) {
+  %1 = udiv i32 %x, 3
+  ret i32 %1
+}
+define i32 @b(i32 %x) {
+  ret i32 %x
+}
+"#;
+        let mut recorder = Recorder::default();
+        let violations =
+            HotPathVerifier::default().verify_entries_observed(ir, None, &mut recorder);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            recorder.0,
+            [
+                "start 1/2 a",
+                "violation division",
+                "done a 1",
+                "start 2/2 b",
+                "done b 0",
+            ]
+            .map(|s| s.to_string())
+        );
+    }
+}
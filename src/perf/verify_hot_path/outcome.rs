@@ -0,0 +1,129 @@
+//! Overall result of a verification run, for CI and tooling.
+
+use std::process::ExitCode;
+
+use super::{Severity, Violation};
+
+/// Worst result of a run, ordered from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    /// No violations.
+    Clean,
+    /// Only warnings.
+    Warnings,
+    /// At least one error-severity violation (policy failure).
+    Errors,
+    /// The verifier itself could not run: unreadable IR, bad arguments,
+    /// failed build. Says nothing about the code under test.
+    ToolFailure,
+}
+
+impl Outcome {
+    pub fn from_violations(violations: &[Violation]) -> Self {
+        violations
+            .iter()
+            .map(|v| match v.severity {
+                Severity::Info => Outcome::Clean,
+                Severity::Warning => Outcome::Warnings,
+                Severity::Error | Severity::Critical => Outcome::Errors,
+            })
+            .max()
+            .unwrap_or(Outcome::Clean)
+    }
+
+    /// The worse of two outcomes.
+    pub fn combine(self, other: Outcome) -> Outcome {
+        self.max(other)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Outcome::Clean => "clean",
+            Outcome::Warnings => "warnings",
+            Outcome::Errors => "errors",
+            Outcome::ToolFailure => "tool-failure",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Outcome::Clean,
+            Outcome::Warnings,
+            Outcome::Errors,
+            Outcome::ToolFailure,
+        ]
+        .into_iter()
+        .find(|outcome| outcome.name() == name)
+    }
+
+    /// Process exit code: 0 clean, 1 warnings only, 2 errors, 3 tool failure.
+    pub fn exit_code(self) -> u8 {
+        match self {
+            Outcome::Clean => 0,
+            Outcome::Warnings => 1,
+            Outcome::Errors => 2,
+            Outcome::ToolFailure => 3,
+        }
+    }
+
+    /// Whether a pipeline should pass; warnings pass if `allow_warnings`.
+    pub fn is_success(self, allow_warnings: bool) -> bool {
+        match self {
+            Outcome::Clean => true,
+            Outcome::Warnings => allow_warnings,
+            Outcome::Errors | Outcome::ToolFailure => false,
+        }
+    }
+}
+
+impl From<Outcome> for ExitCode {
+    fn from(outcome: Outcome) -> Self {
+        ExitCode::from(outcome.exit_code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    fn violation(severity: Severity) -> Violation {
+        Violation {
+            function: "f".to_string(),
+            check: "division".to_string(),
+            severity,
+            message: "division".to_string(),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_from_violations() {
+        assert_eq!(Outcome::from_violations(&[]), Outcome::Clean);
+        let warn = violation(Severity::Warning);
+        let err = violation(Severity::Error);
+        assert_eq!(
+            Outcome::from_violations(std::slice::from_ref(&warn)),
+            Outcome::Warnings
+        );
+        assert_eq!(Outcome::from_violations(&[warn, err]), Outcome::Errors);
+        assert_eq!(
+            Outcome::Errors.combine(Outcome::ToolFailure),
+            Outcome::ToolFailure
+        );
+    }
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let codes = [
+            Outcome::Clean,
+            Outcome::Warnings,
+            Outcome::Errors,
+            Outcome::ToolFailure,
+        ]
+        .map(Outcome::exit_code);
+        assert_eq!(codes, [0, 1, 2, 3]);
+        assert!(Outcome::Warnings.is_success(true));
+        assert!(!Outcome::Warnings.is_success(false));
+    }
+}
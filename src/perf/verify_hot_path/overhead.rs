@@ -0,0 +1,119 @@
+//! Proof that `mark_hot!` is observation-only.
+//!
+//! The marker is a `#[used]` static next to the function, not code in it.
+//! `verify_zero_overhead` compares a marked function with an otherwise
+//! identical unmarked twin in the same IR: either LLVM merged them (one is
+//! an alias of the other, which MergeFunctions only does for identical
+//! bodies) or their bodies must match line for line after dropping debug
+//! annotations. `mark_hot::probe` provides such a pair in this crate.
+
+use std::string::String;
+use std::vec::Vec;
+
+use super::{find_function, mangle_rust_path};
+
+/// Body lines without debug locations and records.
+fn normalized(body: &str) -> Vec<&str> {
+    body.lines()
+        .map(|line| line.split(", !dbg !").next().unwrap_or(line).trim_end())
+        .filter(|line| {
+            let code = line.trim();
+            !code.is_empty() && !code.starts_with("#dbg_") && !code.starts_with(';')
+        })
+        .collect()
+}
+
+/// `@a = ... alias ..., ptr @b` between the two functions, either way round.
+fn merged(ir: &str, a: &str, b: &str) -> bool {
+    let (a, b) = (mangle_rust_path(a), mangle_rust_path(b));
+    ir.lines().any(|line| {
+        let Some((lhs, rhs)) = line.split_once(" = ") else {
+            return false;
+        };
+        rhs.contains(" alias ")
+            && ((lhs.contains(&a) && rhs.contains(&b)) || (lhs.contains(&b) && rhs.contains(&a)))
+    })
+}
+
+/// Ok if `marked` compiled to exactly the same code as `unmarked`.
+pub fn verify_zero_overhead(ir: &str, marked: &str, unmarked: &str) -> Result<(), String> {
+    if merged(ir, marked, unmarked) {
+        return Ok(());
+    }
+    let with = find_function(ir, marked)?;
+    let without = find_function(ir, unmarked)?;
+    let with = normalized(&ir[with.body]);
+    let without = normalized(&ir[without.body]);
+    if let Some((index, (a, b))) = with
+        .iter()
+        .zip(&without)
+        .enumerate()
+        .find(|(_, (a, b))| a != b)
+    {
+        return Err(format!(
+            "{} differs from {} at line {}: `{}` vs `{}`",
+            marked,
+            unmarked,
+            index + 1,
+            a.trim(),
+            b.trim()
+        ));
+    }
+    if with.len() != without.len() {
+        return Err(format!(
+            "{} has {} instructions, {} has {}",
+            marked,
+            with.len(),
+            unmarked,
+            without.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_and_merged_bodies() {
+        let ir = "define i32 @_ZN1p6marked17h0000000000000001E(i32 %x) #1 {
+start:
+  %0 = mul i32 %x, 3, !dbg !10
+  ret i32 %0
+}
+define i32 @_ZN1p8unmarked17h0000000000000002E(i32 %x) #2 {
+start:
+  %0 = mul i32 %x, 3
+  ret i32 %0
+}
+define i32 @_ZN1p5other17h0000000000000003E(i32 %x) {
+start:
+  %0 = mul i32 %x, 5
+  ret i32 %0
+}";
+        assert!(verify_zero_overhead(ir, "p::marked", "p::unmarked").is_ok());
+        let err = verify_zero_overhead(ir, "p::marked", "p::other").unwrap_err();
+        assert!(
+            err.contains("at line 2: `%0 = mul i32 %x, 3` vs `%0 = mul i32 %x, 5`"),
+            "{}",
+            err
+        );
+
+        let aliased = "@_ZN1p8unmarked17h0000000000000002E = unnamed_addr alias i32 (i32), ptr @_ZN1p6marked17h0000000000000001E";
+        assert!(verify_zero_overhead(aliased, "p::marked", "p::unmarked").is_ok());
+    }
+
+    #[test]
+    fn test_mark_hot_adds_no_code() {
+        use crate::perf::profile::{BuildProfile, crate_ir};
+        let manifest = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let ir = crate_ir(manifest, "base", BuildProfile::Release).unwrap();
+        verify_zero_overhead(
+            &ir,
+            "base::macros::mark_hot::probe::marked",
+            "base::macros::mark_hot::probe::unmarked",
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,190 @@
+//! Running the verifier from several threads.
+//!
+//! A `HotPathVerifier` is not changed by verification: every `verify*`
+//! method takes `&self`, and `HotPathCheck` requires `Send + Sync`, so one
+//! verifier can be shared by reference or through an `Arc` between the jobs
+//! of a build orchestrator. The only state a run mutates is its
+//! `VerifyCache`; `verify_entries_parallel` shares it behind a `Mutex` that
+//! is held for lookups and stores but not while a function is checked.
+//!
+//! The report types (`Violation`, `Outcome`, `Attestation`, `TrendRecord`,
+//! ...) are plain owned data. The assertions below keep all of these
+//! `Send + Sync`, so a field that is not fails the build of this crate
+//! rather than of its users.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::vec::Vec;
+
+use super::cache::CacheRef;
+use super::index::FunctionIndex;
+use super::{
+    Attestation, BatchReport, CacheStats, CallReport, CheckDoc, CheckRegistration, CheckRegistry,
+    CodeSize, Delta, FunctionCost, FunctionCoverage, FunctionPolicy, HotCandidate, HotEntry,
+    HotPathVerifier, LoopReport, Outcome, Profile, RegisterPressure, Regression, Server, Severity,
+    SourceLocation, SymbolMap, TargetProfile, TrendRecord, VerifyCache, Violation, debug_info,
+};
+
+const _: () = {
+    const fn send_sync<T: Send + Sync>() {}
+    send_sync::<HotPathVerifier>();
+    send_sync::<CheckRegistry>();
+    send_sync::<CheckRegistration>();
+    send_sync::<FunctionPolicy>();
+    send_sync::<Profile>();
+    send_sync::<TargetProfile>();
+    send_sync::<VerifyCache>();
+    send_sync::<CacheStats>();
+    send_sync::<Server>();
+    send_sync::<HotEntry>();
+    send_sync::<Violation>();
+    send_sync::<Severity>();
+    send_sync::<SourceLocation>();
+    send_sync::<Outcome>();
+    send_sync::<Attestation>();
+    send_sync::<BatchReport>();
+    send_sync::<TrendRecord>();
+    send_sync::<Regression>();
+    send_sync::<Delta>();
+    send_sync::<CallReport>();
+    send_sync::<LoopReport>();
+    send_sync::<FunctionCost>();
+    send_sync::<CodeSize>();
+    send_sync::<SymbolMap>();
+    send_sync::<RegisterPressure>();
+    send_sync::<CheckDoc>();
+    send_sync::<HotCandidate>();
+    send_sync::<FunctionCoverage>();
+};
+
+impl HotPathVerifier {
+    /// `verify_entries` on up to `threads` worker threads (at least one).
+    /// The result is in the same order as the sequential run. With `cache`,
+    /// results are reused and recorded as in `verify_entries_cached`; the
+    /// same cache may be passed by concurrent calls for different modules.
+    pub fn verify_entries_parallel(
+        &self,
+        ir: &str,
+        threads: usize,
+        cache: Option<&Mutex<VerifyCache>>,
+    ) -> Vec<Violation> {
+        let debug = debug_info::DebugInfo::parse(ir);
+        let functions = FunctionIndex::new(ir);
+        let entries = self.entries(ir);
+        let next = AtomicUsize::new(0);
+        let workers = threads.clamp(1, entries.len().max(1));
+        let mut results: Vec<(usize, Vec<Violation>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(entry) = entries.get(index) else {
+                                break;
+                            };
+                            let mut violations = Vec::new();
+                            self.collect(
+                                &functions,
+                                &debug,
+                                entry,
+                                self.policy(&entry.name),
+                                cache.map(CacheRef::Shared),
+                                &mut violations,
+                            );
+                            done.push((index, violations));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+        results.sort_by_key(|(index, _)| *index);
+        results
+            .into_iter()
+            .flat_map(|(_, violations)| violations)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+    use std::string::String;
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn module(functions: usize) -> String {
+        let mut ir = String::new();
+        for i in 0..functions {
+            ir.push_str(&format!(
+                "@alloc_f{i} = private unnamed_addr constant [4 x i8] c\"f{i:02}\\00\", align 1
+@HOT_FUNC_F{i} = constant <{{ ptr, [8 x i8] }}> <{{ ptr @alloc_f{i}, [8 x i8] c\"\\01\\00\\00\\00\\00\\00\\00\\00\" }}>, section \".hot_funcs\", align 8
+define i32 @f{i:02}(i32 %a) {{
+  %q = udiv i32 %a, {d}
+  ret i32 %q
+}}
+",
+                d = i + 2
+            ));
+        }
+        ir
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let ir = module(12);
+        let verifier = HotPathVerifier::default();
+        let sequential = verifier.verify_entries(&ir);
+        assert_eq!(sequential.len(), 12);
+        for threads in [0, 1, 4, 64] {
+            assert_eq!(
+                verifier.verify_entries_parallel(&ir, threads, None),
+                sequential
+            );
+        }
+
+        let cache = Mutex::new(VerifyCache::new());
+        assert_eq!(
+            verifier.verify_entries_parallel(&ir, 4, Some(&cache)),
+            sequential
+        );
+        assert_eq!(
+            verifier.verify_entries_parallel(&ir, 4, Some(&cache)),
+            sequential
+        );
+        assert_eq!(
+            cache.lock().unwrap().stats(),
+            CacheStats {
+                hits: 12,
+                misses: 12
+            }
+        );
+    }
+
+    #[test]
+    fn test_shared_verifier() {
+        let verifier = Arc::new(HotPathVerifier::default());
+        let modules: Vec<String> = (1..=3).map(module).collect();
+        let counts: Vec<usize> = std::thread::scope(|scope| {
+            let handles: Vec<_> = modules
+                .iter()
+                .map(|ir| {
+                    let verifier = Arc::clone(&verifier);
+                    scope.spawn(move || verifier.verify_entries(ir).len())
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(counts, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,254 @@
+//! Structural problems in IR that the verifier would otherwise read past.
+//!
+//! The checks work on lines and patterns, so a truncated or hand-edited
+//! module does not stop a run: a function without its closing brace simply
+//! extends into the next one, and a `.hot_funcs` record whose string is
+//! gone drops out of the hot set. That keeps `verify_entries` usable on
+//! whatever a build left behind, but its findings then describe IR that
+//! does not exist. `parse_errors` lists these problems, and
+//! `verify_entries_checked` refuses to verify a module that has any.
+
+use core::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::{HotPathVerifier, Violation, hot_records};
+
+/// One structural problem; lines are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A `define` line without the `{` that opens its body.
+    MalformedDefine { line: usize },
+    /// A body that reaches the next `define` or the end of the input
+    /// without its closing `}`; `line` is that of the `define`.
+    UnterminatedFunction { symbol: String, line: usize },
+    /// A `}` line outside any function body.
+    UnmatchedBrace { line: usize },
+    /// A line with an odd number of `"` outside its comment.
+    UnterminatedString { line: usize },
+    /// A `.hot_funcs` record whose name could not be read: its string
+    /// global or function is missing.
+    UnresolvedRecord { line: usize, record: String },
+}
+
+impl ParseError {
+    pub fn line(&self) -> usize {
+        match self {
+            ParseError::MalformedDefine { line }
+            | ParseError::UnterminatedFunction { line, .. }
+            | ParseError::UnmatchedBrace { line }
+            | ParseError::UnterminatedString { line }
+            | ParseError::UnresolvedRecord { line, .. } => *line,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MalformedDefine { line } => {
+                write!(f, "line {}: `define` without a body", line)
+            }
+            ParseError::UnterminatedFunction { symbol, line } => {
+                write!(f, "line {}: body of @{} is not closed", line, symbol)
+            }
+            ParseError::UnmatchedBrace { line } => {
+                write!(f, "line {}: `}}` outside a function", line)
+            }
+            ParseError::UnterminatedString { line } => {
+                write!(f, "line {}: unterminated string", line)
+            }
+            ParseError::UnresolvedRecord { line, record } => write!(
+                f,
+                "line {}: .hot_funcs record names nothing in the module: {}",
+                line, record
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The line without its `;` comment, or `None` if a string is left open.
+fn code(line: &str) -> Option<&str> {
+    let mut quoted = false;
+    for (at, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return Some(&line[..at]),
+            _ => {}
+        }
+    }
+    (!quoted).then_some(line)
+}
+
+/// Symbol of a `define` line, without `@` and quotes.
+fn defined_symbol(line: &str) -> String {
+    let Some((_, rest)) = line.split_once('@') else {
+        return String::new();
+    };
+    match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or_default().to_string(),
+        None => rest
+            .split(|c: char| c == '(' || c.is_whitespace())
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+/// Structural problems in `ir`, in line order.
+pub fn parse_errors(ir: &str) -> Vec<ParseError> {
+    let mut errors = Vec::new();
+    // Symbol and line of the body being read.
+    let mut open: Option<(String, usize)> = None;
+    for (index, raw) in ir.lines().enumerate() {
+        let line = index + 1;
+        let Some(code) = code(raw) else {
+            errors.push(ParseError::UnterminatedString { line });
+            continue;
+        };
+        let code = code.trim();
+        if code.starts_with("define ") {
+            if let Some((symbol, line)) = open.take() {
+                errors.push(ParseError::UnterminatedFunction { symbol, line });
+            }
+            match code.find('{') {
+                None => errors.push(ParseError::MalformedDefine { line }),
+                // A one-line body (`define i32 @f() { ret i32 0 }`).
+                Some(at) if code[at..].ends_with('}') => {}
+                Some(_) => open = Some((defined_symbol(code), line)),
+            }
+        } else if code == "}" && open.take().is_none() {
+            errors.push(ParseError::UnmatchedBrace { line });
+        }
+    }
+    if let Some((symbol, line)) = open {
+        errors.push(ParseError::UnterminatedFunction { symbol, line });
+    }
+    for (offset, record, name) in hot_records(ir) {
+        if name.is_none() {
+            errors.push(ParseError::UnresolvedRecord {
+                line: ir[..offset].lines().count() + 1,
+                record: record.trim().to_string(),
+            });
+        }
+    }
+    errors.sort_by_key(ParseError::line);
+    errors
+}
+
+impl HotPathVerifier {
+    /// `verify_entries` for IR without structural problems; otherwise the
+    /// problems (see `parse_errors`) and no verification. Neither this nor
+    /// `verify_entries` panics on malformed input.
+    pub fn verify_entries_checked(&self, ir: &str) -> Result<Vec<Violation>, Vec<ParseError>> {
+        let errors = parse_errors(ir);
+        if errors.is_empty() {
+            Ok(self.verify_entries(ir))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::Profile;
+    use crate::perf::verify_hot_path::fuzz::{SEED_IR, corpus};
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_errors(SEED_IR), []);
+        assert_eq!(parse_errors("define i32 @f() { ret i32 0 }\n"), []);
+
+        let cut = &SEED_IR[..SEED_IR.find("  %q = sdiv").unwrap()];
+        assert_eq!(
+            parse_errors(cut),
+            [
+                ParseError::UnresolvedRecord {
+                    line: 5,
+                    record: "@HOT_FUNC.2 = internal constant ptr @c_kernel, section \"hot_funcs\", align 8".to_string()
+                },
+                ParseError::UnterminatedFunction {
+                    symbol: "tick".to_string(),
+                    line: 30
+                }
+            ]
+        );
+
+        let ir = "\
+@HOT = constant ptr @gone, section \".hot_funcs\"
+define void @f(ptr %p) {
+define void @\"g h\"() {
+  ret void ; \"quoted\" comment
+}
+}
+define void @h()
+@s = constant [2 x i8] c\"a
+";
+        let errors = parse_errors(ir);
+        assert_eq!(
+            errors,
+            [
+                ParseError::UnresolvedRecord {
+                    line: 1,
+                    record: "@HOT = constant ptr @gone, section \".hot_funcs\"".to_string()
+                },
+                ParseError::UnterminatedFunction {
+                    symbol: "f".to_string(),
+                    line: 2
+                },
+                ParseError::UnmatchedBrace { line: 6 },
+                ParseError::MalformedDefine { line: 7 },
+                ParseError::UnterminatedString { line: 8 },
+            ]
+        );
+        assert_eq!(errors[1].to_string(), "line 2: body of @f is not closed");
+    }
+
+    #[test]
+    fn test_adversarial_corpus() {
+        // Every check that sizes types or walks bodies: the ISR tier's
+        // alloca limit and a frame budget on top of the defaults.
+        let verifiers = [
+            HotPathVerifier::default(),
+            HotPathVerifier::new().with_profile(Profile::InterruptHandler),
+        ]
+        .map(|verifier| {
+            verifier
+                .with_date("2026-01-01")
+                .with_function_policy("app::dsp::mix", |p| p.stack_limit(256))
+                .with_function_policy("tick", |p| p.stack_limit(0))
+        });
+        // Sizes that overflow a u64 are over any limit.
+        let huge = SEED_IR.replace(
+            "alloca [64 x i8]",
+            "alloca [4294967295 x [4294967295 x i64]]",
+        );
+        let inputs = corpus(SEED_IR, 7, 24);
+        assert!(inputs.iter().any(|ir| !parse_errors(ir).is_empty()));
+        for verifier in &verifiers {
+            assert!(verifier.verify_entries_checked(SEED_IR).is_ok());
+            let violations = verifier.verify_entries(&huge);
+            assert!(
+                violations
+                    .iter()
+                    .any(|v| v.check == "stack_limit" || v.check == "stack_alloca_size")
+            );
+            for ir in &inputs {
+                let _ = verifier.verify_entries_checked(ir);
+                verifier.verify_entries(ir);
+            }
+        }
+
+        // Too long for a lookup pattern: reported, not a panic.
+        let mut verifier = HotPathVerifier::default();
+        verifier.add_function(&"a".repeat(1 << 20));
+        let violations = verifier.verify_entries("");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].check, "missing");
+        assert!(violations[0].message.contains("too long"));
+    }
+}
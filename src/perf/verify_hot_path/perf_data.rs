@@ -0,0 +1,257 @@
+//! Hot-set discovery from profiler output.
+//!
+//! `mark_hot!` records which functions the author believes are hot; a
+//! profile records which ones are. This module reads sample counts per
+//! symbol and lists the functions that take at least a given share of the
+//! samples but carry no annotation, so the verifier's coverage can follow
+//! the measurements.
+//!
+//! Three inputs are accepted and told apart by their content:
+//!
+//! - `perf report --stdio` (optionally `-n` for a samples column); without
+//!   the column the overhead percentage stands in for the count,
+//! - `perf script`, where each sample is attributed to its leaf frame,
+//! - plain `symbol,samples` CSV with an optional header line.
+//!
+//! Symbols are demangled (legacy and v0) and stripped of hashes and offsets
+//! before they are compared with `HotEntry` paths.
+
+use core::fmt;
+use std::collections::HashMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::HotEntry;
+use super::calls::demangle;
+
+/// Samples attributed to one symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSamples {
+    /// Demangled, without hash or offset.
+    pub symbol: String,
+    pub samples: u64,
+}
+
+/// A sampled function that is not marked hot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotCandidate {
+    pub function: String,
+    pub samples: u64,
+    /// Percent of all samples in the profile.
+    pub share: f64,
+}
+
+impl fmt::Display for HotCandidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.1}% of samples ({}) but not marked hot; add `mark_hot!({})`",
+            self.function,
+            self.share,
+            self.samples,
+            self.function.rsplit("::").next().unwrap_or(&self.function)
+        )
+    }
+}
+
+/// Symbol as written by perf or in the CSV, reduced to a Rust path.
+pub(super) fn clean(symbol: &str) -> String {
+    let symbol = symbol.trim();
+    let symbol = symbol.split("+0x").next().unwrap_or(symbol);
+    let mut path = demangle(symbol);
+    // perf's own demangler keeps the legacy hash segment.
+    if let Some((head, hash)) = path.rsplit_once("::h")
+        && hash.len() == 16
+        && hash.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        path.truncate(head.len());
+    }
+    path
+}
+
+/// `perf report --stdio` line: `12.34%  [1234]  comm  dso  [.] symbol`.
+fn report_line(line: &str) -> Option<SymbolSamples> {
+    let (head, symbol) = line
+        .split_once(" [.] ")
+        .or_else(|| line.split_once(" [k] "))?;
+    let mut words = head.split_whitespace();
+    let percent: f64 = words.next()?.strip_suffix('%')?.parse().ok()?;
+    let samples = match words.next().and_then(|w| w.parse::<u64>().ok()) {
+        Some(samples) => samples,
+        // Hundredths of a percent keep the shares of a report without `-n`.
+        None => (percent * 100.0).round() as u64,
+    };
+    Some(SymbolSamples {
+        symbol: clean(symbol),
+        samples,
+    })
+}
+
+/// Leaf frame of each `perf script` sample: the first indented
+/// `address symbol (dso)` line after a sample header.
+fn script(text: &str) -> Vec<SymbolSamples> {
+    let mut samples = Vec::new();
+    let mut leaf = true;
+    for line in text.lines() {
+        if line.trim().is_empty() || !line.starts_with(char::is_whitespace) {
+            leaf = true;
+            continue;
+        }
+        if !leaf {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let (Some(address), Some(symbol)) = (words.next(), words.next()) else {
+            continue;
+        };
+        if address.bytes().all(|b| b.is_ascii_hexdigit()) {
+            leaf = false;
+            samples.push(SymbolSamples {
+                symbol: clean(symbol),
+                samples: 1,
+            });
+        }
+    }
+    samples
+}
+
+/// `symbol,samples` lines; anything else (a header) is skipped.
+fn csv(text: &str) -> Vec<SymbolSamples> {
+    text.lines()
+        .filter_map(|line| {
+            let (symbol, samples) = line.rsplit_once(',')?;
+            Some(SymbolSamples {
+                symbol: clean(symbol.trim_matches('"')),
+                samples: samples.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// Samples per symbol in `text`, merged by symbol and ordered by count
+/// (most first).
+pub fn parse(text: &str) -> Vec<SymbolSamples> {
+    let report: Vec<SymbolSamples> = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(report_line)
+        .collect();
+    let raw = if !report.is_empty() {
+        report
+    } else {
+        let frames = script(text);
+        if frames.is_empty() { csv(text) } else { frames }
+    };
+    let mut merged: HashMap<String, u64> = HashMap::new();
+    for entry in raw {
+        *merged.entry(entry.symbol).or_default() += entry.samples;
+    }
+    let mut samples: Vec<SymbolSamples> = merged
+        .into_iter()
+        .map(|(symbol, samples)| SymbolSamples { symbol, samples })
+        .collect();
+    samples.sort_by(|a, b| b.samples.cmp(&a.samples).then(a.symbol.cmp(&b.symbol)));
+    samples
+}
+
+/// Whether `symbol` is the function an entry names; entries may give the
+/// full path or only its tail (`dsp::mix` for `app::dsp::mix`).
+pub(super) fn annotated(symbol: &str, entries: &[HotEntry]) -> bool {
+    entries.iter().any(|entry| {
+        let name = entry.name.as_str();
+        symbol == name
+            || symbol.ends_with(&format!("::{}", name))
+            || name.ends_with(&format!("::{}", symbol))
+    })
+}
+
+/// Functions with at least `min_share` percent of the samples that no
+/// entry covers, most sampled first.
+pub fn hot_candidates(
+    samples: &[SymbolSamples],
+    entries: &[HotEntry],
+    min_share: f64,
+) -> Vec<HotCandidate> {
+    let total: u64 = samples.iter().map(|s| s.samples).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+    samples
+        .iter()
+        .map(|s| HotCandidate {
+            function: s.symbol.to_string(),
+            samples: s.samples,
+            share: s.samples as f64 * 100.0 / total as f64,
+        })
+        .filter(|c| c.share >= min_share && !annotated(&c.function, entries))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        let report = "\
+# Overhead  Samples  Command  Shared Object  Symbol
+    62.50%      500  synth    synth          [.] synth::dsp::mix
+    25.00%      200  synth    synth          [.] _ZN5synth3dsp6filter17h0123456789abcdefE
+    12.50%      100  synth    [kernel]       [k] clear_page_erms
+";
+        let samples = parse(report);
+        assert_eq!(samples[0].symbol, "synth::dsp::mix");
+        assert_eq!(samples[1].symbol, "synth::dsp::filter");
+        assert_eq!(samples[1].samples, 200);
+
+        let without_counts = "    40.00%  synth  synth  [.] synth::dsp::mix::h0123456789abcdef\n";
+        assert_eq!(
+            parse(without_counts),
+            [SymbolSamples {
+                symbol: "synth::dsp::mix".to_string(),
+                samples: 4000
+            }]
+        );
+
+        let script = "\
+synth  1234 [000] 10.000001:     250000 cycles:
+\t    55d0c0a01234 synth::dsp::mix+0x34 (/usr/bin/synth)
+\t    55d0c0a05678 synth::main+0x78 (/usr/bin/synth)
+
+synth  1234 [000] 10.000002:     250000 cycles:
+\t    55d0c0a01240 synth::dsp::mix+0x40 (/usr/bin/synth)
+
+synth  1234 [000] 10.000003:     250000 cycles:
+\t    55d0c0a09000 synth::ui::draw+0x10 (/usr/bin/synth)
+";
+        let samples = parse(script);
+        assert_eq!(samples.len(), 2);
+        assert_eq!(
+            samples[0],
+            SymbolSamples {
+                symbol: "synth::dsp::mix".to_string(),
+                samples: 2
+            }
+        );
+
+        let csv = "symbol,samples\nsynth::dsp::mix,30\n\"synth::ui::draw\",10\nsynth::dsp::mix,5\n";
+        let samples = parse(csv);
+        assert_eq!(samples[0].samples, 35);
+        assert_eq!(samples[1].symbol, "synth::ui::draw");
+    }
+
+    #[test]
+    fn test_hot_candidates() {
+        let samples =
+            parse("synth::dsp::mix,50\nsynth::dsp::filter,30\nsynth::ui::draw,15\nlog,5\n");
+        let entries = [HotEntry::parse("dsp::mix")];
+        let candidates = hot_candidates(&samples, &entries, 10.0);
+        let names: Vec<&str> = candidates.iter().map(|c| c.function.as_str()).collect();
+        assert_eq!(names, ["synth::dsp::filter", "synth::ui::draw"]);
+        assert_eq!(
+            candidates[0].to_string(),
+            "synth::dsp::filter: 30.0% of samples (30) but not marked hot; add `mark_hot!(filter)`"
+        );
+        assert!(hot_candidates(&[], &entries, 0.0).is_empty());
+    }
+}
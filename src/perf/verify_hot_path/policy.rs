@@ -0,0 +1,247 @@
+//! Per-function overrides for `HotPathVerifier`.
+//!
+//! A `FunctionPolicy` applies to one hot function in addition to its
+//! `mark_hot!` lists, so special cases (a mixer that may divide, a kernel
+//! with a tight stack budget) are configured next to the verifier without
+//! touching the global check set.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::Profile;
+
+/// Overrides for one function, built with `HotPathVerifier::with_function_policy`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionPolicy {
+    pub(super) allow: Vec<String>,
+    /// `(check, YYYY-MM-DD)` expiries of entries in `allow`.
+    pub(super) until: Vec<(String, String)>,
+    pub(super) require: Vec<String>,
+    pub(super) stack_limit: Option<u64>,
+    pub(super) tier: Option<Profile>,
+}
+
+impl FunctionPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips the named check for this function.
+    pub fn allow(mut self, check: &str) -> Self {
+        self.allow.push(check.to_string());
+        self
+    }
+
+    /// Skips the named check for this function until the end of `date`
+    /// (`YYYY-MM-DD`); after that the check runs again and the expired
+    /// exemption is reported as an error.
+    pub fn allow_until(mut self, check: &str, date: &str) -> Self {
+        self.allow.push(check.to_string());
+        self.until.push((check.to_string(), date.to_string()));
+        self
+    }
+
+    /// Enables an opt-in check, as `mark_hot!(f, require(..))` does.
+    pub fn require(mut self, check: &str) -> Self {
+        self.require.push(check.to_string());
+        self
+    }
+
+    /// Adds the checks of `profile` for this function, as
+    /// `mark_hot!(f, tier = ..)` does; e.g. `Profile::InterruptHandler` for
+    /// an ISR verified next to soft real-time code.
+    pub fn tier(mut self, profile: Profile) -> Self {
+        self.tier = Some(profile);
+        self
+    }
+
+    /// Fails if the fixed allocas of the function exceed `bytes`.
+    pub fn stack_limit(mut self, bytes: u64) -> Self {
+        self.stack_limit = Some(bytes);
+        self
+    }
+
+    pub(super) fn allows(&self, check: &str) -> bool {
+        self.allow.iter().any(|allowed| allowed == check)
+    }
+}
+
+/// Whether `date` has the `YYYY-MM-DD` shape with a plausible month and day.
+pub(super) fn is_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    let number = |range: core::ops::Range<usize>| {
+        date.get(range)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse::<u32>().ok())
+    };
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && number(0..4).is_some()
+        && number(5..7).is_some_and(|month| (1..=12).contains(&month))
+        && number(8..10).is_some_and(|day| (1..=31).contains(&day))
+}
+
+/// Whether an exemption that lasts until `until` no longer applies on
+/// `today`. A malformed date never applies, so a typo cannot make an
+/// exemption permanent.
+pub(super) fn expired(until: &str, today: &str) -> bool {
+    !is_date(until) || until < today
+}
+
+/// `YYYY-MM-DD` of the day `days` after 1970-01-01 (proleptic Gregorian).
+pub(super) fn civil_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Current UTC date, `YYYY-MM-DD`.
+pub(super) fn today() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    civil_date((secs / 86_400) as i64)
+}
+
+/// Splits at the first comma outside brackets.
+pub(super) fn split_type(text: &str) -> (&str, &str) {
+    let mut depth = 0i32;
+    for (pos, c) in text.char_indices() {
+        match c {
+            '[' | '{' | '<' | '(' => depth += 1,
+            ']' | '}' | '>' | ')' => depth -= 1,
+            ',' if depth == 0 => return (text[..pos].trim(), &text[pos + 1..]),
+            _ => {}
+        }
+    }
+    (text.trim(), "")
+}
+
+/// Size in bytes of an IR type, assuming 64-bit pointers and no padding
+/// inside structs. `None` for types it does not understand; a size that
+/// does not fit in a `u64` saturates to `u64::MAX`, which no limit admits.
+pub(super) fn type_size(ty: &str) -> Option<u64> {
+    let ty = ty.trim();
+    if let Some(inner) = ty
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .or_else(|| ty.strip_prefix('<').and_then(|t| t.strip_suffix('>')))
+        .filter(|inner| !inner.starts_with('{'))
+    {
+        let (count, element) = inner.split_once(" x ")?;
+        return Some(
+            count
+                .trim()
+                .parse::<u64>()
+                .ok()?
+                .saturating_mul(type_size(element)?),
+        );
+    }
+    if let Some(fields) = ty
+        .strip_prefix("<{")
+        .and_then(|t| t.strip_suffix("}>"))
+        .or_else(|| ty.strip_prefix('{').and_then(|t| t.strip_suffix('}')))
+    {
+        let mut size = 0;
+        let mut rest = fields;
+        while !rest.trim().is_empty() {
+            let (field, tail) = split_type(rest);
+            size = type_size(field)?.saturating_add(size);
+            rest = tail;
+        }
+        return Some(size);
+    }
+    match ty {
+        "ptr" | "double" | "i64" => Some(8),
+        "float" => Some(4),
+        "half" | "bfloat" => Some(2),
+        "fp128" | "x86_fp80" => Some(16),
+        _ => {
+            let bits: u64 = ty.strip_prefix('i')?.parse().ok()?;
+            Some(bits.div_ceil(8))
+        }
+    }
+}
+
+/// Allocated type and element count of an `alloca` line; the count is
+/// `None` when it is a runtime value.
+pub(super) fn alloca(line: &str) -> Option<(&str, Option<u64>)> {
+    let rest = line.split_once(" = alloca ")?.1;
+    let rest = rest.strip_prefix("inalloca ").unwrap_or(rest);
+    let (ty, tail) = split_type(rest);
+    // `alloca T, i64 N` allocates N elements.
+    let (count, _) = split_type(tail);
+    let count = if count.starts_with('i') && !count.starts_with("inalloca") {
+        count.split_whitespace().nth(1)?.parse::<u64>().ok()
+    } else {
+        Some(1)
+    };
+    Some((ty, count))
+}
+
+/// Bytes of constant-size allocas in `body`; dynamic allocas are not counted.
+pub(super) fn frame_size(body: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| {
+            let (ty, count) = alloca(line)?;
+            Some(type_size(ty)?.saturating_mul(count?))
+        })
+        .fold(0, u64::saturating_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_sizes() {
+        assert_eq!(type_size("[16 x i8]"), Some(16));
+        assert_eq!(type_size("<4 x float>"), Some(16));
+        assert_eq!(type_size("{ i32, [2 x ptr] }"), Some(20));
+        assert_eq!(type_size("<{ i8, i64 }>"), Some(9));
+        assert_eq!(type_size("i1"), Some(1));
+        assert_eq!(type_size("%SomeStruct"), None);
+        assert_eq!(type_size("[18446744073709551615 x i64]"), Some(u64::MAX));
+        assert_eq!(
+            type_size("{ [4294967295 x [4294967295 x i64]], i8 }"),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_frame_size() {
+        let body = "
+  %buf = alloca [64 x i8], align 1
+  %x = alloca i32, align 4
+  %arr = alloca float, i64 8, align 4
+  %dyn = alloca i8, i64 %n, align 1";
+        assert_eq!(frame_size(body), 64 + 4 + 32);
+    }
+
+    #[test]
+    fn test_builder() {
+        let policy = FunctionPolicy::new().allow("division").stack_limit(256);
+        assert!(policy.allows("division") && !policy.allows("allocation"));
+        assert_eq!(policy.stack_limit, Some(256));
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(11_016), "2000-02-29");
+        assert_eq!(civil_date(20_000), "2024-10-04");
+        assert!(is_date(&today()));
+        assert!(!is_date("2025-9-01") && !is_date("2025-13-01") && !is_date("soon"));
+        assert!(!expired("2025-09-01", "2025-09-01"));
+        assert!(expired("2025-09-01", "2025-09-02"));
+        assert!(expired("2025-09-1", "2000-01-01"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Collecting `HotPathCheck`s from other crates.
+//!
+//! A crate that ships its own checks either exposes a
+//! `pub fn register_checks(registry: &mut CheckRegistry)` hook that the
+//! verifying test calls explicitly, or declares them with `register_check!`,
+//! which places a `CheckRegistration` in the `hot_checks` link section.
+//! `CheckRegistry::linked()` then picks up every check registered by any
+//! crate linked into the verifying binary, with no list to keep in sync.
+//! Like `metrics`, enumeration needs the ELF start/stop symbols (Linux and
+//! bare-metal targets).
+
+use std::boxed::Box;
+use std::vec::Vec;
+
+use super::{
+    AllocaInLoopCheck, AllocationCheck, AtomicCheck, BranchHintCheck, CallCheck, ContractCheck,
+    DeadCodeCheck, DeallocationCheck, DivisionCheck, FloatingPointCheck, FunctionCallCheck,
+    HotPathCheck, IndirectionCheck, LibcCompareCheck, LibmCheck, MemIntrinsicCheck,
+    NonInboundsGepCheck, OverflowCheck, PanicPathCheck, RecursionCheck, TrapCheck,
+    UnalignedAccessCheck, VolatileLoadCheck, VolatileStoreCheck,
+};
+
+/// Section record produced by `register_check!`.
+#[repr(C)]
+pub struct CheckRegistration {
+    /// Type name of the check, for listings.
+    pub name: &'static str,
+    /// Crate that registered it.
+    pub crate_name: &'static str,
+    pub factory: fn() -> Box<dyn HotPathCheck>,
+}
+
+/// Boxes a check; used by `register_check!` factories.
+pub fn boxed<C: HotPathCheck + 'static>(check: C) -> Box<dyn HotPathCheck> {
+    Box::new(check)
+}
+
+// The built-in checks are registered too, which also guarantees the section
+// (and its start/stop symbols) exists in every binary.
+crate::register_check!(IndirectionCheck);
+crate::register_check!(AllocationCheck);
+crate::register_check!(DeallocationCheck);
+crate::register_check!(FunctionCallCheck);
+crate::register_check!(RecursionCheck);
+crate::register_check!(CallCheck);
+crate::register_check!(AtomicCheck);
+crate::register_check!(AllocaInLoopCheck);
+crate::register_check!(TrapCheck);
+crate::register_check!(OverflowCheck);
+crate::register_check!(VolatileLoadCheck);
+crate::register_check!(VolatileStoreCheck);
+crate::register_check!(DivisionCheck);
+crate::register_check!(FloatingPointCheck);
+crate::register_check!(LibcCompareCheck);
+crate::register_check!(LibmCheck);
+crate::register_check!(MemIntrinsicCheck = MemIntrinsicCheck::new());
+crate::register_check!(ContractCheck);
+crate::register_check!(PanicPathCheck);
+crate::register_check!(UnalignedAccessCheck);
+crate::register_check!(NonInboundsGepCheck);
+crate::register_check!(BranchHintCheck);
+crate::register_check!(DeadCodeCheck);
+
+#[cfg(any(target_os = "linux", target_os = "none"))]
+unsafe extern "C" {
+    static __start_hot_checks: u8;
+    static __stop_hot_checks: u8;
+}
+
+/// Every `register_check!` record in the final binary, in link order.
+#[cfg(any(target_os = "linux", target_os = "none"))]
+pub fn linked_registrations() -> &'static [CheckRegistration] {
+    // SAFETY: the linker places every `hot_checks` record contiguously between
+    // these symbols; records are `repr(C)` statics of identical size and alignment.
+    unsafe {
+        let start = core::ptr::addr_of!(__start_hot_checks) as *const CheckRegistration;
+        let stop = core::ptr::addr_of!(__stop_hot_checks) as *const CheckRegistration;
+        let len = (stop as usize - start as usize) / core::mem::size_of::<CheckRegistration>();
+        core::slice::from_raw_parts(start, len)
+    }
+}
+
+/// Set of checks keyed by `HotPathCheck::name()`; the first registration of
+/// a name wins.
+#[derive(Default)]
+pub struct CheckRegistry {
+    checks: Vec<Box<dyn HotPathCheck>>,
+}
+
+impl CheckRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `check` unless one with the same name is present; returns whether it was added.
+    pub fn register(&mut self, check: Box<dyn HotPathCheck>) -> bool {
+        if self.contains(check.name()) {
+            return false;
+        }
+        self.checks.push(check);
+        true
+    }
+
+    /// Runs a crate's `register_checks` hook.
+    pub fn register_from(&mut self, hook: fn(&mut CheckRegistry)) -> &mut Self {
+        hook(self);
+        self
+    }
+
+    /// Adds every check registered with `register_check!` in this binary.
+    #[cfg(any(target_os = "linux", target_os = "none"))]
+    pub fn register_linked(&mut self) -> &mut Self {
+        for registration in linked_registrations() {
+            self.register((registration.factory)());
+        }
+        self
+    }
+
+    /// A registry holding every linked check, built-ins included.
+    #[cfg(any(target_os = "linux", target_os = "none"))]
+    pub fn linked() -> Self {
+        let mut registry = Self::new();
+        registry.register_linked();
+        registry
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.checks.iter().any(|check| check.name() == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.checks.iter().map(|check| check.name())
+    }
+
+    pub fn len(&self) -> usize {
+        self.checks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    pub fn into_checks(self) -> Vec<Box<dyn HotPathCheck>> {
+        self.checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perf::verify_hot_path::{HotPathVerifier, Severity};
+
+    /// Downstream-style check: flags `fdiv`.
+    struct FloatDivCheck;
+    impl HotPathCheck for FloatDivCheck {
+        fn name(&self) -> &str {
+            "float_div"
+        }
+        fn severity(&self) -> Severity {
+            Severity::Error
+        }
+        fn check_line(&self, line: &str) -> Option<String> {
+            line.contains(" fdiv ")
+                .then(|| "contains float division".to_string())
+        }
+    }
+    crate::register_check!(FloatDivCheck);
+
+    fn register_checks(registry: &mut CheckRegistry) {
+        registry.register(Box::new(FloatDivCheck));
+    }
+
+    const IR: &str = "define float @f(float %a) {\n  %1 = fdiv float %a, 3.0\n  ret float %1\n}";
+
+    #[test]
+    fn test_hook_registration() {
+        let mut registry = CheckRegistry::new();
+        registry.register_from(register_checks);
+        assert!(!registry.register(Box::new(FloatDivCheck)));
+        assert_eq!(registry.len(), 1);
+
+        let verifier = HotPathVerifier::default().with_registry(registry);
+        let err = verifier.verify(IR, "f").unwrap_err();
+        assert!(err.contains("float division"), "{}", err);
+        assert!(HotPathVerifier::default().verify(IR, "f").is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_linked_registration() {
+        let linked = linked_registrations();
+        assert!(linked.iter().any(|r| r.name == "FloatDivCheck"));
+        assert!(linked.iter().all(|r| r.crate_name == "base"));
+
+        let registry = CheckRegistry::linked();
+        assert!(registry.contains("float_div"));
+        assert!(registry.contains("allocation"));
+        assert_eq!(registry.names().filter(|n| *n == "division").count(), 1);
+
+        assert!(
+            HotPathVerifier::with_linked_checks()
+                .verify(IR, "f")
+                .is_err()
+        );
+    }
+}
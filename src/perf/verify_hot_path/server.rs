@@ -0,0 +1,430 @@
+//! Long-running verifier for editor integration.
+//!
+//! Speaks JSON-RPC 2.0 over stdio with LSP framing (`Content-Length`
+//! headers), so the transport of any LSP client library can drive it. IR
+//! stays in memory between requests and results go through a
+//! `VerifyCache`, so re-verifying after a rebuild only re-checks functions
+//! whose IR changed.
+//!
+//! Requests (`params` in braces):
+//!
+//! * `load {path, text?}`: read (or take) the IR, verify every hot
+//!   function and stream one `hotVerify/diagnostic` notification per
+//!   violation; the result lists the functions and the counts.
+//! * `reload {path}`: `load` from disk again after a rebuild.
+//! * `verify {path, function}`: diagnostics of one function.
+//! * `functions {path}`: hot functions of a loaded file.
+//! * `explain {code}`: a `CheckDoc` (see `explain`).
+//! * `shutdown`: replies and stops the server.
+
+pub mod json;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::{HotEntry, HotPathVerifier, Severity, VerifyCache, VerifyObserver, Violation, explain};
+pub use json::Json;
+
+/// JSON-RPC error codes used by the server.
+pub const PARSE_ERROR: i32 = -32700;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+/// Request-specific failure (unreadable file, unknown function, ...).
+pub const REQUEST_FAILED: i32 = -32000;
+
+/// Next `Content-Length`-framed message, or `None` at end of input.
+pub fn read_message(reader: &mut dyn BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = std::vec![0; length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message(writer: &mut dyn Write, message: &Json) -> io::Result<()> {
+    let body = message.render();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn diagnostic(violation: &Violation) -> Json {
+    let location = violation.location.as_ref();
+    Json::object([
+        ("function", violation.function.as_str().into()),
+        ("check", violation.check.as_str().into()),
+        (
+            "code",
+            explain::code(&violation.check).map_or(Json::Null, Json::from),
+        ),
+        ("severity", violation.severity.name().into()),
+        ("message", violation.message.as_str().into()),
+        (
+            "file",
+            location.map_or(Json::Null, |l| l.file.as_str().into()),
+        ),
+        (
+            "line",
+            location.map_or(Json::Null, |l| Json::Number(l.line as f64)),
+        ),
+        (
+            "column",
+            location.map_or(Json::Null, |l| Json::Number(l.column.max(1) as f64)),
+        ),
+    ])
+}
+
+/// Sends each violation as a notification while a file is verified.
+struct Notifier<'a> {
+    path: &'a str,
+    notify: &'a mut dyn FnMut(Json),
+}
+
+impl VerifyObserver for Notifier<'_> {
+    fn on_violation(&mut self, violation: &Violation) {
+        (self.notify)(Json::object([
+            ("jsonrpc", "2.0".into()),
+            ("method", "hotVerify/diagnostic".into()),
+            (
+                "params",
+                Json::object([
+                    ("path", self.path.into()),
+                    ("diagnostic", diagnostic(violation)),
+                ]),
+            ),
+        ]))
+    }
+}
+
+/// Request failure: JSON-RPC error code and message.
+type Failure = (i32, String);
+
+pub struct Server {
+    verifier: HotPathVerifier,
+    files: HashMap<String, String>,
+    cache: VerifyCache,
+    running: bool,
+}
+
+impl Server {
+    pub fn new(verifier: HotPathVerifier) -> Self {
+        Self {
+            verifier,
+            files: HashMap::new(),
+            cache: VerifyCache::new(),
+            running: true,
+        }
+    }
+
+    /// False once `shutdown` was handled.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Handles one message; returns the response for requests (messages
+    /// with an `id`) and `None` for notifications. Diagnostics are passed
+    /// to `notify` as they are found.
+    pub fn handle(&mut self, message: &Json, notify: &mut dyn FnMut(Json)) -> Option<Json> {
+        let id = message.get("id").cloned();
+        let method = message.get("method").and_then(Json::as_str).unwrap_or("");
+        let params = message.get("params").unwrap_or(&Json::Null);
+        let result = self.dispatch(method, params, notify);
+        let id = id?;
+        Some(match result {
+            Ok(result) => Json::object([("jsonrpc", "2.0".into()), ("id", id), ("result", result)]),
+            Err((code, text)) => error_response(id, code, text),
+        })
+    }
+
+    fn dispatch(
+        &mut self,
+        method: &str,
+        params: &Json,
+        notify: &mut dyn FnMut(Json),
+    ) -> Result<Json, Failure> {
+        let param = |name: &str| {
+            params.get(name).and_then(Json::as_str).ok_or_else(|| {
+                (
+                    INVALID_PARAMS,
+                    format!("`{}` needs a string `{}`", method, name),
+                )
+            })
+        };
+        match method {
+            "load" | "reload" => {
+                let path = param("path")?;
+                let text = match (method, params.get("text").and_then(Json::as_str)) {
+                    ("load", Some(text)) => text.to_string(),
+                    _ => std::fs::read_to_string(path)
+                        .map_err(|e| (REQUEST_FAILED, format!("{}: {}", path, e)))?,
+                };
+                self.files.insert(path.to_string(), text);
+                Ok(self.verify_file(path, notify))
+            }
+            "verify" => {
+                let path = param("path")?;
+                let function = param("function")?;
+                let ir = self.ir(path)?;
+                let entry = self
+                    .verifier
+                    .entries(ir)
+                    .into_iter()
+                    .find(|entry| entry.name == function)
+                    .unwrap_or_else(|| HotEntry::parse(function));
+                let diagnostics = self
+                    .verifier
+                    .violations(ir, &entry)
+                    .iter()
+                    .map(diagnostic)
+                    .collect();
+                Ok(Json::object([("diagnostics", Json::Array(diagnostics))]))
+            }
+            "functions" => {
+                let ir = self.ir(param("path")?)?;
+                Ok(Json::Array(
+                    self.verifier
+                        .entries(ir)
+                        .into_iter()
+                        .map(|entry| Json::String(entry.name))
+                        .collect(),
+                ))
+            }
+            "explain" => {
+                let code = param("code")?;
+                let doc = explain::explain(code)
+                    .ok_or_else(|| (REQUEST_FAILED, format!("no check with code `{}`", code)))?;
+                Ok(Json::object([
+                    ("code", doc.code.into()),
+                    ("check", doc.check.into()),
+                    ("summary", doc.summary.into()),
+                    ("explanation", doc.explanation.into()),
+                ]))
+            }
+            "shutdown" => {
+                self.running = false;
+                Ok(Json::Null)
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+        }
+    }
+
+    fn ir(&self, path: &str) -> Result<&str, Failure> {
+        self.files
+            .get(path)
+            .map(String::as_str)
+            .ok_or_else(|| (REQUEST_FAILED, format!("{} is not loaded", path)))
+    }
+
+    fn verify_file(&mut self, path: &str, notify: &mut dyn FnMut(Json)) -> Json {
+        let ir = &self.files[path];
+        let mut notifier = Notifier { path, notify };
+        let violations =
+            self.verifier
+                .verify_entries_observed(ir, Some(&mut self.cache), &mut notifier);
+        let count = |counted: fn(Severity) -> bool| {
+            violations.iter().filter(|v| counted(v.severity)).count()
+        };
+        Json::object([
+            (
+                "functions",
+                Json::Array(
+                    self.verifier
+                        .entries(ir)
+                        .into_iter()
+                        .map(|entry| Json::String(entry.name))
+                        .collect(),
+                ),
+            ),
+            ("errors", Json::Number(count(Severity::is_failure) as f64)),
+            (
+                "warnings",
+                Json::Number(count(|s| s == Severity::Warning) as f64),
+            ),
+            ("infos", Json::Number(count(|s| s == Severity::Info) as f64)),
+        ])
+    }
+
+    /// Reads framed messages from `reader` until end of input or `shutdown`,
+    /// writing responses and notifications to `writer`.
+    pub fn serve(&mut self, reader: &mut dyn BufRead, writer: &mut dyn Write) -> io::Result<()> {
+        while self.running {
+            let Some(text) = read_message(reader)? else {
+                break;
+            };
+            let response = match Json::parse(&text) {
+                Ok(message) => {
+                    let mut pending = Vec::new();
+                    let response = self.handle(&message, &mut |n| pending.push(n));
+                    for notification in &pending {
+                        write_message(writer, notification)?;
+                    }
+                    response
+                }
+                Err(error) => Some(error_response(Json::Null, PARSE_ERROR, error)),
+            };
+            if let Some(response) = response {
+                write_message(writer, &response)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn error_response(id: Json, code: i32, message: String) -> Json {
+    Json::object([
+        ("jsonrpc", "2.0".into()),
+        ("id", id),
+        (
+            "error",
+            Json::object([
+                ("code", Json::Number(code as f64)),
+                ("message", message.into()),
+            ]),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @k(i32 %a) {
+  %1 = udiv i32 %a, 3
+  ret i32 %1
+}
+"#;
+
+    fn request(id: u32, method: &str, params: Json) -> Json {
+        Json::object([
+            ("jsonrpc", "2.0".into()),
+            ("id", Json::Number(id as f64)),
+            ("method", method.into()),
+            ("params", params),
+        ])
+    }
+
+    #[test]
+    fn test_load_streams_diagnostics() {
+        let mut server = Server::new(HotPathVerifier::default());
+        let mut notifications = Vec::new();
+        let load = request(
+            1,
+            "load",
+            Json::object([("path", "k.ll".into()), ("text", IR.into())]),
+        );
+        let response = server
+            .handle(&load, &mut |n| notifications.push(n))
+            .unwrap();
+        let result = response.get("result").unwrap();
+        assert_eq!(result.get("warnings"), Some(&Json::Number(1.0)));
+        assert_eq!(notifications.len(), 1);
+        let diagnostic = notifications[0]
+            .get("params")
+            .and_then(|p| p.get("diagnostic"))
+            .unwrap();
+        assert_eq!(
+            diagnostic.get("code").and_then(Json::as_str),
+            Some("HP0008")
+        );
+
+        let verify = request(
+            2,
+            "verify",
+            Json::object([("path", "k.ll".into()), ("function", "k".into())]),
+        );
+        let response = server.handle(&verify, &mut |_| {}).unwrap();
+        let diagnostics = response.get("result").and_then(|r| r.get("diagnostics"));
+        assert!(matches!(diagnostics, Some(Json::Array(d)) if d.len() == 1));
+
+        let unknown = server
+            .handle(&request(3, "format", Json::Null), &mut |_| {})
+            .unwrap();
+        assert_eq!(
+            unknown.get("error").and_then(|e| e.get("code")),
+            Some(&Json::Number(METHOD_NOT_FOUND as f64))
+        );
+        let not_loaded = request(4, "functions", Json::object([("path", "x.ll".into())]));
+        assert!(
+            server
+                .handle(&not_loaded, &mut |_| {})
+                .unwrap()
+                .get("error")
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_serve_over_framed_stdio() {
+        let dir = std::env::temp_dir().join(format!("hot-verify-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("k.ll");
+        std::fs::write(&path, IR).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let mut input = Vec::new();
+        for message in [
+            request(1, "reload", Json::object([("path", path.as_str().into())])),
+            request(2, "explain", Json::object([("code", "HP0008".into())])),
+            request(3, "shutdown", Json::Null),
+            request(
+                4,
+                "functions",
+                Json::object([("path", path.as_str().into())]),
+            ),
+        ] {
+            write_message(&mut input, &message).unwrap();
+        }
+        input.extend_from_slice(b"Content-Length: 3\r\n\r\n{x}");
+
+        let mut output = Vec::new();
+        let mut server = Server::new(HotPathVerifier::default());
+        server
+            .serve(&mut io::Cursor::new(input), &mut output)
+            .unwrap();
+        assert!(!server.is_running());
+
+        let mut reader = io::Cursor::new(output);
+        let mut messages = Vec::new();
+        while let Some(text) = read_message(&mut reader).unwrap() {
+            messages.push(Json::parse(&text).unwrap());
+        }
+        // Diagnostic, reload result, explain result, shutdown result; the
+        // request after shutdown is not read.
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            messages[0].get("method").and_then(Json::as_str),
+            Some("hotVerify/diagnostic")
+        );
+        assert_eq!(
+            messages[2]
+                .get("result")
+                .and_then(|r| r.get("check"))
+                .and_then(Json::as_str),
+            Some("division")
+        );
+        assert_eq!(messages[3].get("result"), Some(&Json::Null));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,434 @@
+//! False sharing between hot functions on different threads.
+//!
+//! Collects the statics each hot function writes (`store`, `atomicrmw`,
+//! `cmpxchg` through a global, a constant `getelementptr` into one, or an
+//! SSA value derived from either) and reports two functions on different
+//! threads that write different bytes of the same cache line: every write
+//! invalidates the other core's copy of the line.
+//!
+//! Which function runs on which thread comes from the caller
+//! (`with_thread`); unmapped functions are assumed to run on threads of
+//! their own. Statics are placed by the linker, so lines shared by two
+//! different statics need their addresses from `nm` (`with_nm`); without
+//! them only writes to the same static are compared, assuming it starts on
+//! a line boundary.
+
+use std::collections::HashMap;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use core::ops::Range;
+
+use super::policy::{split_type, type_size};
+use super::{HotEntry, Severity, Violation, demangle, find_function_body};
+use crate::perf::layout::CACHE_LINE;
+
+/// Bytes of a static written by a hot function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticWrite {
+    pub function: String,
+    /// Symbol of the static, without `@`.
+    pub global: String,
+    /// Written bytes relative to the start of the static; the whole static
+    /// when the offset is not a constant.
+    pub bytes: Range<u64>,
+}
+
+/// Sizes of the globals defined in `ir`.
+fn global_sizes(ir: &str) -> HashMap<&str, u64> {
+    let mut sizes = HashMap::new();
+    for line in ir.lines() {
+        let Some((name, rest)) = line.trim().split_once(" = ") else {
+            continue;
+        };
+        let Some(name) = name.strip_prefix('@') else {
+            continue;
+        };
+        let mut words = rest;
+        loop {
+            let (word, tail) = words.split_once(' ').unwrap_or((words, ""));
+            match word {
+                "global" | "constant" => {
+                    if let Some(size) = type_size(leading_type(tail)) {
+                        sizes.insert(name.trim_matches('"'), size);
+                    }
+                    break;
+                }
+                "" => break,
+                _ => words = tail,
+            }
+        }
+    }
+    sizes
+}
+
+/// The type at the start of `text`, before the initializer.
+fn leading_type(text: &str) -> &str {
+    let mut depth = 0i32;
+    for (pos, c) in text.char_indices() {
+        match c {
+            '[' | '{' | '<' => depth += 1,
+            ']' | '}' | '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &text[..=pos];
+                }
+            }
+            ' ' | ',' if depth == 0 => return &text[..pos],
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Static and byte offset a pointer operand refers to, if constant.
+fn resolve<'a>(
+    operand: &'a str,
+    temps: &HashMap<&str, (&'a str, Option<u64>)>,
+) -> Option<(&'a str, Option<u64>)> {
+    let operand = operand.trim();
+    // A name may be followed by an ordering (`store atomic .., ptr @X release`).
+    let name = operand.split_whitespace().next().unwrap_or("");
+    if let Some(global) = name.strip_prefix('@') {
+        return Some((global.trim_matches('"'), Some(0)));
+    }
+    if name.starts_with('%') {
+        return temps.get(name).copied();
+    }
+    let gep = operand
+        .strip_prefix("getelementptr")?
+        .trim_start()
+        .trim_start_matches("inbounds ")
+        .trim_start_matches("nuw ")
+        .trim()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    gep_offset(gep, temps)
+}
+
+/// `T, ptr base, idx...` of a `getelementptr`.
+fn gep_offset<'a>(
+    gep: &'a str,
+    temps: &HashMap<&str, (&'a str, Option<u64>)>,
+) -> Option<(&'a str, Option<u64>)> {
+    let (ty, rest) = split_type(gep);
+    let (base, mut rest) = split_type(rest);
+    let (global, base_offset) = resolve(base.strip_prefix("ptr ")?, temps)?;
+    let mut element = ty;
+    let mut offset = base_offset;
+    let mut first = true;
+    while !rest.trim().is_empty() {
+        let (index, tail) = split_type(rest);
+        rest = tail;
+        let index = index
+            .split_whitespace()
+            .nth(1)
+            .and_then(|i| i.parse::<u64>().ok());
+        let stride = if first {
+            type_size(element)
+        } else {
+            // Only arrays are stepped into; struct fields need the layout.
+            let inner = element.strip_prefix('[')?.strip_suffix(']')?;
+            element = inner.split_once(" x ")?.1;
+            type_size(element)
+        };
+        first = false;
+        offset = match (offset, index, stride) {
+            (Some(offset), Some(index), Some(stride)) => index
+                .checked_mul(stride)
+                .and_then(|step| step.checked_add(offset)),
+            _ => None,
+        };
+    }
+    Some((global, offset))
+}
+
+/// Every static write in `body`, attributed to `function`.
+pub fn static_writes(ir: &str, body: &str, function: &str) -> Vec<StaticWrite> {
+    let sizes = global_sizes(ir);
+    let mut temps = HashMap::new();
+    let mut writes = Vec::new();
+    for line in body.lines().map(str::trim) {
+        let (result, code) = match line.split_once(" = ") {
+            Some((result, code)) => (Some(result), code),
+            None => (None, line),
+        };
+        let (pointer, size) = if let Some(gep) = code.strip_prefix("getelementptr ") {
+            let gep = gep
+                .trim_start_matches("inbounds ")
+                .trim_start_matches("nuw ");
+            if let (Some(result), Some(target)) = (result, gep_offset(gep, &temps)) {
+                temps.insert(result, target);
+            }
+            continue;
+        } else if let Some(store) = code.strip_prefix("store ") {
+            let store = store
+                .trim_start_matches("atomic ")
+                .trim_start_matches("volatile ");
+            let (value, rest) = split_type(store);
+            let ty = value.rsplit_once(' ').map_or(value, |(ty, _)| ty);
+            let (pointer, _) = split_type(rest);
+            (pointer, type_size(ty))
+        } else if let Some(rmw) = code.strip_prefix("atomicrmw ") {
+            let rmw = rmw.trim_start_matches("volatile ");
+            let (target, rest) = split_type(rmw.split_once(' ').map_or("", |(_, r)| r));
+            let (value, _) = split_type(rest);
+            (
+                target,
+                type_size(value.split_whitespace().next().unwrap_or("")),
+            )
+        } else if let Some(cmpxchg) = code.strip_prefix("cmpxchg ") {
+            let (target, rest) = split_type(cmpxchg.trim_start_matches("weak "));
+            let (value, _) = split_type(rest);
+            (
+                target,
+                type_size(value.split_whitespace().next().unwrap_or("")),
+            )
+        } else {
+            continue;
+        };
+        let Some((global, offset)) = pointer
+            .strip_prefix("ptr ")
+            .and_then(|p| resolve(p, &temps))
+        else {
+            continue;
+        };
+        let whole = 0..sizes.get(global).copied().unwrap_or(1).max(1);
+        let bytes = match (offset, size) {
+            (Some(offset), Some(size)) => offset..offset + size.max(1),
+            _ => whole,
+        };
+        writes.push(StaticWrite {
+            function: function.to_string(),
+            global: global.to_string(),
+            bytes,
+        });
+    }
+    writes
+}
+
+/// Reports cross-thread writes to one cache line.
+#[derive(Debug, Clone)]
+pub struct FalseSharingCheck {
+    line: u64,
+    threads: Vec<(String, String)>,
+    addresses: HashMap<String, u64>,
+}
+
+impl Default for FalseSharingCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FalseSharingCheck {
+    pub fn new() -> Self {
+        Self {
+            line: CACHE_LINE as u64,
+            threads: Vec::new(),
+            addresses: HashMap::new(),
+        }
+    }
+
+    pub fn with_cache_line(mut self, bytes: usize) -> Self {
+        self.line = bytes.max(1) as u64;
+        self
+    }
+
+    /// Hot function `function` (a `mark_hot!` path) runs on `thread`.
+    pub fn with_thread(mut self, function: &str, thread: &str) -> Self {
+        self.threads
+            .push((function.to_string(), thread.to_string()));
+        self
+    }
+
+    /// Addresses of statics from `nm` output of the final binary.
+    pub fn with_nm(mut self, nm: &str) -> Self {
+        for line in nm.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (address, symbol) = match fields.as_slice() {
+                [address, _, _, symbol] | [address, _, symbol] => (address, symbol),
+                _ => continue,
+            };
+            if let Ok(address) = u64::from_str_radix(address, 16) {
+                self.addresses.insert(symbol.to_string(), address);
+            }
+        }
+        self
+    }
+
+    fn thread<'a>(&'a self, function: &'a str) -> &'a str {
+        self.threads
+            .iter()
+            .find(|(f, _)| f == function)
+            .map_or(function, |(_, thread)| thread)
+    }
+
+    /// Cache lines (absolute with addresses, else relative to the static)
+    /// and absolute byte range of a write; `None` if it cannot be placed.
+    fn place(&self, write: &StaticWrite) -> (Option<u64>, Range<u64>) {
+        let base = self.addresses.get(&write.global).copied();
+        let start = base.unwrap_or(0) + write.bytes.start;
+        let end = base.unwrap_or(0) + write.bytes.end;
+        (base, start..end)
+    }
+
+    /// Warnings for pairs of `entries` on different threads that write
+    /// different bytes of one line. Entries allowing `false_sharing` are
+    /// left out.
+    pub fn verify(&self, ir: &str, entries: &[HotEntry]) -> Vec<Violation> {
+        let writes: Vec<StaticWrite> = entries
+            .iter()
+            .filter(|entry| !entry.allow.iter().any(|a| a == "false_sharing"))
+            .filter_map(|entry| {
+                let body = find_function_body(ir, &entry.name).ok()?;
+                Some(static_writes(ir, &body, &entry.name))
+            })
+            .flatten()
+            .collect();
+        let mut violations: Vec<Violation> = Vec::new();
+        for (i, a) in writes.iter().enumerate() {
+            for b in &writes[i + 1..] {
+                if a.function == b.function || self.thread(&a.function) == self.thread(&b.function)
+                {
+                    continue;
+                }
+                let ((base_a, bytes_a), (base_b, bytes_b)) = (self.place(a), self.place(b));
+                // Without addresses only offsets within one static compare.
+                let comparable = a.global == b.global || (base_a.is_some() && base_b.is_some());
+                let overlap = bytes_a.start < bytes_b.end && bytes_b.start < bytes_a.end;
+                let lines = |r: &Range<u64>| r.start / self.line..=(r.end - 1) / self.line;
+                let (lines_a, lines_b) = (lines(&bytes_a), lines(&bytes_b));
+                let shared =
+                    lines_a.start().max(lines_b.start()) <= lines_a.end().min(lines_b.end());
+                if !comparable || overlap || !shared {
+                    continue;
+                }
+                let message = format!(
+                    "writes {} (bytes {}..{}) in the cache line that {} on thread `{}` writes through {} (bytes {}..{})",
+                    demangle(&a.global),
+                    a.bytes.start,
+                    a.bytes.end,
+                    b.function,
+                    self.thread(&b.function),
+                    demangle(&b.global),
+                    b.bytes.start,
+                    b.bytes.end
+                );
+                if violations
+                    .iter()
+                    .all(|v| v.function != a.function || v.message != message)
+                {
+                    violations.push(Violation {
+                        function: a.function.clone(),
+                        check: "false_sharing".to_string(),
+                        severity: Severity::Warning,
+                        message,
+                        location: None,
+                    });
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"@STATS = global [4 x i64] zeroinitializer, align 8
+@HEAD = global i32 0, align 4
+@TAIL = global i32 0, align 4
+define void @producer(i64 %n) {
+  store i64 %n, ptr @STATS, align 8
+  %1 = atomicrmw add ptr @HEAD, i32 1 release
+  ret void
+}
+define void @consumer(i64 %n) {
+  %p = getelementptr inbounds [4 x i64], ptr @STATS, i64 0, i64 2
+  store i64 %n, ptr %p, align 8
+  store atomic i32 0, ptr @TAIL release, align 4
+  ret void
+}
+define void @logger(i64 %n) {
+  store i64 %n, ptr getelementptr inbounds (i8, ptr @STATS, i64 8), align 8
+  ret void
+}
+"#;
+
+    fn entries() -> Vec<HotEntry> {
+        ["producer", "consumer", "logger"]
+            .into_iter()
+            .map(HotEntry::parse)
+            .collect()
+    }
+
+    #[test]
+    fn test_static_writes() {
+        let body = find_function_body(IR, "consumer").unwrap();
+        let writes = static_writes(IR, &body, "consumer");
+        let found: Vec<(&str, Range<u64>)> = writes
+            .iter()
+            .map(|w| (w.global.as_str(), w.bytes.clone()))
+            .collect();
+        assert_eq!(found, [("STATS", 16..24), ("TAIL", 0..4)]);
+    }
+
+    #[test]
+    fn test_same_static_across_threads() {
+        let check = FalseSharingCheck::new()
+            .with_thread("producer", "audio")
+            .with_thread("logger", "audio");
+        let violations = check.verify(IR, &entries());
+        let messages: Vec<(&str, &str)> = violations
+            .iter()
+            .map(|v| (v.function.as_str(), v.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    "producer",
+                    "writes STATS (bytes 0..8) in the cache line that consumer on thread `consumer` writes through STATS (bytes 16..24)"
+                ),
+                (
+                    "consumer",
+                    "writes STATS (bytes 16..24) in the cache line that logger on thread `audio` writes through STATS (bytes 8..16)"
+                ),
+            ]
+        );
+        assert!(
+            FalseSharingCheck::new()
+                .with_cache_line(16)
+                .verify(IR, &entries())
+                .iter()
+                .all(|v| v.message.contains("logger"))
+        );
+    }
+
+    #[test]
+    fn test_neighbouring_statics_need_addresses() {
+        let only_queue = [HotEntry::parse("producer"), HotEntry::parse("consumer")];
+        let check = FalseSharingCheck::new().with_thread("consumer", "audio");
+        // `STATS` alone: bytes 0..8 and 16..24 share the first line.
+        assert_eq!(check.verify(IR, &only_queue).len(), 1);
+        let nm = "0000000000004000 0000000000000020 B STATS\n\
+                  0000000000004100 0000000000000004 B HEAD\n\
+                  0000000000004104 0000000000000004 B TAIL\n";
+        let violations = check.with_nm(nm).verify(IR, &only_queue);
+        assert_eq!(violations.len(), 2);
+        assert!(
+            violations[1]
+                .message
+                .starts_with("writes HEAD (bytes 0..4)")
+        );
+        assert!(violations[1].message.contains("through TAIL"));
+
+        let allowing = [
+            HotEntry::parse("producer;allow=false_sharing"),
+            HotEntry::parse("consumer"),
+        ];
+        assert!(FalseSharingCheck::new().verify(IR, &allowing).is_empty());
+    }
+}
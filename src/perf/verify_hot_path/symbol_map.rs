@@ -0,0 +1,152 @@
+//! Hot-function symbol map for external tools.
+//!
+//! Maps each `mark_hot!` path to its mangled symbol, the byte range of its
+//! `define` in the IR and, once an `nm` listing of the final object is
+//! merged in, its address and size. Profilers and trace decoders use it to
+//! attribute samples to the verified hot set.
+//!
+//! `render` writes one tab-separated line per function:
+//! `name symbol ir_start ir_end address size`, with `-` for unknown fields.
+
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use core::ops::Range;
+
+use super::{find_function, find_hot_entries_from_ir};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolEntry {
+    /// Path recorded by `mark_hot!`.
+    pub name: String,
+    /// Mangled (or `no_mangle`) symbol.
+    pub symbol: String,
+    /// Byte range of the `define ... { ... }` in the IR it was built from.
+    pub ir_range: Range<usize>,
+    pub address: Option<u64>,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolMap {
+    pub entries: Vec<SymbolEntry>,
+}
+
+impl SymbolMap {
+    /// Hot functions defined in `ir`; markers without a definition are skipped.
+    pub fn from_ir(ir: &str) -> Self {
+        let entries = find_hot_entries_from_ir(ir)
+            .into_iter()
+            .filter_map(|entry| {
+                let found = find_function(ir, &entry.name).ok()?;
+                Some(SymbolEntry {
+                    symbol: found.symbol.to_string(),
+                    ir_range: found.define,
+                    address: None,
+                    size: None,
+                    name: entry.name,
+                })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Fills in addresses from `nm` output (`nm -S` adds sizes). The Thumb
+    /// bit is cleared so addresses match the program counter.
+    pub fn with_nm(mut self, nm: &str) -> Self {
+        for line in nm.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (address, size, symbol) = match fields.as_slice() {
+                [address, size, _kind, symbol] => (address, Some(size), symbol),
+                [address, _kind, symbol] => (address, None, symbol),
+                _ => continue,
+            };
+            let Ok(address) = u64::from_str_radix(address, 16) else {
+                continue;
+            };
+            for entry in self.entries.iter_mut().filter(|e| e.symbol == *symbol) {
+                entry.address = Some(address & !1);
+                entry.size = size.and_then(|size| u64::from_str_radix(size, 16).ok());
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SymbolEntry> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    /// Hot function containing `address` (needs sizes from `nm -S`).
+    pub fn lookup(&self, address: u64) -> Option<&SymbolEntry> {
+        self.entries
+            .iter()
+            .find(|entry| match (entry.address, entry.size) {
+                (Some(start), Some(size)) => (start..start + size).contains(&address),
+                _ => false,
+            })
+    }
+
+    pub fn render(&self) -> String {
+        let hex = |value: Option<u64>| value.map_or("-".to_string(), |v| format!("{:#x}", v));
+        self.entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{}\t{}\t{}\t{}\t{}\t{}\n",
+                    entry.name,
+                    entry.symbol,
+                    entry.ir_range.start,
+                    entry.ir_range.end,
+                    hex(entry.address),
+                    hex(entry.size)
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"@alloc_k = private unnamed_addr constant [10 x i8] c"base::mix\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\09\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+@alloc_c = private unnamed_addr constant [9 x i8] c"base::cb\00", align 1
+@HOT_FUNC_CB = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_c, [8 x i8] c"\08\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @_ZN4base3mix17h0123456789abcdefE(i32 %a) unnamed_addr {
+start:
+  ret i32 %a
+}
+define void @cb() {
+  ret void
+}
+"#;
+
+    #[test]
+    fn test_symbol_map() {
+        let nm = "\
+0000000000011a40 0000000000000024 T _ZN4base3mix17h0123456789abcdefE
+0000000000011a65 T cb
+                 U abort
+";
+        let map = SymbolMap::from_ir(IR).with_nm(nm);
+        let mix = map.get("base::mix").unwrap();
+        assert_eq!(mix.symbol, "_ZN4base3mix17h0123456789abcdefE");
+        assert!(IR[mix.ir_range.clone()].starts_with("define i32 @_ZN4base3mix"));
+        assert!(IR[mix.ir_range.clone()].ends_with('}'));
+        assert_eq!((mix.address, mix.size), (Some(0x11a40), Some(0x24)));
+        let cb = map.get("base::cb").unwrap();
+        assert_eq!(
+            (cb.symbol.as_str(), cb.address, cb.size),
+            ("cb", Some(0x11a64), None)
+        );
+
+        assert_eq!(map.lookup(0x11a50).unwrap().name, "base::mix");
+        assert!(map.lookup(0x11a64).is_none());
+        let text = map.render();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("base::mix\t_ZN4base3mix17h0123456789abcdefE\t"));
+        assert!(text.contains("base::cb\tcb\t"));
+        assert!(text.contains("\t0x11a64\t-\n"));
+    }
+}
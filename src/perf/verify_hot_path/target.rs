@@ -0,0 +1,197 @@
+//! Target core descriptions shared by the cost model and target advisories.
+//!
+//! A `TargetProfile` says which operations the core has instructions for
+//! and roughly what they cost. `from_triple` maps common triples to a
+//! preset; anything unknown falls back to a generic 64-bit core.
+
+/// Capabilities and approximate cycle costs of a core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetProfile {
+    pub name: &'static str,
+    /// Native register width.
+    pub word_bits: u32,
+    /// Count-leading-zeros instruction.
+    pub has_clz: bool,
+    /// Count-trailing-zeros (directly or as bit-reverse + CLZ).
+    pub has_ctz: bool,
+    pub has_popcount: bool,
+    pub has_hw_divide: bool,
+    /// 32x32->64 multiply (UMULL/SMULL class).
+    pub has_long_multiply: bool,
+    pub has_fpu_f32: bool,
+    pub has_fpu_f64: bool,
+    /// Cycles for a simple ALU op, multiply, divide, load/store, taken branch.
+    pub cost_alu: u32,
+    pub cost_mul: u32,
+    pub cost_div: u32,
+    pub cost_mem: u32,
+    pub cost_branch: u32,
+    /// Cost of an operation emulated in software (libcall or sequence).
+    pub cost_emulated: u32,
+}
+
+impl TargetProfile {
+    /// ARMv6-M: no CLZ, no divider, no long multiply, no FPU.
+    pub const CORTEX_M0: Self = Self {
+        name: "cortex-m0",
+        word_bits: 32,
+        has_clz: false,
+        has_ctz: false,
+        has_popcount: false,
+        has_hw_divide: false,
+        has_long_multiply: false,
+        has_fpu_f32: false,
+        has_fpu_f64: false,
+        cost_alu: 1,
+        cost_mul: 1,
+        cost_div: 40,
+        cost_mem: 2,
+        cost_branch: 3,
+        cost_emulated: 40,
+    };
+
+    /// ARMv7-M without FPU.
+    pub const CORTEX_M3: Self = Self {
+        name: "cortex-m3",
+        has_clz: true,
+        has_ctz: true,
+        has_hw_divide: true,
+        has_long_multiply: true,
+        cost_div: 12,
+        ..Self::CORTEX_M0
+    };
+
+    /// ARMv7E-M with single-precision FPU.
+    pub const CORTEX_M4F: Self = Self {
+        name: "cortex-m4f",
+        has_fpu_f32: true,
+        ..Self::CORTEX_M3
+    };
+
+    /// ARMv7E-M with double-precision FPU.
+    pub const CORTEX_M7: Self = Self {
+        name: "cortex-m7",
+        has_fpu_f64: true,
+        cost_branch: 2,
+        ..Self::CORTEX_M4F
+    };
+
+    /// Generic out-of-order 64-bit desktop/server core.
+    pub const X86_64: Self = Self {
+        name: "x86_64",
+        word_bits: 64,
+        has_clz: true,
+        has_ctz: true,
+        has_popcount: true,
+        has_hw_divide: true,
+        has_long_multiply: true,
+        has_fpu_f32: true,
+        has_fpu_f64: true,
+        cost_alu: 1,
+        cost_mul: 3,
+        cost_div: 25,
+        cost_mem: 4,
+        cost_branch: 1,
+        cost_emulated: 20,
+    };
+
+    pub const AARCH64: Self = Self {
+        name: "aarch64",
+        cost_div: 12,
+        ..Self::X86_64
+    };
+
+    /// Preset for a target triple (`thumbv6m-none-eabi`, `x86_64-unknown-linux-gnu`, ...).
+    pub fn from_triple(triple: &str) -> Self {
+        let arch = triple.split('-').next().unwrap_or(triple);
+        if arch.starts_with("thumbv6m") {
+            Self::CORTEX_M0
+        } else if arch.starts_with("thumbv7em") {
+            if triple.ends_with("eabihf") {
+                Self::CORTEX_M4F
+            } else {
+                Self::CORTEX_M3
+            }
+        } else if arch.starts_with("thumbv7m") || arch.starts_with("thumbv8m.base") {
+            Self::CORTEX_M3
+        } else if arch.starts_with("thumbv8m.main") {
+            if triple.ends_with("eabihf") {
+                Self::CORTEX_M4F
+            } else {
+                Self::CORTEX_M3
+            }
+        } else if arch.starts_with("aarch64") || arch.starts_with("arm64") {
+            Self::AARCH64
+        } else {
+            Self::X86_64
+        }
+    }
+
+    /// Preset by name (`cortex-m0`, `cortex-m3`, `cortex-m4f`, `cortex-m7`,
+    /// `x86_64`, `aarch64`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::CORTEX_M0,
+            Self::CORTEX_M3,
+            Self::CORTEX_M4F,
+            Self::CORTEX_M7,
+            Self::X86_64,
+            Self::AARCH64,
+        ]
+        .into_iter()
+        .find(|preset| preset.name == name)
+    }
+
+    /// Preset for the module's `target triple = "..."` line, if present.
+    pub fn from_ir(ir: &str) -> Option<Self> {
+        ir.lines()
+            .find_map(|line| line.strip_prefix("target triple = \""))
+            .and_then(|rest| rest.split('"').next())
+            .map(Self::from_triple)
+    }
+
+    /// The host's profile.
+    pub fn host() -> Self {
+        Self::from_triple(std::env::consts::ARCH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_triple() {
+        assert_eq!(
+            TargetProfile::from_triple("thumbv6m-none-eabi"),
+            TargetProfile::CORTEX_M0
+        );
+        assert_eq!(
+            TargetProfile::from_triple("thumbv7em-none-eabihf"),
+            TargetProfile::CORTEX_M4F
+        );
+        assert_eq!(
+            TargetProfile::from_triple("thumbv7em-none-eabi").name,
+            "cortex-m3"
+        );
+        assert_eq!(
+            TargetProfile::from_triple("aarch64-unknown-linux-gnu").name,
+            "aarch64"
+        );
+        assert_eq!(
+            TargetProfile::from_triple("riscv64gc-unknown-linux-gnu").word_bits,
+            64
+        );
+        assert_eq!(
+            TargetProfile::from_ir("target triple = \"thumbv6m-none-eabi\"\n"),
+            Some(TargetProfile::CORTEX_M0)
+        );
+        assert_eq!(
+            TargetProfile::from_name("cortex-m7"),
+            Some(TargetProfile::CORTEX_M7)
+        );
+        assert_eq!(TargetProfile::from_name("z80"), None);
+        let m0 = TargetProfile::from_name("cortex-m0").unwrap();
+        assert!(!m0.has_clz && !m0.has_hw_divide && m0.word_bits == 32);
+    }
+}
@@ -0,0 +1,328 @@
+//! History of hot-function metrics across runs.
+//!
+//! Every run can append one `TrendRecord` per hot function to a CSV file:
+//! revision, unix timestamp, instruction count and cycle estimate (see
+//! `cost`) and the number of errors and warnings. The file is plain CSV with
+//! a header line, so spreadsheets and plotting scripts read it directly and
+//! a regression shows up as a step in the chart at the commit that caused it.
+//!
+//! `regressions` turns the history into a gate: it compares a run against
+//! the newest accepted record of each function and reports any function
+//! whose cycle estimate grew by more than a percentage, whether or not a
+//! check fired.
+
+use std::io::{self, Write};
+use std::path::Path;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use super::target::TargetProfile;
+use super::{Severity, Violation, cost_report};
+
+pub const HEADER: &str = "revision,timestamp,function,instructions,cycles,errors,warnings";
+
+/// One function in one run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrendRecord {
+    /// Git commit of the run, `unknown` outside a repository.
+    pub revision: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub function: String,
+    pub instructions: usize,
+    pub cycles: u64,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+impl TrendRecord {
+    /// Records for every hot function in `ir`, with the counts taken from
+    /// `violations` (the result of verifying the same IR).
+    pub fn collect(
+        ir: &str,
+        violations: &[Violation],
+        target: &TargetProfile,
+        revision: &str,
+        timestamp: u64,
+    ) -> Vec<Self> {
+        cost_report(ir, target)
+            .into_iter()
+            .map(|cost| {
+                let count = |counted: fn(Severity) -> bool| {
+                    violations
+                        .iter()
+                        .filter(|v| v.function == cost.function && counted(v.severity))
+                        .count()
+                };
+                Self {
+                    revision: revision.to_string(),
+                    timestamp,
+                    errors: count(Severity::is_failure),
+                    warnings: count(|severity| severity == Severity::Warning),
+                    function: cost.function,
+                    instructions: cost.instructions,
+                    cycles: cost.cycles,
+                }
+            })
+            .collect()
+    }
+
+    /// CSV line without the newline.
+    pub fn render(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}",
+            quote(&self.revision),
+            self.timestamp,
+            quote(&self.function),
+            self.instructions,
+            self.cycles,
+            self.errors,
+            self.warnings
+        )
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields = split(line)?;
+        let [
+            revision,
+            timestamp,
+            function,
+            instructions,
+            cycles,
+            errors,
+            warnings,
+        ] = <[String; 7]>::try_from(fields).ok()?;
+        Some(Self {
+            revision,
+            timestamp: timestamp.parse().ok()?,
+            function,
+            instructions: instructions.parse().ok()?,
+            cycles: cycles.parse().ok()?,
+            errors: errors.parse().ok()?,
+            warnings: warnings.parse().ok()?,
+        })
+    }
+}
+
+/// A function whose cycle estimate grew beyond the allowed percentage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub function: String,
+    /// Revision of the baseline record.
+    pub baseline_revision: String,
+    pub baseline_cycles: u64,
+    pub cycles: u64,
+    /// Growth over the baseline in percent.
+    pub growth: f64,
+}
+
+impl core::fmt::Display for Regression {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}: {} -> {} cycles (+{:.1}% since {})",
+            self.function, self.baseline_cycles, self.cycles, self.growth, self.baseline_revision
+        )
+    }
+}
+
+/// Newest record of each function in `history` (later lines win).
+pub fn baseline(history: &[TrendRecord]) -> Vec<&TrendRecord> {
+    let mut latest: Vec<&TrendRecord> = Vec::new();
+    for record in history {
+        match latest.iter_mut().find(|r| r.function == record.function) {
+            Some(slot) if slot.timestamp <= record.timestamp => *slot = record,
+            Some(_) => {}
+            None => latest.push(record),
+        }
+    }
+    latest
+}
+
+/// Functions of `current` whose cycles exceed their `baseline` record by
+/// more than `max_growth` percent. Functions without a baseline are new and
+/// pass.
+pub fn regressions(
+    baseline: &[&TrendRecord],
+    current: &[TrendRecord],
+    max_growth: f64,
+) -> Vec<Regression> {
+    current
+        .iter()
+        .filter_map(|record| {
+            let base = baseline.iter().find(|b| b.function == record.function)?;
+            let growth = if base.cycles == 0 {
+                if record.cycles == 0 {
+                    0.0
+                } else {
+                    f64::INFINITY
+                }
+            } else {
+                (record.cycles as f64 - base.cycles as f64) * 100.0 / base.cycles as f64
+            };
+            (growth > max_growth).then(|| Regression {
+                function: record.function.clone(),
+                baseline_revision: base.revision.clone(),
+                baseline_cycles: base.cycles,
+                cycles: record.cycles,
+                growth,
+            })
+        })
+        .collect()
+}
+
+/// Quotes a field containing a comma, quote or newline (demangled generic
+/// paths such as `f<u8, u16>` have commas).
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn split(line: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (_, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+/// Appends `records` to the CSV at `path`, writing the header first if the
+/// file is new or empty.
+pub fn append(path: &Path, records: &[TrendRecord]) -> io::Result<()> {
+    let fresh = std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut text = String::new();
+    if fresh {
+        text.push_str(HEADER);
+        text.push('\n');
+    }
+    for record in records {
+        text.push_str(&record.render());
+        text.push('\n');
+    }
+    file.write_all(text.as_bytes())
+}
+
+/// Every record in the CSV at `path`; lines that do not parse (the header,
+/// hand edits) are skipped.
+pub fn load(path: &Path) -> io::Result<Vec<TrendRecord>> {
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .filter_map(TrendRecord::parse)
+        .collect())
+}
+
+/// `git rev-parse HEAD` in the current directory.
+pub fn git_revision() -> Option<String> {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    let revision = String::from_utf8(output.stdout).ok()?;
+    (output.status.success() && !revision.trim().is_empty()).then(|| revision.trim().to_string())
+}
+
+pub fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IR: &str = r#"@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{ ptr, [8 x i8] }> <{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }>, section ".hot_funcs", align 8
+define i32 @k(i32 %a) {
+  %1 = udiv i32 %a, 3
+  ret i32 %1
+}
+"#;
+
+    #[test]
+    fn test_collect_and_round_trip() {
+        let violations =
+            crate::perf::verify_hot_path::HotPathVerifier::default().verify_entries(IR);
+        let records = TrendRecord::collect(IR, &violations, &TargetProfile::X86_64, "abc123", 7);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].function, "k");
+        assert_eq!(records[0].instructions, 2);
+        assert_eq!((records[0].errors, records[0].warnings), (0, 1));
+
+        let generic = TrendRecord {
+            function: "f<u8, \"x\">".to_string(),
+            ..records[0].clone()
+        };
+        assert_eq!(TrendRecord::parse(&generic.render()), Some(generic));
+        assert_eq!(TrendRecord::parse(HEADER), None);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let record = |revision: &str, timestamp, function: &str, cycles| TrendRecord {
+            revision: revision.to_string(),
+            timestamp,
+            function: function.to_string(),
+            instructions: 0,
+            cycles,
+            errors: 0,
+            warnings: 0,
+        };
+        let history = [
+            record("a", 1, "f", 100),
+            record("a", 1, "g", 50),
+            record("b", 2, "f", 200),
+        ];
+        let base = baseline(&history);
+        assert_eq!(base.len(), 2);
+        assert_eq!(base[0].revision, "b");
+
+        let current = [
+            record("c", 3, "f", 210),
+            record("c", 3, "g", 60),
+            record("c", 3, "h", 999),
+        ];
+        let found = regressions(&base, &current, 10.0);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].function, "g");
+        assert_eq!(found[0].growth, 20.0);
+        assert_eq!(found[0].to_string(), "g: 50 -> 60 cycles (+20.0% since a)");
+        assert!(regressions(&base, &current, 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_append_writes_header_once() {
+        let path = std::env::temp_dir().join(format!("hot-trend-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let record = TrendRecord::collect(IR, &[], &TargetProfile::X86_64, "a", 1).remove(0);
+        append(&path, core::slice::from_ref(&record)).unwrap();
+        append(&path, core::slice::from_ref(&record)).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.matches(HEADER).count(), 1);
+        assert_eq!(load(&path).unwrap(), [record.clone(), record]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,312 @@
+//! WebAssembly backend: the hot path checks for `wasm32` builds.
+//!
+//! Works on the text format (`.wat`), as printed by `wasm2wat` or
+//! `wasm-tools print` from the linked `.wasm`. Entries come from the IR of
+//! the same build, as for the assembly backend. What matters on Wasm is
+//! different from native code:
+//!
+//! * `host_call`: a call to an imported function leaves the module; the
+//!   embedder's implementation (JavaScript, a plugin host) has no bound.
+//! * `allocation`: `memory.grow` is how the allocator gets pages; it can
+//!   fail, and some engines copy the whole linear memory.
+//! * `indirection`: `call_indirect` and `call_ref` go through a table with a
+//!   signature check on every call.
+//!
+//! The names match the IR checks where the hazard is the same, so an
+//! `allow(..)` list on `mark_hot!` carries over.
+
+use std::string::ToString;
+use std::vec::Vec;
+
+use super::{HotEntry, Severity, Violation, demangle};
+
+/// One function of a module, defined or imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WasmFunction<'a> {
+    /// Position in the function index space (imports first).
+    pub index: usize,
+    /// `$name` without the `$`, if the module has names.
+    pub id: Option<&'a str>,
+    /// `(module, field)` of an imported function.
+    pub import: Option<(&'a str, &'a str)>,
+    pub exports: Vec<&'a str>,
+    /// Text of the `(func ..)` form.
+    pub text: &'a str,
+}
+
+impl WasmFunction<'_> {
+    /// Whether the function is `name`, a `mark_hot!` path or a plain symbol.
+    pub fn is(&self, name: &str) -> bool {
+        let last = name.rsplit("::").next().unwrap_or(name);
+        self.id.is_some_and(|id| {
+            id == name || demangle(id) == name || strip_hash(id) == name || id == last
+        }) || self.exports.iter().any(|e| *e == name || *e == last)
+    }
+}
+
+/// `a::b::h0123456789abcdef` without the hash segment.
+fn strip_hash(id: &str) -> &str {
+    match id.rsplit_once("::h") {
+        Some((path, hash)) if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            path
+        }
+        _ => id,
+    }
+}
+
+/// Top-level forms inside `(module ..)`, skipping comments and strings.
+fn forms(wat: &str) -> Vec<&str> {
+    let bytes = wat.as_bytes();
+    let mut forms = Vec::new();
+    let (mut depth, mut start, mut i) = (0usize, 0, 0);
+    while i < bytes.len() {
+        match bytes[i] {
+            b';' if bytes.get(i + 1) == Some(&b';') => {
+                i = wat[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                continue;
+            }
+            b'(' if bytes.get(i + 1) == Some(&b';') => {
+                i = wat[i..].find(";)").map_or(bytes.len(), |n| i + n + 2);
+                continue;
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'(' => {
+                depth += 1;
+                if depth == 2 {
+                    start = i;
+                }
+            }
+            b')' => {
+                if depth == 2 {
+                    forms.push(&wat[start..=i]);
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    forms
+}
+
+/// Quoted strings of `text`, in order.
+fn strings(text: &str) -> Vec<&str> {
+    text.split('"').skip(1).step_by(2).collect()
+}
+
+/// `$id` following `keyword` in `text`.
+fn id_after<'a>(text: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = text[text.find(keyword)? + keyword.len()..].trim_start();
+    let id = rest.strip_prefix('$')?;
+    let id = match id.strip_prefix('"') {
+        Some(quoted) => &quoted[..quoted.find('"')?],
+        None => &id[..id.find(|c: char| c.is_whitespace() || c == ')')?],
+    };
+    Some(id)
+}
+
+/// Every function of the module in index order.
+pub fn functions(wat: &str) -> Vec<WasmFunction<'_>> {
+    let forms = forms(wat);
+    let mut imported = Vec::new();
+    let mut defined = Vec::new();
+    for form in &forms {
+        if form.starts_with("(import") && form.contains("(func") {
+            let names = strings(form);
+            imported.push((form, names.first().copied().zip(names.get(1).copied())));
+        } else if form.starts_with("(func") {
+            // Inline `(func $f (import "m" "n") ..)`.
+            let head = &form[..form.find("(param").unwrap_or(form.len())];
+            match head.find("(import") {
+                Some(at) => {
+                    let names = strings(&head[at..]);
+                    imported.push((form, names.first().copied().zip(names.get(1).copied())));
+                }
+                None => defined.push(*form),
+            }
+        }
+    }
+    let mut functions: Vec<WasmFunction> = imported
+        .into_iter()
+        .map(|(form, import)| (*form, import))
+        .chain(defined.into_iter().map(|form| (form, None)))
+        .enumerate()
+        .map(|(index, (text, import))| WasmFunction {
+            index,
+            id: id_after(text, "(func"),
+            import,
+            exports: Vec::new(),
+            text,
+        })
+        .collect();
+    for form in &forms {
+        if let Some(rest) = form.strip_prefix("(export")
+            && let Some(name) = strings(rest).first()
+            && let Some(at) = rest.find("(func")
+        {
+            let target = rest[at + 5..].trim_start().trim_end_matches(')').trim();
+            if let Some(function) = resolve(&mut functions, target) {
+                function.exports.push(name);
+            }
+        }
+    }
+    for function in &mut functions {
+        if let Some(at) = function.text.find("(export") {
+            function
+                .exports
+                .extend(strings(&function.text[at..]).first());
+        }
+    }
+    functions
+}
+
+/// Function named by a `call`/`export` operand: `$id` or an index.
+fn resolve<'a, 'b>(
+    functions: &'b mut [WasmFunction<'a>],
+    target: &str,
+) -> Option<&'b mut WasmFunction<'a>> {
+    match target.strip_prefix('$') {
+        Some(id) => {
+            let id = id.trim_matches('"');
+            functions.iter_mut().find(|f| f.id == Some(id))
+        }
+        None => functions.get_mut(target.parse::<usize>().ok()?),
+    }
+}
+
+/// Text of the function `name` (see `WasmFunction::is`).
+pub fn function_body<'a>(wat: &'a str, name: &str) -> Option<&'a str> {
+    functions(wat)
+        .into_iter()
+        .find(|f| f.import.is_none() && f.is(name))
+        .map(|f| f.text)
+}
+
+/// Violations in the bodies of `entries`. Functions missing from the module
+/// are skipped: the IR checks report them.
+pub fn verify_wasm(wat: &str, entries: &[HotEntry]) -> Vec<Violation> {
+    let functions = functions(wat);
+    let mut violations = Vec::new();
+    for entry in entries {
+        let Some(function) = functions
+            .iter()
+            .find(|f| f.import.is_none() && f.is(&entry.name))
+        else {
+            continue;
+        };
+        let allowed = |check: &str| entry.allow.iter().any(|a| a == check);
+        let mut found = Vec::new();
+        for line in function.text.lines().map(str::trim) {
+            let mut words = line.trim_start_matches('(').split_whitespace();
+            let (op, operand) = (words.next().unwrap_or(""), words.next().unwrap_or(""));
+            match op {
+                "call" | "return_call" => {
+                    let callee = functions.iter().find(|f| match operand.strip_prefix('$') {
+                        Some(id) => f.id == Some(id.trim_end_matches(')').trim_matches('"')),
+                        None => operand.trim_end_matches(')').parse() == Ok(f.index),
+                    });
+                    if let Some((module, field)) = callee.and_then(|f| f.import) {
+                        found.push((
+                            "host_call",
+                            format!("calls imported function {}.{}", module, field),
+                        ));
+                    }
+                }
+                "memory.grow" => {
+                    found.push(("allocation", "grows linear memory".to_string()));
+                }
+                "call_indirect" | "return_call_indirect" | "call_ref" | "return_call_ref" => {
+                    found.push(("indirection", format!("{} through a table", op)));
+                }
+                _ => {}
+            }
+        }
+        for (check, message) in found {
+            if !allowed(check) {
+                violations.push(Violation {
+                    function: entry.name.clone(),
+                    check: check.to_string(),
+                    severity: Severity::Error,
+                    message,
+                    location: None,
+                });
+            }
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAT: &str = r#"(module
+  (type (;0;) (func (param i32) (result i32)))
+  (import "env" "host_log" (func $host_log (type 0)))
+  (import "env" "now" (func (;1;) (type 0)))
+  (func $dsp::process::h0123456789abcdef (type 0) (param i32) (result i32)
+    local.get 0
+    call $host_log
+    call 1
+    call $helper
+    i32.const 1
+    memory.grow
+    call_indirect (type 0)) ;; "(func $fake)"
+  (func $helper (type 0) (param i32) (result i32)
+    local.get 0)
+  (func (;4;) (type 0) (param i32) (result i32)
+    local.get 0
+    i32.const 2
+    i32.mul)
+  (table (;0;) 1 1 funcref)
+  (memory (;0;) 17)
+  (export "gain" (func 4)))
+"#;
+
+    #[test]
+    fn test_functions() {
+        let functions = functions(WAT);
+        assert_eq!(functions.len(), 5);
+        assert_eq!(functions[0].import, Some(("env", "host_log")));
+        assert_eq!(functions[1].id, None);
+        assert_eq!(functions[4].exports, ["gain"]);
+        assert!(functions[2].is("dsp::process"));
+        assert!(function_body(WAT, "gain").unwrap().contains("i32.mul"));
+        assert!(function_body(WAT, "host_log").is_none());
+    }
+
+    #[test]
+    fn test_verify_wasm() {
+        let entries = [
+            HotEntry::parse("dsp::process"),
+            HotEntry::parse("gain"),
+            HotEntry::parse("absent"),
+        ];
+        let violations = verify_wasm(WAT, &entries);
+        let found: Vec<(&str, &str)> = violations
+            .iter()
+            .map(|v| (v.check.as_str(), v.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("host_call", "calls imported function env.host_log"),
+                ("host_call", "calls imported function env.now"),
+                ("allocation", "grows linear memory"),
+                ("indirection", "call_indirect through a table"),
+            ]
+        );
+
+        let allowing = [HotEntry::parse("dsp::process;allow=host_call,indirection")];
+        let checks: Vec<String> = verify_wasm(WAT, &allowing)
+            .into_iter()
+            .map(|v| v.check)
+            .collect();
+        assert_eq!(checks, ["allocation"]);
+    }
+}
@@ -0,0 +1,235 @@
+//! Re-verifying IR files as they are rewritten.
+//!
+//! `IrWatcher` polls modification times (no platform notification API, so it
+//! works the same everywhere) of the given `.ll` files, or of every `.ll`
+//! file in the given directories. Changed files are re-verified through a
+//! `VerifyCache`, so only functions whose IR actually changed are checked
+//! again, and the result is reported as a delta against the previous run.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::string::String;
+use std::time::SystemTime;
+use std::vec::Vec;
+
+use super::{HotPathVerifier, Severity, VerifyCache, Violation};
+
+/// Change in one file's violations since the last poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delta {
+    pub file: PathBuf,
+    pub added: Vec<Violation>,
+    pub resolved: Vec<Violation>,
+    /// Violations now present in the file.
+    pub total: usize,
+}
+
+impl Delta {
+    /// One line per change: `+ error[check] function: message`, `- ...` for
+    /// resolved ones, then a summary line.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for (sign, violations) in [('+', &self.added), ('-', &self.resolved)] {
+            for v in violations {
+                out.push_str(&format!(
+                    "{} {}[{}] {}: {}\n",
+                    sign,
+                    v.severity.name(),
+                    v.check,
+                    v.function,
+                    v.message
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "{}: +{} -{} ({} total)\n",
+            self.file.display(),
+            self.added.len(),
+            self.resolved.len(),
+            self.total
+        ));
+        out
+    }
+}
+
+/// Identity of a violation across runs; locations shift with unrelated edits.
+fn key(v: &Violation) -> (&str, &str, Severity, &str) {
+    (&v.function, &v.check, v.severity, &v.message)
+}
+
+/// `after - before` as a multiset on `key`.
+fn difference(after: &[Violation], before: &[Violation]) -> Vec<Violation> {
+    let mut remaining: HashMap<_, usize> = HashMap::new();
+    for v in before {
+        *remaining.entry(key(v)).or_default() += 1;
+    }
+    let mut out = Vec::new();
+    for v in after {
+        match remaining.get_mut(&key(v)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => out.push(v.clone()),
+        }
+    }
+    out
+}
+
+/// Polls IR files and reports what changed.
+pub struct IrWatcher {
+    paths: Vec<PathBuf>,
+    seen: HashMap<PathBuf, SystemTime>,
+    last: HashMap<PathBuf, Vec<Violation>>,
+    cache: VerifyCache,
+}
+
+impl IrWatcher {
+    /// Watches `.ll` files and directories (non-recursively) of `.ll` files.
+    pub fn new(paths: Vec<PathBuf>, cache: VerifyCache) -> Self {
+        Self {
+            paths,
+            seen: HashMap::new(),
+            last: HashMap::new(),
+            cache,
+        }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for path in &self.paths {
+            if path.is_dir() {
+                if let Ok(dir) = std::fs::read_dir(path) {
+                    files.extend(
+                        dir.filter_map(|e| e.ok().map(|e| e.path()))
+                            .filter(|p| p.extension().is_some_and(|ext| ext == "ll")),
+                    );
+                }
+            } else {
+                files.push(path.clone());
+            }
+        }
+        files.sort();
+        files
+    }
+
+    /// Re-verifies files modified since the last poll (all files on the
+    /// first poll) and returns a delta for each. Unreadable files are skipped.
+    pub fn poll(&mut self, verifier: &HotPathVerifier) -> Vec<Delta> {
+        let mut deltas = Vec::new();
+        for file in self.files() {
+            let Ok(modified) = std::fs::metadata(&file).and_then(|m| m.modified()) else {
+                continue;
+            };
+            if self.seen.get(&file) == Some(&modified) {
+                continue;
+            }
+            let Ok(ir) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            self.seen.insert(file.clone(), modified);
+            let violations = verifier.verify_entries_cached(&ir, &mut self.cache);
+            let before = self.last.remove(&file).unwrap_or_default();
+            deltas.push(Delta {
+                added: difference(&violations, &before),
+                resolved: difference(&before, &violations),
+                total: violations.len(),
+                file: file.clone(),
+            });
+            self.last.insert(file, violations);
+        }
+        deltas
+    }
+
+    pub fn cache(&self) -> &VerifyCache {
+        &self.cache
+    }
+
+    /// Polls every `interval` forever, passing each non-empty batch of deltas to `report`.
+    pub fn run(
+        &mut self,
+        verifier: &HotPathVerifier,
+        interval: std::time::Duration,
+        mut report: impl FnMut(&[Delta]),
+    ) -> ! {
+        loop {
+            let deltas = self.poll(verifier);
+            if !deltas.is_empty() {
+                report(&deltas);
+            }
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// Whether `path` is being watched directly or through its directory.
+    pub fn watches(&self, path: &Path) -> bool {
+        self.paths
+            .iter()
+            .any(|p| p == path || path.parent() == Some(p.as_path()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ir(body: &str) -> String {
+        format!(
+            r#"@alloc_k = private unnamed_addr constant [2 x i8] c"k\00", align 1
+@HOT_FUNC = constant <{{ ptr, [8 x i8] }}> <{{ ptr @alloc_k, [8 x i8] c"\01\00\00\00\00\00\00\00" }}>, section ".hot_funcs", align 8
+define i32 @k(i32 %a) {{
+{}
+  ret i32 %a
+}}
+"#,
+            body
+        )
+    }
+
+    #[test]
+    fn test_poll_reports_deltas() {
+        let dir = std::env::temp_dir().join(format!("hot-verify-watch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("k.ll");
+        std::fs::write(&file, ir("  %1 = udiv i32 %a, 3")).unwrap();
+
+        let verifier = HotPathVerifier::default();
+        let mut watcher = IrWatcher::new(std::vec![dir.clone()], VerifyCache::new());
+        assert!(watcher.watches(&file));
+        let first = watcher.poll(&verifier);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].added[0].check, "division");
+        assert!(watcher.poll(&verifier).is_empty());
+
+        std::fs::write(&file, ir("  %1 = call i32 @g(i32 %a)")).unwrap();
+        // Force a distinct mtime on filesystems with coarse timestamps.
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let second = watcher.poll(&verifier);
+        assert_eq!(second[0].added[0].check, "function_call");
+        assert_eq!(second[0].resolved[0].check, "division");
+        let text = second[0].render();
+        assert!(text.starts_with("+ error[function_call] k:"), "{}", text);
+        assert!(text.contains("\n- warning[division] k:"));
+        assert!(text.ends_with("+1 -1 (1 total)\n"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_difference_is_multiset() {
+        let v = |check: &str| Violation {
+            function: "f".into(),
+            check: check.into(),
+            severity: Severity::Warning,
+            message: "m".into(),
+            location: None,
+        };
+        let before = [v("a"), v("a")];
+        let after = [v("a"), v("a"), v("a"), v("b")];
+        let added = difference(&after, &before);
+        assert_eq!(added.len(), 2);
+        assert!(difference(&before, &after).is_empty());
+    }
+}
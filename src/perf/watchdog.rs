@@ -0,0 +1,147 @@
+//! Software watchdog for hot loops.
+//!
+//! Hot code calls `checkpoint!(watchdog, id)`, which stores the current cycle
+//! count into slot `id` with a single relaxed store. The non-RT side polls the
+//! slots and reports any armed slot whose last checkpoint is older than the
+//! timeout. Timestamps are truncated to `usize` and compared with wrapping
+//! arithmetic, so timeouts must stay below `usize::MAX / 2` cycles.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::perf::clock;
+
+/// Slot value meaning "never checked in".
+const IDLE: usize = 0;
+
+/// Checkpoint slots for up to `N` monitored loops.
+pub struct Watchdog<const N: usize> {
+    slots: [AtomicUsize; N],
+}
+
+/// A loop that has not checked in within the timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stall {
+    pub id: usize,
+    /// Cycles since the last checkpoint.
+    pub age: usize,
+}
+
+impl<const N: usize> Watchdog<N> {
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { AtomicUsize::new(IDLE) }; N],
+        }
+    }
+
+    /// Records that loop `id` is alive at `now`. Out-of-range ids are ignored.
+    #[inline(always)]
+    pub fn checkpoint_at(&self, id: usize, now: u64) {
+        if let Some(slot) = self.slots.get(id) {
+            // Avoid storing the IDLE marker when the counter truncates to zero.
+            slot.store((now as usize) | 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records that loop `id` is alive now.
+    #[inline(always)]
+    pub fn checkpoint(&self, id: usize) {
+        self.checkpoint_at(id, clock::cycles());
+    }
+
+    /// Stops monitoring loop `id` until its next checkpoint.
+    pub fn disarm(&self, id: usize) {
+        if let Some(slot) = self.slots.get(id) {
+            slot.store(IDLE, Ordering::Relaxed);
+        }
+    }
+
+    /// Cycles since loop `id` last checked in, or `None` if it is idle.
+    pub fn age(&self, id: usize, now: u64) -> Option<usize> {
+        let last = self.slots.get(id)?.load(Ordering::Relaxed);
+        if last == IDLE {
+            None
+        } else {
+            Some((now as usize).wrapping_sub(last))
+        }
+    }
+
+    /// Calls `on_stall` for every armed loop older than `timeout` cycles; returns the count.
+    pub fn poll(&self, now: u64, timeout: usize, mut on_stall: impl FnMut(Stall)) -> usize {
+        let mut stalled = 0;
+        for id in 0..N {
+            if let Some(age) = self.age(id, now)
+                && age > timeout
+            {
+                on_stall(Stall { id, age });
+                stalled += 1;
+            }
+        }
+        stalled
+    }
+
+    /// Returns the first stalled loop, if any.
+    pub fn first_stall(&self, now: u64, timeout: usize) -> Option<Stall> {
+        (0..N).find_map(|id| {
+            self.age(id, now)
+                .filter(|age| *age > timeout)
+                .map(|age| Stall { id, age })
+        })
+    }
+}
+
+impl<const N: usize> Default for Watchdog<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_slots_never_stall() {
+        let watchdog: Watchdog<4> = Watchdog::new();
+        assert_eq!(watchdog.poll(1_000_000, 10, |_| {}), 0);
+        assert_eq!(watchdog.age(0, 100), None);
+    }
+
+    #[test]
+    fn test_detects_stall() {
+        let watchdog: Watchdog<2> = Watchdog::new();
+        watchdog.checkpoint_at(0, 1001);
+        watchdog.checkpoint_at(1, 1901);
+        let mut stalls = [None; 2];
+        let count = watchdog.poll(2001, 500, |stall| stalls[stall.id] = Some(stall.age));
+        assert_eq!(count, 1);
+        assert_eq!(stalls, [Some(1000), None]);
+        assert_eq!(
+            watchdog.first_stall(2001, 500),
+            Some(Stall { id: 0, age: 1000 })
+        );
+    }
+
+    #[test]
+    fn test_disarm_and_out_of_range() {
+        let watchdog: Watchdog<1> = Watchdog::new();
+        watchdog.checkpoint_at(0, 11);
+        watchdog.checkpoint_at(5, 11);
+        watchdog.disarm(0);
+        assert_eq!(watchdog.first_stall(1_000, 10), None);
+    }
+
+    #[test]
+    fn test_wrapping_age() {
+        let watchdog: Watchdog<1> = Watchdog::new();
+        watchdog.checkpoint_at(0, usize::MAX as u64);
+        assert_eq!(watchdog.age(0, 9), Some(10));
+    }
+
+    #[test]
+    fn test_checkpoint_macro() {
+        static WATCHDOG: Watchdog<2> = Watchdog::new();
+        crate::checkpoint!(WATCHDOG, 1);
+        assert!(WATCHDOG.age(1, crate::perf::clock::cycles()).is_some());
+        assert_eq!(WATCHDOG.age(0, 0), None);
+    }
+}
@@ -0,0 +1,350 @@
+//! Portable `f32` vectors for DSP kernels.
+//!
+//! `F32x4`/`F32x8` expose the handful of lane-wise operations hot loops need
+//! (arithmetic, `mul_add`, min/max, compare + select, reductions). On stable
+//! they are aligned arrays processed with fixed-count loops, which LLVM turns
+//! into vector instructions predictably; building with
+//! `RUSTFLAGS="--cfg base_portable_simd"` on nightly backs them with
+//! `core::simd` instead. The API is identical either way.
+//!
+//! `mul_add` is a separate multiply and add in both backends: fused FMA
+//! needs libm on targets without the instruction.
+
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[cfg(base_portable_simd)]
+use core::simd::{
+    Mask, Select, Simd,
+    cmp::{SimdPartialEq, SimdPartialOrd},
+    num::SimdFloat,
+};
+
+macro_rules! simd_vector {
+    ($name:ident, $mask:ident, $lanes:literal, $align:literal) => {
+        #[doc = concat!("Lane mask produced by `", stringify!($name), "` comparisons.")]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[cfg(not(base_portable_simd))]
+        pub struct $mask([bool; $lanes]);
+
+        #[doc = concat!("Lane mask produced by `", stringify!($name), "` comparisons.")]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[cfg(base_portable_simd)]
+        pub struct $mask(Mask<i32, $lanes>);
+
+        impl $mask {
+            #[inline(always)]
+            pub fn from_array(lanes: [bool; $lanes]) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return Self(lanes);
+                #[cfg(base_portable_simd)]
+                return Self(Mask::from_array(lanes));
+            }
+
+            #[inline(always)]
+            pub fn to_array(self) -> [bool; $lanes] {
+                #[cfg(not(base_portable_simd))]
+                return self.0;
+                #[cfg(base_portable_simd)]
+                return self.0.to_array();
+            }
+
+            #[inline(always)]
+            pub fn any(self) -> bool {
+                #[cfg(not(base_portable_simd))]
+                return self.0.iter().any(|&lane| lane);
+                #[cfg(base_portable_simd)]
+                return self.0.any();
+            }
+
+            #[inline(always)]
+            pub fn all(self) -> bool {
+                #[cfg(not(base_portable_simd))]
+                return self.0.iter().all(|&lane| lane);
+                #[cfg(base_portable_simd)]
+                return self.0.all();
+            }
+
+            /// Lane-wise `if mask { if_true } else { if_false }`.
+            #[inline(always)]
+            pub fn select(self, if_true: $name, if_false: $name) -> $name {
+                #[cfg(not(base_portable_simd))]
+                return $name(core::array::from_fn(|i| {
+                    if self.0[i] { if_true.0[i] } else { if_false.0[i] }
+                }));
+                #[cfg(base_portable_simd)]
+                return $name(self.0.select(if_true.0, if_false.0));
+            }
+        }
+
+        #[doc = concat!(stringify!($lanes), " `f32` lanes, ", stringify!($align), "-byte aligned.")]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[cfg(not(base_portable_simd))]
+        #[repr(C, align($align))]
+        pub struct $name([f32; $lanes]);
+
+        #[doc = concat!(stringify!($lanes), " `f32` lanes, ", stringify!($align), "-byte aligned.")]
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[cfg(base_portable_simd)]
+        #[repr(transparent)]
+        pub struct $name(Simd<f32, $lanes>);
+
+        impl $name {
+            pub const LANES: usize = $lanes;
+
+            #[inline(always)]
+            #[cfg_attr(base_portable_simd, allow(dead_code))]
+            fn map2(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+                let (a, b) = (self.to_array(), other.to_array());
+                Self::from_array(core::array::from_fn(|i| f(a[i], b[i])))
+            }
+
+            #[inline(always)]
+            #[cfg_attr(base_portable_simd, allow(dead_code))]
+            fn compare(self, other: Self, f: impl Fn(f32, f32) -> bool) -> $mask {
+                let (a, b) = (self.to_array(), other.to_array());
+                $mask::from_array(core::array::from_fn(|i| f(a[i], b[i])))
+            }
+
+            #[inline(always)]
+            pub fn splat(value: f32) -> Self {
+                Self::from_array([value; $lanes])
+            }
+
+            #[inline(always)]
+            pub fn from_array(lanes: [f32; $lanes]) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return Self(lanes);
+                #[cfg(base_portable_simd)]
+                return Self(Simd::from_array(lanes));
+            }
+
+            #[inline(always)]
+            pub fn to_array(self) -> [f32; $lanes] {
+                #[cfg(not(base_portable_simd))]
+                return self.0;
+                #[cfg(base_portable_simd)]
+                return self.0.to_array();
+            }
+
+            #[inline(always)]
+            pub fn load(lanes: &[f32; $lanes]) -> Self {
+                Self::from_array(*lanes)
+            }
+
+            #[inline(always)]
+            pub fn store(self, out: &mut [f32; $lanes]) {
+                *out = self.to_array();
+            }
+
+            /// `self * b + c`, unfused.
+            #[inline(always)]
+            pub fn mul_add(self, b: Self, c: Self) -> Self {
+                self * b + c
+            }
+
+            #[inline(always)]
+            pub fn min(self, other: Self) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return self.map2(other, |a, b| if b < a { b } else { a });
+                #[cfg(base_portable_simd)]
+                return Self(self.0.simd_min(other.0));
+            }
+
+            #[inline(always)]
+            pub fn max(self, other: Self) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return self.map2(other, |a, b| if b > a { b } else { a });
+                #[cfg(base_portable_simd)]
+                return Self(self.0.simd_max(other.0));
+            }
+
+            #[inline(always)]
+            pub fn clamp(self, lo: Self, hi: Self) -> Self {
+                self.max(lo).min(hi)
+            }
+
+            #[inline(always)]
+            pub fn abs(self) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return Self(self.0.map(|a| f32::from_bits(a.to_bits() & 0x7FFF_FFFF)));
+                #[cfg(base_portable_simd)]
+                return Self(self.0.abs());
+            }
+
+            #[inline(always)]
+            pub fn lt(self, other: Self) -> $mask {
+                #[cfg(not(base_portable_simd))]
+                return self.compare(other, |a, b| a < b);
+                #[cfg(base_portable_simd)]
+                return $mask(self.0.simd_lt(other.0));
+            }
+
+            #[inline(always)]
+            pub fn le(self, other: Self) -> $mask {
+                #[cfg(not(base_portable_simd))]
+                return self.compare(other, |a, b| a <= b);
+                #[cfg(base_portable_simd)]
+                return $mask(self.0.simd_le(other.0));
+            }
+
+            #[inline(always)]
+            pub fn gt(self, other: Self) -> $mask {
+                other.lt(self)
+            }
+
+            #[inline(always)]
+            pub fn ge(self, other: Self) -> $mask {
+                other.le(self)
+            }
+
+            #[inline(always)]
+            pub fn eq(self, other: Self) -> $mask {
+                #[cfg(not(base_portable_simd))]
+                return self.compare(other, |a, b| a == b);
+                #[cfg(base_portable_simd)]
+                return $mask(self.0.simd_eq(other.0));
+            }
+
+            /// Sum of all lanes (pairwise, so the result is order-independent of the backend).
+            #[inline(always)]
+            pub fn reduce_sum(self) -> f32 {
+                let mut lanes = self.to_array();
+                let mut width = $lanes;
+                while width > 1 {
+                    width >>= 1;
+                    for i in 0..width {
+                        lanes[i] += lanes[i + width];
+                    }
+                }
+                lanes[0]
+            }
+
+            #[inline(always)]
+            pub fn reduce_max(self) -> f32 {
+                let lanes = self.to_array();
+                let mut max = lanes[0];
+                for &lane in &lanes[1..] {
+                    if lane > max {
+                        max = lane;
+                    }
+                }
+                max
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::splat(0.0)
+            }
+        }
+
+        simd_vector!(@binop $name, Add, add, AddAssign, add_assign, +);
+        simd_vector!(@binop $name, Sub, sub, SubAssign, sub_assign, -);
+        simd_vector!(@binop $name, Mul, mul, MulAssign, mul_assign, *);
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn neg(self) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return Self(self.0.map(|a| -a));
+                #[cfg(base_portable_simd)]
+                return Self(-self.0);
+            }
+        }
+    };
+    (@binop $name:ident, $trait:ident, $method:ident, $assign:ident, $assign_method:ident, $op:tt) => {
+        impl $trait for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $method(self, other: Self) -> Self {
+                #[cfg(not(base_portable_simd))]
+                return self.map2(other, |a, b| a $op b);
+                #[cfg(base_portable_simd)]
+                return Self(self.0 $op other.0);
+            }
+        }
+
+        impl $assign for $name {
+            #[inline(always)]
+            fn $assign_method(&mut self, other: Self) {
+                *self = *self $op other;
+            }
+        }
+    };
+}
+
+simd_vector!(F32x4, Mask4, 4, 16);
+simd_vector!(F32x8, Mask8, 8, 32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = F32x4::from_array([1.0, 2.0, 3.0, 4.0]);
+        let b = F32x4::splat(2.0);
+        assert_eq!((a + b).to_array(), [3.0, 4.0, 5.0, 6.0]);
+        assert_eq!((a - b).to_array(), [-1.0, 0.0, 1.0, 2.0]);
+        assert_eq!((a * b).to_array(), [2.0, 4.0, 6.0, 8.0]);
+        assert_eq!((-a).abs(), a);
+        assert_eq!(
+            a.mul_add(b, F32x4::splat(1.0)).to_array(),
+            [3.0, 5.0, 7.0, 9.0]
+        );
+        let mut acc = F32x4::default();
+        acc += a;
+        acc *= b;
+        acc -= a;
+        assert_eq!(acc, a);
+    }
+
+    #[test]
+    fn test_min_max_select() {
+        let a = F32x8::from_array([1.0, 5.0, -3.0, 0.0, 8.0, -1.0, 2.0, 2.0]);
+        let zero = F32x8::splat(0.0);
+        assert_eq!(
+            a.max(zero).to_array(),
+            [1.0, 5.0, 0.0, 0.0, 8.0, 0.0, 2.0, 2.0]
+        );
+        assert_eq!(a.min(zero).reduce_sum(), -4.0);
+        assert_eq!(
+            a.clamp(F32x8::splat(-1.0), F32x8::splat(2.0)).reduce_max(),
+            2.0
+        );
+
+        let negative = a.lt(zero);
+        assert!(negative.any());
+        assert!(!negative.all());
+        let rectified = negative.select(-a, a);
+        assert_eq!(rectified, a.abs());
+        assert!(a.ge(zero).to_array()[3]);
+        assert!(a.eq(a).all());
+    }
+
+    #[test]
+    fn test_load_store_alignment() {
+        assert_eq!(core::mem::align_of::<F32x4>(), 16);
+        assert_eq!(core::mem::align_of::<F32x8>(), 32);
+        let input = [0.5f32; 8];
+        let mut output = [0.0f32; 8];
+        (F32x8::load(&input) * F32x8::splat(4.0)).store(&mut output);
+        assert_eq!(output, [2.0; 8]);
+    }
+
+    #[test]
+    fn test_kernel_over_slice() {
+        use crate::slice::SliceExt;
+        let data: [f32; 10] = core::array::from_fn(|i| i as f32);
+        let (chunks, tail) = data.as_arrays::<4>();
+        let mut acc = F32x4::default();
+        for chunk in chunks {
+            acc += F32x4::load(chunk);
+        }
+        let total = acc.reduce_sum() + tail.iter().sum::<f32>();
+        assert_eq!(total, 45.0);
+    }
+}
@@ -0,0 +1,253 @@
+//! Zero-allocation slice tools for vectorizable hot loops.
+//!
+//! Everything here hands out fixed-size arrays (`&[T; N]`) or pre-validated
+//! sub-slices, so the loop bodies index with compile-time-known bounds and
+//! LLVM drops the per-element bounds checks (and the panic edges the
+//! verifier reports as calls). Validation happens once, up front.
+
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// Slice extensions; implemented for `[T]`.
+pub trait SliceExt<T> {
+    /// Splits into `N`-element arrays plus the tail that does not fill one.
+    fn as_arrays<const N: usize>(&self) -> (&[[T; N]], &[T]);
+
+    /// Mutable variant of [`as_arrays`](SliceExt::as_arrays).
+    fn as_arrays_mut<const N: usize>(&mut self) -> (&mut [[T; N]], &mut [T]);
+
+    /// Overlapping windows as `&[T; N]`, stepping by one element.
+    fn windows_exact<const N: usize>(&self) -> WindowsExact<'_, T, N>;
+
+    /// Splits off `K` disjoint mutable slices with the given lengths, in order,
+    /// and returns them with the remainder. `None` if the lengths exceed the slice.
+    fn split_many_mut<const K: usize>(
+        &mut self,
+        lens: [usize; K],
+    ) -> Option<([&mut [T]; K], &mut [T])>;
+
+    /// Every `step`-th element starting at `start` (e.g. one channel of interleaved audio).
+    fn stride(&self, start: usize, step: usize) -> Stride<'_, T>;
+
+    /// Mutable variant of [`stride`](SliceExt::stride).
+    fn stride_mut(&mut self, start: usize, step: usize) -> StrideMut<'_, T>;
+}
+
+impl<T> SliceExt<T> for [T] {
+    #[inline]
+    fn as_arrays<const N: usize>(&self) -> (&[[T; N]], &[T]) {
+        self.as_chunks::<N>()
+    }
+
+    #[inline]
+    fn as_arrays_mut<const N: usize>(&mut self) -> (&mut [[T; N]], &mut [T]) {
+        self.as_chunks_mut::<N>()
+    }
+
+    #[inline]
+    fn windows_exact<const N: usize>(&self) -> WindowsExact<'_, T, N> {
+        const { assert!(N > 0, "window must not be empty") };
+        WindowsExact {
+            slice: self,
+            pos: 0,
+        }
+    }
+
+    fn split_many_mut<const K: usize>(
+        &mut self,
+        lens: [usize; K],
+    ) -> Option<([&mut [T]; K], &mut [T])> {
+        let mut total = 0usize;
+        for len in lens {
+            total = total.checked_add(len)?;
+        }
+        if total > self.len() {
+            return None;
+        }
+        let mut rest = self;
+        let parts = lens.map(|len| {
+            let (head, tail) = core::mem::take(&mut rest).split_at_mut(len);
+            rest = tail;
+            head
+        });
+        Some((parts, rest))
+    }
+
+    #[inline]
+    fn stride(&self, start: usize, step: usize) -> Stride<'_, T> {
+        assert!(step > 0, "stride step must not be zero");
+        Stride {
+            slice: self,
+            pos: start,
+            step,
+        }
+    }
+
+    #[inline]
+    fn stride_mut(&mut self, start: usize, step: usize) -> StrideMut<'_, T> {
+        assert!(step > 0, "stride step must not be zero");
+        StrideMut {
+            ptr: NonNull::from(&mut *self).cast(),
+            len: self.len(),
+            pos: start,
+            step,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Iterator over overlapping `&[T; N]` windows.
+pub struct WindowsExact<'a, T, const N: usize> {
+    slice: &'a [T],
+    pos: usize,
+}
+
+impl<'a, T, const N: usize> Iterator for WindowsExact<'a, T, N> {
+    type Item = &'a [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [T; N]> {
+        if self.pos + N > self.slice.len() {
+            return None;
+        }
+        // SAFETY: `pos + N <= len` was checked above and arrays share slice layout.
+        let window = unsafe { &*(self.slice.as_ptr().add(self.pos) as *const [T; N]) };
+        self.pos += 1;
+        Some(window)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.slice.len() + 1).saturating_sub(self.pos + N);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for WindowsExact<'_, T, N> {}
+impl<T, const N: usize> FusedIterator for WindowsExact<'_, T, N> {}
+
+/// Iterator over every `step`-th element of a slice.
+pub struct Stride<'a, T> {
+    slice: &'a [T],
+    pos: usize,
+    step: usize,
+}
+
+impl<'a, T> Iterator for Stride<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        if self.pos >= self.slice.len() {
+            return None;
+        }
+        // SAFETY: `pos < len` was checked above.
+        let item = unsafe { self.slice.get_unchecked(self.pos) };
+        self.pos = self.pos.saturating_add(self.step);
+        Some(item)
+    }
+}
+
+impl<T> FusedIterator for Stride<'_, T> {}
+
+/// Mutable iterator over every `step`-th element of a slice.
+pub struct StrideMut<'a, T> {
+    ptr: NonNull<T>,
+    len: usize,
+    pos: usize,
+    step: usize,
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> Iterator for StrideMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.pos >= self.len {
+            return None;
+        }
+        // SAFETY: `pos < len`, and `pos` strictly increases, so each element
+        // is handed out at most once.
+        let item = unsafe { &mut *self.ptr.as_ptr().add(self.pos) };
+        self.pos = self.pos.saturating_add(self.step);
+        Some(item)
+    }
+}
+
+impl<T> FusedIterator for StrideMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_arrays() {
+        let data = [1, 2, 3, 4, 5, 6, 7];
+        let (chunks, tail) = data.as_arrays::<3>();
+        assert_eq!(chunks, &[[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(tail, &[7]);
+
+        let mut data = [1.0f32; 8];
+        let (chunks, tail) = data.as_arrays_mut::<4>();
+        for chunk in chunks.iter_mut() {
+            for x in chunk.iter_mut() {
+                *x *= 2.0;
+            }
+        }
+        assert!(tail.is_empty());
+        assert_eq!(data, [2.0; 8]);
+    }
+
+    #[test]
+    fn test_windows_exact() {
+        let data = [1, 2, 3, 4];
+        let mut windows = data.windows_exact::<2>();
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows.next(), Some(&[1, 2]));
+        assert_eq!(windows.next(), Some(&[2, 3]));
+        assert_eq!(windows.next(), Some(&[3, 4]));
+        assert_eq!(windows.next(), None);
+        assert_eq!([1, 2].windows_exact::<3>().count(), 0);
+    }
+
+    #[test]
+    fn test_split_many_mut() {
+        let mut data = [0u8; 10];
+        let ([a, b, c], rest) = data.split_many_mut([2, 3, 4]).unwrap();
+        a.fill(1);
+        b.fill(2);
+        c.fill(3);
+        rest.fill(4);
+        assert_eq!(data, [1, 1, 2, 2, 2, 3, 3, 3, 3, 4]);
+
+        assert!(data.split_many_mut([6, 5]).is_none());
+        assert!(data.split_many_mut([usize::MAX, 2]).is_none());
+    }
+
+    #[test]
+    fn test_stride() {
+        let interleaved = [0, 10, 1, 11, 2, 12];
+        let mut left = [0; 3];
+        for (dst, src) in left.iter_mut().zip(interleaved.stride(0, 2)) {
+            *dst = *src;
+        }
+        assert_eq!(left, [0, 1, 2]);
+        assert_eq!(interleaved.stride(1, 2).copied().max(), Some(12));
+        assert_eq!(interleaved.stride(9, 2).count(), 0);
+    }
+
+    #[test]
+    fn test_stride_mut() {
+        let mut interleaved = [1, 1, 1, 1, 1];
+        for x in interleaved.stride_mut(1, 2) {
+            *x = 0;
+        }
+        assert_eq!(interleaved, [1, 0, 1, 0, 1]);
+        for x in interleaved.stride_mut(0, usize::MAX) {
+            *x = 7;
+        }
+        assert_eq!(interleaved[0], 7);
+    }
+}
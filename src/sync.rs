@@ -0,0 +1,7 @@
+pub mod event_flags;
+pub mod mailbox;
+pub mod spsc;
+
+pub use event_flags::EventFlags;
+pub use mailbox::{Client, Envelope, Mailbox, MailboxError, RetryPolicy, Server};
+pub use spsc::{Consumer, Producer, SpscQueue};
@@ -0,0 +1,131 @@
+//! Event flags signalled from an ISR and consumed by a task.
+//!
+//! Up to 32 independent flags. Instead of `fetch_or`/`swap` (which lower to
+//! `atomicrmw` and fail `AtomicCheck`), the setter and the consumer each own
+//! one word and only ever load and store: the setter toggles a bit of
+//! `raised` to signal, the consumer copies `raised` into `acked` to consume,
+//! and a flag is pending while the two words differ in that bit.
+//!
+//! Orderings: `raised` is stored with `Release` and loaded by the consumer
+//! with `Acquire`, so data written before `set()` is visible after `take()`
+//! returns the flag. `acked` carries no data back; the setter reads it
+//! `Relaxed`, the consumer stores it `Release` to order the consume after its
+//! own reads of the signalled data.
+//!
+//! Both sides are wait-free. The protocol needs exactly one setter context
+//! (one ISR, or ISRs that cannot preempt each other) and one consumer, on the
+//! same core: a `set()` racing a `take()` on another core can observe a stale
+//! `acked` and be merged into the flag being consumed.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// 32 event flags shared between one setter and one consumer.
+#[derive(Debug, Default)]
+pub struct EventFlags {
+    raised: AtomicU32,
+    acked: AtomicU32,
+}
+
+impl EventFlags {
+    pub const fn new() -> Self {
+        Self {
+            raised: AtomicU32::new(0),
+            acked: AtomicU32::new(0),
+        }
+    }
+
+    /// Raises `bits` (setter side). Already-pending bits stay pending once.
+    #[inline(always)]
+    pub fn set(&self, bits: u32) {
+        let raised = self.raised.load(Ordering::Relaxed);
+        let pending = raised ^ self.acked.load(Ordering::Relaxed);
+        let flip = bits & !pending;
+        if flip != 0 {
+            self.raised.store(raised ^ flip, Ordering::Release);
+        }
+    }
+
+    /// Pending flags without consuming them.
+    #[inline(always)]
+    pub fn pending(&self) -> u32 {
+        self.raised.load(Ordering::Acquire) ^ self.acked.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    pub fn is_set(&self, bits: u32) -> bool {
+        self.pending() & bits == bits
+    }
+
+    /// Consumes and returns all pending flags (consumer side).
+    #[inline(always)]
+    pub fn take(&self) -> u32 {
+        self.take_mask(u32::MAX)
+    }
+
+    /// Consumes and returns the pending flags within `mask`; others stay pending.
+    #[inline(always)]
+    pub fn take_mask(&self, mask: u32) -> u32 {
+        let acked = self.acked.load(Ordering::Relaxed);
+        let taken = (self.raised.load(Ordering::Acquire) ^ acked) & mask;
+        if taken != 0 {
+            self.acked.store(acked ^ taken, Ordering::Release);
+        }
+        taken
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    const RX: u32 = 1 << 0;
+    const TX: u32 = 1 << 1;
+    const ERR: u32 = 1 << 31;
+
+    #[test]
+    fn test_set_and_take() {
+        let flags = EventFlags::new();
+        assert_eq!(flags.take(), 0);
+        flags.set(RX | ERR);
+        assert!(flags.is_set(RX));
+        assert!(!flags.is_set(RX | TX));
+        assert_eq!(flags.take(), RX | ERR);
+        assert_eq!(flags.pending(), 0);
+    }
+
+    #[test]
+    fn test_set_coalesces() {
+        let flags = EventFlags::new();
+        flags.set(RX);
+        flags.set(RX);
+        flags.set(RX | TX);
+        assert_eq!(flags.take(), RX | TX);
+        flags.set(RX);
+        assert_eq!(flags.take(), RX);
+        assert_eq!(flags.take(), 0);
+    }
+
+    #[test]
+    fn test_take_mask() {
+        let flags = EventFlags::new();
+        flags.set(RX | TX);
+        assert_eq!(flags.take_mask(TX | ERR), TX);
+        assert_eq!(flags.pending(), RX);
+        flags.set(TX);
+        assert_eq!(flags.take(), RX | TX);
+    }
+
+    #[test]
+    fn test_static_across_threads() {
+        static FLAGS: EventFlags = EventFlags::new();
+        static DATA: AtomicU32 = AtomicU32::new(0);
+        let setter = std::thread::spawn(|| {
+            DATA.store(42, Ordering::Relaxed);
+            FLAGS.set(RX);
+        });
+        setter.join().unwrap();
+        assert_eq!(FLAGS.take(), RX);
+        assert_eq!(DATA.load(Ordering::Relaxed), 42);
+    }
+}
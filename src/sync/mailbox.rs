@@ -0,0 +1,285 @@
+//! Typed command/response mailbox between a control thread and an RT loop.
+//!
+//! Two SPSC queues: requests flow to the RT side, responses flow back. Every
+//! request carries an ID so the client can match responses and discard stale
+//! ones from attempts it already gave up on. `Client::call()` sends, waits
+//! until a `Deadline`, and resends up to `RetryPolicy::retries` times; the RT
+//! side only ever calls `Server::serve()`, which handles at most one request
+//! per call and never blocks.
+
+use core::fmt;
+
+use crate::sync::{Consumer, Producer, SpscQueue};
+use crate::time::Deadline;
+
+/// A request or response tagged with its request ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope<T> {
+    pub id: u32,
+    pub body: T,
+}
+
+/// Timeout per attempt (in clock ticks) and number of resends after the first attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub timeout: u64,
+    pub retries: u8,
+}
+
+/// Mailbox failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailboxError {
+    /// The request queue had no room.
+    Full,
+    /// No response to request `id` after `attempts` sends.
+    Timeout { id: u32, attempts: u8 },
+}
+
+impl fmt::Display for MailboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailboxError::Full => f.write_str("mailbox request queue full"),
+            MailboxError::Timeout { id, attempts } => {
+                write!(f, "request {} timed out after {} attempt(s)", id, attempts)
+            }
+        }
+    }
+}
+
+impl core::error::Error for MailboxError {}
+
+/// Request and response queues with `N` slots each (`N` a power of two).
+pub struct Mailbox<Req, Resp, const N: usize> {
+    requests: SpscQueue<Envelope<Req>, N>,
+    responses: SpscQueue<Envelope<Resp>, N>,
+}
+
+impl<Req, Resp, const N: usize> Mailbox<Req, Resp, N> {
+    pub const fn new() -> Self {
+        Self {
+            requests: SpscQueue::new(),
+            responses: SpscQueue::new(),
+        }
+    }
+
+    /// Splits into the control-side client and the RT-side server.
+    pub fn split(&mut self) -> (Client<'_, Req, Resp, N>, Server<'_, Req, Resp, N>) {
+        let (request_tx, request_rx) = self.requests.split();
+        let (response_tx, response_rx) = self.responses.split();
+        (
+            Client {
+                requests: request_tx,
+                responses: response_rx,
+                next_id: 0,
+                stale: 0,
+            },
+            Server {
+                requests: request_rx,
+                responses: response_tx,
+            },
+        )
+    }
+}
+
+impl<Req, Resp, const N: usize> Default for Mailbox<Req, Resp, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Control-thread end: sends requests and collects responses.
+pub struct Client<'a, Req, Resp, const N: usize> {
+    requests: Producer<'a, Envelope<Req>, N>,
+    responses: Consumer<'a, Envelope<Resp>, N>,
+    next_id: u32,
+    stale: u32,
+}
+
+impl<Req, Resp, const N: usize> Client<'_, Req, Resp, N> {
+    /// Queues `body` under a fresh ID without waiting.
+    pub fn send(&mut self, body: Req) -> Result<u32, MailboxError> {
+        let id = self.next_id;
+        self.requests
+            .push(Envelope { id, body })
+            .map_err(|_| MailboxError::Full)?;
+        self.next_id = self.next_id.wrapping_add(1);
+        Ok(id)
+    }
+
+    /// Next response, if any.
+    pub fn try_recv(&mut self) -> Option<Envelope<Resp>> {
+        self.responses.pop()
+    }
+
+    /// Responses discarded by `call()` because they answered an abandoned attempt.
+    pub fn stale(&self) -> u32 {
+        self.stale
+    }
+
+    /// Sends `body` and spins until its response arrives, resending on timeout.
+    ///
+    /// `now` supplies the clock that `policy.timeout` is measured in.
+    pub fn call(
+        &mut self,
+        body: Req,
+        policy: RetryPolicy,
+        mut now: impl FnMut() -> u64,
+    ) -> Result<Resp, MailboxError>
+    where
+        Req: Clone,
+    {
+        let mut attempts = 0;
+        loop {
+            let id = self.send(body.clone())?;
+            attempts += 1;
+            let deadline = Deadline::after(now(), policy.timeout);
+            while !deadline.is_expired_at(now()) {
+                match self.responses.pop() {
+                    Some(response) if response.id == id => return Ok(response.body),
+                    Some(_) => self.stale = self.stale.saturating_add(1),
+                    None => core::hint::spin_loop(),
+                }
+            }
+            if attempts > policy.retries {
+                return Err(MailboxError::Timeout { id, attempts });
+            }
+        }
+    }
+}
+
+/// RT-loop end: answers requests without blocking.
+pub struct Server<'a, Req, Resp, const N: usize> {
+    requests: Consumer<'a, Envelope<Req>, N>,
+    responses: Producer<'a, Envelope<Resp>, N>,
+}
+
+impl<Req, Resp, const N: usize> Server<'_, Req, Resp, N> {
+    /// Handles one pending request; returns its ID, or `None` if there was
+    /// none or the response queue is full (the request then stays queued).
+    #[inline]
+    pub fn serve(&mut self, handler: impl FnOnce(Req) -> Resp) -> Option<u32> {
+        if self.responses.is_full() {
+            return None;
+        }
+        let request = self.requests.pop()?;
+        let id = request.id;
+        let body = handler(request.body);
+        // Cannot fail: only this side pushes and room was checked above.
+        let _ = self.responses.push(Envelope { id, body });
+        Some(id)
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.requests.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Command {
+        SetGain(f32),
+        GetGain,
+    }
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        timeout: 1_000,
+        retries: 2,
+    };
+
+    #[test]
+    fn test_send_and_serve() {
+        let mut mailbox: Mailbox<Command, f32, 4> = Mailbox::new();
+        let (mut client, mut server) = mailbox.split();
+        let mut gain = 1.0;
+        let a = client.send(Command::SetGain(2.0)).unwrap();
+        let b = client.send(Command::GetGain).unwrap();
+        assert_ne!(a, b);
+        while server
+            .serve(|cmd| {
+                if let Command::SetGain(g) = cmd {
+                    gain = g;
+                }
+                gain
+            })
+            .is_some()
+        {}
+        assert_eq!(client.try_recv(), Some(Envelope { id: a, body: 2.0 }));
+        assert_eq!(client.try_recv(), Some(Envelope { id: b, body: 2.0 }));
+    }
+
+    #[test]
+    fn test_full_and_backpressure() {
+        let mut mailbox: Mailbox<u8, u8, 2> = Mailbox::new();
+        let (mut client, mut server) = mailbox.split();
+        client.send(1).unwrap();
+        client.send(2).unwrap();
+        assert_eq!(client.send(3), Err(MailboxError::Full));
+        server.serve(|x| x);
+        server.serve(|x| x);
+        client.send(3).unwrap();
+        assert_eq!(server.serve(|x| x), None);
+        assert!(server.has_pending());
+    }
+
+    #[test]
+    fn test_call_timeout_and_stale_response() {
+        let mut mailbox: Mailbox<u8, u8, 4> = Mailbox::new();
+        let (mut client, mut server) = mailbox.split();
+        let mut tick = 0;
+        let clock = || {
+            tick += 100;
+            tick
+        };
+        let err = client.call(7, POLICY, clock).unwrap_err();
+        assert_eq!(err, MailboxError::Timeout { id: 2, attempts: 3 });
+
+        // Late answers to the abandoned attempts are dropped by the next call.
+        while server.serve(|x| x + 1).is_some() {}
+        client.send(0).unwrap();
+        server.serve(|_| 42);
+        let mut tick = 0;
+        let clock = || {
+            tick += 1;
+            tick
+        };
+        assert_eq!(
+            client.call(9, POLICY, clock),
+            Err(MailboxError::Timeout { id: 6, attempts: 3 })
+        );
+        assert_eq!(client.stale(), 4);
+    }
+
+    #[test]
+    fn test_call_across_threads() {
+        let mut mailbox: Mailbox<Command, f32, 8> = Mailbox::new();
+        let (mut client, mut server) = mailbox.split();
+        let done = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let mut gain = 0.0;
+                while !done.load(Ordering::Acquire) {
+                    server.serve(|cmd| {
+                        if let Command::SetGain(g) = cmd {
+                            gain = g;
+                        }
+                        gain
+                    });
+                }
+            });
+            let start = std::time::Instant::now();
+            let policy = RetryPolicy {
+                timeout: 5_000_000_000,
+                retries: 0,
+            };
+            let clock = || start.elapsed().as_nanos() as u64;
+            assert_eq!(client.call(Command::SetGain(3.0), policy, clock), Ok(3.0));
+            assert_eq!(client.call(Command::GetGain, policy, clock), Ok(3.0));
+            done.store(true, Ordering::Release);
+        });
+    }
+}
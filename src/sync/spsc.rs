@@ -0,0 +1,251 @@
+//! Lock-free single-producer single-consumer queue with fixed capacity.
+//!
+//! Indices are only ever loaded and stored (acquire/release), never
+//! read-modify-written, so both ends pass `AtomicCheck`. `N` must be a power
+//! of two so slot indexing lowers to a mask instead of `urem`. With the
+//! `alloc` feature a queue can be placed on the heap once at startup with
+//! `new_boxed`.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::validate::const_validate_pow2;
+
+/// Fixed-capacity SPSC queue holding up to `N` items.
+pub struct SpscQueue<T, const N: usize> {
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    buffer: UnsafeCell<[MaybeUninit<T>; N]>,
+}
+
+// SAFETY: producer and consumer touch disjoint slots, synchronized via head/tail.
+unsafe impl<T: Send, const N: usize> Sync for SpscQueue<T, N> {}
+
+impl<T, const N: usize> SpscQueue<T, N> {
+    pub const fn new() -> Self {
+        const { const_validate_pow2(N, "SpscQueue capacity must be a power of two") };
+        Self {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            buffer: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Splits the queue into its producer and consumer halves.
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        (Producer { queue: self }, Consumer { queue: self })
+    }
+
+    /// Enqueues `value`, handing it back if the queue is full.
+    ///
+    /// # Safety
+    /// At most one context may act as producer at any time.
+    #[inline]
+    pub unsafe fn enqueue(&self, value: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == N {
+            return Err(value);
+        }
+        // SAFETY: the slot at `tail` is not visible to the consumer until the store below.
+        unsafe { (*self.buffer.get())[tail & (N - 1)].write(value) };
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    /// Dequeues the oldest item, if any.
+    ///
+    /// # Safety
+    /// At most one context may act as consumer at any time.
+    #[inline]
+    pub unsafe fn dequeue(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        // SAFETY: the slot at `head` was published by the producer's release store.
+        let value = unsafe { (*self.buffer.get())[head & (N - 1)].assume_init_read() };
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T, const N: usize> SpscQueue<T, N> {
+    /// Empty queue on the heap, initialized in place so a large `N` never
+    /// passes through the stack.
+    pub fn new_boxed() -> alloc::boxed::Box<Self> {
+        const { const_validate_pow2(N, "SpscQueue capacity must be a power of two") };
+        let mut boxed = alloc::boxed::Box::<Self>::new_uninit();
+        let ptr = boxed.as_mut_ptr();
+        // SAFETY: both indices are written; `buffer` is `MaybeUninit` and
+        // needs no initialization.
+        unsafe {
+            core::ptr::addr_of_mut!((*ptr).head).write(AtomicUsize::new(0));
+            core::ptr::addr_of_mut!((*ptr).tail).write(AtomicUsize::new(0));
+            boxed.assume_init()
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SpscQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for SpscQueue<T, N> {
+    fn drop(&mut self) {
+        // SAFETY: `&mut self` guarantees exclusive access.
+        while unsafe { self.dequeue() }.is_some() {}
+    }
+}
+
+/// Producer half of an `SpscQueue`.
+pub struct Producer<'a, T, const N: usize> {
+    queue: &'a SpscQueue<T, N>,
+}
+
+// SAFETY: the producer half may move to another context as long as it stays unique.
+unsafe impl<T: Send, const N: usize> Send for Producer<'_, T, N> {}
+
+impl<T, const N: usize> Producer<'_, T, N> {
+    #[inline]
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        // SAFETY: `Producer` is unique by construction.
+        unsafe { self.queue.enqueue(value) }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.queue.is_full()
+    }
+}
+
+/// Consumer half of an `SpscQueue`.
+pub struct Consumer<'a, T, const N: usize> {
+    queue: &'a SpscQueue<T, N>,
+}
+
+// SAFETY: the consumer half may move to another context as long as it stays unique.
+unsafe impl<T: Send, const N: usize> Send for Consumer<'_, T, N> {}
+
+impl<T, const N: usize> Consumer<'_, T, N> {
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        // SAFETY: `Consumer` is unique by construction.
+        unsafe { self.queue.dequeue() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_boxed_queue() {
+        let mut queue = SpscQueue::<[u8; 64], 4096>::new_boxed();
+        let (mut tx, mut rx) = queue.split();
+        assert!(tx.push([7; 64]).is_ok());
+        assert_eq!(rx.pop(), Some([7; 64]));
+        assert_eq!(queue.capacity(), 4096);
+    }
+
+    #[test]
+    fn test_push_pop_order() {
+        let mut queue: SpscQueue<u32, 4> = SpscQueue::new();
+        let (mut tx, mut rx) = queue.split();
+        assert!(tx.push(1).is_ok());
+        assert!(tx.push(2).is_ok());
+        assert_eq!(rx.pop(), Some(1));
+        assert_eq!(rx.pop(), Some(2));
+        assert_eq!(rx.pop(), None);
+    }
+
+    #[test]
+    fn test_full_queue_rejects() {
+        let mut queue: SpscQueue<u32, 2> = SpscQueue::new();
+        let (mut tx, mut rx) = queue.split();
+        assert!(tx.push(1).is_ok());
+        assert!(tx.push(2).is_ok());
+        assert!(tx.is_full());
+        assert_eq!(tx.push(3), Err(3));
+        assert_eq!(rx.pop(), Some(1));
+        assert!(tx.push(3).is_ok());
+        assert_eq!(rx.len(), 2);
+    }
+
+    #[test]
+    fn test_wraparound() {
+        let mut queue: SpscQueue<usize, 4> = SpscQueue::new();
+        let (mut tx, mut rx) = queue.split();
+        for i in 0..100 {
+            assert!(tx.push(i).is_ok());
+            assert_eq!(rx.pop(), Some(i));
+        }
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn test_drop_releases_items() {
+        use std::rc::Rc;
+        let item = Rc::new(());
+        {
+            let mut queue: SpscQueue<Rc<()>, 4> = SpscQueue::new();
+            let (mut tx, _rx) = queue.split();
+            assert!(tx.push(item.clone()).is_ok());
+            assert!(tx.push(item.clone()).is_ok());
+            assert_eq!(Rc::strong_count(&item), 3);
+        }
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
+
+    #[test]
+    fn test_cross_thread() {
+        let mut queue: SpscQueue<u32, 8> = SpscQueue::new();
+        let (mut tx, mut rx) = queue.split();
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for i in 0..1000 {
+                    while tx.push(i).is_err() {}
+                }
+            });
+            let mut expected = 0;
+            while expected < 1000 {
+                if let Some(v) = rx.pop() {
+                    assert_eq!(v, expected);
+                    expected += 1;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,7 @@
+pub mod deadline;
+pub mod timeout;
+pub mod timer_wheel;
+
+pub use deadline::Deadline;
+pub use timeout::{Timeout, TimeoutScope};
+pub use timer_wheel::{TimerId, TimerWheel};
@@ -0,0 +1,77 @@
+//! Absolute deadlines on a tick or cycle counter.
+//!
+//! A `Deadline` is just the expiry timestamp; every query takes `now` so it
+//! works with any monotonic clock (timer-wheel ticks, `perf::clock::cycles()`,
+//! a test counter). The `*_at`-less variants read the cycle counter.
+
+use crate::perf::clock;
+
+/// Point in time after which an operation should give up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline {
+    at: u64,
+}
+
+impl Deadline {
+    /// A deadline that never expires.
+    pub const NEVER: Deadline = Deadline { at: u64::MAX };
+
+    pub const fn at(at: u64) -> Self {
+        Self { at }
+    }
+
+    /// `timeout` ticks after `now`, saturating.
+    pub const fn after(now: u64, timeout: u64) -> Self {
+        Self {
+            at: now.saturating_add(timeout),
+        }
+    }
+
+    /// `timeout` cycles from the current cycle count.
+    #[inline]
+    pub fn from_now(timeout: u64) -> Self {
+        Self::after(clock::cycles(), timeout)
+    }
+
+    pub const fn expires(&self) -> u64 {
+        self.at
+    }
+
+    #[inline(always)]
+    pub const fn is_expired_at(&self, now: u64) -> bool {
+        now >= self.at
+    }
+
+    #[inline]
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(clock::cycles())
+    }
+
+    /// Ticks left until expiry, 0 once expired.
+    #[inline(always)]
+    pub const fn remaining_at(&self, now: u64) -> u64 {
+        self.at.saturating_sub(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let deadline = Deadline::after(100, 50);
+        assert_eq!(deadline.expires(), 150);
+        assert!(!deadline.is_expired_at(149));
+        assert!(deadline.is_expired_at(150));
+        assert_eq!(deadline.remaining_at(120), 30);
+        assert_eq!(deadline.remaining_at(200), 0);
+        assert!(Deadline::after(10, 1) < Deadline::after(5, 10));
+    }
+
+    #[test]
+    fn test_never_saturates() {
+        assert_eq!(Deadline::after(u64::MAX - 1, 10), Deadline::NEVER);
+        assert!(!Deadline::NEVER.is_expired_at(u64::MAX - 1));
+    }
+}
@@ -0,0 +1,78 @@
+//! Bounded-time scopes for `scoped_timeout!`.
+//!
+//! A `TimeoutScope` pairs a `Deadline` with the clock it is measured in.
+//! Code that must finish in bounded time (init and calibration sequences
+//! polling hardware) calls `check` at its own yield points; there is no
+//! timer interrupt, so a step between two checks is never cut short.
+
+use core::fmt;
+
+use super::Deadline;
+use crate::perf::clock;
+
+/// The deadline had passed at a yield point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeout {
+    pub deadline: Deadline,
+    /// Clock reading at the failed check.
+    pub now: u64,
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "deadline {} exceeded at {}",
+            self.deadline.expires(),
+            self.now
+        )
+    }
+}
+
+impl core::error::Error for Timeout {}
+
+/// Deadline plus clock, checked at yield points.
+pub struct TimeoutScope<F = fn() -> u64> {
+    deadline: Deadline,
+    now: F,
+}
+
+impl TimeoutScope {
+    /// Scope on the cycle counter.
+    pub fn new(deadline: Deadline) -> Self {
+        Self {
+            deadline,
+            now: clock::cycles,
+        }
+    }
+}
+
+impl<F: FnMut() -> u64> TimeoutScope<F> {
+    /// Scope on the clock `now` (ticks, a test counter).
+    pub fn with_clock(deadline: Deadline, now: F) -> Self {
+        Self { deadline, now }
+    }
+
+    pub fn deadline(&self) -> Deadline {
+        self.deadline
+    }
+
+    /// Yield point: `Err` once the deadline has passed.
+    #[inline]
+    pub fn check(&mut self) -> Result<(), Timeout> {
+        let now = (self.now)();
+        if self.deadline.is_expired_at(now) {
+            Err(Timeout {
+                deadline: self.deadline,
+                now,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ticks left until the deadline, 0 once it has passed.
+    pub fn remaining(&mut self) -> u64 {
+        self.deadline.remaining_at((self.now)())
+    }
+}
@@ -0,0 +1,322 @@
+//! Hierarchical timer wheel with fixed storage.
+//!
+//! Four levels of 64 slots cover 2^24 ticks; longer delays are parked in the
+//! top level and re-cascaded until they fit. Timers live in a pool of
+//! `TIMERS` nodes linked into per-slot intrusive lists by `u16` index, so
+//! `schedule` and `cancel` are O(1) and nothing allocates. `tick()` advances
+//! one tick and passes each expired payload to a callback; the payload type
+//! `E` is the dispatch mechanism (an enum, or a `fn` pointer to call).
+//!
+//! Slot math is shifts and masks only; no division on the tick path.
+
+const BITS: u32 = 6;
+const SLOTS: usize = 1 << BITS;
+const MASK: u64 = SLOTS as u64 - 1;
+const LEVELS: usize = 4;
+/// Longest delay that maps to a slot directly; longer ones cascade.
+const MAX_DELTA: u64 = (1 << (BITS * LEVELS as u32)) - 1;
+const NIL: u16 = u16::MAX;
+
+/// Handle to a scheduled timer; stale after it fires or is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId {
+    index: u16,
+    generation: u16,
+}
+
+struct Node<E> {
+    expires: u64,
+    event: Option<E>,
+    prev: u16,
+    next: u16,
+    /// `level * SLOTS + slot`, or `NIL` when free.
+    slot: u16,
+    generation: u16,
+}
+
+/// Timer wheel holding up to `TIMERS` pending timers with payload `E`.
+pub struct TimerWheel<E, const TIMERS: usize> {
+    nodes: [Node<E>; TIMERS],
+    heads: [[u16; SLOTS]; LEVELS],
+    free: u16,
+    now: u64,
+    pending: usize,
+}
+
+impl<E, const TIMERS: usize> TimerWheel<E, TIMERS> {
+    const FREE_NODE: Node<E> = Node {
+        expires: 0,
+        event: None,
+        prev: NIL,
+        next: NIL,
+        slot: NIL,
+        generation: 0,
+    };
+
+    pub const fn new() -> Self {
+        const {
+            assert!(
+                TIMERS > 0 && TIMERS < NIL as usize,
+                "TIMERS must be in 1..65535"
+            )
+        };
+        let mut nodes = [Self::FREE_NODE; TIMERS];
+        let mut i = 0;
+        while i + 1 < TIMERS {
+            nodes[i].next = (i + 1) as u16;
+            i += 1;
+        }
+        Self {
+            nodes,
+            heads: [[NIL; SLOTS]; LEVELS],
+            free: 0,
+            now: 0,
+            pending: 0,
+        }
+    }
+
+    /// Current tick count.
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Number of scheduled timers.
+    pub fn len(&self) -> usize {
+        self.pending
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending == 0
+    }
+
+    /// Schedules `event` to fire `delay` ticks from now (0 behaves as 1).
+    ///
+    /// Returns the event back if all `TIMERS` nodes are in use.
+    pub fn schedule(&mut self, delay: u64, event: E) -> Result<TimerId, E> {
+        if self.free == NIL {
+            return Err(event);
+        }
+        let index = self.free;
+        let node = &mut self.nodes[index as usize];
+        self.free = node.next;
+        node.expires = self.now.saturating_add(if delay == 0 { 1 } else { delay });
+        node.event = Some(event);
+        let id = TimerId {
+            index,
+            generation: node.generation,
+        };
+        self.link(index);
+        self.pending += 1;
+        Ok(id)
+    }
+
+    /// Cancels a pending timer, returning its event; `None` if it already fired.
+    pub fn cancel(&mut self, id: TimerId) -> Option<E> {
+        let node = self.nodes.get(id.index as usize)?;
+        if node.generation != id.generation || node.slot == NIL {
+            return None;
+        }
+        self.unlink(id.index);
+        Some(self.release(id.index))
+    }
+
+    /// Ticks remaining until `id` fires, if still pending.
+    pub fn remaining(&self, id: TimerId) -> Option<u64> {
+        let node = self.nodes.get(id.index as usize)?;
+        if node.generation != id.generation || node.slot == NIL {
+            return None;
+        }
+        Some(node.expires - self.now)
+    }
+
+    /// Advances one tick and calls `on_expire` for every timer due now.
+    pub fn tick(&mut self, mut on_expire: impl FnMut(TimerId, E)) {
+        self.tick_with(&mut on_expire);
+    }
+
+    /// Advances `ticks` ticks, firing timers in expiry order.
+    pub fn advance(&mut self, ticks: u64, mut on_expire: impl FnMut(TimerId, E)) {
+        for _ in 0..ticks {
+            self.tick_with(&mut on_expire);
+        }
+    }
+
+    fn tick_with(&mut self, on_expire: &mut impl FnMut(TimerId, E)) {
+        self.now += 1;
+        let now = self.now;
+
+        let mut level = 1;
+        while level < LEVELS && now & ((1 << (BITS * level as u32)) - 1) == 0 {
+            let slot = ((now >> (BITS * level as u32)) & MASK) as usize;
+            let mut cursor = core::mem::replace(&mut self.heads[level][slot], NIL);
+            while cursor != NIL {
+                let next = self.nodes[cursor as usize].next;
+                self.link(cursor);
+                cursor = next;
+            }
+            level += 1;
+        }
+
+        let slot = (now & MASK) as usize;
+        let mut cursor = core::mem::replace(&mut self.heads[0][slot], NIL);
+        while cursor != NIL {
+            let next = self.nodes[cursor as usize].next;
+            let id = TimerId {
+                index: cursor,
+                generation: self.nodes[cursor as usize].generation,
+            };
+            let event = self.release(cursor);
+            on_expire(id, event);
+            cursor = next;
+        }
+    }
+
+    /// Pushes node `index` onto the head of the slot matching its expiry.
+    fn link(&mut self, index: u16) {
+        let expires = self.nodes[index as usize].expires;
+        let delta = expires - self.now;
+        let target = if delta > MAX_DELTA {
+            self.now + MAX_DELTA
+        } else {
+            expires
+        };
+        let mut level = 0;
+        while level + 1 < LEVELS && (target - self.now) >> (BITS * (level as u32 + 1)) != 0 {
+            level += 1;
+        }
+        let slot = ((target >> (BITS * level as u32)) & MASK) as usize;
+
+        let head = self.heads[level][slot];
+        if head != NIL {
+            self.nodes[head as usize].prev = index;
+        }
+        let node = &mut self.nodes[index as usize];
+        node.prev = NIL;
+        node.next = head;
+        node.slot = (level * SLOTS + slot) as u16;
+        self.heads[level][slot] = index;
+    }
+
+    fn unlink(&mut self, index: u16) {
+        let (prev, next, slot) = {
+            let node = &self.nodes[index as usize];
+            (node.prev, node.next, node.slot as usize)
+        };
+        if prev == NIL {
+            self.heads[slot >> BITS][slot & (SLOTS - 1)] = next;
+        } else {
+            self.nodes[prev as usize].next = next;
+        }
+        if next != NIL {
+            self.nodes[next as usize].prev = prev;
+        }
+    }
+
+    /// Returns an unlinked node to the free list and takes its event.
+    fn release(&mut self, index: u16) -> E {
+        let node = &mut self.nodes[index as usize];
+        node.slot = NIL;
+        node.prev = NIL;
+        node.next = self.free;
+        node.generation = node.generation.wrapping_add(1);
+        self.free = index;
+        self.pending -= 1;
+        node.event.take().expect("scheduled timer has an event")
+    }
+}
+
+impl<E, const TIMERS: usize> Default for TimerWheel<E, TIMERS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_fires_on_exact_tick() {
+        let mut wheel: TimerWheel<u32, 8> = TimerWheel::new();
+        wheel.schedule(3, 1).unwrap();
+        wheel.schedule(1, 2).unwrap();
+        wheel.schedule(0, 3).unwrap();
+        let mut fired = Vec::new();
+        for tick in 1..=4 {
+            wheel.tick(|_, e| fired.push((tick, e)));
+        }
+        fired.sort();
+        assert_eq!(fired, [(1, 2), (1, 3), (3, 1)]);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn test_long_delays_cascade() {
+        let delays = [63, 64, 65, 4095, 4096, 4097, 300_000, MAX_DELTA + 10];
+        let mut wheel: TimerWheel<u64, 16> = TimerWheel::new();
+        wheel.advance(37, |_, _| {});
+        for delay in delays {
+            wheel.schedule(delay, wheel.now() + delay).unwrap();
+        }
+        let mut fired = 0;
+        let end = wheel.now() + MAX_DELTA + 11;
+        while wheel.now() < end {
+            let now = wheel.now() + 1;
+            wheel.tick(|_, due| {
+                assert_eq!(due, now);
+                fired += 1;
+            });
+        }
+        assert_eq!(fired, delays.len());
+    }
+
+    #[test]
+    fn test_cancel_and_stale_ids() {
+        let mut wheel: TimerWheel<&str, 4> = TimerWheel::new();
+        let a = wheel.schedule(10, "a").unwrap();
+        let b = wheel.schedule(10, "b").unwrap();
+        let c = wheel.schedule(10, "c").unwrap();
+        assert_eq!(wheel.remaining(b), Some(10));
+        assert_eq!(wheel.cancel(b), Some("b"));
+        assert_eq!(wheel.cancel(b), None);
+        assert_eq!(wheel.remaining(b), None);
+
+        let mut fired = Vec::new();
+        wheel.advance(10, |id, e| fired.push((id, e)));
+        fired.sort_by_key(|(_, e)| *e);
+        assert_eq!(fired, [(a, "a"), (c, "c")]);
+        assert_eq!(wheel.cancel(a), None);
+    }
+
+    #[test]
+    fn test_pool_exhaustion() {
+        let mut wheel: TimerWheel<u8, 2> = TimerWheel::new();
+        wheel.schedule(5, 1).unwrap();
+        let id = wheel.schedule(5, 2).unwrap();
+        assert_eq!(wheel.schedule(5, 3), Err(3));
+        wheel.cancel(id);
+        assert!(wheel.schedule(5, 3).is_ok());
+        assert_eq!(wheel.len(), 2);
+    }
+
+    #[test]
+    fn test_fn_pointer_dispatch() {
+        fn bump(counter: &mut u32) {
+            *counter += 1;
+        }
+        fn reset(counter: &mut u32) {
+            *counter = 0;
+        }
+        let mut wheel: TimerWheel<fn(&mut u32), 4> = TimerWheel::new();
+        wheel.schedule(1, bump).unwrap();
+        wheel.schedule(2, bump).unwrap();
+        wheel.schedule(70, reset).unwrap();
+        let mut counter = 0;
+        wheel.advance(2, |_, action| action(&mut counter));
+        assert_eq!(counter, 2);
+        wheel.advance(68, |_, action| action(&mut counter));
+        assert_eq!(counter, 0);
+    }
+}
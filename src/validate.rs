@@ -0,0 +1,67 @@
+//! Compile-time validation of const-generic parameters.
+//!
+//! Called from a `const { .. }` block, a failed validation is a const
+//! evaluation error at the use site, e.g. `SpscQueue::<u8, 12>::new()`
+//! fails to build with "SpscQueue capacity must be a power of two". The
+//! helpers return their argument so they can also initialize constants.
+//! At runtime they panic with the same message.
+
+/// `n` if it is a power of two.
+#[track_caller]
+pub const fn const_validate_pow2(n: usize, message: &'static str) -> usize {
+    if !n.is_power_of_two() {
+        panic!("{}", message);
+    }
+    n
+}
+
+/// `n` if it is not zero.
+#[track_caller]
+pub const fn const_validate_nonzero(n: usize, message: &'static str) -> usize {
+    if n == 0 {
+        panic!("{}", message);
+    }
+    n
+}
+
+/// `n` if `min <= n <= max`.
+#[track_caller]
+pub const fn const_validate_range(
+    n: usize,
+    min: usize,
+    max: usize,
+    message: &'static str,
+) -> usize {
+    if n < min || n > max {
+        panic!("{}", message);
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASK: usize = const_validate_pow2(64, "mask table must be 2^k") - 1;
+
+    #[test]
+    fn test_valid_values_pass_through() {
+        assert_eq!(MASK, 63);
+        assert_eq!(const_validate_nonzero(3, "empty"), 3);
+        assert_eq!(const_validate_range(16, 2, 16, "out of range"), 16);
+        assert_eq!(const_validate_range(2, 2, 16, "out of range"), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "ring capacity must be a power of two")]
+    fn test_runtime_failure_keeps_message() {
+        let n = core::hint::black_box(12);
+        const_validate_pow2(n, "ring capacity must be a power of two");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_range_upper_bound() {
+        const_validate_range(core::hint::black_box(17), 2, 16, "out of range");
+    }
+}